@@ -1,12 +1,13 @@
 import { store } from "../core/store.js";
 import { loadFromUrl } from "../core/datasource.js";
-import { aggregateUsers, filterUsers, kpis, timeSeriesAvgScore } from "../core/selectors.js";
+import { aggregateUsers, entriesForRepo, filterUsers, kpis, reposOf, timeSeriesAvgScore } from "../core/selectors.js";
 import { applyShadowStyles, el } from "../ui/shadow.js";
 
 import "./topbar.js";
 import "./kpi-card.js";
 import "./line-chart.js";
 import "./user-table.js";
+import "./repo-table.js";
 import "./detail-drawer.js";
 
 const css = `
@@ -21,6 +22,7 @@ const css = `
 dd-kpi-card { grid-column: span 3; }
 .chart { grid-column: 1 / span 7; }
 .table { grid-column: 8 / -1; }
+.repos { grid-column: 1 / -1; }
 .status {
   margin-top: 16px;
   padding: 14px;
@@ -42,6 +44,8 @@ export class DdApp extends HTMLElement {
   #shadow = this.attachShadow({ mode: "open" });
   #chart = document.createElement("dd-line-chart") as any;
   #table = document.createElement("dd-user-table") as any;
+  #repos = document.createElement("dd-repo-table") as any;
+  #reposCell = el("div", { class: "repos" });
   #status = el("div", { class: "status" });
 
   connectedCallback() {
@@ -59,7 +63,9 @@ export class DdApp extends HTMLElement {
       kpiGrid,
       el("div", { class: "chart" }, [this.#chart]),
       el("div", { class: "table" }, [this.#table]),
+      this.#reposCell,
     ]);
+    this.#reposCell.append(this.#repos);
 
     this.#shadow.append(el("dd-topbar"), el("div", { class: "wrap" }, [grid, this.#status]), el("dd-detail-drawer"));
 
@@ -84,7 +90,10 @@ export class DdApp extends HTMLElement {
 
   render() {
     const s = store.getState();
-    const entries = s.data?.entries ?? [];
+    const entries = entriesForRepo(s.data, s.selectedRepo);
+    const repos = reposOf(s.data);
+    this.#repos.repos = repos;
+    this.#reposCell.style.display = repos.length > 1 ? "block" : "none";
     const stats = kpis(entries);
 
     const cards = Array.from(this.#shadow.querySelectorAll("dd-kpi-card"));
@@ -1,6 +1,6 @@
 import { applyShadowStyles, el } from "../ui/shadow.js";
 import { store } from "../core/store.js";
-import { entriesForUser } from "../core/selectors.js";
+import { entriesForRepo, entriesForUser } from "../core/selectors.js";
 import type { DashboardEntry } from "../core/types.js";
 
 const css = `
@@ -59,6 +59,8 @@ const css = `
 .pill { padding: 4px 10px; border-radius: 999px; border: 1px solid rgba(255,255,255,0.10); background: rgba(0,0,0,0.12); font-size: 12px; color: var(--muted); }
 .pill.pass { border-color: rgba(20,241,149,0.25); color: rgba(20,241,149,0.95); }
 .pill.fail { border-color: rgba(255,92,124,0.25); color: rgba(255,92,124,0.95); }
+.pill.sev-medium { color: rgba(255,196,92,0.95); }
+.pill.sev-high, .pill.sev-critical { border-color: rgba(255,92,124,0.25); color: rgba(255,92,124,0.95); }
 pre {
   white-space: pre-wrap;
   word-break: break-word;
@@ -108,7 +110,7 @@ export class DdDetailDrawer extends HTMLElement {
   render() {
     const s = store.getState();
     const email = s.selectedEmail;
-    const entries = s.data?.entries ?? [];
+    const entries = entriesForRepo(s.data, s.selectedRepo);
     const open = Boolean(email);
     this.#drawer.classList.toggle("open", open);
     if (!email) return;
@@ -130,7 +132,7 @@ export class DdDetailDrawer extends HTMLElement {
           el("div", { class: "mono" }, [trunc(e.commit.sha, 10), " • ", e.commit.author_date_iso]),
           pill,
         ]),
-        el("div", { class: "muted" }, [`score ${e.transcript.score.total_score.toFixed(2)} • patch ${trunc(e.transcript.diff_fingerprint.patch_id, 12)}`]),
+        el("div", { class: "muted" }, [`${e.repo_id && !s.selectedRepo ? `${e.repo_id} • ` : ""}score ${e.transcript.score.total_score.toFixed(2)} • patch ${trunc(e.transcript.diff_fingerprint.patch_id, 12)}`]),
         el("div", { class: "muted" }, [trunc(e.commit.subject, 70)])
       );
       this.#commitList.appendChild(item);
@@ -156,15 +158,31 @@ export class DdDetailDrawer extends HTMLElement {
     const qById = new Map(entry.transcript.exam.questions.map((q) => [q.id, q]));
     const blocks: Node[] = [];
     for (const q of entry.transcript.score.per_question) {
-      const prompt = qById.get(q.id)?.prompt ?? "";
+      const question = qById.get(q.id);
+      const prompt = question?.prompt ?? "";
       const header = el("div", { class: "row" }, [
-        el("div", {}, [`${q.id} [${q.category}]`]),
-        el("div", { class: "mono muted" }, [`${q.score.toFixed(2)} (c ${q.completeness.toFixed(2)}, s ${q.specificity.toFixed(2)})`]),
+        el("div", {}, [`${q.id} [${q.category}]${q.choice_correct === undefined ? "" : q.choice_correct ? " ✓ choice" : " ✗ choice"}${q.evidence_valid === undefined ? "" : q.evidence_valid ? " ✓ evidence" : " ✗ evidence"}`]),
+        el("div", { class: "mono muted" }, [`${q.score.toFixed(2)} (c ${q.completeness.toFixed(2)}, s ${q.specificity.toFixed(2)}, r ${(q.category_relevance ?? 1).toFixed(2)})`]),
       ]);
       blocks.push(el("div", { class: "item" }, [header, el("div", { class: "muted" }, [trunc(prompt.replace(/\s+/g, " "), 220)])]));
+      const hunks = question?.related_hunks ?? [];
+      const paths = question?.related_paths ?? [];
+      if (hunks.length > 0 || paths.length > 0) {
+        blocks.push(el("div", { class: "mono muted" }, [trunc(hunks.length > 0 ? hunks.join(", ") : paths.join(", "), 220)]));
+      }
+      for (const f of entry.transcript.score.hallucination_flags.filter((f) => f.question_id === q.id)) {
+        blocks.push(
+          el("div", { class: "row" }, [
+            el("span", { class: `pill sev-${f.severity}` }, [f.severity]),
+            el("div", { class: "muted" }, [`${f.kind}${f.claimed_artifact ? `: ${f.claimed_artifact}` : ""} — ${trunc(f.evidence, 160)}`]),
+          ])
+        );
+      }
 
       if (showAnswers) {
         const ans = entry.transcript.answers.answers[q.id] ?? "";
+        const who = entry.transcript.answers.respondents?.[q.id];
+        if (who) blocks.push(el("div", { class: "muted" }, [`answered by ${who.name ? `${who.name} <${who.email}>` : who.email}`]));
         blocks.push(el("pre", {}, [ans.trim() || "(empty / not exported)"]));
       }
     }
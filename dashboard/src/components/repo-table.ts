@@ -0,0 +1,99 @@
+import { applyShadowStyles, el } from "../ui/shadow.js";
+import { store } from "../core/store.js";
+import type { DashboardRepo } from "../core/types.js";
+
+const css = `
+:host { display:block; }
+.card {
+  background: linear-gradient(180deg, rgba(255,255,255,0.03), rgba(255,255,255,0.012));
+  border: 1px solid var(--border);
+  border-radius: var(--radius);
+  box-shadow: var(--shadow);
+  overflow: hidden;
+}
+.head {
+  padding: 14px 16px;
+  display:flex;
+  align-items:baseline;
+  justify-content:space-between;
+  border-bottom: 1px solid rgba(255,255,255,0.06);
+}
+.title { color: var(--muted); font-size: 12px; letter-spacing: 0.06em; text-transform: uppercase; }
+.meta { color: var(--muted-2); font-size: 12px; }
+table { width:100%; border-collapse: collapse; font-size: 13px; }
+th, td { padding: 12px 14px; border-bottom: 1px solid rgba(255,255,255,0.06); }
+th { text-align:left; color: var(--muted); font-weight: 600; font-size: 12px; }
+tr { cursor: pointer; }
+tr:hover td { background: rgba(255,255,255,0.03); }
+tr.selected td { background: rgba(124,92,255,0.12); }
+.score { color: rgba(255,255,255,0.88); font-variant-numeric: tabular-nums; }
+.muted { color: var(--muted-2); }
+`;
+
+function pct(v: number): string {
+  return `${(v * 100).toFixed(1)}%`;
+}
+
+export class DdRepoTable extends HTMLElement {
+  #shadow = this.attachShadow({ mode: "open" });
+  #tbody = el("tbody");
+  #meta = el("div", { class: "meta" });
+  #repos: DashboardRepo[] = [];
+
+  connectedCallback() {
+    applyShadowStyles(this.#shadow, css);
+    const table = el("table", {}, [
+      el("thead", {}, [
+        el("tr", {}, [
+          el("th", {}, ["Repository"]),
+          el("th", {}, ["Transcripts"]),
+          el("th", {}, ["Coverage"]),
+          el("th", {}, ["Pass rate"]),
+          el("th", {}, ["Avg score"]),
+        ]),
+      ]),
+      this.#tbody,
+    ]);
+
+    this.#shadow.append(
+      el("div", { class: "card" }, [el("div", { class: "head" }, [el("div", { class: "title" }, ["Repositories"]), this.#meta]), table])
+    );
+
+    store.addEventListener("change", () => this.render());
+    this.render();
+  }
+
+  set repos(v: DashboardRepo[]) {
+    this.#repos = v;
+    this.render();
+  }
+
+  render() {
+    const s = store.getState();
+    this.#meta.textContent = `${this.#repos.length} repositories`;
+
+    while (this.#tbody.firstChild) this.#tbody.removeChild(this.#tbody.firstChild);
+    for (const r of this.#repos) {
+      const tr = el("tr");
+      if (s.selectedRepo === r.repo_id) tr.classList.add("selected");
+      tr.addEventListener("click", () =>
+        store.setState({
+          selectedRepo: s.selectedRepo === r.repo_id ? null : r.repo_id,
+          selectedEmail: null,
+          selectedCommit: null,
+        })
+      );
+      const st = r.stats;
+      tr.append(
+        el("td", { title: r.sources.join("\n") }, [r.repo_id]),
+        el("td", { class: "score" }, [String(st.transcripts)]),
+        el("td", { class: st.commits ? "score" : "muted" }, [st.commits ? `${pct(st.coverage)} (${st.covered}/${st.commits})` : "—"]),
+        el("td", { class: "score" }, [pct(st.pass_rate)]),
+        el("td", { class: "score" }, [st.avg_total_score.toFixed(2)])
+      );
+      this.#tbody.appendChild(tr);
+    }
+  }
+}
+
+customElements.define("dd-repo-table", DdRepoTable);
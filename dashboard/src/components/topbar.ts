@@ -1,5 +1,6 @@
 import { store } from "../core/store.js";
 import { loadFromFile } from "../core/datasource.js";
+import { reposOf } from "../core/selectors.js";
 import { applyShadowStyles, el } from "../ui/shadow.js";
 
 const css = `
@@ -50,6 +51,14 @@ input[type="text"]:focus { border-color: rgba(124,92,255,0.55); box-shadow: 0 0
   font-size: 12px;
 }
 .btn:hover { border-color: rgba(255,255,255,0.18); background: rgba(255,255,255,0.05); }
+select {
+  padding: 9px 10px;
+  border-radius: 10px;
+  border: 1px solid rgba(255,255,255,0.10);
+  background: rgba(255,255,255,0.03);
+  color: var(--text);
+  font-size: 12px;
+}
 .toggle { display:flex; align-items:center; gap:8px; color: var(--muted); font-size: 12px; }
 .toggle input { width: 16px; height:16px; }
 `;
@@ -61,6 +70,7 @@ export class DdTopbar extends HTMLElement {
   #loadBtn = el("button", { class: "btn", type: "button" }, ["Load JSON…"]) as HTMLButtonElement;
   #showAnswers = el("input", { type: "checkbox" }) as HTMLInputElement;
   #repo = el("div", { class: "sub" });
+  #repoSelect = el("select") as HTMLSelectElement;
 
   connectedCallback() {
     applyShadowStyles(this.#shadow, css);
@@ -80,11 +90,15 @@ export class DdTopbar extends HTMLElement {
         this.#file.value = "";
       }
     });
+    this.#repoSelect.addEventListener("change", () =>
+      store.setState({ selectedRepo: this.#repoSelect.value || null, selectedEmail: null, selectedCommit: null })
+    );
     this.#showAnswers.addEventListener("change", () => store.setState({ showAnswers: this.#showAnswers.checked }));
 
     const left = el("div", { class: "left" }, [
       el("div", { class: "brand" }, ["aigit / dashboard"]),
       this.#repo,
+      this.#repoSelect,
     ]);
     const right = el("div", { class: "right" }, [
       this.#filter,
@@ -101,8 +115,19 @@ export class DdTopbar extends HTMLElement {
 
   render() {
     const s = store.getState();
-    const repoId = s.data?.repo_id ?? "no data (export required)";
-    this.#repo.textContent = `repo: ${repoId}`;
+    const repos = reposOf(s.data);
+    if (repos.length > 1) {
+      this.#repo.textContent = `${repos.length} repositories`;
+      const options = [el("option", { value: "" }, ["All repositories"])];
+      for (const r of repos) options.push(el("option", { value: r.repo_id }, [r.repo_id]));
+      this.#repoSelect.replaceChildren(...options);
+      this.#repoSelect.value = s.selectedRepo ?? "";
+      this.#repoSelect.style.display = "";
+    } else {
+      const repoId = repos[0]?.repo_id ?? s.data?.repo_id ?? "no data (export required)";
+      this.#repo.textContent = `repo: ${repoId}`;
+      this.#repoSelect.style.display = "none";
+    }
     this.#filter.value = s.userFilter;
     this.#showAnswers.checked = s.showAnswers;
   }
@@ -1,4 +1,4 @@
-import type { DashboardData } from "./types.js";
+import type { DashboardData, OrgExportData } from "./types.js";
 
 export class DashboardDataError extends Error {
   name = "DashboardDataError";
@@ -8,9 +8,24 @@ function isObject(v: unknown): v is Record<string, unknown> {
   return typeof v === "object" && v !== null;
 }
 
+// `aigit org-export` output is read as a multi-repo dataset, one repo per key.
+function fromOrgExport(raw: OrgExportData): DashboardData {
+  if (!isObject(raw.repos)) throw new DashboardDataError("data.json: org export repos must be an object");
+  const repos = Object.entries(raw.repos).map(([repo_id, r]) => ({ repo_id, sources: r.sources ?? [], stats: r.stats }));
+  const entries = Object.entries(raw.repos).flatMap(([repo_id, r]) => (r.entries ?? []).map((e) => ({ ...e, repo_id })));
+  return {
+    schema_version: raw.schema_version,
+    generated_at: raw.generated_at,
+    repo_id: repos.length === 1 ? repos[0].repo_id : undefined,
+    repos,
+    entries,
+  };
+}
+
 export function validateDashboardData(raw: unknown): DashboardData {
   if (!isObject(raw)) throw new DashboardDataError("data.json: expected object");
   if (!("schema_version" in raw)) throw new DashboardDataError("data.json: missing schema_version");
+  if (String(raw.schema_version).startsWith("aigit-org-export/")) return fromOrgExport(raw as OrgExportData);
   if (!("entries" in raw)) throw new DashboardDataError("data.json: missing entries");
   const entries = (raw as any).entries;
   if (!Array.isArray(entries)) throw new DashboardDataError("data.json: entries must be an array");
@@ -1,4 +1,4 @@
-import type { DashboardEntry, UserRow } from "./types.js";
+import type { DashboardData, DashboardEntry, DashboardRepo, UserRow } from "./types.js";
 
 export function toMs(iso: string): number {
   const ms = Date.parse(iso);
@@ -44,6 +44,41 @@ export function filterUsers(users: UserRow[], query: string): UserRow[] {
   return users.filter((u) => u.name.toLowerCase().includes(q) || u.email.toLowerCase().includes(q));
 }
 
+export function repoOf(data: DashboardData, e: DashboardEntry): string {
+  return e.repo_id ?? data.repo_id ?? "";
+}
+
+export function entriesForRepo(data: DashboardData | null, repoId: string | null): DashboardEntry[] {
+  if (!data) return [];
+  if (repoId === null) return data.entries;
+  return data.entries.filter((e) => repoOf(data, e) === repoId);
+}
+
+// Repositories in the dataset; 0.1 exports carry none, so they are derived from the entries.
+export function reposOf(data: DashboardData | null): DashboardRepo[] {
+  if (!data) return [];
+  if (data.repos?.length) return data.repos;
+  const ids = [...new Set(data.entries.map((e) => repoOf(data, e)))].sort();
+  return ids.map((repo_id) => {
+    const entries = data.entries.filter((e) => repoOf(data, e) === repo_id);
+    const k = kpis(entries);
+    return {
+      repo_id,
+      sources: [],
+      stats: {
+        commits: 0,
+        covered: 0,
+        coverage: 0,
+        transcripts: k.total,
+        passes: k.pass,
+        pass_rate: k.passRate,
+        avg_total_score: k.avgScore,
+        category_scores: {},
+      },
+    };
+  });
+}
+
 export function entriesForUser(entries: DashboardEntry[], email: string): DashboardEntry[] {
   return entries
     .filter((e) => e.commit.author_email === email)
@@ -7,6 +7,8 @@ export type AppState = {
   error: string | null;
   data: DashboardData | null;
   userFilter: string;
+  // null shows every repository in the dataset.
+  selectedRepo: string | null;
   selectedEmail: string | null;
   selectedCommit: string | null;
   showAnswers: boolean;
@@ -30,7 +32,7 @@ export class Store extends EventTarget {
   }
 
   setData(data: DashboardData) {
-    this.setState({ data, status: "ready", error: null });
+    this.setState({ data, status: "ready", error: null, selectedRepo: null, selectedEmail: null, selectedCommit: null });
   }
 
   setError(message: string) {
@@ -43,6 +45,7 @@ export const store = new Store({
   error: null,
   data: null,
   userFilter: "",
+  selectedRepo: null,
   selectedEmail: null,
   selectedCommit: null,
   showAnswers: false,
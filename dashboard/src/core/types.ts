@@ -1,5 +1,15 @@
 export type Decision = "pass" | "fail";
 
+export type Severity = "low" | "medium" | "high" | "critical";
+
+export type HallucinationFlag = {
+  question_id: string;
+  kind: string;
+  claimed_artifact?: string | null;
+  evidence: string;
+  severity: Severity;
+};
+
 export type Transcript = {
   schema_version: string;
   commit?: string | null;
@@ -7,12 +17,28 @@ export type Transcript = {
   repo_id: string;
   repo_fingerprint: string;
   diff_fingerprint: { patch_id: string };
-  exam: { questions: Array<{ id: string; category: string; prompt: string; choices?: string[] | null }> };
-  answers: { answers: Record<string, string> };
+  exam: {
+    questions: Array<{
+      id: string;
+      category: string;
+      prompt: string;
+      choices?: string[] | null;
+      related_paths?: string[];
+      related_hunks?: string[];
+      correct_choice?: string | null;
+      kind?: "open" | "evidence" | null;
+    }>;
+    tier?: string;
+  };
+  answers: {
+    answers: Record<string, string>;
+    respondents?: Record<string, { name?: string | null; email: string }>;
+    committer?: string | null;
+  };
   score: {
     total_score: number;
-    hallucination_flags: string[];
-    per_question: Array<{ id: string; category: string; score: number; completeness: number; specificity: number; notes: string[] }>;
+    hallucination_flags: HallucinationFlag[];
+    per_question: Array<{ id: string; category: string; score: number; completeness: number; specificity: number; category_relevance?: number; notes: Array<string | { code: 'missing_specifics' | 'inaccuracy' | 'boilerplate' | 'excellent'; message: string; related_path?: string }>; choice_correct?: boolean; evidence_valid?: boolean }>;
   };
   decision: Decision;
 };
@@ -26,17 +52,43 @@ export type CommitMeta = {
 };
 
 export type DashboardEntry = {
+  repo_id?: string;
   commit: CommitMeta;
   transcript: Transcript;
 };
 
+export type RepoStats = {
+  commits: number;
+  covered: number;
+  coverage: number;
+  transcripts: number;
+  passes: number;
+  pass_rate: number;
+  avg_total_score: number;
+  category_scores: Record<string, number>;
+};
+
+export type DashboardRepo = {
+  repo_id: string;
+  sources: string[];
+  stats: RepoStats;
+};
+
 export type DashboardData = {
-  schema_version: "aigit-dashboard/0.1" | string;
+  schema_version: "aigit-dashboard/0.2" | "aigit-dashboard/0.1" | string;
   generated_at: string;
-  repo_id: string;
+  // Set when the dataset holds a single repository.
+  repo_id?: string;
+  repos?: DashboardRepo[];
   entries: DashboardEntry[];
 };
 
+export type OrgExportData = {
+  schema_version: "aigit-org-export/0.1" | string;
+  generated_at: string;
+  repos: Record<string, { sources: string[]; stats: RepoStats; entries: DashboardEntry[] }>;
+};
+
 export type UserRow = {
   name: string;
   email: string;
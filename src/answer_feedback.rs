@@ -0,0 +1,67 @@
+use crate::examiner::{ExamContext, ExamQuestion, SHORT_ANSWER_WORDS};
+
+/// Signal words listed when none has been used yet.
+const MAX_LISTED_SIGNALS: usize = 4;
+
+/// A one-line check of a partly typed answer against what the built-in grader looks for: length,
+/// the question category's signal words, and whether a changed file or symbol is named.
+pub fn summarize(ctx: &ExamContext, question: &ExamQuestion, answer: &str) -> String {
+    let mut parts = Vec::new();
+
+    let words = crate::i18n::word_count(answer);
+    if words < SHORT_ANSWER_WORDS {
+        parts.push(format!("{words} words (aim for {SHORT_ANSWER_WORDS}+)"));
+    } else {
+        parts.push(format!("{words} words"));
+    }
+
+    let grader = &ctx.policy.static_grader;
+    if grader.has_specific_keywords(&question.category) {
+        let lower = answer.to_lowercase();
+        let keywords = grader.keywords_for(&question.category, ctx.policy.language.as_deref());
+        let used: Vec<&str> = keywords
+            .iter()
+            .filter(|k| lower.contains(&k.to_lowercase()))
+            .map(String::as_str)
+            .collect();
+        if used.is_empty() {
+            let listed: Vec<&str> = keywords
+                .iter()
+                .take(MAX_LISTED_SIGNALS)
+                .map(String::as_str)
+                .collect();
+            parts.push(format!(
+                "no {} signals yet (e.g. {})",
+                question.category,
+                listed.join(", ")
+            ));
+        } else {
+            parts.push(format!(
+                "{} signals: {}",
+                question.category,
+                used.join(", ")
+            ));
+        }
+    }
+
+    let named = ctx
+        .changed_files
+        .iter()
+        .find(|f| !f.is_empty() && answer.contains(f.as_str()))
+        .cloned()
+        .or_else(|| {
+            ctx.changed_symbols
+                .iter()
+                .find(|s| crate::symbols::mentions(answer, &s.name))
+                .map(|s| s.name.clone())
+        });
+    match named {
+        Some(name) => parts.push(format!("names {name}")),
+        None if !ctx.changed_files.is_empty() => {
+            parts.push("no changed file or symbol named yet".to_string())
+        }
+        None => {}
+    }
+
+    parts.join(" | ")
+}
@@ -0,0 +1,87 @@
+use crate::config::Policy;
+use crate::examiner::{Exam, ExamQuestion};
+use crate::transcript::{Answers, Note, NoteCode, QuestionScore, Score};
+
+/// The letter naming choice `idx` ("A" for 0).
+pub fn letter(idx: usize) -> Option<String> {
+    ('A'..='Z').nth(idx).map(String::from)
+}
+
+/// Index of a question's correct choice, when it has choices and a `correct_choice` naming one
+/// of them (by letter or by text).
+pub fn key_index(question: &ExamQuestion) -> Option<usize> {
+    let choices = question.choices.as_ref().filter(|c| !c.is_empty())?;
+    let key = question.correct_choice.as_deref()?;
+    crate::self_check::chosen(key, choices)
+}
+
+/// Overrides the grade of every keyed multiple-choice question with its deterministic result and
+/// shifts the total (and its confidence interval) by the same amount.
+pub fn apply(policy: &Policy, exam: &Exam, answers: &Answers, score: &mut Score) {
+    let mut shift = 0.0;
+    for q in &exam.questions {
+        let Some(correct) = key_index(q) else {
+            continue;
+        };
+        let Some(question_score) = score.per_question.iter_mut().find(|s| s.id == q.id) else {
+            continue;
+        };
+        let choices = q.choices.as_deref().unwrap_or_default();
+        let answer = answers.get(&q.id).unwrap_or_default();
+        let picked = crate::self_check::chosen(answer, choices);
+        let right = picked == Some(correct);
+
+        shift +=
+            policy.score_weight(&q.category) * set_deterministic(question_score, right, answer);
+        question_score.choice_correct = Some(right);
+        if !right {
+            let expected = letter(correct).unwrap_or_default();
+            question_score.notes.push(Note::new(
+                NoteCode::Inaccuracy,
+                match picked.and_then(letter) {
+                    Some(got) => format!("multiple choice: picked {got}, correct is {expected}"),
+                    None => format!("multiple choice: no choice recognized, correct is {expected}"),
+                },
+            ));
+        }
+    }
+    shift_total(policy, score, shift);
+}
+
+/// Replaces a question's grade with a right/wrong result (1.0 or 0.0) and returns how much its
+/// score moved. Free-text notes (length, file mentions) no longer apply and are cleared.
+pub(crate) fn set_deterministic(
+    question_score: &mut QuestionScore,
+    right: bool,
+    answer: &str,
+) -> f64 {
+    let value = if right { 1.0 } else { 0.0 };
+    let moved = value - question_score.score;
+    question_score.score = value;
+    question_score.completeness = if answer.trim().is_empty() { 0.0 } else { 1.0 };
+    question_score.specificity = value;
+    question_score.category_relevance = 1.0;
+    question_score.interval = None;
+    question_score.notes.clear();
+    moved
+}
+
+/// Moves the total (and its confidence interval) by `shift`, the summed change of per-question
+/// scores times their `score_weights`, spread over the weight of every graded question.
+pub(crate) fn shift_total(policy: &Policy, score: &mut Score, shift: f64) {
+    let weights: f64 = score
+        .per_question
+        .iter()
+        .map(|q| policy.score_weight(&q.category))
+        .sum();
+    if weights <= 0.0 || shift == 0.0 {
+        return;
+    }
+    let shift = shift / weights;
+    let clamp = |v: f64| v.clamp(0.0, 1.0);
+    score.total_score = clamp(score.total_score + shift);
+    if let Some(confidence) = &mut score.confidence {
+        confidence.total.low = clamp(confidence.total.low + shift);
+        confidence.total.high = clamp(confidence.total.high + shift);
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::BTreeMap;
+
+use crate::examiner::Exam;
+use crate::transcript::Answers;
+
+const ANSWER_MARKER: &str = "<!-- answer -->";
+
+/// Renders the exam as a Markdown form: one `## [id] category` section per question with the
+/// prompt (and choices) quoted, followed by an answer area.
+pub fn render(exam: &Exam) -> String {
+    render_filled(exam, &BTreeMap::new())
+}
+
+/// [`render`], with `answers` already written in their answer areas.
+pub fn render_filled(exam: &Exam, answers: &BTreeMap<String, String>) -> String {
+    let mut out = String::new();
+    out.push_str("# aigit exam\n\n");
+    out.push_str("<!-- Write each answer below its `<!-- answer -->` marker. Do not change the `## [id]` headings. -->\n");
+    for q in &exam.questions {
+        out.push_str(&format!("\n## [{}] {}\n\n", q.id, q.category));
+        for line in q.prompt.lines() {
+            out.push_str(&format!("> {line}\n"));
+        }
+        if let Some(choices) = q.choices.as_ref().filter(|c| !c.is_empty()) {
+            out.push_str(">\n");
+            for (idx, choice) in choices.iter().enumerate() {
+                let letter = (b'A' + idx as u8) as char;
+                out.push_str(&format!("> {letter}) {choice}\n"));
+            }
+            out.push_str(">\n> (answer with the letter)\n");
+        } else if q.is_evidence() {
+            out.push_str(">\n> (cite a line as path:line, a range as path:start-end, or a hunk as path @@ -a,b +c,d @@)\n");
+        }
+        out.push('\n');
+        out.push_str(ANSWER_MARKER);
+        out.push_str("\n\n");
+        if let Some(answer) = answers.get(&q.id).filter(|a| !a.trim().is_empty()) {
+            out.push_str(answer.trim_end());
+            out.push_str("\n\n");
+        }
+    }
+    out
+}
+
+/// Parses a filled-in form produced by [`render`]. Text after each question's answer marker, up
+/// to the next question heading, is its answer.
+pub fn parse(raw: &str) -> Answers {
+    let mut answers = BTreeMap::new();
+    let mut current: Option<(String, Vec<&str>, bool)> = None;
+    for line in raw.lines() {
+        if let Some(id) = heading_id(line) {
+            if let Some((id, lines, _)) = current.take() {
+                answers.insert(id, join_answer(&lines));
+            }
+            current = Some((id, Vec::new(), false));
+            continue;
+        }
+        let Some((_, lines, in_answer)) = current.as_mut() else {
+            continue;
+        };
+        if !*in_answer {
+            if line.trim() == ANSWER_MARKER {
+                *in_answer = true;
+            }
+            continue;
+        }
+        lines.push(line);
+    }
+    if let Some((id, lines, _)) = current {
+        answers.insert(id, join_answer(&lines));
+    }
+    Answers::new(answers)
+}
+
+fn heading_id(line: &str) -> Option<String> {
+    let rest = line.strip_prefix("## [")?;
+    let (id, _) = rest.split_once(']')?;
+    let id = id.trim();
+    (!id.is_empty()).then(|| id.to_string())
+}
+
+fn join_answer(lines: &[&str]) -> String {
+    lines.join("\n").trim().to_string()
+}
@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::codex_cli::{exam_schema_json, score_schema_json, truncate_for_error};
+use crate::config::{AnthropicPolicy, Policy};
+
+pub const DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
+const API_VERSION: &str = "2023-06-01";
+
+/// Client for Anthropic's Messages API. Structured output is obtained by forcing a single tool
+/// call whose `input_schema` is the exam/score schema.
+#[derive(Debug, Clone)]
+pub struct AnthropicClient {
+    base_url: String,
+    endpoint: String,
+    api_key_env: String,
+    model: Option<String>,
+    max_tokens: u32,
+    timeout: Duration,
+}
+
+impl AnthropicClient {
+    pub fn from_policy(policy: &Policy) -> Self {
+        let cfg: &AnthropicPolicy = &policy.anthropic;
+        let base_url = cfg
+            .base_url
+            .as_deref()
+            .unwrap_or(DEFAULT_BASE_URL)
+            .trim_end_matches('/')
+            .to_string();
+        Self {
+            endpoint: format!("{base_url}/v1/messages"),
+            base_url,
+            api_key_env: cfg
+                .api_key_env
+                .clone()
+                .unwrap_or_else(|| "ANTHROPIC_API_KEY".to_string()),
+            model: cfg
+                .model
+                .clone()
+                .or_else(|| policy.model.clone().filter(|m| m != "static")),
+            max_tokens: cfg.max_tokens.unwrap_or(4096),
+            timeout: Duration::from_secs(cfg.timeout_secs.unwrap_or(120)),
+        }
+    }
+
+    /// Cheap reachability and credentials check: `GET /v1/models`.
+    pub fn ping(&self, timeout: Duration) -> Result<()> {
+        self.model()?;
+        let key = self.api_key()?;
+        let url = format!("{}/v1/models", self.base_url);
+        let agent = ureq::AgentBuilder::new().timeout(timeout).build();
+        match agent
+            .get(&url)
+            .set("x-api-key", key.trim())
+            .set("anthropic-version", API_VERSION)
+            .call()
+        {
+            Ok(_) => Ok(()),
+            Err(ureq::Error::Status(code @ (401 | 403), _)) => Err(anyhow!(
+                "anthropic: {url} rejected the key in {} (status {code})",
+                self.api_key_env
+            )),
+            Err(ureq::Error::Status(..)) => Ok(()),
+            Err(err) => Err(anyhow!("anthropic: {url} is unreachable: {err}")),
+        }
+    }
+
+    fn model(&self) -> Result<&str> {
+        self.model.as_deref().ok_or_else(|| {
+            anyhow!("anthropic.model (or model) must be set when provider = \"anthropic\"")
+        })
+    }
+
+    fn api_key(&self) -> Result<String> {
+        std::env::var(&self.api_key_env)
+            .ok()
+            .filter(|k| !k.trim().is_empty())
+            .ok_or_else(|| anyhow!("anthropic: {} is not set", self.api_key_env))
+    }
+
+    pub fn run_json_judge(&self, prompt: &str) -> Result<String> {
+        self.run_json_with_schema(prompt, "aigit_score", &score_schema_json())
+    }
+
+    pub fn run_json_generate_exam(&self, prompt: &str) -> Result<String> {
+        self.run_json_with_schema(prompt, "aigit_exam", &exam_schema_json())
+    }
+
+    fn run_json_with_schema(
+        &self,
+        prompt: &str,
+        tool_name: &str,
+        schema: &serde_json::Value,
+    ) -> Result<String> {
+        let model = self.model()?;
+        let key = self.api_key()?;
+
+        let mut schema = schema.clone();
+        if let Some(obj) = schema.as_object_mut() {
+            obj.remove("$schema");
+            obj.remove("title");
+        }
+        let body = serde_json::json!({
+            "model": model,
+            "max_tokens": self.max_tokens,
+            "temperature": 0,
+            "tools": [{
+                "name": tool_name,
+                "description": "Submit the result as structured JSON.",
+                "input_schema": schema,
+            }],
+            "tool_choice": { "type": "tool", "name": tool_name },
+            "messages": [{ "role": "user", "content": prompt }],
+        });
+
+        let agent = ureq::AgentBuilder::new().timeout(self.timeout).build();
+        let resp = match agent
+            .post(&self.endpoint)
+            .set("x-api-key", key.trim())
+            .set("anthropic-version", API_VERSION)
+            .send_json(body)
+        {
+            Ok(resp) => resp,
+            Err(ureq::Error::Status(code, resp)) => {
+                let text = resp.into_string().unwrap_or_default();
+                return Err(anyhow!(
+                    "anthropic request to {} failed (status {code}):\n{}",
+                    self.endpoint,
+                    truncate_for_error(&text)
+                ));
+            }
+            Err(err) => {
+                return Err(anyhow!("anthropic request to {} failed: {err}", self.endpoint))
+            }
+        };
+        let value: serde_json::Value = resp
+            .into_json()
+            .with_context(|| format!("anthropic: invalid JSON response from {}", self.endpoint))?;
+        let blocks = value["content"].as_array().cloned().unwrap_or_default();
+        blocks
+            .iter()
+            .find(|b| b["type"] == "tool_use" && b["name"] == tool_name)
+            .map(|b| b["input"].to_string())
+            .ok_or_else(|| {
+                anyhow!(
+                    "anthropic: response has no {tool_name} tool call:\n{}",
+                    truncate_for_error(&value.to_string())
+                )
+            })
+    }
+}
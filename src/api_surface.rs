@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+
+use crate::examiner::{Exam, ExamContext, ExamQuestion, QuestionKind};
+use crate::transcript::Answers;
+
+/// Id and category of the question asked when a diff changes a crate's public API. An exam that
+/// has it cannot pass with it unanswered.
+pub const QUESTION_ID: &str = "api_surface";
+pub const CATEGORY: &str = "api_surface";
+
+/// Rust files that are not part of a library's API.
+const NOT_LIBRARY: &[&str] = &["tests/", "benches/", "examples/", "src/bin/"];
+
+/// A public item added, removed or given a new signature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiChange {
+    pub name: String,
+    /// "function", "struct", "enum", "trait", "module", "type", "constant" or "macro".
+    pub kind: String,
+    pub path: String,
+    /// "added", "removed" or "changed".
+    pub change: String,
+    /// The item's declaration line before and after the change.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+impl std::fmt::Display for ApiChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} in {}",
+            self.change, self.kind, self.name, self.path
+        )
+    }
+}
+
+fn is_library(path: &str) -> bool {
+    path.ends_with(".rs")
+        && path != "build.rs"
+        && !path.ends_with("src/main.rs")
+        && !NOT_LIBRARY
+            .iter()
+            .any(|dir| path.starts_with(dir) || path.contains(&format!("/{dir}")))
+}
+
+/// A declaration line without its body or trailing punctuation, whitespace collapsed.
+fn signature(line: &str) -> String {
+    let line = line.split("//").next().unwrap_or(line);
+    let line = line.trim().trim_end_matches(['{', ';']).trim_end();
+    line.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Public items of library `.rs` files whose declaration lines the diff adds or removes. An
+/// item both removed and added with a different declaration is "changed"; one whose
+/// declaration is unchanged (moved within the file) is left out.
+pub fn detect(diff: &str) -> Vec<ApiChange> {
+    let mut out: Vec<ApiChange> = Vec::new();
+    for hunk in crate::question_links::hunks(diff) {
+        if !is_library(hunk.path) {
+            continue;
+        }
+        for line in hunk.text.iter().skip(1) {
+            let (removed, text) = match line.split_at_checked(1) {
+                Some(("-", text)) => (true, text),
+                Some(("+", text)) => (false, text),
+                _ => continue,
+            };
+            let Some((name, kind)) = crate::symbols::public_rust_definition(text) else {
+                continue;
+            };
+            let decl = signature(text);
+            let existing = out
+                .iter_mut()
+                .find(|c| c.path == hunk.path && c.name == name && c.kind == kind);
+            match existing {
+                Some(c) => {
+                    if removed {
+                        c.before.get_or_insert(decl);
+                    } else {
+                        c.after.get_or_insert(decl);
+                    }
+                    if c.before.is_some() && c.after.is_some() {
+                        c.change = "changed".to_string();
+                    }
+                }
+                None => out.push(ApiChange {
+                    name,
+                    kind: kind.to_string(),
+                    path: hunk.path.to_string(),
+                    change: if removed { "removed" } else { "added" }.to_string(),
+                    before: removed.then(|| decl.clone()),
+                    after: (!removed).then_some(decl),
+                }),
+            }
+        }
+    }
+    out.retain(|c| c.change != "changed" || c.before != c.after);
+    out
+}
+
+fn enabled(ctx: &ExamContext) -> bool {
+    ctx.policy.api_surface.enabled.unwrap_or(true)
+}
+
+/// "removed function parse in src/lib.rs, changed struct Config in src/config.rs".
+fn describe(changes: &[ApiChange]) -> String {
+    changes
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The line the generate prompt adds when the diff changes the public API.
+pub fn prompt_requirement(ctx: &ExamContext) -> Option<String> {
+    if !enabled(ctx) || ctx.api_changes.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "- The diff changes the public API ({}). Ask one question with id `{QUESTION_ID}` and category `{CATEGORY}` on its semver impact (major, minor or patch) and which downstream users break and how they migrate.\n",
+        describe(&ctx.api_changes)
+    ))
+}
+
+/// Adds the API question when the diff changes the public API and the exam has none.
+pub fn ensure_question(ctx: &ExamContext, exam: &mut Exam) {
+    if !enabled(ctx)
+        || ctx.api_changes.is_empty()
+        || exam.questions.iter().any(|q| q.category == CATEGORY)
+    {
+        return;
+    }
+    let what = describe(&ctx.api_changes);
+    let prompt = crate::i18n::api_surface_question(ctx.policy.language.as_deref(), &what)
+        .unwrap_or_else(|| {
+            format!(
+                "This change alters the public API ({what}). Is it a major, minor or patch change under semver, which downstream users does it break, and how do they migrate?"
+            )
+        });
+    let mut paths: Vec<String> = Vec::new();
+    for c in &ctx.api_changes {
+        if !paths.contains(&c.path) {
+            paths.push(c.path.clone());
+        }
+    }
+    exam.questions.push(ExamQuestion {
+        id: QUESTION_ID.to_string(),
+        category: CATEGORY.to_string(),
+        prompt,
+        choices: None,
+        related_paths: paths,
+        related_hunks: Vec::new(),
+        correct_choice: None,
+        kind: Some(QuestionKind::Open),
+    });
+}
+
+/// Whether the exam asks about the API change and the answer is missing.
+pub fn unanswered(exam: &Exam, answers: &Answers) -> bool {
+    exam.questions
+        .iter()
+        .filter(|q| q.category == CATEGORY)
+        .any(|q| !answers.is_answered(&q.id))
+}
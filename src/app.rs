@@ -1,7 +1,10 @@
 use anyhow::Result;
 use clap::Parser;
 
-use crate::cli::{Cli, Commands, ConfigCmd, DashboardCmd, PolicyCmd};
+use crate::cli::{
+    CacheCmd, Cli, Commands, ConfigCmd, DashboardCmd, NotesCmd, PacksCmd, PolicyCmd, ReportCmd,
+    TranscriptCmd,
+};
 use crate::git::{Git, GitRepo};
 
 pub(crate) fn run() -> u8 {
@@ -25,18 +28,57 @@ fn try_run() -> Result<u8> {
         }
     };
     let git = Git::new(repo);
+    if !matches!(cli.command, Commands::HookRun(_)) {
+        crate::bypass::check(&git);
+    }
 
     match cli.command {
         Commands::Exam(args) => crate::commands::exam::cmd_exam(&git, args, cli.verbose),
         Commands::Commit(args) => crate::commands::commit::cmd_commit(&git, args, cli.verbose),
         Commands::Verify(args) => crate::commands::verify::cmd_verify(&git, args, cli.verbose),
         Commands::InstallHook(args) => crate::commands::install_hook::cmd_install_hook(&git, args),
+        Commands::HookRun(args) => crate::commands::hook_run::cmd_hook_run(&git, args),
         Commands::Dashboard(args) => match args.command {
             DashboardCmd::Export(args) => crate::commands::dashboard::cmd_dashboard_export(&git, args),
             DashboardCmd::Serve(args) => crate::commands::dashboard::cmd_dashboard_serve(&git, args),
         },
+        Commands::Stats(args) => crate::commands::stats::cmd_stats(&git, args),
+        Commands::OrgExport(args) => crate::commands::org_export::cmd_org_export(&git, args),
+        Commands::Grade(args) => crate::commands::grade::cmd_grade(&git, args, cli.verbose),
+        Commands::Appeal(args) => crate::commands::appeal::cmd_appeal(&git, args, cli.verbose),
+        Commands::Countersign(args) => crate::commands::countersign::cmd_countersign(&git, args),
+        Commands::Calibrate(args) => {
+            crate::commands::calibrate::cmd_calibrate(&git, args, cli.verbose)
+        }
+        Commands::Show(args) => crate::commands::show::cmd_show(&git, args),
+        Commands::Bundle(args) => crate::commands::bundle::cmd_bundle(&git, args),
+        Commands::Transcript { command } => match command {
+            TranscriptCmd::Find(args) => crate::commands::transcript::cmd_transcript_find(&git, args),
+            TranscriptCmd::Migrate(args) => {
+                crate::commands::transcript::cmd_transcript_migrate(&git, args)
+            }
+        },
+        Commands::Notes { command } => match command {
+            NotesCmd::Push(args) => crate::commands::notes::cmd_notes_push(&git, args),
+            NotesCmd::Fetch(args) => crate::commands::notes::cmd_notes_fetch(&git, args),
+            NotesCmd::Status(args) => crate::commands::notes::cmd_notes_status(&git, args),
+        },
+        Commands::Cache { command } => match command {
+            CacheCmd::Clear => crate::commands::cache::cmd_cache_clear(&git),
+        },
+        Commands::Report { command } => match command {
+            ReportCmd::Mr(args) => crate::commands::report::cmd_report_mr(&git, args),
+            ReportCmd::Gerrit(args) => crate::commands::report::cmd_report_gerrit(&git, args),
+        },
+        Commands::Bench(args) => crate::commands::bench::cmd_bench(&git, args, cli.verbose),
+        Commands::Packs { command } => match command {
+            PacksCmd::Add(args) => crate::commands::packs::cmd_packs_add(&git, args),
+            PacksCmd::List => crate::commands::packs::cmd_packs_list(&git),
+            PacksCmd::Remove(args) => crate::commands::packs::cmd_packs_remove(&git, args),
+        },
         Commands::Policy { command } => match command {
             PolicyCmd::Validate => crate::commands::policy::cmd_policy_validate(&git, cli.verbose),
+            PolicyCmd::Pull => crate::commands::policy::cmd_policy_pull(&git),
         },
         Commands::Config { command } => match command {
             ConfigCmd::Set(args) => crate::commands::config::cmd_config_set(&git, args),
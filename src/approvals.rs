@@ -0,0 +1,80 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ApproverGroup, Policy};
+use crate::examiner::Exam;
+use crate::git::GitRepo;
+use crate::signing::TranscriptSignature;
+use crate::transcript::Transcript;
+
+/// An approver's sign-off on someone else's exam, added by `aigit countersign`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Countersignature {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub email: String,
+    pub timestamp: DateTime<Utc>,
+    /// `approvals.groups` the approver signed for.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
+    /// The approver's signature over the transcript's `content_hash`; a countersignature
+    /// counts only when `signing.allowed_signers` vouches for it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<TranscriptSignature>,
+}
+
+/// The approver groups whose countersignature a change needs.
+pub fn required<'a>(
+    policy: &'a Policy,
+    exam: &Exam,
+    changed_files: &[String],
+) -> Vec<&'a ApproverGroup> {
+    let categories: Vec<&str> = exam.questions.iter().map(|q| q.category.as_str()).collect();
+    policy
+        .approvals
+        .groups
+        .iter()
+        .filter(|g| g.applies_to(&categories, changed_files))
+        .collect()
+}
+
+/// Required groups without a countersignature from one of their approvers, as failure reasons.
+/// The commit's author cannot countersign their own change, and a countersignature whose
+/// signature does not verify (see `signing::check_countersignature`) does not count.
+pub fn missing(
+    repo: &GitRepo,
+    policy: &Policy,
+    transcript: &Transcript,
+    author_email: &str,
+    changed_files: &[String],
+) -> Result<Vec<String>> {
+    let mut out = Vec::new();
+    let mut rejected = Vec::new();
+    for group in required(policy, &transcript.exam, changed_files) {
+        let mut signed = false;
+        for c in &transcript.countersignatures {
+            if c.email.eq_ignore_ascii_case(author_email) || !group.allows(repo, &c.email)? {
+                continue;
+            }
+            match crate::signing::check_countersignature(repo, policy, transcript, c) {
+                Ok(()) => {
+                    signed = true;
+                    break;
+                }
+                Err(err) => rejected.push(format!("{err:#}")),
+            }
+        }
+        if !signed {
+            out.push(format!(
+                "needs a countersignature from the {} approvers (`aigit countersign`)",
+                group.name
+            ));
+        }
+    }
+    if !out.is_empty() {
+        rejected.dedup();
+        out.extend(rejected);
+    }
+    Ok(out)
+}
@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::transcript::{Decision, Transcript};
+
+/// in-toto Statement layer the attestation is written in.
+pub const STATEMENT_TYPE: &str = "https://in-toto.io/Statement/v1";
+
+/// Predicate type of an aigit proof-of-understanding attestation.
+pub const PREDICATE_TYPE: &str = "https://github.com/omarmahamid/aigit/aigit-pou/v0.1";
+
+/// An in-toto statement that `subject` (a commit) passed or failed its exam, for attestation
+/// stores and policy engines that already take provenance attestations. Unsigned: sign it with
+/// the tool that files it (e.g. `cosign attest-blob`), as for any other statement.
+#[derive(Serialize)]
+pub struct Statement {
+    #[serde(rename = "_type")]
+    pub statement_type: String,
+    pub subject: Vec<Subject>,
+    #[serde(rename = "predicateType")]
+    pub predicate_type: String,
+    pub predicate: Predicate,
+}
+
+#[derive(Serialize)]
+pub struct Subject {
+    pub name: String,
+    /// `gitCommit`: the commit's hash, per the in-toto digest set.
+    pub digest: BTreeMap<String, String>,
+}
+
+/// The `aigit-pou` predicate: the decision and what it rests on, without the answers; the full
+/// transcript is pinned by `transcript_sha256`.
+#[derive(Serialize)]
+pub struct Predicate {
+    pub decision: Decision,
+    pub total_score: f64,
+    pub min_total_score: f64,
+    pub required_categories: Vec<String>,
+    pub exam_taken_at: DateTime<Utc>,
+    pub repo_id: String,
+    pub repo_fingerprint: String,
+    /// `git patch-id` of the examined diff.
+    pub patch_id: String,
+    pub examiner: Examiner,
+    pub questions: Vec<QuestionResult>,
+    pub hallucination_flags: usize,
+    pub integrity_flags: usize,
+    /// Set when an appeal arbiter re-graded the answers; whether it overturned the decision.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appeal_overturned: Option<bool>,
+    /// The committer who signed the transcript (see `[signing]`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signed_by: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub countersigned_by: Vec<String>,
+    /// Commit the transcript was reused from (`aigit transcript find --attach`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reused_from: Option<String>,
+    /// Commit the transcript's commit amended (`aigit commit --amend`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amended_from: Option<String>,
+    /// `Transcript::content_hash`, as trailers and the chain pin it.
+    pub transcript_sha256: String,
+}
+
+#[derive(Serialize)]
+pub struct Examiner {
+    pub provider: String,
+    pub model: String,
+    pub prompt_version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grader: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct QuestionResult {
+    pub id: String,
+    pub category: String,
+    pub score: f64,
+}
+
+/// The statement for the transcript of `commit`.
+pub fn statement(t: &Transcript, commit: &str) -> Result<Statement> {
+    Ok(Statement {
+        statement_type: STATEMENT_TYPE.to_string(),
+        subject: vec![Subject {
+            name: t.repo_id.clone(),
+            digest: BTreeMap::from([("gitCommit".to_string(), commit.to_string())]),
+        }],
+        predicate_type: PREDICATE_TYPE.to_string(),
+        predicate: Predicate {
+            decision: t.decision,
+            total_score: t.score.total_score,
+            min_total_score: t.thresholds.min_total_score,
+            required_categories: t.thresholds.required_categories.clone(),
+            exam_taken_at: t.timestamp,
+            repo_id: t.repo_id.clone(),
+            repo_fingerprint: t.repo_fingerprint.clone(),
+            patch_id: t.diff_fingerprint.patch_id.clone(),
+            examiner: Examiner {
+                provider: t.provider.provider.clone(),
+                model: t.provider.model.clone(),
+                prompt_version: t.provider.prompt_version.clone(),
+                grader: t.provider.grader.clone(),
+            },
+            questions: t
+                .score
+                .per_question
+                .iter()
+                .map(|q| QuestionResult {
+                    id: q.id.clone(),
+                    category: q.category.clone(),
+                    score: q.score,
+                })
+                .collect(),
+            hallucination_flags: t.score.hallucination_flags.len(),
+            integrity_flags: t.score.integrity_flags.len(),
+            appeal_overturned: t.appeal.as_ref().map(|a| a.overturned),
+            signed_by: t.signature.as_ref().map(|s| s.signer.clone()),
+            countersigned_by: t
+                .countersignatures
+                .iter()
+                .map(|c| c.email.clone())
+                .collect(),
+            reused_from: t.reused_from.as_ref().map(|r| r.commit.clone()),
+            amended_from: t.amended.as_ref().map(|a| a.from.clone()),
+            transcript_sha256: t.content_hash()?,
+        },
+    })
+}
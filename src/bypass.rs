@@ -0,0 +1,132 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, SubsecRound, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Policy;
+use crate::git::{CommitMeta, Git, GitRepo};
+use crate::transcript::TranscriptStore;
+
+/// Recent commits checked for bypasses when `hooks.bypass_lookback` is unset.
+const DEFAULT_LOOKBACK: usize = 50;
+
+/// Commits of the local user that reached HEAD without a transcript, i.e. without passing
+/// through `aigit commit` (`git commit --no-verify`, or `AIGIT_ALLOW_COMMIT` set by hand).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BypassLog {
+    /// When tracking started, and HEAD at that time; older commits predate it and are never
+    /// reported.
+    since: DateTime<Utc>,
+    #[serde(default)]
+    head: Option<String>,
+    #[serde(default)]
+    commits: Vec<Bypass>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Bypass {
+    #[serde(flatten)]
+    commit: CommitMeta,
+    detected_at: DateTime<Utc>,
+}
+
+fn log_path(repo: &GitRepo) -> PathBuf {
+    repo.git_dir.join("aigit").join("bypasses.json")
+}
+
+/// Looks for the user's recent commits without a transcript, records new ones in
+/// `.git/aigit/bypasses.json` and, unless `hooks.track_bypasses` says otherwise, warns about every
+/// one still lacking a transcript. Runs before each command; never fails it.
+pub fn check(git: &Git) {
+    let Ok(policy) = Policy::load_from_repo(&git.repo) else {
+        return;
+    };
+    let mode = policy.hooks.track_bypasses.as_deref().unwrap_or("nag");
+    if mode == "off" {
+        return;
+    }
+    let missing = match find(git, &policy) {
+        Ok(missing) => missing,
+        Err(err) => {
+            eprintln!("aigit: bypass tracking: {err:#}");
+            return;
+        }
+    };
+    if mode != "nag" || missing.is_empty() {
+        return;
+    }
+    eprintln!(
+        "aigit: {} of your commits skipped the exam (committed with --no-verify or AIGIT_ALLOW_COMMIT?):",
+        missing.len()
+    );
+    for c in &missing {
+        eprintln!("aigit:   {} {}", &c.sha[..c.sha.len().min(12)], c.subject);
+    }
+    eprintln!(
+        "aigit: they are recorded in {}; `aigit verify` rejects them until they carry a transcript",
+        log_path(&git.repo).display()
+    );
+}
+
+/// The user's commits since tracking started that have no transcript, newest first. The first
+/// call only starts tracking.
+fn find(git: &Git, policy: &Policy) -> Result<Vec<CommitMeta>> {
+    let path = log_path(&git.repo);
+    let mut log: BypassLog = match std::fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str(&raw)
+            .with_context(|| format!("failed to parse {}", path.display()))?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            // Commit dates have whole seconds: one made in this same second counts as after
+            // `since`, unless `head` already has it.
+            let log = BypassLog {
+                since: Utc::now().trunc_subsecs(0),
+                head: git.rev_parse_head().ok(),
+                commits: Vec::new(),
+            };
+            write(&path, &log)?;
+            return Ok(Vec::new());
+        }
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read {}", path.display()));
+        }
+    };
+    let Some(email) = git.user_email() else {
+        return Ok(Vec::new());
+    };
+
+    let covered: BTreeSet<String> = TranscriptStore::from_policy(policy)?
+        .list(&git.repo)?
+        .into_iter()
+        .collect();
+    let lookback = policy.hooks.bypass_lookback.unwrap_or(DEFAULT_LOOKBACK);
+    let missing: Vec<CommitMeta> = git
+        .commits_since(&log.since, log.head.as_deref(), lookback)?
+        .into_iter()
+        .filter(|c| c.author_email.eq_ignore_ascii_case(&email) && !covered.contains(&c.sha))
+        .collect();
+
+    let before = log.commits.len();
+    for c in &missing {
+        if !log.commits.iter().any(|b| b.commit.sha == c.sha) {
+            log.commits.push(Bypass {
+                commit: c.clone(),
+                detected_at: Utc::now(),
+            });
+        }
+    }
+    if log.commits.len() != before {
+        write(&path, &log)?;
+    }
+    Ok(missing)
+}
+
+fn write(path: &Path, log: &BypassLog) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    std::fs::write(path, serde_json::to_vec_pretty(log)?)
+        .with_context(|| format!("failed to write {}", path.display()))
+}
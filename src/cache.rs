@@ -0,0 +1,113 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::examiner::{Exam, ExamContext, GENERATE_PROMPT_VERSION};
+use crate::git::GitRepo;
+
+/// A generated exam saved under `.git/aigit/cache/`, so re-running `aigit exam` on the same diff
+/// does not ask the provider again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedExam {
+    pub created_at: DateTime<Utc>,
+    pub diff_patch_id: String,
+    pub provider: String,
+    pub model: String,
+    pub prompt_version: String,
+    pub exam: Exam,
+}
+
+/// Everything that shapes a generated exam. The policy, the profile focus and the work item
+/// description are included so a changed `.aigit.toml`, profile or description never serves a
+/// stale exam.
+#[derive(Serialize)]
+struct CacheKey<'a> {
+    diff_patch_id: &'a str,
+    provider: &'a str,
+    model: &'a str,
+    prompt_version: &'a str,
+    focus_categories: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    work_item: Option<&'a str>,
+    policy: &'a crate::config::Policy,
+}
+
+pub fn cache_dir(repo: &GitRepo) -> PathBuf {
+    repo.git_dir.join("aigit").join("cache")
+}
+
+fn model_of(ctx: &ExamContext) -> String {
+    ctx.policy.model.clone().unwrap_or_else(|| "static".to_string())
+}
+
+fn provider_of(ctx: &ExamContext) -> String {
+    ctx.policy
+        .provider
+        .clone()
+        .unwrap_or_else(|| "local".to_string())
+}
+
+fn entry_path(repo: &GitRepo, ctx: &ExamContext) -> Result<PathBuf> {
+    // Progress display settings (`--quiet`) do not change what the provider generates.
+    let mut policy = ctx.policy.clone();
+    policy.codex_cli.progress = None;
+    policy.codex_cli.progress_interval_ms = None;
+    let key = CacheKey {
+        diff_patch_id: &ctx.diff_patch_id,
+        provider: &provider_of(ctx),
+        model: &model_of(ctx),
+        prompt_version: GENERATE_PROMPT_VERSION,
+        focus_categories: &ctx.focus_categories,
+        work_item: ctx.work_item.as_deref(),
+        policy: &policy,
+    };
+    let digest = Sha256::digest(serde_json::to_vec(&key)?);
+    Ok(cache_dir(repo).join(format!("{}.json", hex::encode(digest))))
+}
+
+/// The cached exam for this context, if any. Unreadable entries count as misses.
+pub fn load(repo: &GitRepo, ctx: &ExamContext) -> Option<Exam> {
+    let raw = std::fs::read_to_string(entry_path(repo, ctx).ok()?).ok()?;
+    serde_json::from_str::<CachedExam>(&raw).ok().map(|c| c.exam)
+}
+
+pub fn store(repo: &GitRepo, ctx: &ExamContext, exam: &Exam) -> Result<()> {
+    let path = entry_path(repo, ctx)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    let entry = CachedExam {
+        created_at: Utc::now(),
+        diff_patch_id: ctx.diff_patch_id.clone(),
+        provider: provider_of(ctx),
+        model: model_of(ctx),
+        prompt_version: GENERATE_PROMPT_VERSION.to_string(),
+        exam: exam.clone(),
+    };
+    std::fs::write(&path, serde_json::to_vec_pretty(&entry)?)
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Deletes every cached exam; returns how many were removed.
+pub fn clear(repo: &GitRepo) -> Result<usize> {
+    let dir = cache_dir(repo);
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(err) => return Err(err).with_context(|| format!("failed to read {}", dir.display())),
+    };
+    let mut removed = 0;
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|e| e == "json") {
+            std::fs::remove_file(&path)
+                .with_context(|| format!("failed to remove {}", path.display()))?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
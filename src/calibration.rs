@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Policy;
+use crate::examiner::Exam;
+use crate::git::GitRepo;
+use crate::transcript::Answers;
+
+/// Where the calibration suite lives unless `calibration.suite` says otherwise.
+const DEFAULT_SUITE: &str = ".aigit/calibration";
+
+/// A reference exam with answers and the score they deserve, graded by `aigit calibrate` to
+/// learn how far a grader is from that score.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationCase {
+    /// Shown in the report (default: the file name).
+    #[serde(default)]
+    pub name: Option<String>,
+    /// The change the exam is about, as a unified diff.
+    pub diff: String,
+    pub exam: Exam,
+    pub answers: Answers,
+    /// The total score (0..1) the answers should get.
+    pub expected_score: f64,
+}
+
+/// What `aigit calibrate` learned about one grader.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraderCalibration {
+    /// Added to the grader's total score before it is compared with thresholds.
+    pub offset: f64,
+    /// Suite cases the offset was learned from.
+    pub cases: usize,
+    pub calibrated_at: DateTime<Utc>,
+}
+
+/// `<suite>/offsets.json`: learned offsets keyed by grader identity (see
+/// `Policy::grader_identity`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Offsets {
+    pub graders: BTreeMap<String, GraderCalibration>,
+}
+
+pub fn suite_dir(repo: &GitRepo, policy: &Policy) -> PathBuf {
+    repo.workdir
+        .join(policy.calibration.suite.as_deref().unwrap_or(DEFAULT_SUITE))
+}
+
+fn offsets_path(repo: &GitRepo, policy: &Policy) -> PathBuf {
+    suite_dir(repo, policy).join("offsets.json")
+}
+
+impl Offsets {
+    pub fn load(repo: &GitRepo, policy: &Policy) -> Result<Self> {
+        let path = offsets_path(repo, policy);
+        let raw = match std::fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to read {}", path.display()))
+            }
+        };
+        serde_json::from_str(&raw).with_context(|| format!("failed to parse {}", path.display()))
+    }
+
+    pub fn save(&self, repo: &GitRepo, policy: &Policy) -> Result<PathBuf> {
+        let path = offsets_path(repo, policy);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        let mut raw = serde_json::to_vec_pretty(self)?;
+        raw.push(b'\n');
+        std::fs::write(&path, raw)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+/// The suite's cases: every `*.json` under `<suite>/cases/`, by file name.
+pub fn load_cases(repo: &GitRepo, policy: &Policy) -> Result<Vec<CalibrationCase>> {
+    let dir = suite_dir(repo, policy).join("cases");
+    let entries = std::fs::read_dir(&dir).map_err(|err| {
+        anyhow!(
+            "no calibration suite at {} ({err}); add reference cases there first",
+            dir.display()
+        )
+    })?;
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("json"))
+        .collect();
+    paths.sort();
+    let mut cases = Vec::new();
+    for path in paths {
+        cases.push(load_case(&path)?);
+    }
+    if cases.is_empty() {
+        return Err(anyhow!(
+            "the calibration suite at {} has no cases",
+            dir.display()
+        ));
+    }
+    Ok(cases)
+}
+
+fn load_case(path: &Path) -> Result<CalibrationCase> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let mut case: CalibrationCase = serde_json::from_str(&raw)
+        .map_err(|err| anyhow!("{}: not a calibration case: {err}", path.display()))?;
+    if !(0.0..=1.0).contains(&case.expected_score) {
+        return Err(anyhow!(
+            "{}: expected_score must be between 0 and 1",
+            path.display()
+        ));
+    }
+    if case.name.is_none() {
+        case.name = path.file_stem().map(|s| s.to_string_lossy().into_owned());
+    }
+    Ok(case)
+}
+
+/// Files a unified diff changes, from its `+++ b/` (or, for deletions, `--- a/`) lines.
+pub fn changed_files(diff: &str) -> Vec<String> {
+    let mut files: Vec<String> = Vec::new();
+    let mut removed: Option<&str> = None;
+    for line in diff.lines() {
+        if let Some(path) = line.strip_prefix("--- a/") {
+            removed = Some(path);
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            let path = match path.strip_prefix("b/") {
+                Some(path) => path,
+                None => match removed.take() {
+                    Some(path) => path,
+                    None => continue,
+                },
+            };
+            if !files.iter().any(|f| f == path) {
+                files.push(path.to_string());
+            }
+        }
+    }
+    files
+}
+
+/// Loads the learned offsets into the policy, unless `calibration.normalize` is off.
+pub fn apply(repo: &GitRepo, policy: &mut Policy) -> Result<()> {
+    if policy.calibration.normalize.unwrap_or(true) {
+        policy.calibration.learned = Offsets::load(repo, policy)?.graders;
+    }
+    Ok(())
+}
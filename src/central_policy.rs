@@ -0,0 +1,122 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config::PolicySource;
+use crate::git::{Git, GitRepo};
+
+/// `ssh-keygen -Y` namespace policy bundles must be signed with.
+pub const SIGNATURE_NAMESPACE: &str = "aigit-policy";
+
+/// Where a pulled policy bundle is installed. It is layered under the repo's `.aigit.toml`.
+pub fn installed_path(repo: &GitRepo) -> PathBuf {
+    repo.git_dir.join("aigit").join("central-policy.toml")
+}
+
+fn metadata_path(repo: &GitRepo) -> PathBuf {
+    repo.git_dir.join("aigit").join("central-policy.json")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledPolicy {
+    pub source: String,
+    pub sha256: String,
+    pub signed_by: Option<String>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Fetches the bundle named by `source`, verifies its signature, and installs it.
+pub fn pull(git: &Git, source: &PolicySource) -> Result<InstalledPolicy> {
+    let (label, bundle, signature) = fetch(git, source)?;
+
+    // Reject bundles that would not load before replacing a working one.
+    toml::from_str::<toml::Table>(&bundle)
+        .with_context(|| format!("policy bundle from {label} is not valid TOML"))?;
+
+    let signed_by = if source.require_signature.unwrap_or(true) {
+        let allowed = source.allowed_signers.as_deref().ok_or_else(|| {
+            anyhow!("policy_source.allowed_signers is required to verify the bundle (or set policy_source.require_signature = false)")
+        })?;
+        let identity = source
+            .signer
+            .as_deref()
+            .ok_or_else(|| anyhow!("policy_source.signer is required to verify the bundle"))?;
+        let signature = signature
+            .ok_or_else(|| anyhow!("no signature found for policy bundle from {label}"))?;
+        crate::signing::verify_ssh(
+            &git.repo.workdir.join(allowed),
+            identity,
+            SIGNATURE_NAMESPACE,
+            bundle.as_bytes(),
+            &signature,
+        )
+        .map_err(|err| anyhow!("policy bundle {err:#}"))?;
+        Some(identity.to_string())
+    } else {
+        None
+    };
+
+    let path = installed_path(&git.repo);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    std::fs::write(&path, &bundle).with_context(|| format!("failed to write {}", path.display()))?;
+
+    let installed = InstalledPolicy {
+        source: label,
+        sha256: hex::encode(Sha256::digest(bundle.as_bytes())),
+        signed_by,
+        fetched_at: Utc::now(),
+    };
+    std::fs::write(
+        metadata_path(&git.repo),
+        serde_json::to_vec_pretty(&installed)?,
+    )?;
+    Ok(installed)
+}
+
+/// Returns a human label for the source, the bundle text, and its signature if one exists.
+fn fetch(git: &Git, source: &PolicySource) -> Result<(String, String, Option<String>)> {
+    match (&source.url, &source.git_ref) {
+        (Some(url), None) => {
+            if !crate::remote_store::is_secure_url(url) {
+                return Err(anyhow!(
+                    "policy_source.url {url} must use https (plain http only to localhost)"
+                ));
+            }
+            let bundle = http_get(url)?
+                .ok_or_else(|| anyhow!("policy bundle not found at {url}"))?;
+            let signature = http_get(&format!("{url}.sig"))?;
+            Ok((url.clone(), bundle, signature))
+        }
+        (None, Some(git_ref)) => {
+            let remote = source.remote.as_deref().unwrap_or("origin");
+            let path = source.path.as_deref().unwrap_or("aigit.toml");
+            git.fetch_ref(remote, git_ref)?;
+            let bundle = git.show_file("FETCH_HEAD", path)?;
+            let signature = git.show_file("FETCH_HEAD", &format!("{path}.sig")).ok();
+            Ok((format!("{remote} {git_ref}:{path}"), bundle, signature))
+        }
+        (Some(_), Some(_)) => Err(anyhow!(
+            "policy_source: set either url or git_ref, not both"
+        )),
+        (None, None) => Err(anyhow!(
+            "no policy source configured; set policy_source.url or policy_source.git_ref in .aigit.toml"
+        )),
+    }
+}
+
+fn http_get(url: &str) -> Result<Option<String>> {
+    match ureq::get(url).call() {
+        Ok(resp) => Ok(Some(
+            resp.into_string()
+                .with_context(|| format!("failed to read response from {url}"))?,
+        )),
+        Err(ureq::Error::Status(404, _)) => Ok(None),
+        Err(err) => Err(anyhow!("GET {url} failed: {err}")),
+    }
+}
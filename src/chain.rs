@@ -0,0 +1,182 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::git::GitRepo;
+use crate::transcript::{Transcript, TranscriptStore};
+
+/// Ref holding the chain head: a blob with the newest chained transcript's commit and hash.
+pub const CHAIN_REF: &str = "refs/aigit/chain";
+
+/// A transcript's place in the chain: the transcript stored before it (absent for the first).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainLink {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<ChainEntry>,
+}
+
+/// A chained transcript: the commit it is stored for and its `Transcript::content_hash`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainEntry {
+    pub commit: String,
+    pub hash: String,
+}
+
+/// The chain head, if the chain was started.
+pub fn head(repo: &GitRepo) -> Result<Option<ChainEntry>> {
+    let Some(blob) = git(repo, &["rev-parse", "-q", "--verify", CHAIN_REF], None).ok() else {
+        return Ok(None);
+    };
+    let raw = git(repo, &["cat-file", "blob", blob.trim()], None)?;
+    let mut commit = None;
+    let mut hash = None;
+    for line in raw.lines() {
+        match line.split_once(' ') {
+            Some(("commit", v)) => commit = Some(v.trim().to_string()),
+            Some(("hash", v)) => hash = Some(v.trim().to_string()),
+            _ => {}
+        }
+    }
+    match (commit, hash) {
+        (Some(commit), Some(hash)) => Ok(Some(ChainEntry { commit, hash })),
+        _ => Err(anyhow!("{CHAIN_REF} does not name a commit and hash")),
+    }
+}
+
+/// Points the chain head at `entry`, failing if someone else moved it since `old` was read.
+fn set_head(repo: &GitRepo, entry: &ChainEntry, old: Option<&str>) -> Result<()> {
+    let content = format!("commit {}\nhash {}\n", entry.commit, entry.hash);
+    let blob = git(repo, &["hash-object", "-w", "--stdin"], Some(&content))?;
+    let args = [
+        "update-ref",
+        "-m",
+        "aigit: chain",
+        CHAIN_REF,
+        blob.trim(),
+        old.unwrap_or(""),
+    ];
+    git(repo, &args, None).with_context(|| format!("failed to update {CHAIN_REF}"))?;
+    Ok(())
+}
+
+/// Links a new transcript to the chain head, before it is signed and stored.
+pub fn link(repo: &GitRepo, t: &mut Transcript) -> Result<()> {
+    t.chain = Some(ChainLink {
+        previous: head(repo)?,
+    });
+    Ok(())
+}
+
+/// After `t` was stored for `commit`, moves the chain head to it when it is the head's
+/// transcript again (updated, or moved to a rewritten commit) or the one just linked to it.
+pub fn advance(
+    repo: &GitRepo,
+    store: &TranscriptStore,
+    commit: &str,
+    t: &Transcript,
+) -> Result<()> {
+    let Some(link) = &t.chain else {
+        return Ok(());
+    };
+    let head = head(repo)?;
+    let old = git(repo, &["rev-parse", "-q", "--verify", CHAIN_REF], None).ok();
+    let replaces_head = match &head {
+        None => link.previous.is_none(),
+        Some(h) if link.previous.as_ref() == Some(h) || h.commit == commit => true,
+        Some(h) => store
+            .load(repo, &h.commit)
+            .is_ok_and(|head_t| head_t.chain.as_ref() == Some(link)),
+    };
+    if !replaces_head {
+        return Ok(());
+    }
+    let entry = ChainEntry {
+        commit: commit.to_string(),
+        hash: t.content_hash()?,
+    };
+    set_head(repo, &entry, old.as_deref().map(str::trim))
+}
+
+/// What walking the chain back from its head found.
+pub struct ChainReport {
+    /// Transcripts checked, newest first.
+    pub commits: Vec<String>,
+    /// Where the chain breaks, and why.
+    pub broken: Option<(String, String)>,
+}
+
+/// Walks the chain from its head to its first transcript, checking that each transcript is
+/// still stored and still hashes to what the next one (or the head) recorded.
+pub fn walk(repo: &GitRepo, store: &TranscriptStore) -> Result<Option<ChainReport>> {
+    let Some(mut expected) = head(repo)? else {
+        return Ok(None);
+    };
+    let mut report = ChainReport {
+        commits: Vec::new(),
+        broken: None,
+    };
+    loop {
+        let t = match store.load(repo, &expected.commit) {
+            Ok(t) => t,
+            Err(_) => {
+                report.broken = Some((expected.commit, "transcript deleted".to_string()));
+                break;
+            }
+        };
+        let actual = t.content_hash()?;
+        if actual != expected.hash {
+            report.broken = Some((
+                expected.commit,
+                format!(
+                    "transcript replaced (hash {} where the chain recorded {})",
+                    &actual[..12],
+                    &expected.hash[..expected.hash.len().min(12)]
+                ),
+            ));
+            break;
+        }
+        report.commits.push(expected.commit.clone());
+        let Some(link) = t.chain else {
+            report.broken = Some((expected.commit, "transcript has no chain link".to_string()));
+            break;
+        };
+        match link.previous {
+            Some(previous) if report.commits.contains(&previous.commit) => {
+                report.broken = Some((previous.commit, "chain loops".to_string()));
+                break;
+            }
+            Some(previous) => expected = previous,
+            None => break,
+        }
+    }
+    Ok(Some(report))
+}
+
+fn git(repo: &GitRepo, args: &[&str], stdin: Option<&str>) -> Result<String> {
+    let mut child = Command::new("git")
+        .current_dir(&repo.workdir)
+        .args(args)
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to run git")?;
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(input.as_bytes())?;
+    }
+    let out = child.wait_with_output()?;
+    if !out.status.success() {
+        return Err(anyhow!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&out.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8(out.stdout)?)
+}
@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+
+use crate::examiner::{Exam, ExamContext, ExamQuestion, QuestionKind};
+
+/// Id and category of the question asked when a diff edits CI pipeline files.
+pub const QUESTION_ID: &str = "ci_pipeline";
+pub const CATEGORY: &str = "ci_pipeline";
+
+/// CI pipeline files and the system that runs them, on top of `ci_pipeline.paths`.
+const CI_PATHS: &[(&str, &str)] = &[
+    (".github/workflows/**", "GitHub Actions"),
+    (".github/actions/**", "GitHub Actions"),
+    ("**/.gitlab-ci.yml", "GitLab CI"),
+    ("**/.gitlab-ci/**", "GitLab CI"),
+    ("**/Jenkinsfile", "Jenkins"),
+    ("**/Jenkinsfile.*", "Jenkins"),
+    (".circleci/**", "CircleCI"),
+    ("**/azure-pipelines*.yml", "Azure Pipelines"),
+    (".buildkite/**", "Buildkite"),
+];
+
+/// Changed lines of a pipeline that hand secrets or tokens to its steps.
+const SECRET_NEEDLES: &[&str] = &[
+    "secrets.",
+    "secrets:",
+    "GITHUB_TOKEN",
+    "CI_JOB_TOKEN",
+    "pull_request_target",
+    "id-token:",
+    "credentials(",
+    "withCredentials",
+];
+
+/// A CI pipeline file the diff edits.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CiChange {
+    pub path: String,
+    /// "GitHub Actions", "GitLab CI", "Jenkins", ...; "CI" for a `ci_pipeline.paths` match.
+    pub system: String,
+    /// Whether the changed lines reference secrets, tokens or credentials.
+    pub secrets: bool,
+}
+
+impl std::fmt::Display for CiChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.system, self.path)?;
+        if self.secrets {
+            f.write_str(" (uses secrets)")?;
+        }
+        Ok(())
+    }
+}
+
+/// CI pipeline files among the changed files, and whether their changed lines use secrets.
+pub fn detect(extra_paths: &[String], diff: &str, changed_files: &[String]) -> Vec<CiChange> {
+    let mut systems: Vec<(globset::GlobSet, &str)> = CI_PATHS
+        .iter()
+        .filter_map(|(p, system)| {
+            crate::config::build_globset(&[p.to_string()])
+                .ok()
+                .map(|set| (set, *system))
+        })
+        .collect();
+    if let Ok(set) = crate::config::build_globset(extra_paths) {
+        systems.push((set, "CI"));
+    }
+    let mut out: Vec<CiChange> = Vec::new();
+    for f in changed_files {
+        if let Some((_, system)) = systems.iter().find(|(set, _)| set.is_match(f.as_str())) {
+            out.push(CiChange {
+                path: f.clone(),
+                system: system.to_string(),
+                secrets: false,
+            });
+        }
+    }
+    for hunk in crate::question_links::hunks(diff) {
+        let Some(change) = out.iter_mut().find(|c| c.path == hunk.path) else {
+            continue;
+        };
+        change.secrets |= hunk.text.iter().skip(1).any(|line| {
+            line.strip_prefix(['+', '-'])
+                .is_some_and(|code| SECRET_NEEDLES.iter().any(|n| code.contains(n)))
+        });
+    }
+    out
+}
+
+fn enabled(ctx: &ExamContext) -> bool {
+    ctx.policy.ci_pipeline.enabled.unwrap_or(true)
+}
+
+fn describe(changes: &[CiChange]) -> String {
+    changes
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The line the generate prompt adds when the diff edits CI pipeline files.
+pub fn prompt_requirement(ctx: &ExamContext) -> Option<String> {
+    if !enabled(ctx) || ctx.ci_changes.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "- The diff edits CI pipeline files ({}). Ask one question with id `{QUESTION_ID}` and category `{CATEGORY}` on the pipeline's blast radius (which jobs, branches, releases or deployments it affects), which secrets or tokens its steps can reach and whether untrusted code can read them, and how a broken pipeline would be detected and reverted.\n",
+        describe(&ctx.ci_changes)
+    ))
+}
+
+/// Adds the CI pipeline question when the diff edits pipeline files and the exam has none.
+pub fn ensure_question(ctx: &ExamContext, exam: &mut Exam) {
+    if !enabled(ctx)
+        || ctx.ci_changes.is_empty()
+        || exam.questions.iter().any(|q| q.category == CATEGORY)
+    {
+        return;
+    }
+    let what = describe(&ctx.ci_changes);
+    let prompt = crate::i18n::ci_pipeline_question(ctx.policy.language.as_deref(), &what)
+        .unwrap_or_else(|| {
+            format!(
+                "This change edits the CI pipeline ({what}). Which jobs, branches, releases or deployments does it affect, which secrets or tokens can its steps reach (and can code from a fork or pull request read them), and how would you notice a broken pipeline and revert it?"
+            )
+        });
+    exam.questions.push(ExamQuestion {
+        id: QUESTION_ID.to_string(),
+        category: CATEGORY.to_string(),
+        prompt,
+        choices: None,
+        related_paths: ctx.ci_changes.iter().map(|c| c.path.clone()).collect(),
+        related_hunks: Vec::new(),
+        correct_choice: None,
+        kind: Some(QuestionKind::Open),
+    });
+}
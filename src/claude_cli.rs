@@ -0,0 +1,147 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::codex_cli::{
+    exam_schema_json, program_exists, score_schema_json, split_command_line, truncate_for_error,
+    wait_with_input,
+};
+use crate::config::{ClaudeCliPolicy, Policy};
+
+pub const NPX_CLAUDE_DOWNLOAD: &str = "npx -y @anthropic-ai/claude-code";
+
+/// Tools the examiner may use by default: enough to read the repository, nothing that writes or
+/// runs commands.
+const DEFAULT_ALLOWED_TOOLS: &[&str] = &["Read", "Grep", "Glob"];
+
+#[derive(Debug, Clone)]
+pub struct ClaudeCliRunner {
+    base_command: String,
+    model: Option<String>,
+    permission_mode: String,
+    allowed_tools: Vec<String>,
+    timeout: Duration,
+}
+
+impl ClaudeCliRunner {
+    pub fn from_policy(policy: &Policy) -> Self {
+        let cfg: &ClaudeCliPolicy = &policy.claude_cli;
+        Self {
+            base_command: cfg.command.clone().unwrap_or_else(|| "claude".to_string()),
+            model: cfg
+                .model
+                .clone()
+                .or_else(|| policy.model.clone().filter(|m| m != "static")),
+            permission_mode: cfg
+                .permission_mode
+                .clone()
+                .unwrap_or_else(|| "plan".to_string()),
+            allowed_tools: cfg.allowed_tools.clone().unwrap_or_else(|| {
+                DEFAULT_ALLOWED_TOOLS.iter().map(|t| t.to_string()).collect()
+            }),
+            timeout: Duration::from_secs(cfg.timeout_secs.unwrap_or(120)),
+        }
+    }
+
+    /// Cheap availability check: the base command's program resolves to an executable.
+    pub fn ping(&self) -> Result<()> {
+        let (program, _) = split_command_line(&self.base_command)?;
+        if program_exists(&program) {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "claude CLI not found: {program} (hint: set `claude_cli.command` in .aigit.toml, e.g. \"{}\")",
+                NPX_CLAUDE_DOWNLOAD
+            ))
+        }
+    }
+
+    pub fn run_json_judge(&self, cwd: &Path, prompt: &str) -> Result<String> {
+        self.run_json_with_schema(cwd, prompt, &score_schema_json())
+    }
+
+    pub fn run_json_generate_exam(&self, cwd: &Path, prompt: &str) -> Result<String> {
+        self.run_json_with_schema(cwd, prompt, &exam_schema_json())
+    }
+
+    fn run_json_with_schema(
+        &self,
+        cwd: &Path,
+        prompt: &str,
+        schema: &serde_json::Value,
+    ) -> Result<String> {
+        let (program, mut args) = split_command_line(&self.base_command)?;
+        // Non-interactive print mode; the prompt is read from stdin.
+        if !args.iter().any(|a| a == "-p" || a == "--print") {
+            args.push("--print".to_string());
+        }
+        if let Some(model) = &self.model {
+            args.push("--model".to_string());
+            args.push(model.clone());
+        }
+        args.extend([
+            "--output-format".to_string(),
+            "json".to_string(),
+            "--json-schema".to_string(),
+            serde_json::to_string(schema)?,
+            "--permission-mode".to_string(),
+            self.permission_mode.clone(),
+            "--allowedTools".to_string(),
+            self.allowed_tools.join(","),
+        ]);
+
+        let mut cmd = Command::new(&program);
+        cmd.current_dir(cwd)
+            .args(&args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .env("NO_COLOR", "1");
+
+        let child = cmd.spawn().with_context(|| {
+            format!(
+                "failed to spawn claude CLI: {program} (hint: set `claude_cli.command` in .aigit.toml, e.g. \"{}\")",
+                NPX_CLAUDE_DOWNLOAD
+            )
+        })?;
+
+        let stdout = wait_with_input(child, prompt, self.timeout, "claude CLI")?;
+        extract_result(&stdout)
+    }
+}
+
+/// Models sometimes wrap JSON in a ```json fence even when asked not to.
+fn strip_code_fence(text: &str) -> &str {
+    let trimmed = text.trim();
+    let Some(rest) = trimmed.strip_prefix("```") else {
+        return trimmed;
+    };
+    let body = rest.split_once('\n').map(|(_, b)| b).unwrap_or("");
+    body.trim_end().strip_suffix("```").unwrap_or(body).trim()
+}
+
+/// Pulls the structured answer out of `--output-format json`: `structured_output` when the CLI
+/// validated against `--json-schema`, otherwise the `result` text.
+fn extract_result(stdout: &str) -> Result<String> {
+    let value: serde_json::Value = serde_json::from_str(stdout.trim()).with_context(|| {
+        format!(
+            "claude CLI did not print a JSON result:\n{}",
+            truncate_for_error(stdout)
+        )
+    })?;
+    if value["is_error"].as_bool() == Some(true) {
+        return Err(anyhow!(
+            "claude CLI reported an error: {}",
+            truncate_for_error(value["result"].as_str().unwrap_or_default())
+        ));
+    }
+    if let Some(structured) = value.get("structured_output").filter(|v| !v.is_null()) {
+        return Ok(structured.to_string());
+    }
+    value["result"]
+        .as_str()
+        .map(|s| strip_code_fence(s).to_string())
+        .ok_or_else(|| anyhow!("claude CLI result has no output:\n{}", truncate_for_error(stdout)))
+}
@@ -25,8 +25,54 @@ pub(crate) enum Commands {
     Verify(VerifyArgs),
     /// Install git hook to enforce using `aigit commit`
     InstallHook(InstallHookArgs),
+    /// Run a git hook's checks (what the hooks installed by install-hook call)
+    HookRun(HookRunArgs),
     /// Dashboard utilities (export transcripts for the web UI)
     Dashboard(DashboardArgs),
+    /// Aggregate exam statistics from stored transcripts
+    Stats(StatsArgs),
+    /// Aggregate transcripts from many repositories into one dataset
+    OrgExport(OrgExportArgs),
+    /// Summaries of stored transcripts for code review
+    Report {
+        #[command(subcommand)]
+        command: ReportCmd,
+    },
+    /// Time each exam pipeline stage over a change
+    Bench(BenchArgs),
+    /// Grade a saved exam again (after a provider timeout or crash)
+    Grade(GradeArgs),
+    /// Have the appeal arbiter re-grade a commit's (or saved transcript's) answers
+    Appeal(AppealArgs),
+    /// Sign off on a commit's exam as one of the approvers `approvals.groups` requires
+    Countersign(CountersignArgs),
+    /// Grade the calibration suite and learn the configured grader's score offset
+    Calibrate(CalibrateArgs),
+    /// Show a commit's transcript as JSON or as a self-contained HTML report
+    Show(ShowArgs),
+    /// Export a signed archive of a release range's transcripts, policy and verification results
+    Bundle(BundleArgs),
+    /// Transcript utilities
+    #[command(alias = "transcripts")]
+    Transcript {
+        #[command(subcommand)]
+        command: TranscriptCmd,
+    },
+    /// Share transcripts (refs/notes/aigit) with a remote
+    Notes {
+        #[command(subcommand)]
+        command: NotesCmd,
+    },
+    /// Cache of generated exams (under .git/aigit/cache/)
+    Cache {
+        #[command(subcommand)]
+        command: CacheCmd,
+    },
+    /// Question packs under .aigit/packs/
+    Packs {
+        #[command(subcommand)]
+        command: PacksCmd,
+    },
     /// Policy utilities
     Policy {
         #[command(subcommand)]
@@ -42,6 +88,145 @@ pub(crate) enum Commands {
 #[derive(Subcommand, Debug)]
 pub(crate) enum PolicyCmd {
     Validate,
+    /// Fetch, verify, and install the org policy bundle from `policy_source`
+    Pull,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct ShowArgs {
+    /// Commit whose transcript to show
+    pub(crate) commit: String,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = ShowFormat::Json)]
+    pub(crate) format: ShowFormat,
+
+    /// Output path (default: stdout)
+    #[arg(long)]
+    pub(crate) out: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum ShowFormat {
+    Json,
+    /// One HTML file with inline styles, e.g. to attach to a change-management ticket
+    Html,
+    /// An unsigned in-toto statement (predicate type aigit-pou) whose subject is the commit,
+    /// for attestation stores and policy engines
+    #[value(name = "in-toto")]
+    InToto,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct BundleArgs {
+    /// Commits to bundle, as a revision range (e.g. v1.2.0..v1.3.0)
+    pub(crate) range: String,
+
+    /// Output path of the archive (.tar.gz)
+    #[arg(long)]
+    pub(crate) out: String,
+
+    /// Leave the manifest unsigned (by default it is signed like a transcript, with
+    /// user.signingkey)
+    #[arg(long, default_value_t = false)]
+    pub(crate) no_sign: bool,
+
+    /// Fail instead of fetching missing parent commits in shallow clones
+    #[arg(long, default_value_t = false)]
+    pub(crate) no_fetch: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum TranscriptCmd {
+    /// Find stored transcripts of an identical change (same patch-id), e.g. for backports
+    Find(TranscriptFindArgs),
+    /// Rewrite transcripts stored in git notes in an older schema to the current one
+    Migrate(TranscriptMigrateArgs),
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct TranscriptMigrateArgs {
+    /// Only report what would be rewritten
+    #[arg(long, default_value_t = false)]
+    pub(crate) dry_run: bool,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct TranscriptFindArgs {
+    /// Patch-id to look for (default: the staged diff's)
+    #[arg(long, conflicts_with = "commit")]
+    pub(crate) patch_id: Option<String>,
+
+    /// Look for the change introduced by this commit
+    #[arg(long)]
+    pub(crate) commit: Option<String>,
+
+    /// Also search the transcripts of another local clone (repeatable)
+    #[arg(long = "repo")]
+    pub(crate) repos: Vec<String>,
+
+    /// Copy the newest passing match onto this commit, recording where it came from
+    #[arg(long)]
+    pub(crate) attach: Option<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = TranscriptFormat::Table)]
+    pub(crate) format: TranscriptFormat,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum TranscriptFormat {
+    Table,
+    Json,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum NotesCmd {
+    /// Push transcripts, merging the remote's first if it has moved on
+    Push(NotesArgs),
+    /// Fetch the remote's transcripts and merge them into the local ones
+    Fetch(NotesArgs),
+    /// Compare the local transcripts with the remote's
+    Status(NotesArgs),
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct NotesArgs {
+    /// Remote to sync with (default: notes.remote, else origin)
+    #[arg(long)]
+    pub(crate) remote: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum CacheCmd {
+    /// Delete all cached exams and provider responses
+    Clear,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum PacksCmd {
+    /// Install (or update) a question pack from a git URL or a local path
+    Add(PacksAddArgs),
+    /// List installed packs and whether the policy uses them
+    List,
+    /// Uninstall a pack
+    Remove(PacksRemoveArgs),
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct PacksAddArgs {
+    /// Git URL or local directory containing aigit-pack.toml (or the manifest file itself)
+    pub(crate) source: String,
+
+    /// Branch, tag or commit to install from a git source (default: its HEAD)
+    #[arg(long = "ref")]
+    pub(crate) git_ref: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct PacksRemoveArgs {
+    /// Name of the installed pack
+    pub(crate) name: String,
 }
 
 #[derive(Subcommand, Debug)]
@@ -63,15 +248,74 @@ pub(crate) struct ExamArgs {
     #[arg(long, value_enum)]
     pub(crate) format: Option<ExamFormat>,
 
-    /// Answers JSON path, or '-' for stdin (only used with --format json)
+    /// Answers file (JSON, or YAML/TOML/Markdown by `.yaml`/`.yml`/`.toml`/`.md` extension), or
+    /// '-' for JSON on stdin (only used with --format json). Repeat it to combine answers from
+    /// several respondents, one file each
     #[arg(long)]
-    pub(crate) answers: Option<String>,
+    pub(crate) answers: Vec<String>,
+
+    /// Directory of per-question answer files (`<question_id>.md`), assembled before grading
+    /// (only used with --format json)
+    #[arg(long, conflicts_with = "answers")]
+    pub(crate) answers_dir: Option<String>,
+
+    /// Write the exam as a Markdown form to this path (fill it in, then pass it to --answers)
+    #[arg(long, conflicts_with_all = ["answers", "answers_dir", "protocol"])]
+    pub(crate) emit_template: Option<String>,
+
+    /// Line-delimited handshake for wrapper tools: write the exam packet as one JSON line,
+    /// read one answers JSON line from stdin, then write the transcript as one JSON line
+    #[arg(long, value_enum, conflicts_with_all = ["format", "answers", "answers_dir"])]
+    pub(crate) protocol: Option<ExamProtocol>,
+
+    /// Ask the provider for a fresh exam instead of reusing the cached one for this diff
+    #[arg(long, default_value_t = false)]
+    pub(crate) no_cache: bool,
+
+    /// Continue the exam saved for this diff (`.git/aigit/sessions/`), with its draft answers
+    #[arg(long, default_value_t = false, conflicts_with_all = ["emit_template", "protocol", "no_cache"])]
+    pub(crate) resume: bool,
+
+    /// Grade against the exam in this packet (as printed by `--format json`) instead of
+    /// generating one; its diff_patch_id must match the diff being examined
+    #[arg(long, conflicts_with_all = ["emit_template", "protocol", "resume", "no_cache"])]
+    pub(crate) exam: Option<String>,
+
+    /// Never generate an exam: grade against --exam, or the exam emitted earlier for this diff
+    #[arg(long, default_value_t = false, conflicts_with_all = ["emit_template", "protocol", "resume", "no_cache"])]
+    pub(crate) grade_only: bool,
+
+    /// Ask again only the weak questions of the exam this diff last failed, and grade the
+    /// merged answers (attempts are kept in the transcript)
+    #[arg(long, default_value_t = false, conflicts_with_all = ["emit_template", "protocol", "resume", "exam", "grade_only", "no_cache", "answers", "answers_dir"])]
+    pub(crate) retake: bool,
+
+    /// With --retake: add a harder follow-up from the examiner to each retaken question
+    /// (default: `retake.follow_ups` in policy)
+    #[arg(long, default_value_t = false, requires = "retake")]
+    pub(crate) follow_ups: bool,
+
+    /// Issue or pull request description (e.g. DESCRIPTION.md) the questions should check the
+    /// diff against (default: `.git/PULLREQ_EDITMSG` when it is newer than HEAD)
+    #[arg(long, value_name = "FILE")]
+    pub(crate) context_file: Option<String>,
+
+    /// Do not stream provider progress (Codex CLI stderr, elapsed time) while waiting
+    #[arg(long, short = 'q', default_value_t = false)]
+    pub(crate) quiet: bool,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum ExamProtocol {
+    Stdio,
 }
 
 #[derive(Clone, Copy, Debug, ValueEnum)]
 pub(crate) enum ExamFormat {
     Tui,
     Json,
+    /// Answer all questions in a Markdown form opened in `$EDITOR`
+    Editor,
 }
 
 #[derive(Parser, Debug)]
@@ -80,6 +324,28 @@ pub(crate) struct CommitArgs {
     #[arg(short = 'm', long)]
     pub(crate) message: Option<String>,
 
+    /// Amend HEAD (like `git commit --amend`) and move its transcript to the amended commit; the
+    /// exam is taken again only when the amend changes the diff
+    #[arg(long, default_value_t = false)]
+    pub(crate) amend: bool,
+
+    /// Run the exam and print the transcript that would be stored, without committing
+    #[arg(long, default_value_t = false)]
+    pub(crate) dry_run: bool,
+
+    /// Issue or pull request description (e.g. DESCRIPTION.md) the questions should check the
+    /// diff against (default: `.git/PULLREQ_EDITMSG` when it is newer than HEAD)
+    #[arg(long, value_name = "FILE")]
+    pub(crate) context_file: Option<String>,
+
+    /// Ask the provider for a fresh exam instead of reusing the cached one for this diff
+    #[arg(long, default_value_t = false)]
+    pub(crate) no_cache: bool,
+
+    /// Do not stream provider progress (Codex CLI stderr, elapsed time) while waiting
+    #[arg(long, short = 'q', default_value_t = false)]
+    pub(crate) quiet: bool,
+
     /// Pass-through args to `git commit` after `--`
     #[arg(last = true)]
     pub(crate) git_args: Vec<String>,
@@ -87,7 +353,58 @@ pub(crate) struct CommitArgs {
 
 #[derive(Parser, Debug)]
 pub(crate) struct VerifyArgs {
-    pub(crate) commitish: String,
+    #[arg(
+        required_unless_present_any = ["range", "all", "chain"],
+        conflicts_with_all = ["range", "all"]
+    )]
+    pub(crate) commitish: Option<String>,
+
+    /// Verify every commit in a range (e.g. origin/main..HEAD)
+    #[arg(long, conflicts_with = "all")]
+    pub(crate) range: Option<String>,
+
+    /// Verify every commit reachable from HEAD
+    #[arg(long, default_value_t = false)]
+    pub(crate) all: bool,
+
+    /// Walk the transcript hash chain from refs/aigit/chain and report deleted or replaced
+    /// transcripts (before checking any commits given)
+    #[arg(long, default_value_t = false)]
+    pub(crate) chain: bool,
+
+    #[arg(long, value_enum, default_value_t = VerifyFormat::Table)]
+    pub(crate) format: VerifyFormat,
+
+    /// Do not fetch missing history in shallow clones; fail with a hint instead
+    #[arg(long, default_value_t = false)]
+    pub(crate) no_fetch: bool,
+
+    /// Re-check thresholds against the current policy or the one recorded at exam time
+    /// (default: `verify.policy`, else current)
+    #[arg(long, value_enum)]
+    pub(crate) policy: Option<PolicyBasis>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum VerifyFormat {
+    Table,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum PolicyBasis {
+    Current,
+    Recorded,
+}
+
+impl PolicyBasis {
+    /// The `verify.policy` value.
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            PolicyBasis::Current => "current",
+            PolicyBasis::Recorded => "recorded",
+        }
+    }
 }
 
 #[derive(Parser, Debug)]
@@ -100,6 +417,16 @@ pub(crate) struct InstallHookArgs {
     pub(crate) force: bool,
 }
 
+#[derive(Parser, Debug)]
+pub(crate) struct HookRunArgs {
+    #[arg(value_enum)]
+    pub(crate) hook: HookMode,
+
+    /// Arguments git passed to the hook
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    pub(crate) args: Vec<String>,
+}
+
 #[derive(Parser, Debug)]
 pub(crate) struct DashboardArgs {
     #[command(subcommand)]
@@ -127,6 +454,10 @@ pub(crate) struct DashboardExportArgs {
     /// Maximum number of transcripts to export (newest first)
     #[arg(long)]
     pub(crate) limit: Option<usize>,
+
+    /// Build a multi-repository dataset from an `aigit org-export` file instead of this repo's notes
+    #[arg(long, value_name = "FILE")]
+    pub(crate) org: Option<String>,
 }
 
 #[derive(Parser, Debug)]
@@ -142,11 +473,201 @@ pub(crate) struct DashboardServeArgs {
     /// Port to bind to
     #[arg(long, default_value_t = 5173)]
     pub(crate) port: u16,
+
+    /// Serve this dataset (e.g. a multi-repo export) as /data.json instead of the one in --dir
+    #[arg(long, value_name = "FILE")]
+    pub(crate) data: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct StatsArgs {
+    /// Grouping key
+    #[arg(long, value_enum, default_value_t = StatsBy::Author)]
+    pub(crate) by: StatsBy,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = StatsFormat::Md)]
+    pub(crate) format: StatsFormat,
+
+    /// Only include commits authored on or after this date (YYYY-MM-DD)
+    #[arg(long)]
+    pub(crate) since: Option<String>,
+
+    /// Only include commits authored before this date (YYYY-MM-DD)
+    #[arg(long)]
+    pub(crate) until: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct OrgExportArgs {
+    /// File listing one repository per line: a local clone (relative to the file) or a remote URL
+    /// (cloned without blobs, with its transcript notes). Blank lines and `#` comments are skipped
+    #[arg(long)]
+    pub(crate) repos_file: String,
+
+    /// Output path for the dataset (default: stdout)
+    #[arg(long)]
+    pub(crate) out: Option<String>,
+
+    /// Fetch transcript notes from `origin` into local clones first
+    #[arg(long, default_value_t = false)]
+    pub(crate) fetch_notes: bool,
+
+    /// Include full answer text in the export (can be sensitive)
+    #[arg(long, default_value_t = false)]
+    pub(crate) include_answers: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum ReportCmd {
+    /// Summarize a merge request's commits and their exam results as a review note
+    Mr(ReportMrArgs),
+    /// Verify a commit and vote on its Gerrit change with a review label and message
+    Gerrit(ReportGerritArgs),
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct ReportGerritArgs {
+    /// Commit (patch set revision) to verify
+    #[arg(default_value = "HEAD")]
+    pub(crate) commitish: String,
+
+    /// Post the review with the HTTP credentials in $GERRIT_USER / $GERRIT_HTTP_PASSWORD
+    #[arg(long, default_value_t = false)]
+    pub(crate) post: bool,
+
+    /// Gerrit base URL, e.g. https://review.example.com (default: $GERRIT_URL)
+    #[arg(long)]
+    pub(crate) gerrit_url: Option<String>,
+
+    /// Change to review (default: $GERRIT_CHANGE_NUMBER, else the commit's Change-Id trailer)
+    #[arg(long)]
+    pub(crate) change: Option<String>,
+
+    /// Label to vote on: +1 when the commit verifies, -1 when it does not
+    #[arg(long, default_value = "Verified")]
+    pub(crate) label: String,
+
+    /// Do not fetch missing history in shallow clones
+    #[arg(long, default_value_t = false)]
+    pub(crate) no_fetch: bool,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum ReportFormat {
+    Markdown,
+    /// JSON body for GitLab's merge request notes API
+    Gitlab,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct ReportMrArgs {
+    /// Commits of the merge request (default: $CI_MERGE_REQUEST_DIFF_BASE_SHA..HEAD)
+    #[arg(long)]
+    pub(crate) range: Option<String>,
+
+    #[arg(long, value_enum, default_value_t = ReportFormat::Markdown)]
+    pub(crate) format: ReportFormat,
+
+    /// Post the note to the merge request with the token in $GITLAB_TOKEN
+    #[arg(long, default_value_t = false)]
+    pub(crate) post: bool,
+
+    /// GitLab API base URL, e.g. https://gitlab.com/api/v4 (default: $CI_API_V4_URL)
+    #[arg(long)]
+    pub(crate) gitlab_url: Option<String>,
+
+    /// Project id or path (default: $CI_PROJECT_ID)
+    #[arg(long)]
+    pub(crate) project: Option<String>,
+
+    /// Merge request IID (default: $CI_MERGE_REQUEST_IID)
+    #[arg(long)]
+    pub(crate) mr: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum StatsBy {
+    Author,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum StatsFormat {
+    Json,
+    Md,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct GradeArgs {
+    /// Retry grading of the answers saved by an interrupted `aigit commit` or `aigit exam`
+    #[arg(long, default_value_t = false)]
+    pub(crate) resume: bool,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct AppealArgs {
+    /// Commit whose stored transcript to appeal, or a transcript JSON file from
+    /// `aigit exam --format json --answers ...` (updated in place)
+    pub(crate) target: String,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct CountersignArgs {
+    /// Commit whose transcript to countersign
+    pub(crate) commitish: String,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct CalibrateArgs {
+    /// Report the offset without writing it to the suite's offsets.json
+    #[arg(long, default_value_t = false)]
+    pub(crate) dry_run: bool,
+}
+
+#[derive(Parser, Debug)]
+pub(crate) struct BenchArgs {
+    /// Diff range to benchmark, e.g. HEAD~1..HEAD (default: staged changes)
+    #[arg(long)]
+    pub(crate) range: Option<String>,
+
+    /// Answers JSON used for the grading stage (default: placeholder answers)
+    #[arg(long)]
+    pub(crate) answers: Option<String>,
+
+    /// Number of times to run the pipeline
+    #[arg(long, default_value_t = 1)]
+    pub(crate) iterations: u32,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = BenchFormat::Table)]
+    pub(crate) format: BenchFormat,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum BenchFormat {
+    Table,
+    Json,
 }
 
 #[derive(Clone, Copy, Debug, ValueEnum)]
 pub(crate) enum HookMode {
+    /// Block `git commit` unless it runs through `aigit commit`
     PreCommit,
+    /// Verify every commit being pushed, blocking the push on a missing or failing transcript
+    PrePush,
+    /// Carry transcripts over to amended and rebased commits whose diff is unchanged
+    PostRewrite,
+}
+
+impl HookMode {
+    /// The git hook's file name.
+    pub(crate) fn hook_name(self) -> &'static str {
+        match self {
+            HookMode::PreCommit => "pre-commit",
+            HookMode::PrePush => "pre-push",
+            HookMode::PostRewrite => "post-rewrite",
+        }
+    }
 }
 
 #[derive(Parser, Debug)]
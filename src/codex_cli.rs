@@ -1,14 +1,18 @@
 use std::path::Path;
-use std::process::{Command, Stdio};
+use std::process::{Child, Command, Stdio};
 use std::time::Duration;
 
 use anyhow::{anyhow, Context, Result};
 use wait_timeout::ChildExt;
 
 use crate::config::{CodexCliPolicy, Policy};
+use crate::progress::Progress;
 
 pub const NPX_OPENAI_DOWNLOAD: &str = "npx -y @openai/codex@0.93.0";
 
+/// How often the spinner and elapsed time are refreshed while `codex exec` runs.
+const PROGRESS_TICK: Duration = Duration::from_millis(250);
+
 #[derive(Debug, Clone)]
 pub struct CodexCliRunner {
     base_command: String,
@@ -16,6 +20,11 @@ pub struct CodexCliRunner {
     model: Option<String>,
     sandbox: String,
     timeout: Duration,
+    max_retries: u32,
+    retry_backoff: Duration,
+    repair_prompt: bool,
+    progress: bool,
+    progress_interval: Duration,
 }
 
 impl CodexCliRunner {
@@ -36,15 +45,83 @@ impl CodexCliRunner {
             model: cfg.model.clone().or_else(|| policy.model.clone()),
             sandbox,
             timeout,
+            max_retries: cfg.max_retries.unwrap_or(1),
+            retry_backoff: Duration::from_millis(cfg.retry_backoff_ms.unwrap_or(500)),
+            repair_prompt: cfg.repair_prompt.unwrap_or(true),
+            progress: cfg.progress.unwrap_or(true),
+            progress_interval: Duration::from_millis(cfg.progress_interval_ms.unwrap_or(1000)),
         }
     }
 
-    pub fn run_json_judge(&self, cwd: &Path, prompt: &str) -> Result<String> {
-        self.run_json_with_schema(cwd, prompt, &score_schema_json())
+    /// Cheap availability check: the base command's program resolves to an executable. Does not
+    /// run Codex, which would cost a model call.
+    pub fn ping(&self) -> Result<()> {
+        let (program, _) = split_command_line(&self.base_command)?;
+        if program_exists(&program) {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "Codex CLI not found: {program} (hint: set `codex_cli.command` in .aigit.toml, e.g. \"{}\")",
+                NPX_OPENAI_DOWNLOAD
+            ))
+        }
     }
 
-    pub fn run_json_generate_exam(&self, cwd: &Path, prompt: &str) -> Result<String> {
-        self.run_json_with_schema(cwd, prompt, &exam_schema_json())
+    pub fn run_json_judge<T>(
+        &self,
+        cwd: &Path,
+        prompt: &str,
+        parse: impl Fn(&str) -> Result<T>,
+    ) -> Result<T> {
+        self.run_with_retries(cwd, prompt, &score_schema_json(), parse)
+    }
+
+    pub fn run_json_generate_exam<T>(
+        &self,
+        cwd: &Path,
+        prompt: &str,
+        parse: impl Fn(&str) -> Result<T>,
+    ) -> Result<T> {
+        self.run_with_retries(cwd, prompt, &exam_schema_json(), parse)
+    }
+
+    /// Runs Codex and parses its output, retrying up to `max_retries` times with exponential
+    /// backoff. A failed run is retried as-is; an unparseable response is retried with a repair
+    /// prompt naming the parse error (unless `repair_prompt = false`).
+    fn run_with_retries<T>(
+        &self,
+        cwd: &Path,
+        prompt: &str,
+        schema: &serde_json::Value,
+        parse: impl Fn(&str) -> Result<T>,
+    ) -> Result<T> {
+        let mut next_prompt = prompt.to_string();
+        let mut attempt = 0;
+        loop {
+            let err = match self.run_json_with_schema(cwd, &next_prompt, schema) {
+                Ok(raw) => match parse(&raw) {
+                    Ok(parsed) => return Ok(parsed),
+                    Err(err) => {
+                        if self.repair_prompt {
+                            next_prompt = repair_prompt(prompt, &raw, &err);
+                        }
+                        err
+                    }
+                },
+                Err(err) => err,
+            };
+            if attempt >= self.max_retries {
+                if attempt == 0 {
+                    return Err(err);
+                }
+                return Err(anyhow!(
+                    "codex exec failed after {} attempts: {err:#}",
+                    attempt + 1
+                ));
+            }
+            std::thread::sleep(self.retry_backoff.saturating_mul(1 << attempt.min(16)));
+            attempt += 1;
+        }
     }
 
     fn run_json_with_schema(
@@ -99,7 +176,7 @@ impl CodexCliRunner {
             .env("NO_COLOR", "1")
             .env("RUST_LOG", "error");
 
-        let mut child = cmd.spawn().with_context(|| {
+        let child = cmd.spawn().with_context(|| {
             format!(
                 "failed to spawn Codex CLI: {} {} (hint: set `codex_cli.command` in .aigit.toml, e.g. \"{}\")",
                 program,
@@ -108,52 +185,112 @@ impl CodexCliRunner {
             )
         })?;
 
-        {
-            use std::io::Write;
-            let mut stdin = child
-                .stdin
-                .take()
-                .ok_or_else(|| anyhow!("codex exec missing stdin"))?;
-            stdin
-                .write_all(prompt.as_bytes())
-                .context("failed to write prompt to codex stdin")?;
-        }
+        let progress = self
+            .progress
+            .then(|| Progress::start("codex", self.timeout, self.progress_interval));
+        wait_with_progress(child, prompt, self.timeout, "codex exec", progress.as_ref())?;
 
-        let stdout_handle = child.stdout.take().map(read_to_end_thread);
-        let stderr_handle = child.stderr.take().map(read_to_end_thread);
+        let raw = std::fs::read_to_string(&output_path)
+            .with_context(|| format!("codex exec did not write {}", output_path.display()))?;
+        Ok(raw)
+    }
+}
 
-        let status = match child.wait_timeout(self.timeout)? {
-            Some(s) => s,
-            None => {
-                let _ = child.kill();
-                let _ = child.wait();
-                return Err(anyhow!(
-                    "codex exec timed out after {}s",
-                    self.timeout.as_secs()
-                ));
+/// The original prompt plus the rejected response and why it was rejected.
+fn repair_prompt(prompt: &str, raw: &str, err: &anyhow::Error) -> String {
+    format!(
+        "{prompt}\n\nYour previous response could not be used: {err:#}\n\
+         Previous response:\n{}\n\n\
+         Reply again with a corrected JSON object that matches the schema exactly.\n",
+        truncate_for_error(raw)
+    )
+}
+
+/// Writes `input` to a spawned child's stdin, waits up to `timeout`, and returns its stdout.
+/// Non-zero exits and timeouts become errors that include both output streams.
+pub(crate) fn wait_with_input(
+    child: Child,
+    input: &str,
+    timeout: Duration,
+    label: &str,
+) -> Result<String> {
+    wait_with_progress(child, input, timeout, label, None)
+}
+
+/// `wait_with_input`, streaming the child's stderr and elapsed time to `progress` while it runs.
+/// The input is written from its own thread, alongside the output readers and under the
+/// timeout, so a child that writes before it has read everything cannot deadlock.
+pub(crate) fn wait_with_progress(
+    mut child: Child,
+    input: &str,
+    timeout: Duration,
+    label: &str,
+    progress: Option<&Progress>,
+) -> Result<String> {
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("{label} missing stdin"))?;
+    let input = input.to_string();
+    let stdin_handle = std::thread::spawn(move || {
+        use std::io::Write;
+        stdin.write_all(input.as_bytes())
+    });
+
+    let stdout_handle = child.stdout.take().map(read_to_end_thread);
+    let stderr_handle = child.stderr.take().map(|stderr| match progress {
+        Some(progress) => read_lines_thread(stderr, progress.clone()),
+        None => read_to_end_thread(stderr),
+    });
+
+    let status = match progress {
+        Some(progress) => {
+            let started = std::time::Instant::now();
+            loop {
+                let left = timeout.saturating_sub(started.elapsed());
+                if left.is_zero() {
+                    break None;
+                }
+                if let Some(status) = child.wait_timeout(left.min(PROGRESS_TICK))? {
+                    break Some(status);
+                }
+                progress.tick();
             }
-        };
-
-        let stdout = stdout_handle
-            .map(|h| h.join().unwrap_or_default())
-            .unwrap_or_default();
-        let stderr = stderr_handle
-            .map(|h| h.join().unwrap_or_default())
-            .unwrap_or_default();
-
-        if !status.success() {
-            return Err(anyhow!(
-                "codex exec failed (exit={})\nstdout:\n{}\nstderr:\n{}",
-                status,
-                truncate_for_error(&stdout),
-                truncate_for_error(&stderr)
-            ));
         }
+        None => child.wait_timeout(timeout)?,
+    };
+    if let Some(progress) = progress {
+        progress.finish();
+    }
+    let status = match status {
+        Some(s) => s,
+        None => {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(anyhow!("{label} timed out after {}s", timeout.as_secs()));
+        }
+    };
 
-        let raw = std::fs::read_to_string(&output_path)
-            .with_context(|| format!("codex exec did not write {}", output_path.display()))?;
-        Ok(raw)
+    let stdout = stdout_handle
+        .map(|h| h.join().unwrap_or_default())
+        .unwrap_or_default();
+    let stderr = stderr_handle
+        .map(|h| h.join().unwrap_or_default())
+        .unwrap_or_default();
+
+    if !status.success() {
+        return Err(anyhow!(
+            "{label} failed (exit={})\nstdout:\n{}\nstderr:\n{}",
+            status,
+            truncate_for_error(&stdout),
+            truncate_for_error(&stderr)
+        ));
     }
+    stdin_handle
+        .join()
+        .map_err(|_| anyhow!("{label} stdin writer panicked"))?
+        .with_context(|| format!("failed to write prompt to {label} stdin"))?;
+    Ok(stdout)
 }
 
 fn read_to_end_thread(mut reader: impl std::io::Read + Send + 'static) -> std::thread::JoinHandle<String> {
@@ -164,7 +301,41 @@ fn read_to_end_thread(mut reader: impl std::io::Read + Send + 'static) -> std::t
     })
 }
 
-fn truncate_for_error(s: &str) -> String {
+/// Like `read_to_end_thread`, also handing each line to `progress` as it arrives.
+fn read_lines_thread(
+    reader: impl std::io::Read + Send + 'static,
+    progress: Progress,
+) -> std::thread::JoinHandle<String> {
+    std::thread::spawn(move || {
+        use std::io::BufRead;
+        let mut reader = std::io::BufReader::new(reader);
+        let mut out = String::new();
+        let mut buf = Vec::new();
+        while let Ok(n) = reader.read_until(b'\n', &mut buf) {
+            if n == 0 {
+                break;
+            }
+            let line = String::from_utf8_lossy(&buf);
+            progress.line(&line);
+            out.push_str(&line);
+            buf.clear();
+        }
+        out
+    })
+}
+
+/// True if `program` is a path to a file, or a bare name found on `PATH`.
+pub(crate) fn program_exists(program: &str) -> bool {
+    let path = Path::new(program);
+    if path.components().count() > 1 {
+        return path.is_file();
+    }
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+pub(crate) fn truncate_for_error(s: &str) -> String {
     const MAX: usize = 8000;
     if s.len() <= MAX {
         return s.to_string();
@@ -174,7 +345,7 @@ fn truncate_for_error(s: &str) -> String {
     out
 }
 
-fn split_command_line(input: &str) -> Result<(String, Vec<String>)> {
+pub(crate) fn split_command_line(input: &str) -> Result<(String, Vec<String>)> {
     let parts = shlex::split(input).ok_or_else(|| anyhow!("invalid base command: {input}"))?;
     if parts.is_empty() {
         return Err(anyhow!("base command is empty"));
@@ -184,7 +355,7 @@ fn split_command_line(input: &str) -> Result<(String, Vec<String>)> {
     Ok((program, parts_iter.collect()))
 }
 
-fn score_schema_json() -> serde_json::Value {
+pub(crate) fn score_schema_json() -> serde_json::Value {
     serde_json::json!({
         "$schema": "https://json-schema.org/draft/2020-12/schema",
         "title": "aigit.Score",
@@ -195,21 +366,45 @@ fn score_schema_json() -> serde_json::Value {
             "total_score": { "type": "number", "minimum": 0.0, "maximum": 1.0 },
             "hallucination_flags": {
                 "type": "array",
-                "items": { "type": "string" }
+                "items": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "required": ["question_id", "kind", "claimed_artifact", "evidence", "severity"],
+                    "properties": {
+                        "question_id": { "type": "string" },
+                        "kind": { "type": "string" },
+                        "claimed_artifact": { "type": ["string", "null"] },
+                        "evidence": { "type": "string" },
+                        "severity": { "type": "string", "enum": ["low", "medium", "high", "critical"] }
+                    }
+                }
             },
             "per_question": {
                 "type": "array",
                 "items": {
                     "type": "object",
                     "additionalProperties": false,
-                    "required": ["id", "category", "score", "completeness", "specificity", "notes"],
+                    "required": ["id", "category", "score", "completeness", "specificity", "category_relevance", "notes"],
                     "properties": {
                         "id": { "type": "string" },
                         "category": { "type": "string" },
                         "score": { "type": "number", "minimum": 0.0, "maximum": 1.0 },
                         "completeness": { "type": "number", "minimum": 0.0, "maximum": 1.0 },
                         "specificity": { "type": "number", "minimum": 0.0, "maximum": 1.0 },
-                        "notes": { "type": "array", "items": { "type": "string" } }
+                        "category_relevance": { "type": "number", "minimum": 0.0, "maximum": 1.0 },
+                        "notes": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "additionalProperties": false,
+                                "required": ["code", "message", "related_path"],
+                                "properties": {
+                                    "code": { "type": "string", "enum": ["missing_specifics", "inaccuracy", "boilerplate", "excellent"] },
+                                    "message": { "type": "string" },
+                                    "related_path": { "type": ["string", "null"] }
+                                }
+                            }
+                        }
                     }
                 }
             }
@@ -217,7 +412,7 @@ fn score_schema_json() -> serde_json::Value {
     })
 }
 
-fn exam_schema_json() -> serde_json::Value {
+pub(crate) fn exam_schema_json() -> serde_json::Value {
     serde_json::json!({
         "$schema": "https://json-schema.org/draft/2020-12/schema",
         "title": "aigit.Exam",
@@ -235,7 +430,7 @@ fn exam_schema_json() -> serde_json::Value {
                     "additionalProperties": false,
                     // OpenAI/Codex schema validation requires `required` to list every key in `properties`.
                     // So `choices` is required but may be null for open-ended questions.
-                    "required": ["id", "category", "prompt", "choices"],
+                    "required": ["id", "category", "prompt", "choices", "related_paths", "related_hunks", "correct_choice", "kind"],
                     "properties": {
                         "id": { "type": "string" },
                         "category": { "type": "string" },
@@ -245,7 +440,11 @@ fn exam_schema_json() -> serde_json::Value {
                             "minItems": 2,
                             "maxItems": 6,
                             "items": { "type": "string" }
-                        }
+                        },
+                        "related_paths": { "type": "array", "items": { "type": "string" } },
+                        "related_hunks": { "type": "array", "items": { "type": "string" } },
+                        "correct_choice": { "type": ["string", "null"] },
+                        "kind": { "type": ["string", "null"], "enum": ["open", "evidence", null] }
                     }
                 }
             }
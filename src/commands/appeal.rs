@@ -0,0 +1,163 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+
+use crate::cli::AppealArgs;
+use crate::examiner::ExamContext;
+use crate::git::Git;
+use crate::transcript::{parse_transcript, Appeal, Decision, TranscriptStore};
+
+use super::common;
+
+/// Re-grades the answers in a stored transcript with `appeal.arbiter` and records both grades.
+/// The arbiter's decision replaces the original only when `appeal.can_overturn` is set.
+pub(crate) fn cmd_appeal(git: &Git, args: AppealArgs, verbose: bool) -> Result<u8> {
+    let policy = common::load_policy_verbose(git, verbose)?;
+    let arbiter = policy
+        .appeal
+        .arbiter
+        .clone()
+        .filter(|a| !a.trim().is_empty())
+        .ok_or_else(|| anyhow!("appeal.arbiter must be set in .aigit.toml to appeal"))?;
+
+    let file = Path::new(&args.target);
+    let store = TranscriptStore::from_policy(&policy)?;
+    let (mut transcript, commit) = if file.is_file() {
+        let raw = std::fs::read_to_string(file)
+            .with_context(|| format!("failed to read {}", file.display()))?;
+        let transcript = parse_transcript(&raw, &file.display().to_string())
+            .with_context(|| format!("{} is not an aigit transcript", file.display()))?;
+        let commit = transcript.commit.clone();
+        (transcript, commit)
+    } else {
+        let commit = git.resolve_commitish(&args.target)?;
+        let transcript = store
+            .load(&git.repo, &commit)
+            .with_context(|| format!("no transcript stored for {commit}"))?;
+        (transcript, Some(commit))
+    };
+    if !transcript.answers.withheld.is_empty() {
+        return Err(anyhow!(
+            "the transcript does not keep its answers (store_answers), so there is nothing to re-grade"
+        ));
+    }
+
+    let range = commit.as_ref().map(|c| format!("{c}^!"));
+    let (diff, changed_files) = match &range {
+        Some(range) => git.diff_range(range)?,
+        None => git.diff_staged()?,
+    };
+    let diff_patch_id = git.patch_id_from_diff_text(&diff)?;
+    if diff_patch_id != transcript.diff_fingerprint.patch_id {
+        return Err(anyhow!(
+            "changes differ from the appealed exam (diff fingerprint mismatch)"
+        ));
+    }
+    let diff = common::context_diff(git, &policy, range.as_deref(), None, diff)?;
+    let (redacted_diff, redactions) = crate::redact::redact_diff(&policy, &diff)?;
+    let mut ctx = ExamContext::new(
+        git,
+        diff_patch_id,
+        &redacted_diff,
+        changed_files,
+        redactions,
+        &policy,
+    )?;
+    ctx.commit_message = commit.as_deref().and_then(|c| git.commit_message(c).ok());
+
+    let mut arbiter_policy = policy.clone();
+    arbiter_policy.provider = Some(arbiter.clone());
+    arbiter_policy.grader = None;
+    if let Some(model) = &policy.appeal.model {
+        arbiter_policy.model = Some(model.clone());
+    }
+    let examiner = common::build_examiner(&arbiter_policy);
+    let arbiter_score = examiner
+        .grade_exam(&ctx, &transcript.exam, &transcript.answers)
+        .map_err(|err| anyhow!("appeal arbiter {arbiter} failed to grade: {err:#}"))?;
+    let arbiter_decision = Decision::from_score(
+        &policy,
+        &transcript.exam,
+        &transcript.answers,
+        &arbiter_score,
+        &ctx.changed_files,
+    );
+
+    // A repeated appeal is measured against the original grade, not an earlier arbiter's.
+    let (original_score, original_decision) = match transcript.appeal.take() {
+        Some(previous) => (previous.original_score, previous.original_decision),
+        None => (transcript.score.clone(), transcript.decision),
+    };
+    let overturned =
+        policy.appeal.can_overturn.unwrap_or(false) && arbiter_decision != original_decision;
+    transcript.score = if overturned {
+        arbiter_score.clone()
+    } else {
+        original_score.clone()
+    };
+    transcript.decision = if overturned {
+        arbiter_decision
+    } else {
+        original_decision
+    };
+    transcript.failure_report = crate::failure_report::FailureReport::build(&transcript);
+
+    eprintln!(
+        "aigit: original grade: {} (score {:.2})",
+        decision_label(original_decision),
+        original_score.total_score
+    );
+    eprintln!(
+        "aigit: arbiter {arbiter}: {} (score {:.2})",
+        decision_label(arbiter_decision),
+        arbiter_score.total_score
+    );
+    for q in &arbiter_score.per_question {
+        for n in &q.notes {
+            eprintln!("  - {}: {n}", q.id);
+        }
+    }
+    transcript.appeal = Some(Appeal {
+        timestamp: Utc::now(),
+        arbiter,
+        model: arbiter_policy
+            .model
+            .clone()
+            .unwrap_or_else(|| "static".to_string()),
+        original_score,
+        original_decision,
+        arbiter_score,
+        arbiter_decision,
+        overturned,
+    });
+    if overturned {
+        eprintln!(
+            "aigit: decision overturned: {}",
+            decision_label(transcript.decision)
+        );
+    } else if arbiter_decision != original_decision {
+        eprintln!("aigit: the original decision stands (appeal.can_overturn is false)");
+    }
+
+    crate::signing::sign_if_enabled(git, &policy, &mut transcript)?;
+    if file.is_file() {
+        std::fs::write(file, serde_json::to_string_pretty(&transcript)?)
+            .with_context(|| format!("failed to write {}", file.display()))?;
+        eprintln!("aigit: recorded the appeal in {}", file.display());
+    } else if let Some(commit) = &commit {
+        store.store(&git.repo, commit, &transcript)?;
+        eprintln!("aigit: recorded the appeal in the transcript for {commit}");
+    }
+    Ok(match transcript.decision {
+        Decision::Pass => 0,
+        Decision::Fail => 2,
+    })
+}
+
+fn decision_label(decision: Decision) -> &'static str {
+    match decision {
+        Decision::Pass => "PASS",
+        Decision::Fail => "FAIL",
+    }
+}
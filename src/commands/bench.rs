@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+use crate::cli::{BenchArgs, BenchFormat};
+use crate::examiner::{Exam, ExamContext};
+use crate::git::Git;
+use crate::transcript::{Answers, Decision, Transcript, TranscriptStore};
+
+use super::common;
+
+/// Scratch notes ref the storage stage writes to; deleted once the run finishes.
+const BENCH_NOTES_REF: &str = "aigit-bench";
+
+const STAGES: &[&str] = &["diff", "redaction", "context", "generate", "grade", "store"];
+
+#[derive(Debug, Clone, Serialize)]
+struct StageTiming {
+    stage: String,
+    runs: u32,
+    mean_ms: f64,
+    min_ms: f64,
+    max_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct BenchReport {
+    schema_version: String,
+    examiner: String,
+    iterations: u32,
+    changed_files: usize,
+    diff_bytes: usize,
+    stages: Vec<StageTiming>,
+    total_mean_ms: f64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    skipped: Vec<String>,
+}
+
+pub(crate) fn cmd_bench(git: &Git, args: BenchArgs, verbose: bool) -> Result<u8> {
+    if args.iterations == 0 {
+        return Err(anyhow!("--iterations must be at least 1"));
+    }
+    let mut policy = common::load_policy_verbose(git, verbose)?;
+    // Cached responses would time the cache, not the provider.
+    policy.response_cache.enabled = Some(false);
+    let examiner = common::build_examiner(&policy);
+    let answers = args
+        .answers
+        .as_deref()
+        .map(Answers::load_from_path)
+        .transpose()?;
+    let head = git.rev_parse_head().ok();
+    let store = TranscriptStore::git_notes_ref(BENCH_NOTES_REF);
+
+    let mut samples: BTreeMap<&str, Vec<Duration>> = BTreeMap::new();
+    let mut changed_count = 0;
+    let mut diff_bytes = 0;
+    let result = (|| -> Result<()> {
+        for _ in 0..args.iterations {
+            let t = Instant::now();
+            let (diff, changed_files) = match &args.range {
+                Some(range) => git.diff_range(range)?,
+                None => git.diff_staged()?,
+            };
+            if diff.trim().is_empty() {
+                return Err(anyhow!("no changes to benchmark (diff is empty)"));
+            }
+            let diff_patch_id = git.patch_id_from_diff_text(&diff)?;
+            let diff = common::context_diff(git, &policy, args.range.as_deref(), None, diff)?;
+            samples.entry("diff").or_default().push(t.elapsed());
+            changed_count = changed_files.len();
+            diff_bytes = diff.len();
+
+            let t = Instant::now();
+            let (redacted_diff, redactions) = crate::redact::redact_diff(&policy, &diff)?;
+            samples.entry("redaction").or_default().push(t.elapsed());
+
+            let t = Instant::now();
+            let mut ctx = ExamContext::new(
+                git,
+                diff_patch_id,
+                &redacted_diff,
+                changed_files,
+                redactions,
+                &policy,
+            )?;
+            common::apply_author_profile(git, &policy, &mut ctx, false);
+            samples.entry("context").or_default().push(t.elapsed());
+
+            let t = Instant::now();
+            let exam = examiner.generate_exam(&ctx)?;
+            samples.entry("generate").or_default().push(t.elapsed());
+
+            let answers = answers
+                .clone()
+                .unwrap_or_else(|| placeholder_answers(&exam, &ctx));
+            let t = Instant::now();
+            let score = examiner.grade_exam(&ctx, &exam, &answers)?;
+            samples.entry("grade").or_default().push(t.elapsed());
+
+            let Some(head) = &head else {
+                continue;
+            };
+            let decision = Decision::from_score(&policy, &exam, &answers, &score, &ctx.changed_files);
+            let transcript =
+                Transcript::from_exam_result(git, &policy, &ctx, &exam, &answers, &score, decision)?;
+            let t = Instant::now();
+            store.store(&git.repo, head, &transcript)?;
+            samples.entry("store").or_default().push(t.elapsed());
+        }
+        Ok(())
+    })();
+    if head.is_some() {
+        let _ = git.delete_ref(&format!("refs/notes/{BENCH_NOTES_REF}"));
+    }
+    result?;
+
+    let stages = STAGES
+        .iter()
+        .filter_map(|stage| samples.get(stage).map(|s| timing(stage, s)))
+        .collect::<Vec<_>>();
+    let report = BenchReport {
+        schema_version: "aigit-bench/0.1".to_string(),
+        examiner: common::examiner_label(&policy).to_string(),
+        iterations: args.iterations,
+        changed_files: changed_count,
+        diff_bytes,
+        total_mean_ms: stages.iter().map(|s| s.mean_ms).sum(),
+        stages,
+        skipped: if head.is_none() {
+            vec!["store (no HEAD commit to attach a note to)".to_string()]
+        } else {
+            Vec::new()
+        },
+    };
+
+    match args.format {
+        BenchFormat::Json => {
+            serde_json::to_writer_pretty(std::io::stdout(), &report)?;
+            println!();
+        }
+        BenchFormat::Table => print!("{}", render_table(&report)),
+    }
+    Ok(0)
+}
+
+fn timing(stage: &str, samples: &[Duration]) -> StageTiming {
+    let ms = samples
+        .iter()
+        .map(|d| d.as_secs_f64() * 1000.0)
+        .collect::<Vec<_>>();
+    StageTiming {
+        stage: stage.to_string(),
+        runs: ms.len() as u32,
+        mean_ms: ms.iter().sum::<f64>() / ms.len() as f64,
+        min_ms: ms.iter().copied().fold(f64::INFINITY, f64::min),
+        max_ms: ms.iter().copied().fold(0.0, f64::max),
+    }
+}
+
+/// Non-empty answers so grading does the same work it would for a real submission.
+fn placeholder_answers(exam: &Exam, ctx: &ExamContext) -> Answers {
+    let file = ctx.changed_files.first().map(|s| s.as_str()).unwrap_or("the diff");
+    Answers::new(
+        exam.questions
+            .iter()
+            .map(|q| {
+                (
+                    q.id.clone(),
+                    format!(
+                        "Benchmark placeholder answer for the {} question about {file}.",
+                        q.category
+                    ),
+                )
+            })
+            .collect(),
+    )
+}
+
+fn render_table(report: &BenchReport) -> String {
+    let mut out = format!(
+        "aigit bench: {} examiner, {} iteration(s), {} changed file(s), {} diff bytes\n\n",
+        report.examiner, report.iterations, report.changed_files, report.diff_bytes
+    );
+    out.push_str(&format!(
+        "{:<10} {:>10} {:>10} {:>10}\n",
+        "stage", "mean ms", "min ms", "max ms"
+    ));
+    for s in &report.stages {
+        out.push_str(&format!(
+            "{:<10} {:>10.2} {:>10.2} {:>10.2}\n",
+            s.stage, s.mean_ms, s.min_ms, s.max_ms
+        ));
+    }
+    out.push_str(&format!("{:<10} {:>10.2}\n", "total", report.total_mean_ms));
+    for s in &report.skipped {
+        out.push_str(&format!("skipped: {s}\n"));
+    }
+    out
+}
@@ -0,0 +1,14 @@
+use anyhow::Result;
+
+use crate::git::Git;
+
+pub(crate) fn cmd_cache_clear(git: &Git) -> Result<u8> {
+    let removed = crate::cache::clear(&git.repo)?;
+    let responses = crate::response_cache::clear(&git.repo)?;
+    eprintln!(
+        "aigit: cache: removed {removed} cached exam{} and {responses} cached response{}",
+        if removed == 1 { "" } else { "s" },
+        if responses == 1 { "" } else { "s" }
+    );
+    Ok(0)
+}
@@ -0,0 +1,69 @@
+use anyhow::Result;
+use chrono::Utc;
+
+use crate::calibration::{GraderCalibration, Offsets};
+use crate::cli::CalibrateArgs;
+use crate::examiner::ExamContext;
+use crate::git::Git;
+
+use super::common;
+
+/// Grades every case of the calibration suite with the configured grader and records the mean
+/// gap between the expected and the raw scores as that grader's offset.
+pub(crate) fn cmd_calibrate(git: &Git, args: CalibrateArgs, verbose: bool) -> Result<u8> {
+    let mut policy = common::load_policy_verbose(git, verbose)?;
+    // Re-running the suite against an unchanged grader reuses its responses.
+    policy.response_cache.enabled.get_or_insert(true);
+    let cases = crate::calibration::load_cases(&git.repo, &policy)?;
+    let examiner = common::build_examiner(&policy);
+    let grader = policy.grader_identity();
+
+    let mut gaps = Vec::new();
+    for case in &cases {
+        let (diff, redactions) = crate::redact::redact_diff(&policy, &case.diff)?;
+        let ctx = ExamContext::new(
+            git,
+            git.patch_id_from_diff_text(&case.diff)?,
+            &diff,
+            crate::calibration::changed_files(&case.diff),
+            redactions,
+            &policy,
+        )?;
+        let score = examiner.grade_exam(&ctx, &case.exam, &case.answers)?;
+        let raw = score
+            .normalization
+            .map(|n| n.raw_total)
+            .unwrap_or(score.total_score);
+        let gap = case.expected_score - raw;
+        println!(
+            "{}\texpected {:.2}\tgraded {raw:.2}\t{gap:+.2}",
+            case.name.as_deref().unwrap_or("-"),
+            case.expected_score
+        );
+        gaps.push(gap);
+    }
+    let offset = gaps.iter().sum::<f64>() / gaps.len() as f64;
+    eprintln!(
+        "aigit: calibrate: {grader}: offset {offset:+.3} over {} case(s)",
+        cases.len()
+    );
+    if args.dry_run {
+        return Ok(0);
+    }
+
+    let mut offsets = Offsets::load(&git.repo, &policy)?;
+    offsets.graders.insert(
+        grader,
+        GraderCalibration {
+            offset,
+            cases: cases.len(),
+            calibrated_at: Utc::now(),
+        },
+    );
+    let path = offsets.save(&git.repo, &policy)?;
+    eprintln!(
+        "aigit: calibrate: wrote {}; commit it so every checkout normalizes the same way",
+        path.display()
+    );
+    Ok(0)
+}
@@ -1,23 +1,58 @@
 use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
 
 use crate::cli::CommitArgs;
-use crate::examiner::{ExamContext, Examiner};
+use crate::config::Policy;
+use crate::examiner::{Exam, ExamContext, Examiner};
 use crate::git::Git;
-use crate::transcript::{Decision, TranscriptStore};
+use crate::pending::PendingExam;
+use crate::retake::RetakeState;
+use crate::transcript::{
+    Amended, Answers, Decision, Transcript, TranscriptStore, TRAILER_DATA_KEY, TRAILER_KEY,
+};
 
 use super::common;
 
 pub(crate) fn cmd_commit(git: &Git, args: CommitArgs, verbose: bool) -> Result<u8> {
-    let policy = common::load_policy_verbose(git, verbose)?;
+    let mut policy = common::load_policy_verbose(git, verbose)?;
+    if args.quiet {
+        policy.codex_cli.progress = Some(false);
+    }
 
-    let (diff, changed_files) = git.diff_staged()?;
+    let mut git_args = args.git_args.clone();
+    if args.amend && !amends(&git_args) {
+        git_args.push("--amend".to_string());
+    }
+    let amend_base = amends(&git_args).then(|| git.amend_base()).transpose()?;
+    let (diff, changed_files) = git.diff_staged_since(amend_base.as_deref())?;
     if diff.trim().is_empty() {
-        return Err(anyhow!("no staged changes to commit"));
+        return Err(anyhow!(if amend_base.is_some() {
+            "the amended commit would introduce no changes"
+        } else {
+            "no staged changes to commit"
+        }));
     }
-
     let diff_patch_id = git.patch_id_from_diff_text(&diff)?;
+    if amend_base.is_some() {
+        if let Some(code) = amend_without_exam(
+            git,
+            &policy,
+            &diff_patch_id,
+            args.message.as_deref(),
+            &git_args,
+            args.dry_run,
+        )? {
+            return Ok(code);
+        }
+    }
+    let Some(examiner) = common::select_examiner(git, &mut policy, true, verbose) else {
+        return Ok(3);
+    };
+
+    let diff = common::context_diff(git, &policy, None, amend_base.as_deref(), diff)?;
     let (redacted_diff, redactions) = crate::redact::redact_diff(&policy, &diff)?;
-    let ctx = ExamContext::new(
+    let risk = common::apply_risk(git, &mut policy, &redacted_diff, &changed_files, verbose)?;
+    let mut ctx = ExamContext::new(
         git,
         diff_patch_id,
         &redacted_diff,
@@ -25,29 +60,204 @@ pub(crate) fn cmd_commit(git: &Git, args: CommitArgs, verbose: bool) -> Result<u
         redactions,
         &policy,
     )?;
+    ctx.risk = Some(risk);
+    ctx.commit_message = args.message.clone();
+    common::apply_work_item(git, &policy, &mut ctx, args.context_file.as_deref())?;
+    common::apply_author_profile(git, &policy, &mut ctx, verbose);
 
-    let examiner: Box<dyn Examiner> = common::build_examiner(&policy);
     if verbose {
         eprintln!("aigit: examiner: {}", common::examiner_label(&policy));
     }
-    let exam = examiner.generate_exam(&ctx)?;
-    let answers = crate::transcript::Answers::prompt_tui(&exam)?;
-    let score = examiner.grade_exam(&ctx, &exam, &answers)?;
-    let decision = crate::transcript::Decision::from_score(&policy, &exam, &answers, &score);
+    let exam =
+        common::generate_exam(git, &policy, examiner.as_ref(), &ctx, args.no_cache, verbose)?;
+    let mut answers = if policy.exam_mode.as_deref() == Some("editor") {
+        Answers::prompt_editor(git, &exam, &ctx, None)?
+    } else {
+        Answers::prompt_tui(&exam, &ctx, None)?
+    };
+    crate::respondents::attribute(git, &policy, &mut answers);
+    PendingExam {
+        created_at: chrono::Utc::now(),
+        command: "commit".to_string(),
+        range: None,
+        diff_patch_id: ctx.diff_patch_id.clone(),
+        exam: exam.clone(),
+        answers: answers.clone(),
+        message: args.message.clone(),
+        git_args: git_args.clone(),
+        dry_run: args.dry_run,
+    }
+    .save(&git.repo)?;
+
+    finish_commit(
+        git,
+        &policy,
+        &ctx,
+        examiner.as_ref(),
+        &exam,
+        &answers,
+        args.message.as_deref(),
+        &git_args,
+        args.dry_run,
+        verbose,
+        None,
+    )
+}
+
+/// Whether the `git commit` arguments amend HEAD (`aigit commit --amend`, or `--amend` passed
+/// through after `--`).
+pub(crate) fn amends(git_args: &[String]) -> bool {
+    git_args.iter().any(|a| a == "--amend")
+}
+
+/// `aigit commit --amend` for an amend that keeps HEAD's change (a new message, author or
+/// trailer): moves HEAD's passing transcript to the amended commit without another exam. `None`
+/// when HEAD has no such transcript for the same patch-id, and the amended commit needs one.
+fn amend_without_exam(
+    git: &Git,
+    policy: &Policy,
+    patch_id: &str,
+    message: Option<&str>,
+    git_args: &[String],
+    dry_run: bool,
+) -> Result<Option<u8>> {
+    let store = TranscriptStore::from_policy(policy)?;
+    let head = git.rev_parse_head()?;
+    let Ok(mut transcript) = store.load(&git.repo, &head) else {
+        return Ok(None);
+    };
+    if transcript.diff_fingerprint.patch_id != patch_id || transcript.decision != Decision::Pass {
+        return Ok(None);
+    }
+    eprintln!(
+        "aigit: the amend keeps the change of {}, so its transcript carries over without a new exam",
+        &head[..head.len().min(12)]
+    );
+    transcript.amended = Some(Amended {
+        from: head,
+        timestamp: Utc::now(),
+        reexamined: false,
+    });
+    if dry_run {
+        serde_json::to_writer_pretty(std::io::stdout(), &transcript)?;
+        println!();
+        eprintln!("aigit: dry run: not committing; the transcript above would be stored in git notes");
+        return Ok(Some(0));
+    }
+    commit_and_store(git, policy, transcript, message, git_args).map(Some)
+}
+
+/// Grades saved-or-fresh answers and, on a pass, runs `git commit` and stores the transcript.
+/// On a failure, offers to retake the weak questions; `retaken` lists the questions of the
+/// retake being graded, if this is one.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn finish_commit(
+    git: &Git,
+    policy: &Policy,
+    ctx: &ExamContext,
+    examiner: &dyn Examiner,
+    exam: &Exam,
+    answers: &Answers,
+    message: Option<&str>,
+    git_args: &[String],
+    dry_run: bool,
+    verbose: bool,
+    retaken: Option<&[String]>,
+) -> Result<u8> {
+    let score = match examiner.grade_exam(ctx, exam, answers) {
+        Ok(score) => score,
+        Err(err) => return Ok(common::report_grading_failure(git, policy, &err)),
+    };
+    common::record_provider_success(git, policy);
+    PendingExam::clear(&git.repo);
+    let decision = crate::transcript::Decision::from_score(policy, exam, answers, &score, &ctx.changed_files);
 
     let mut transcript =
-        crate::transcript::Transcript::from_exam_result(git, &policy, &ctx, &exam, &answers, &score, decision)?;
+        crate::transcript::Transcript::from_exam_result(git, policy, ctx, exam, answers, &score, decision)?;
+    // A dry run leaves no failed exam to retake behind.
+    let attempts = if dry_run {
+        Vec::new()
+    } else {
+        crate::retake::record(&git.repo, ctx, None, exam, answers, &score, decision, retaken)?
+    };
+    let tries = attempts.len();
+    if tries > 1 {
+        transcript.attempts = attempts;
+        transcript.withhold_answers(crate::transcript::AnswerStorage::from_policy(policy));
+    }
 
     if verbose {
         eprintln!("exam decision: {:?}", transcript.decision);
     }
-    crate::transcript::print_human_result(&transcript);
+    crate::transcript::print_human_result(&transcript, policy);
+    if dry_run {
+        serde_json::to_writer_pretty(std::io::stdout(), &transcript)?;
+        println!();
+        if transcript.decision == Decision::Pass {
+            eprintln!("aigit: dry run: not committing; the transcript above would be stored in git notes");
+        }
+        return Ok(match transcript.decision {
+            Decision::Pass => 0,
+            Decision::Fail => 2,
+        });
+    }
     if transcript.decision != Decision::Pass {
-        return Ok(2);
+        if tries >= policy.retake.max_attempts() {
+            return Ok(2);
+        }
+        return retake(git, policy, ctx, examiner, message, git_args, verbose);
     }
+    commit_and_store(git, policy, transcript, message, git_args)
+}
 
+/// Runs `git commit` for a passing transcript and stores the transcript for the new commit. An
+/// amend moves HEAD's transcript: the new one takes its place in the chain and its note is
+/// dropped.
+fn commit_and_store(
+    git: &Git,
+    policy: &Policy,
+    mut transcript: Transcript,
+    message: Option<&str>,
+    git_args: &[String],
+) -> Result<u8> {
+    let store = TranscriptStore::from_policy(policy)?;
     let head_before = git.rev_parse_head().ok();
-    git.run_git_commit(args.message.as_deref(), &args.git_args)?;
+    let replaced = match &head_before {
+        Some(head) if amends(git_args) => {
+            transcript.amended.get_or_insert_with(|| Amended {
+                from: head.clone(),
+                timestamp: Utc::now(),
+                reexamined: true,
+            });
+            store.load(&git.repo, head).ok()
+        }
+        _ => None,
+    };
+
+    // The trailer and tree stores put the transcript in the commit, so it is completed before
+    // the commit exists and does not name it.
+    let mut git_args = git_args.to_vec();
+    let mut message = message.map(str::to_string);
+    if store.in_commit() {
+        transcript.commit = None;
+        seal(git, policy, &store, &mut transcript, replaced.as_ref())?;
+        for trailer in store.trailers(&transcript)? {
+            git_args.extend(["--trailer".to_string(), trailer]);
+        }
+        if let Some(old) = &replaced {
+            if message.is_none() {
+                message = Some(without_transcript_trailers(
+                    &git.commit_message("HEAD")?,
+                ));
+            }
+            if old.diff_fingerprint.patch_id != transcript.diff_fingerprint.patch_id {
+                store.unstage(&git.repo, old)?;
+            }
+        }
+        store.stage(&git.repo, &transcript)?;
+    }
+
+    git.run_git_commit(message.as_deref(), &git_args)?;
     let head_after = git
         .rev_parse_head()
         .context("failed to read new HEAD after commit")?;
@@ -55,14 +265,126 @@ pub(crate) fn cmd_commit(git: &Git, args: CommitArgs, verbose: bool) -> Result<u
         return Err(anyhow!("git commit did not create a new commit"));
     }
 
-    transcript.commit = Some(head_after.clone());
-    let store = TranscriptStore::git_notes();
+    if !store.in_commit() {
+        transcript.commit = Some(head_after.clone());
+        if let Err(err) = seal(git, policy, &store, &mut transcript, replaced.as_ref()) {
+            eprintln!("aigit: {err:#}");
+            return Ok(4);
+        }
+    }
     if let Err(err) = store.store(&git.repo, &head_after, &transcript) {
         eprintln!("aigit: failed to store transcript: {err}");
         return Ok(4);
     }
+    if let (Some(_), Some(old_commit)) = (&replaced, &head_before) {
+        if let Err(err) = store.remove(&git.repo, old_commit) {
+            eprintln!(
+                "aigit: warning: {err:#}; the transcript of {old_commit} is still in git notes"
+            );
+        }
+    }
 
-    eprintln!("aigit: stored transcript in git notes for {head_after}");
+    eprintln!(
+        "aigit: stored transcript in {} for {head_after}",
+        store.describe(&transcript)
+    );
+    if policy.notes.auto_push.unwrap_or(false) {
+        let remote = crate::notes_sync::remote(policy, None);
+        match crate::notes_sync::push(
+            &git.repo,
+            &remote,
+            crate::notes_sync::MergeStrategy::from_policy(policy),
+        ) {
+            Ok(_) => eprintln!("aigit: pushed transcripts to {remote}"),
+            Err(err) => eprintln!(
+                "aigit: warning: {err:#}; run `aigit notes push` to share the transcript"
+            ),
+        }
+    }
     Ok(0)
 }
 
+/// Links a passing transcript into the chain and signs it, as the policy asks. A transcript
+/// that `replaces` another (on an amend) takes its place in the chain instead.
+fn seal(
+    git: &Git,
+    policy: &Policy,
+    store: &TranscriptStore,
+    t: &mut Transcript,
+    replaces: Option<&Transcript>,
+) -> Result<()> {
+    match replaces.filter(|old| old.chain.is_some()) {
+        Some(old) => t.chain = old.chain.clone(),
+        None => store
+            .link(&git.repo, t)
+            .context("failed to link transcript into the chain")?,
+    }
+    crate::signing::sign_if_enabled(git, policy, t).context("failed to sign transcript")
+}
+
+/// `message` without the `Aigit-Transcript` trailers of the transcript it is being amended away
+/// from.
+fn without_transcript_trailers(message: &str) -> String {
+    message
+        .lines()
+        .filter(|line| {
+            line.split_once(':')
+                .is_none_or(|(key, _)| key != TRAILER_KEY && key != TRAILER_DATA_KEY)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Offers to ask the weak questions of the exam that just failed again, and commits if the
+/// merged answers pass. Anything but "y" (including no terminal) declines.
+fn retake(
+    git: &Git,
+    policy: &Policy,
+    ctx: &ExamContext,
+    examiner: &dyn Examiner,
+    message: Option<&str>,
+    git_args: &[String],
+    verbose: bool,
+) -> Result<u8> {
+    let Some(state) = RetakeState::load(&git.repo, &ctx.diff_patch_id)? else {
+        return Ok(2);
+    };
+    let weak = state.weak_questions(policy);
+    eprint!(
+        "aigit: retake the {} weak question(s) now? [y/N] ",
+        weak.len()
+    );
+    let mut reply = String::new();
+    std::io::stdin().read_line(&mut reply)?;
+    if !matches!(reply.trim(), "y" | "Y" | "yes") {
+        eprintln!("aigit: retake later with `aigit exam --retake`");
+        return Ok(2);
+    }
+    let retake = state.retake_exam(
+        examiner,
+        ctx,
+        &weak,
+        policy.retake.follow_ups.unwrap_or(false),
+    );
+    let answers = if policy.exam_mode.as_deref() == Some("editor") {
+        Answers::prompt_editor(git, &retake, ctx, None)?
+    } else {
+        Answers::prompt_tui(&retake, ctx, None)?
+    };
+    let (exam, mut answers) = state.merge(&retake, answers);
+    crate::respondents::attribute(git, policy, &mut answers);
+    let retaken: Vec<String> = retake.questions.iter().map(|q| q.id.clone()).collect();
+    finish_commit(
+        git,
+        policy,
+        ctx,
+        examiner,
+        &exam,
+        &answers,
+        message,
+        git_args,
+        false,
+        verbose,
+        Some(&retaken),
+    )
+}
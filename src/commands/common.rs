@@ -1,8 +1,19 @@
-use anyhow::Result;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
 
 use crate::config::Policy;
-use crate::examiner::{CodexCliExaminer, Examiner, StaticExaminer};
+use crate::examiner::{
+    AnswerKeyExaminer, AnthropicExaminer, ClaudeCliExaminer, CodexCliExaminer, Exam, ExamContext,
+    Examiner, ExecExaminer, HttpApiExaminer, HybridExaminer, NormalizedExaminer, OllamaExaminer,
+    QualityCheckedExaminer, RateLimitedExaminer, SampledExaminer, SelfCheckExaminer,
+    StaticExaminer, TokenBudget,
+};
 use crate::git::Git;
+use crate::health::ProviderHealth;
+use crate::profile::AuthorProfile;
+use crate::risk::{RiskAssessment, RiskLevel};
+use crate::transcript::TranscriptStore;
 
 pub(crate) fn load_policy_verbose(git: &Git, verbose: bool) -> Result<Policy> {
     let policy = Policy::load_from_repo(&git.repo)?;
@@ -21,6 +32,13 @@ pub(crate) fn load_policy_verbose(git: &Git, verbose: bool) -> Result<Policy> {
             "aigit: provider: {}",
             policy.provider.clone().unwrap_or_else(|| "local".to_string())
         );
+        let shape = git.clone_shape();
+        if let Some(remote) = &shape.promisor_remote {
+            eprintln!("aigit: partial clone (promisor remote: {remote})");
+        }
+        if shape.sparse_checkout {
+            eprintln!("aigit: sparse checkout");
+        }
     }
     Ok(policy)
 }
@@ -28,14 +46,337 @@ pub(crate) fn load_policy_verbose(git: &Git, verbose: bool) -> Result<Policy> {
 pub(crate) fn examiner_label(policy: &Policy) -> &'static str {
     match policy.provider.as_deref() {
         Some("codex-cli") => "codex-cli",
+        Some("claude-cli") => "claude-cli",
+        Some("exec") => "exec",
+        Some("http-api") => "http-api",
+        Some("anthropic") => "anthropic",
+        Some("ollama") => "ollama",
         _ => "local-static",
     }
 }
 
+/// Grading failed after the answers were saved; tell the user how to retry. Returns exit code 3.
+pub(crate) fn report_grading_failure(git: &Git, policy: &Policy, err: &anyhow::Error) -> u8 {
+    eprintln!("aigit: grading failed: {err:#}");
+    let provider = examiner_label(policy);
+    if provider != "local-static"
+        && ProviderHealth::record(&git.repo, provider, &policy.health, Err(err))
+    {
+        eprintln!("aigit: {provider} keeps failing; the circuit breaker is now open");
+    }
+    eprintln!("aigit: your answers were saved; retry grading with `aigit grade --resume`");
+    3
+}
+
+/// A provider call succeeded. The circuit breaker counts consecutive failures, so this resets
+/// the count.
+pub(crate) fn record_provider_success(git: &Git, policy: &Policy) {
+    let provider = examiner_label(policy);
+    if provider != "local-static" {
+        ProviderHealth::record(&git.repo, provider, &policy.health, Ok(()));
+    }
+}
+
+/// Picks the examiner for an exam that is about to start, consulting the circuit breaker and (for
+/// interactive exams) pinging the provider first. May switch `policy.provider` to
+/// `health.fallback_provider`, so transcripts record the provider that actually graded. Returns
+/// `None` after reporting why the exam cannot start.
+pub(crate) fn select_examiner(
+    git: &Git,
+    policy: &mut Policy,
+    interactive: bool,
+    verbose: bool,
+) -> Option<Box<dyn Examiner>> {
+    let provider = examiner_label(policy);
+    if provider == "local-static" {
+        return Some(build_examiner(policy));
+    }
+    let health = ProviderHealth::load(&git.repo);
+    if let Some(until) = health.open_until(provider, chrono::Utc::now()) {
+        let failures = health.providers[provider].consecutive_failures;
+        return use_fallback(
+            git,
+            policy,
+            &format!("{provider} failed {failures} times in a row (circuit open until {until})"),
+        );
+    }
+    if !interactive || !policy.health.check.unwrap_or(true) {
+        return Some(build_examiner(policy));
+    }
+
+    let examiner = build_examiner(policy);
+    let timeout = Duration::from_secs(policy.health.timeout_secs.unwrap_or(10));
+    match examiner.health_check(timeout) {
+        Ok(()) => {
+            ProviderHealth::record(&git.repo, provider, &policy.health, Ok(()));
+            if verbose {
+                eprintln!("aigit: health check: {provider} is up");
+            }
+            Some(examiner)
+        }
+        Err(err) => {
+            let tripped = ProviderHealth::record(&git.repo, provider, &policy.health, Err(&err));
+            let reason = format!("{provider} health check failed: {err:#}");
+            if tripped {
+                use_fallback(git, policy, &reason)
+            } else {
+                eprintln!("aigit: {reason}");
+                eprintln!("aigit: not starting the exam; fix the provider or retry later");
+                None
+            }
+        }
+    }
+}
+
+fn use_fallback(git: &Git, policy: &mut Policy, reason: &str) -> Option<Box<dyn Examiner>> {
+    let Some(fallback) = policy.health.fallback_provider.clone() else {
+        eprintln!("aigit: {reason}");
+        eprintln!(
+            "aigit: not starting the exam; set health.fallback_provider or delete {} to retry now",
+            git.repo.git_dir.join("aigit").join("provider-health.json").display()
+        );
+        return None;
+    };
+    eprintln!("aigit: {reason}; using fallback provider {fallback}");
+    policy.provider = Some(fallback);
+    Some(build_examiner(policy))
+}
+
 pub(crate) fn build_examiner(policy: &Policy) -> Box<dyn Examiner> {
-    match policy.provider.as_deref() {
+    let mut generator = examiner_for(policy.provider.as_deref(), policy);
+    if examiner_label(policy) != "local-static" && policy.exam_quality.enabled.unwrap_or(true) {
+        generator = Box::new(QualityCheckedExaminer::new(generator, policy));
+    }
+    let examiner = match policy.grader.as_deref() {
+        Some(grader) if Some(grader) != policy.provider.as_deref() => Box::new(HybridExaminer::new(
+            generator,
+            examiner_for(Some(grader), policy),
+        )),
+        _ => generator,
+    };
+    let examiner: Box<dyn Examiner> = if policy.grading.samples.unwrap_or(1) > 1 {
+        Box::new(SampledExaminer::new(examiner, policy))
+    } else {
+        examiner
+    };
+    let examiner: Box<dyn Examiner> = Box::new(AnswerKeyExaminer::new(examiner));
+    let examiner: Box<dyn Examiner> = if policy.self_check.enabled.unwrap_or(false) {
+        Box::new(SelfCheckExaminer::new(examiner))
+    } else {
+        examiner
+    };
+    Box::new(NormalizedExaminer::new(examiner, policy))
+}
+
+/// The examiner for a provider name; anything unrecognized (including "local" and "static") is
+/// the built-in static examiner.
+fn examiner_for(provider: Option<&str>, policy: &Policy) -> Box<dyn Examiner> {
+    let examiner: Box<dyn Examiner> = match provider {
         Some("codex-cli") => Box::new(CodexCliExaminer::new(policy)),
-        _ => Box::new(StaticExaminer::new()),
+        Some("claude-cli") => Box::new(ClaudeCliExaminer::new(policy)),
+        Some("exec") => Box::new(ExecExaminer::new(policy)),
+        Some("http-api") => Box::new(HttpApiExaminer::new(policy)),
+        Some("anthropic") => Box::new(AnthropicExaminer::new(policy)),
+        Some("ollama") => Box::new(OllamaExaminer::new(policy)),
+        _ => return Box::new(StaticExaminer::new()),
+    };
+    // Only calls to a provider count against `[rate_limit]`.
+    Box::new(RateLimitedExaminer::new(examiner, provider.unwrap_or_default()))
+}
+
+/// The diff the examiner sees: `diff` itself, or with `context_lines` /
+/// `include_function_context`, each file's change with its surrounding code for as many files as
+/// the token budget allows. `range` is `None` for the staged changes, taken against
+/// `staged_base` when set (HEAD otherwise).
+pub(crate) fn context_diff(
+    git: &Git,
+    policy: &Policy,
+    range: Option<&str>,
+    staged_base: Option<&str>,
+    diff: String,
+) -> Result<String> {
+    let context_lines = policy.context_lines.unwrap_or(0);
+    let function_context = policy.include_function_context.unwrap_or(false);
+    if context_lines == 0 && !function_context {
+        return Ok(diff);
     }
+    let enriched = git.diff_with_context(range, staged_base, context_lines, function_context)?;
+    let budget = TokenBudget::for_policy(policy, &git.repo.workdir)?;
+    Ok(budget.with_context(&diff, &enriched))
 }
 
+/// Generates the exam for `ctx`, reusing the cached exam for the same diff, provider, model and
+/// prompt version unless `no_cache`. The built-in examiner is cheap and is never cached.
+pub(crate) fn generate_exam(
+    git: &Git,
+    policy: &Policy,
+    examiner: &dyn Examiner,
+    ctx: &ExamContext,
+    no_cache: bool,
+    verbose: bool,
+) -> Result<Exam> {
+    if examiner_label(policy) == "local-static" {
+        let mut exam = examiner.generate_exam(ctx)?;
+        crate::memory_safety::ensure_question(ctx, &mut exam);
+        crate::performance::ensure_question(ctx, &mut exam);
+        crate::api_surface::ensure_question(ctx, &mut exam);
+        crate::config_changes::ensure_question(ctx, &mut exam);
+        crate::ci_pipeline::ensure_question(ctx, &mut exam);
+        crate::question_links::link(ctx, &mut exam);
+        return Ok(exam);
+    }
+    if !no_cache {
+        if let Some(exam) = crate::cache::load(&git.repo, ctx) {
+            if verbose {
+                eprintln!("aigit: cache: reusing the exam generated earlier for this diff");
+            }
+            return Ok(exam);
+        }
+    }
+    let mut exam = examiner.generate_exam(ctx)?;
+    record_provider_success(git, policy);
+    crate::memory_safety::ensure_question(ctx, &mut exam);
+    crate::performance::ensure_question(ctx, &mut exam);
+    crate::api_surface::ensure_question(ctx, &mut exam);
+    crate::config_changes::ensure_question(ctx, &mut exam);
+    crate::ci_pipeline::ensure_question(ctx, &mut exam);
+    crate::question_links::link(ctx, &mut exam);
+    exam.tier = crate::exam_tiers::select(ctx).map(|(name, _)| name.to_string());
+    if let Err(err) = crate::cache::store(&git.repo, ctx, &exam) {
+        eprintln!("aigit: cache: failed to save exam: {err:#}");
+    }
+    Ok(exam)
+}
+
+/// When `profiles.enabled`, refreshes the committer's profile and focuses the exam on their
+/// weakest categories. Profile problems never block an exam.
+pub(crate) fn apply_author_profile(git: &Git, policy: &Policy, ctx: &mut ExamContext, verbose: bool) {
+    if !policy.profiles.enabled.unwrap_or(false) {
+        return;
+    }
+    let Some(email) = git.user_email() else {
+        if verbose {
+            eprintln!("aigit: profiles: user.email is not set; skipping");
+        }
+        return;
+    };
+    let profile = match TranscriptStore::from_policy(policy)
+        .and_then(|store| AuthorProfile::refresh(git, &store, &email))
+    {
+        Ok(p) => p,
+        Err(err) => {
+            eprintln!("aigit: profiles: failed to build profile: {err}");
+            return;
+        }
+    };
+    if profile.exams < policy.profiles.min_exams.unwrap_or(3) {
+        if verbose {
+            eprintln!("aigit: profiles: only {} past exams; not adapting", profile.exams);
+        }
+        return;
+    }
+    ctx.focus_categories = profile.weak_categories(
+        policy.profiles.weak_threshold.unwrap_or(0.75),
+        policy.profiles.max_focus.unwrap_or(2),
+    );
+    if verbose && !ctx.focus_categories.is_empty() {
+        eprintln!(
+            "aigit: profiles: focusing on {}",
+            ctx.focus_categories.join(", ")
+        );
+    }
+}
+
+/// Work item descriptions longer than this are cut before they reach the prompt.
+const MAX_WORK_ITEM_CHARS: usize = 8_000;
+
+/// Adds the issue or pull request description to the exam context: `context_file`, or else
+/// `.git/PULLREQ_EDITMSG` when a pull request is being written, i.e. it was written after HEAD
+/// was committed (an older one describes an earlier pull request). It is redacted like the diff,
+/// and `#` comment lines of `PULLREQ_EDITMSG` are dropped as git drops them from messages.
+pub(crate) fn apply_work_item(
+    git: &Git,
+    policy: &Policy,
+    ctx: &mut ExamContext,
+    context_file: Option<&str>,
+) -> Result<()> {
+    let (path, explicit) = match context_file {
+        Some(file) => (std::path::PathBuf::from(file), true),
+        None => (git.repo.git_dir.join("PULLREQ_EDITMSG"), false),
+    };
+    let raw = match std::fs::read_to_string(&path) {
+        Ok(raw) => raw,
+        Err(err) if !explicit && err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(anyhow!("failed to read {}: {err}", path.display())),
+    };
+    if !explicit {
+        let modified = std::fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .map(chrono::DateTime::<chrono::Utc>::from);
+        if let (Ok(modified), Some(head)) = (modified, git.head_commit_time()) {
+            if modified < head {
+                eprintln!(
+                    "aigit: ignoring {}, which predates HEAD; pass --context-file to use it",
+                    path.display()
+                );
+                return Ok(());
+            }
+        }
+    }
+    let text = if explicit {
+        raw
+    } else {
+        raw.lines()
+            .filter(|l| !l.starts_with('#'))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(());
+    }
+    let mut cut: String = text.chars().take(MAX_WORK_ITEM_CHARS).collect();
+    if cut.len() < text.len() {
+        cut.push_str("\n[aigit: description truncated]");
+    }
+    let (text, hits) = crate::redact::redact_diff(policy, &cut)?;
+    for hit in hits {
+        match ctx.redactions.iter_mut().find(|h| h.pattern == hit.pattern) {
+            Some(existing) => existing.count += hit.count,
+            None => ctx.redactions.push(hit),
+        }
+    }
+    eprintln!(
+        "aigit: including the work item description from {}",
+        path.display()
+    );
+    ctx.work_item = Some(text);
+    Ok(())
+}
+
+/// Assesses how risky the change is and applies its `[risk.<level>]` settings to the policy
+/// before the exam context is built: a stricter `min_total_score` and the level's packs.
+pub(crate) fn apply_risk(
+    git: &Git,
+    policy: &mut Policy,
+    diff: &str,
+    changed_files: &[String],
+    verbose: bool,
+) -> Result<RiskAssessment> {
+    let risk = crate::risk::assess(policy, diff, changed_files);
+    if verbose || risk.level != RiskLevel::Low {
+        eprintln!("aigit: risk: {}", risk.summary());
+    }
+    let Some(level) = crate::risk::level_policy(policy, risk.level).cloned() else {
+        return Ok(risk);
+    };
+    policy.min_total_score = crate::risk::min_total_score(policy, Some(risk.level));
+    let packs: Vec<String> = level
+        .packs
+        .iter()
+        .filter(|p| !policy.packs.contains(p))
+        .cloned()
+        .collect();
+    crate::packs::apply_entries(&git.repo, policy, &packs)?;
+    Ok(risk)
+}
@@ -0,0 +1,84 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+
+use crate::approvals::Countersignature;
+use crate::cli::CountersignArgs;
+use crate::config::Policy;
+use crate::git::Git;
+use crate::transcript::TranscriptStore;
+
+/// Adds the local user's countersignature to a commit's transcript, for the `approvals.groups`
+/// that need one and list the user as an approver.
+pub(crate) fn cmd_countersign(git: &Git, args: CountersignArgs) -> Result<u8> {
+    let policy = Policy::load_from_repo(&git.repo)?;
+    let commit = git.resolve_commitish(&args.commitish)?;
+    let short = &commit[..commit.len().min(12)];
+    let store = TranscriptStore::from_policy(&policy)?;
+    let mut transcript = store
+        .load(&git.repo, &commit)
+        .with_context(|| format!("no transcript stored for {commit}"))?;
+    let email = git
+        .user_email()
+        .ok_or_else(|| anyhow!("set git config user.email to countersign"))?;
+    if git
+        .commit_meta(&commit)?
+        .author_email
+        .eq_ignore_ascii_case(&email)
+    {
+        return Err(anyhow!(
+            "{short} is your own commit; an approver must countersign it"
+        ));
+    }
+
+    let changed_files = git.changed_files_for_commit(&commit)?;
+    let required = crate::approvals::required(&policy, &transcript.exam, &changed_files);
+    if required.is_empty() {
+        return Err(anyhow!(
+            "{short} needs no countersignature under the approvals policy"
+        ));
+    }
+    let mut groups = Vec::new();
+    for group in &required {
+        if group.allows(&git.repo, &email)? {
+            groups.push(group.name.clone());
+        }
+    }
+    if groups.is_empty() {
+        let names: Vec<&str> = required.iter().map(|g| g.name.as_str()).collect();
+        return Err(anyhow!(
+            "{email} is not an approver for {short}, which needs: {}",
+            names.join(", ")
+        ));
+    }
+    if transcript.countersignatures.iter().any(|c| {
+        c.email.eq_ignore_ascii_case(&email)
+            && c.groups == groups
+            && crate::signing::check_countersignature(&git.repo, &policy, &transcript, c).is_ok()
+    }) {
+        eprintln!("aigit: {short} is already countersigned by {email}");
+        return Ok(0);
+    }
+
+    let signature = crate::signing::sign_bytes(
+        git,
+        crate::signing::COUNTERSIGNATURE_NAMESPACE,
+        transcript.content_hash()?.as_bytes(),
+    )
+    .context("failed to sign the countersignature")?;
+    transcript
+        .countersignatures
+        .retain(|c| !c.email.eq_ignore_ascii_case(&email));
+    transcript.countersignatures.push(Countersignature {
+        name: git.user_name(),
+        email: email.clone(),
+        timestamp: Utc::now(),
+        groups: groups.clone(),
+        signature: Some(signature),
+    });
+    store.store(&git.repo, &commit, &transcript)?;
+    eprintln!(
+        "aigit: countersigned {short} as {email} for {}",
+        groups.join(", ")
+    );
+    Ok(0)
+}
@@ -1,43 +1,90 @@
+use std::collections::BTreeSet;
 use std::io::{Read, Write};
 use std::net::{TcpListener, TcpStream};
 use std::path::{Path, PathBuf};
 
-use anyhow::{bail, Context, Result};
+use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, Utc};
 use serde::Serialize;
 
 use crate::cli::{DashboardExportArgs, DashboardServeArgs};
-use crate::git::Git;
+use crate::commands::org_export::{coverage, Coverage, OrgEntry, OrgExport, ORG_EXPORT_SCHEMA};
+use crate::config::Policy;
+use crate::git::{CommitMeta, Git};
 use crate::transcript::{Transcript, TranscriptStore};
 
-#[derive(Debug, Clone, Serialize)]
-struct CommitMeta {
-    sha: String,
-    author_name: String,
-    author_email: String,
-    author_date_iso: String,
-    subject: String,
-}
-
 #[derive(Debug, Clone, Serialize)]
 struct DashboardEntry {
+    repo_id: String,
     commit: CommitMeta,
     transcript: Transcript,
 }
 
+/// One repository in the dataset, with its coverage and score statistics.
+#[derive(Debug, Clone, Serialize)]
+struct DashboardRepo {
+    repo_id: String,
+    sources: Vec<String>,
+    stats: Coverage,
+}
+
 #[derive(Debug, Clone, Serialize)]
 struct DashboardExport {
     schema_version: String,
     generated_at: DateTime<Utc>,
-    repo_id: String,
+    /// Set when the dataset holds a single repository.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repo_id: Option<String>,
+    repos: Vec<DashboardRepo>,
     entries: Vec<DashboardEntry>,
 }
 
+/// Repositories and their transcripts, before sorting and `--limit`.
+type Dataset = (Vec<DashboardRepo>, Vec<DashboardEntry>);
+
 pub(crate) fn cmd_dashboard_export(git: &Git, args: DashboardExportArgs) -> Result<u8> {
+    let (repos, mut entries) = match &args.org {
+        Some(path) => from_org_export(Path::new(path), args.include_answers)?,
+        None => from_repo(git, args.include_answers)?,
+    };
+
+    entries.sort_by(|a, b| b.commit.author_date_iso.cmp(&a.commit.author_date_iso));
+    if let Some(limit) = args.limit {
+        entries.truncate(limit);
+    }
+
+    let export = DashboardExport {
+        schema_version: "aigit-dashboard/0.2".to_string(),
+        generated_at: Utc::now(),
+        repo_id: match repos.as_slice() {
+            [only] => Some(only.repo_id.clone()),
+            _ => None,
+        },
+        repos,
+        entries,
+    };
+
+    let out_path = PathBuf::from(args.out);
+    if let Some(parent) = out_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create output directory {}", parent.display()))?;
+    }
+    let json = serde_json::to_string_pretty(&export)?;
+    std::fs::write(&out_path, json)
+        .with_context(|| format!("failed to write {}", out_path.display()))?;
+
+    eprintln!("aigit: dashboard: wrote {}", out_path.display());
+    Ok(0)
+}
+
+/// This repository's transcripts from git notes.
+fn from_repo(git: &Git, include_answers: bool) -> Result<Dataset> {
+    let policy = Policy::load_from_repo(&git.repo)?;
+    let repo_id = crate::repo_id::resolve(git, &policy)?;
     let store = TranscriptStore::git_notes();
     let mut entries = Vec::new();
-    for sha in list_note_commits(git).unwrap_or_default() {
-        let meta = match commit_meta(git, &sha) {
+    for sha in store.list(&git.repo).unwrap_or_default() {
+        let meta = match git.commit_meta(&sha) {
             Ok(m) => m,
             Err(e) => {
                 eprintln!("aigit: dashboard: skipping {sha}: failed to read commit metadata: {e}");
@@ -52,35 +99,67 @@ pub(crate) fn cmd_dashboard_export(git: &Git, args: DashboardExportArgs) -> Resu
             }
         };
         t.commit = Some(sha.clone());
-        if !args.include_answers {
+        if !include_answers {
             t.answers.answers.clear();
         }
-        entries.push(DashboardEntry { commit: meta, transcript: t });
-    }
-
-    entries.sort_by(|a, b| b.commit.author_date_iso.cmp(&a.commit.author_date_iso));
-    if let Some(limit) = args.limit {
-        entries.truncate(limit);
+        entries.push(OrgEntry { commit: meta, transcript: t });
     }
 
-    let export = DashboardExport {
-        schema_version: "aigit-dashboard/0.1".to_string(),
-        generated_at: Utc::now(),
-        repo_id: git.repo.workdir.to_string_lossy().to_string(),
-        entries,
+    let history: BTreeSet<String> = git
+        .rev_list("HEAD")
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    let repo = DashboardRepo {
+        repo_id: repo_id.clone(),
+        sources: vec![git.repo.workdir.display().to_string()],
+        stats: coverage(history.len(), entries.iter().map(|e| (&history, e))),
     };
+    let entries = entries
+        .into_iter()
+        .map(|e| DashboardEntry {
+            repo_id: repo_id.clone(),
+            commit: e.commit,
+            transcript: e.transcript,
+        })
+        .collect();
+    Ok((vec![repo], entries))
+}
 
-    let out_path = PathBuf::from(args.out);
-    if let Some(parent) = out_path.parent() {
-        std::fs::create_dir_all(parent)
-            .with_context(|| format!("failed to create output directory {}", parent.display()))?;
+/// Every repository of an `aigit org-export` dataset, keeping its per-repository statistics.
+fn from_org_export(path: &Path, include_answers: bool) -> Result<Dataset> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let org: OrgExport = serde_json::from_str(&raw)
+        .with_context(|| format!("failed to parse {} as an org-export dataset", path.display()))?;
+    if org.schema_version != ORG_EXPORT_SCHEMA {
+        return Err(anyhow!(
+            "{}: unsupported schema_version {} (expected {ORG_EXPORT_SCHEMA})",
+            path.display(),
+            org.schema_version
+        ));
     }
-    let json = serde_json::to_string_pretty(&export)?;
-    std::fs::write(&out_path, json)
-        .with_context(|| format!("failed to write {}", out_path.display()))?;
 
-    eprintln!("aigit: dashboard: wrote {}", out_path.display());
-    Ok(0)
+    let mut repos = Vec::new();
+    let mut entries = Vec::new();
+    for (repo_id, repo) in org.repos {
+        for mut e in repo.entries {
+            if !include_answers {
+                e.transcript.answers.answers.clear();
+            }
+            entries.push(DashboardEntry {
+                repo_id: repo_id.clone(),
+                commit: e.commit,
+                transcript: e.transcript,
+            });
+        }
+        repos.push(DashboardRepo {
+            repo_id,
+            sources: repo.sources,
+            stats: repo.stats,
+        });
+    }
+    Ok((repos, entries))
 }
 
 pub(crate) fn cmd_dashboard_serve(git: &Git, args: DashboardServeArgs) -> Result<u8> {
@@ -89,12 +168,25 @@ pub(crate) fn cmd_dashboard_serve(git: &Git, args: DashboardServeArgs) -> Result
         .canonicalize()
         .with_context(|| format!("failed to resolve dashboard dir {}", dir.display()))?;
 
+    let data = match &args.data {
+        Some(path) => {
+            let path = git.repo.workdir.join(path);
+            Some(path.canonicalize().with_context(|| {
+                format!("failed to resolve dashboard data {}", path.display())
+            })?)
+        }
+        None => None,
+    };
+
     let bind = format!("{}:{}", args.host, args.port);
     let listener = TcpListener::bind(&bind).with_context(|| format!("failed to bind {bind}"))?;
     eprintln!(
         "aigit: dashboard: serving {} on http://{bind}",
         dir.display()
     );
+    if let Some(data) = &data {
+        eprintln!("aigit: dashboard: serving {} as /data.json", data.display());
+    }
     eprintln!("aigit: dashboard: press Ctrl+C to stop");
 
     for conn in listener.incoming() {
@@ -106,8 +198,9 @@ pub(crate) fn cmd_dashboard_serve(git: &Git, args: DashboardServeArgs) -> Result
             }
         };
         let dir = dir.clone();
+        let data = data.clone();
         std::thread::spawn(move || {
-            if let Err(e) = handle_http(&mut stream, &dir) {
+            if let Err(e) = handle_http(&mut stream, &dir, data.as_deref()) {
                 eprintln!("aigit: dashboard: request error: {e}");
             }
         });
@@ -116,65 +209,8 @@ pub(crate) fn cmd_dashboard_serve(git: &Git, args: DashboardServeArgs) -> Result
     Ok(0)
 }
 
-fn list_note_commits(git: &Git) -> Result<Vec<String>> {
-    let out = std::process::Command::new("git")
-        .current_dir(&git.repo.workdir)
-        .args(["notes", "--ref=aigit", "list"])
-        .output()
-        .context("failed to run git notes list")?;
-    if !out.status.success() {
-        return Ok(Vec::new());
-    }
-    let raw = String::from_utf8(out.stdout)?;
-    let mut commits = Vec::new();
-    for line in raw.lines() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
-        let mut parts = line.split_whitespace();
-        let _note_sha = parts.next();
-        let commit_sha = parts.next();
-        if let Some(c) = commit_sha {
-            commits.push(c.to_string());
-        }
-    }
-    Ok(commits)
-}
-
-fn commit_meta(git: &Git, sha: &str) -> Result<CommitMeta> {
-    let out = std::process::Command::new("git")
-        .current_dir(&git.repo.workdir)
-        .args([
-            "show",
-            "-s",
-            "--date=iso-strict",
-            "--format=%H%x09%an%x09%ae%x09%ad%x09%s",
-            sha,
-        ])
-        .output()
-        .context("failed to run git show")?;
-    if !out.status.success() {
-        bail!("git show failed");
-    }
-    let line = String::from_utf8(out.stdout)?.trim_end().to_string();
-    let mut parts = line.split('\t');
-    let sha = parts.next().unwrap_or("").to_string();
-    let author_name = parts.next().unwrap_or("").to_string();
-    let author_email = parts.next().unwrap_or("").to_string();
-    let author_date_iso = parts.next().unwrap_or("").to_string();
-    let subject_parts = parts.collect::<Vec<_>>();
-    let subject = subject_parts.join("\t");
-    Ok(CommitMeta {
-        sha,
-        author_name,
-        author_email,
-        author_date_iso,
-        subject,
-    })
-}
-
-fn handle_http(stream: &mut TcpStream, root: &Path) -> Result<()> {
+/// Serves a file under `root`; `/data.json` comes from `data` when one is given.
+fn handle_http(stream: &mut TcpStream, root: &Path, data: Option<&Path>) -> Result<()> {
     let mut buf = [0u8; 8192];
     let n = stream.read(&mut buf).context("failed to read request")?;
     if n == 0 {
@@ -208,7 +244,10 @@ fn handle_http(stream: &mut TcpStream, root: &Path) -> Result<()> {
         rel.to_string()
     };
 
-    let candidate = root.join(rel);
+    let candidate = match data {
+        Some(data) if rel == "data.json" => data.to_path_buf(),
+        _ => root.join(rel),
+    };
     let candidate = match candidate.canonicalize() {
         Ok(p) => p,
         Err(_) => {
@@ -217,7 +256,7 @@ fn handle_http(stream: &mut TcpStream, root: &Path) -> Result<()> {
         }
     };
 
-    if !candidate.starts_with(root) {
+    if !candidate.starts_with(root) && Some(candidate.as_path()) != data {
         write_response(stream, 403, "text/plain; charset=utf-8", b"Forbidden", method == "HEAD")?;
         return Ok(());
     }
@@ -1,26 +1,33 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 
-use crate::cli::{ExamArgs, ExamFormat};
-use crate::examiner::{ExamContext, ExamPacket, Examiner};
+use crate::cli::{ExamArgs, ExamFormat, ExamProtocol};
+use crate::config::Policy;
+use crate::examiner::{Exam, ExamContext, ExamPacket, Examiner, EXAM_PACKET_SCHEMA};
 use crate::git::Git;
+use crate::session::ExamSession;
 use crate::transcript::Decision;
 
 use super::common;
 
 pub(crate) fn cmd_exam(git: &Git, args: ExamArgs, verbose: bool) -> Result<u8> {
-    let policy = common::load_policy_verbose(git, verbose)?;
+    let mut policy = common::load_policy_verbose(git, verbose)?;
+    if args.quiet {
+        policy.codex_cli.progress = Some(false);
+    }
 
     let format = match args.format {
         Some(ExamFormat::Tui) => ExamFormat::Tui,
         Some(ExamFormat::Json) => ExamFormat::Json,
+        Some(ExamFormat::Editor) => ExamFormat::Editor,
         None => match policy.exam_mode.as_deref() {
             Some("json") => ExamFormat::Json,
+            Some("editor") => ExamFormat::Editor,
             _ => ExamFormat::Tui,
         },
     };
 
-    let (diff, changed_files) = if let Some(range) = args.range {
-        git.diff_range(&range)?
+    let (diff, changed_files) = if let Some(range) = &args.range {
+        git.diff_range(range)?
     } else if args.staged {
         git.diff_staged()?
     } else {
@@ -31,10 +38,24 @@ pub(crate) fn cmd_exam(git: &Git, args: ExamArgs, verbose: bool) -> Result<u8> {
     if diff.trim().is_empty() {
         return Err(anyhow!("no changes to examine (diff is empty)"));
     }
+    let interactive = matches!(format, ExamFormat::Tui | ExamFormat::Editor)
+        && args.emit_template.is_none()
+        && args.protocol.is_none();
+    if args.resume && !interactive {
+        return Err(anyhow!("--resume only applies to --format tui or editor"));
+    }
+    if args.retake && !interactive {
+        return Err(anyhow!("--retake only applies to --format tui or editor"));
+    }
+    let Some(examiner) = common::select_examiner(git, &mut policy, interactive, verbose) else {
+        return Ok(3);
+    };
 
     let diff_patch_id = git.patch_id_from_diff_text(&diff)?;
+    let diff = common::context_diff(git, &policy, args.range.as_deref(), None, diff)?;
     let (redacted_diff, redactions) = crate::redact::redact_diff(&policy, &diff)?;
-    let ctx = ExamContext::new(
+    let risk = common::apply_risk(git, &mut policy, &redacted_diff, &changed_files, verbose)?;
+    let mut ctx = ExamContext::new(
         git,
         diff_patch_id,
         &redacted_diff,
@@ -42,19 +63,90 @@ pub(crate) fn cmd_exam(git: &Git, args: ExamArgs, verbose: bool) -> Result<u8> {
         redactions,
         &policy,
     )?;
+    ctx.risk = Some(risk);
+    ctx.commit_message = args
+        .range
+        .as_deref()
+        .and_then(|range| git.commit_message(range).ok());
+    common::apply_work_item(git, &policy, &mut ctx, args.context_file.as_deref())?;
+    common::apply_author_profile(git, &policy, &mut ctx, verbose);
 
-    let examiner: Box<dyn Examiner> = common::build_examiner(&policy);
     if verbose {
         eprintln!("aigit: examiner: {}", common::examiner_label(&policy));
     }
-    let exam = examiner.generate_exam(&ctx)?;
+    if args.retake {
+        let follow_ups = args.follow_ups || policy.retake.follow_ups.unwrap_or(false);
+        let editor = matches!(format, ExamFormat::Editor);
+        return run_retake(git, &policy, &ctx, examiner.as_ref(), &args, editor, follow_ups);
+    }
+    let session = if interactive {
+        resume_session(git, &ctx, args.range.as_deref(), args.resume)?
+    } else {
+        None
+    };
+    let exam = match (&session, &args.exam) {
+        (Some(session), _) => session.exam.clone(),
+        (None, Some(path)) => {
+            let known = known_exam(git, &policy, examiner.as_ref(), &ctx, verbose)?;
+            packet_exam(&ctx, path, known.as_ref())?
+        }
+        (None, None) if args.grade_only => {
+            known_exam(git, &policy, examiner.as_ref(), &ctx, verbose)?.ok_or_else(|| {
+                anyhow!(
+                    "--grade-only: no exam was emitted for this diff yet (run `aigit exam --format json` first, or pass --exam <packet.json>)"
+                )
+            })?
+        }
+        (None, None) => {
+            common::generate_exam(git, &policy, examiner.as_ref(), &ctx, args.no_cache, verbose)?
+        }
+    };
+
+    if let Some(path) = &args.emit_template {
+        std::fs::write(path, crate::answer_template::render(&exam))
+            .with_context(|| format!("failed to write {path}"))?;
+        eprintln!("aigit: wrote exam template to {path}; fill it in and run `aigit exam --format json --answers {path}`");
+        return Ok(0);
+    }
+
+    if let Some(ExamProtocol::Stdio) = args.protocol {
+        return run_stdio_protocol(
+            git,
+            &policy,
+            &ctx,
+            examiner.as_ref(),
+            exam,
+            args.range.clone(),
+        );
+    }
 
     match format {
         ExamFormat::Json => {
-            if let Some(path) = args.answers {
-                let answers = crate::transcript::Answers::load_from_path(&path)?;
+            let answers = match (args.answers.as_slice(), &args.answers_dir) {
+                ([path], _) => Some(crate::transcript::Answers::load_from_path(path)?),
+                ([], Some(dir)) => {
+                    let answers = crate::transcript::Answers::load_from_dir(dir)?;
+                    for id in answers.answers.keys() {
+                        if !exam.questions.iter().any(|q| &q.id == id) {
+                            eprintln!("aigit: answers dir: ignoring {id}.md (no question with that id)");
+                        }
+                    }
+                    Some(answers)
+                }
+                ([], None) => None,
+                (paths, _) => Some(crate::respondents::merge(
+                    paths
+                        .iter()
+                        .map(|p| Ok((p.clone(), crate::transcript::Answers::load_from_path(p)?)))
+                        .collect::<Result<Vec<_>>>()?,
+                )?),
+            };
+            if let Some(mut answers) = answers {
+                crate::respondents::attribute(git, &policy, &mut answers);
+                crate::precheck::warn(&crate::precheck::check(&policy, &exam, &answers.answers));
                 let score = examiner.grade_exam(&ctx, &exam, &answers)?;
-                let decision = crate::transcript::Decision::from_score(&policy, &exam, &answers, &score);
+                common::record_provider_success(git, &policy);
+                let decision = crate::transcript::Decision::from_score(&policy, &exam, &answers, &score, &ctx.changed_files);
                 let transcript = crate::transcript::Transcript::from_exam_result(
                     git, &policy, &ctx, &exam, &answers, &score, decision,
                 )?;
@@ -65,23 +157,66 @@ pub(crate) fn cmd_exam(git: &Git, args: ExamArgs, verbose: bool) -> Result<u8> {
                     Decision::Fail => 2,
                 })
             } else {
-                let packet = ExamPacket::from_context(&ctx, exam);
+                let packet = ExamPacket::for_candidate(&ctx, exam);
                 serde_json::to_writer_pretty(std::io::stdout(), &packet)?;
                 println!();
                 Ok(0)
             }
         }
-        ExamFormat::Tui => {
+        ExamFormat::Tui | ExamFormat::Editor => {
             if verbose {
                 eprintln!("changed files: {:?}", ctx.changed_files);
             }
-            let answers = crate::transcript::Answers::prompt_tui(&exam)?;
-            let score = examiner.grade_exam(&ctx, &exam, &answers)?;
-            let decision = crate::transcript::Decision::from_score(&policy, &exam, &answers, &score);
+            let mut session = session.unwrap_or_else(|| {
+                ExamSession::new(&git.repo, args.range.clone(), &ctx.diff_patch_id, exam.clone())
+            });
+            let answers = match format {
+                ExamFormat::Editor => {
+                    crate::transcript::Answers::prompt_editor(git, &exam, &ctx, Some(&mut session))
+                }
+                _ => crate::transcript::Answers::prompt_tui(&exam, &ctx, Some(&mut session)),
+            };
+            let mut answers = answers.inspect_err(|_| {
+                if session.answers.values().any(|a| !a.trim().is_empty()) {
+                    eprintln!("aigit: your draft answers were saved; continue with `aigit exam --resume`");
+                }
+            })?;
+            crate::respondents::attribute(git, &policy, &mut answers);
+            crate::pending::PendingExam {
+                created_at: chrono::Utc::now(),
+                command: "exam".to_string(),
+                range: args.range.clone(),
+                diff_patch_id: ctx.diff_patch_id.clone(),
+                exam: exam.clone(),
+                answers: answers.clone(),
+                message: None,
+                git_args: Vec::new(),
+                dry_run: false,
+            }
+            .save(&git.repo)?;
+            let score = match examiner.grade_exam(&ctx, &exam, &answers) {
+                Ok(score) => score,
+                Err(err) => return Ok(common::report_grading_failure(git, &policy, &err)),
+            };
+            common::record_provider_success(git, &policy);
+            crate::pending::PendingExam::clear(&git.repo);
+            session.clear();
+            let decision = crate::transcript::Decision::from_score(&policy, &exam, &answers, &score, &ctx.changed_files);
             let transcript = crate::transcript::Transcript::from_exam_result(
                 git, &policy, &ctx, &exam, &answers, &score, decision,
             )?;
-            crate::transcript::print_human_result(&transcript);
+            let attempts = crate::retake::record(
+                &git.repo,
+                &ctx,
+                args.range.as_deref(),
+                &exam,
+                &answers,
+                &score,
+                decision,
+                None,
+            )?;
+            crate::transcript::print_human_result(&transcript, &policy);
+            print_retake_hint(&policy, decision, attempts.len(), &args);
             Ok(match transcript.decision {
                 Decision::Pass => 0,
                 Decision::Fail => 2,
@@ -89,3 +224,265 @@ pub(crate) fn cmd_exam(git: &Git, args: ExamArgs, verbose: bool) -> Result<u8> {
         }
     }
 }
+
+/// `--retake`: asks the weak questions of the exam this diff failed last (with follow-ups when
+/// asked for), then grades the whole exam with the new answers in place of the old.
+fn run_retake(
+    git: &Git,
+    policy: &Policy,
+    ctx: &ExamContext,
+    examiner: &dyn Examiner,
+    args: &ExamArgs,
+    editor: bool,
+    follow_ups: bool,
+) -> Result<u8> {
+    let state = crate::retake::RetakeState::load(&git.repo, &ctx.diff_patch_id)?
+        .ok_or_else(|| anyhow!("no failed exam to retake for this diff; run `aigit exam` first"))?;
+    if state.exhausted(policy) {
+        return Err(anyhow!(
+            "this exam was already attempted {} times (retake.max_attempts); take it again in full with `aigit exam`",
+            state.attempts.len()
+        ));
+    }
+    let weak = state.weak_questions(policy);
+    let retake = state.retake_exam(examiner, ctx, &weak, follow_ups);
+    eprintln!(
+        "aigit: retaking {} of {} question(s): {}",
+        weak.len(),
+        state.exam.questions.len(),
+        weak.join(", ")
+    );
+    let answers = if editor {
+        crate::transcript::Answers::prompt_editor(git, &retake, ctx, None)?
+    } else {
+        crate::transcript::Answers::prompt_tui(&retake, ctx, None)?
+    };
+    let (exam, mut answers) = state.merge(&retake, answers);
+    crate::respondents::attribute(git, policy, &mut answers);
+    let score = match examiner.grade_exam(ctx, &exam, &answers) {
+        Ok(score) => score,
+        Err(err) => return Ok(common::report_grading_failure(git, policy, &err)),
+    };
+    common::record_provider_success(git, policy);
+    let decision = Decision::from_score(policy, &exam, &answers, &score, &ctx.changed_files);
+    let mut transcript = crate::transcript::Transcript::from_exam_result(
+        git, policy, ctx, &exam, &answers, &score, decision,
+    )?;
+    let retaken: Vec<String> = retake.questions.iter().map(|q| q.id.clone()).collect();
+    let attempts = crate::retake::record(
+        &git.repo,
+        ctx,
+        args.range.as_deref(),
+        &exam,
+        &answers,
+        &score,
+        decision,
+        Some(&retaken),
+    )?;
+    let tries = attempts.len();
+    if tries > 1 {
+        transcript.attempts = attempts;
+        transcript.withhold_answers(crate::transcript::AnswerStorage::from_policy(policy));
+    }
+    crate::transcript::print_human_result(&transcript, policy);
+    print_retake_hint(policy, decision, tries, args);
+    Ok(match transcript.decision {
+        Decision::Pass => 0,
+        Decision::Fail => 2,
+    })
+}
+
+/// After a failed exam, points at `--retake` while `retake.max_attempts` allows another try.
+fn print_retake_hint(policy: &Policy, decision: Decision, attempts: usize, args: &ExamArgs) {
+    if decision == Decision::Pass || attempts >= policy.retake.max_attempts() {
+        return;
+    }
+    let range = args
+        .range
+        .as_deref()
+        .map(|r| format!(" --range {r}"))
+        .unwrap_or_default();
+    eprintln!("aigit: answer the weak questions again with `aigit exam{range} --retake`");
+}
+
+/// The exam generated earlier for this diff, without asking the provider: the cached one, or the
+/// built-in examiner's, which is deterministic.
+fn known_exam(
+    git: &Git,
+    policy: &Policy,
+    examiner: &dyn Examiner,
+    ctx: &ExamContext,
+    verbose: bool,
+) -> Result<Option<Exam>> {
+    if common::examiner_label(policy) == "local-static" {
+        return common::generate_exam(git, policy, examiner, ctx, true, verbose).map(Some);
+    }
+    Ok(crate::cache::load(&git.repo, ctx))
+}
+
+/// The exam in an emitted packet, checked against the diff being examined and against `known`,
+/// the exam emitted for it: the packet must ask the same questions (ids, categories, prompts and
+/// choices, in order), so questions cannot be dropped or rewritten before grading. Packets
+/// never carry the answer key, and one written into a packet is ignored: the known exam, with
+/// its key, is what gets graded.
+fn packet_exam(ctx: &ExamContext, path: &str, known: Option<&Exam>) -> Result<Exam> {
+    let raw = std::fs::read_to_string(path).with_context(|| format!("failed to read {path}"))?;
+    let packet: ExamPacket =
+        serde_json::from_str(&raw).with_context(|| format!("failed to parse {path}"))?;
+    if packet.schema_version != EXAM_PACKET_SCHEMA {
+        return Err(anyhow!(
+            "{path}: unsupported exam packet schema {} (expected {EXAM_PACKET_SCHEMA})",
+            packet.schema_version
+        ));
+    }
+    if packet.diff_patch_id != ctx.diff_patch_id {
+        return Err(anyhow!(
+            "{path} was emitted for a different diff (diff_patch_id {}, the diff being examined has {}); run `aigit exam --format json` again",
+            packet.diff_patch_id,
+            ctx.diff_patch_id
+        ));
+    }
+    let known = known.ok_or_else(|| {
+        anyhow!(
+            "--exam {path}: no exam was emitted for this diff here, so the packet cannot be checked (run `aigit exam --format json` and answer that packet)"
+        )
+    })?;
+    let asked = &packet.exam.questions;
+    if asked.len() != known.questions.len() {
+        return Err(anyhow!(
+            "{path}: the packet has {} question(s) but the exam emitted for this diff has {}; answer the packet as emitted",
+            asked.len(),
+            known.questions.len()
+        ));
+    }
+    for (q, k) in asked.iter().zip(&known.questions) {
+        if q.id != k.id
+            || q.category != k.category
+            || q.prompt != k.prompt
+            || q.choices != k.choices
+        {
+            return Err(anyhow!(
+                "{path}: question {} differs from the exam emitted for this diff (expected {} [{}]); answer the packet as emitted",
+                q.id,
+                k.id,
+                k.category
+            ));
+        }
+    }
+    Ok(known.clone())
+}
+
+/// The saved session to continue with `--resume`. Sessions left for an earlier version of the
+/// diff are dropped first; without `--resume`, a draft for this diff is pointed out and replaced
+/// once answering starts.
+fn resume_session(
+    git: &Git,
+    ctx: &ExamContext,
+    range: Option<&str>,
+    resume: bool,
+) -> Result<Option<ExamSession>> {
+    let saved = ExamSession::load(&git.repo, &ctx.diff_patch_id)?;
+    let expired = ExamSession::expire(&git.repo, range, &ctx.diff_patch_id);
+    match saved {
+        Some(session) if resume => {
+            let drafts = session.answers.values().filter(|a| !a.trim().is_empty());
+            eprintln!(
+                "aigit: resuming the exam saved {} ({} draft answer(s))",
+                session.updated_at.format("%Y-%m-%d %H:%M UTC"),
+                drafts.count()
+            );
+            Ok(Some(session))
+        }
+        Some(session) => {
+            eprintln!(
+                "aigit: draft answers saved {} exist for this diff; they are replaced once you answer (`aigit exam --resume` continues them)",
+                session.updated_at.format("%Y-%m-%d %H:%M UTC")
+            );
+            Ok(None)
+        }
+        None if resume && expired > 0 => Err(anyhow!(
+            "the saved exam was for an earlier version of the diff (its patch-id changed) and has expired"
+        )),
+        None if resume => Err(anyhow!("no saved exam to resume for this diff")),
+        None => Ok(None),
+    }
+}
+
+/// `--protocol stdio`: exactly one JSON line out (packet), one in (answers), one out
+/// (transcript, or `{"error": ..., "code": ...}` when the answers are unusable or grading fails,
+/// with the same exit code). Diagnostics only ever go to stderr.
+fn run_stdio_protocol(
+    git: &Git,
+    policy: &Policy,
+    ctx: &ExamContext,
+    examiner: &dyn Examiner,
+    exam: Exam,
+    range: Option<String>,
+) -> Result<u8> {
+    use std::io::{BufRead, Write};
+
+    let mut stdout = std::io::stdout().lock();
+    serde_json::to_writer(&mut stdout, &ExamPacket::for_candidate(ctx, exam.clone()))?;
+    writeln!(stdout)?;
+    stdout.flush()?;
+
+    let mut error_line = |message: String, code: u8| -> Result<u8> {
+        serde_json::to_writer(
+            &mut stdout,
+            &serde_json::json!({ "error": message, "code": code }),
+        )?;
+        writeln!(stdout)?;
+        stdout.flush()?;
+        Ok(code)
+    };
+
+    let mut line = String::new();
+    if std::io::stdin().lock().read_line(&mut line)? == 0 {
+        let message = "stdio protocol: no answers line received on stdin".to_string();
+        eprintln!("aigit: {message}");
+        return error_line(message, 1);
+    }
+    let mut answers: crate::transcript::Answers = match serde_json::from_str(line.trim()) {
+        Ok(answers) => answers,
+        Err(e) => {
+            let message = format!("stdio protocol: invalid answers JSON: {e}");
+            eprintln!("aigit: {message}");
+            return error_line(message, 1);
+        }
+    };
+    crate::respondents::attribute(git, policy, &mut answers);
+    crate::precheck::warn(&crate::precheck::check(policy, &exam, &answers.answers));
+
+    crate::pending::PendingExam {
+        created_at: chrono::Utc::now(),
+        command: "exam".to_string(),
+        range,
+        diff_patch_id: ctx.diff_patch_id.clone(),
+        exam: exam.clone(),
+        answers: answers.clone(),
+        message: None,
+        git_args: Vec::new(),
+        dry_run: false,
+    }
+    .save(&git.repo)?;
+    let score = match examiner.grade_exam(ctx, &exam, &answers) {
+        Ok(score) => score,
+        Err(err) => {
+            let code = common::report_grading_failure(git, policy, &err);
+            return error_line(format!("grading failed: {err:#}"), code);
+        }
+    };
+    crate::pending::PendingExam::clear(&git.repo);
+    common::record_provider_success(git, policy);
+    let decision = Decision::from_score(policy, &exam, &answers, &score, &ctx.changed_files);
+    let transcript = crate::transcript::Transcript::from_exam_result(
+        git, policy, ctx, &exam, &answers, &score, decision,
+    )?;
+    serde_json::to_writer(&mut stdout, &transcript)?;
+    writeln!(stdout)?;
+    stdout.flush()?;
+    Ok(match transcript.decision {
+        Decision::Pass => 0,
+        Decision::Fail => 2,
+    })
+}
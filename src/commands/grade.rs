@@ -0,0 +1,113 @@
+use anyhow::{anyhow, Result};
+
+use crate::cli::GradeArgs;
+use crate::examiner::ExamContext;
+use crate::git::Git;
+use crate::pending::PendingExam;
+use crate::transcript::Decision;
+
+use super::common;
+
+/// Retries grading for the exam saved by an interrupted `aigit commit`/`aigit exam`, without
+/// asking the questions again.
+pub(crate) fn cmd_grade(git: &Git, args: GradeArgs, verbose: bool) -> Result<u8> {
+    if !args.resume {
+        return Err(anyhow!(
+            "nothing to grade; use `aigit grade --resume` to retry a saved exam"
+        ));
+    }
+    let pending = PendingExam::load(&git.repo)?;
+    let mut policy = common::load_policy_verbose(git, verbose)?;
+
+    let amend_base = (pending.command == "commit" && super::commit::amends(&pending.git_args))
+        .then(|| git.amend_base())
+        .transpose()?;
+    let (diff, changed_files) = match &pending.range {
+        Some(range) => git.diff_range(range)?,
+        None => git.diff_staged_since(amend_base.as_deref())?,
+    };
+    if diff.trim().is_empty() {
+        return Err(anyhow!(
+            "the changes from the saved exam are no longer present (diff is empty)"
+        ));
+    }
+    let diff_patch_id = git.patch_id_from_diff_text(&diff)?;
+    if diff_patch_id != pending.diff_patch_id {
+        return Err(anyhow!(
+            "changes differ from the saved exam (diff fingerprint mismatch); run the exam again"
+        ));
+    }
+    let diff = common::context_diff(
+        git,
+        &policy,
+        pending.range.as_deref(),
+        amend_base.as_deref(),
+        diff,
+    )?;
+    let (redacted_diff, redactions) = crate::redact::redact_diff(&policy, &diff)?;
+    let risk = common::apply_risk(git, &mut policy, &redacted_diff, &changed_files, verbose)?;
+    let mut ctx = ExamContext::new(
+        git,
+        diff_patch_id,
+        &redacted_diff,
+        changed_files,
+        redactions,
+        &policy,
+    )?;
+    ctx.risk = Some(risk);
+    ctx.commit_message = match &pending.range {
+        Some(range) => git.commit_message(range).ok(),
+        None => pending.message.clone(),
+    };
+    let examiner = common::build_examiner(&policy);
+    if verbose {
+        eprintln!(
+            "aigit: resuming {} exam saved at {}",
+            pending.command, pending.created_at
+        );
+    }
+
+    if pending.command == "commit" {
+        return super::commit::finish_commit(
+            git,
+            &policy,
+            &ctx,
+            examiner.as_ref(),
+            &pending.exam,
+            &pending.answers,
+            pending.message.as_deref(),
+            &pending.git_args,
+            pending.dry_run,
+            verbose,
+            None,
+        );
+    }
+
+    let score = match examiner.grade_exam(&ctx, &pending.exam, &pending.answers) {
+        Ok(score) => score,
+        Err(err) => return Ok(common::report_grading_failure(git, &policy, &err)),
+    };
+    common::record_provider_success(git, &policy);
+    PendingExam::clear(&git.repo);
+    let decision = Decision::from_score(
+        &policy,
+        &pending.exam,
+        &pending.answers,
+        &score,
+        &ctx.changed_files,
+    );
+    let transcript = crate::transcript::Transcript::from_exam_result(
+        git,
+        &policy,
+        &ctx,
+        &pending.exam,
+        &pending.answers,
+        &score,
+        decision,
+    )?;
+    crate::transcript::print_human_result(&transcript, &policy);
+    Ok(match transcript.decision {
+        Decision::Pass => 0,
+        Decision::Fail => 2,
+    })
+}
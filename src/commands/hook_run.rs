@@ -0,0 +1,136 @@
+use std::io::BufRead;
+
+use anyhow::{anyhow, Result};
+
+use crate::cli::{HookMode, HookRunArgs};
+use crate::config::Policy;
+use crate::git::Git;
+use crate::transcript::TranscriptStore;
+
+use super::verify::{verify_commit, Verdict};
+
+/// An all-zero object id: the missing side of a created or deleted ref.
+const NULL_SHA: &str = "0000000000000000000000000000000000000000";
+
+/// Runs the checks behind an installed git hook. Git's own hook arguments follow the hook name;
+/// pre-push and post-rewrite also read what git writes to the hook's stdin.
+pub(crate) fn cmd_hook_run(git: &Git, args: HookRunArgs) -> Result<u8> {
+    match args.hook {
+        HookMode::PreCommit => Ok(pre_commit()),
+        HookMode::PrePush => {
+            let remote = args
+                .args
+                .first()
+                .ok_or_else(|| anyhow!("pre-push: missing the remote name argument"))?;
+            pre_push(git, remote, &read_stdin()?)
+        }
+        // `aigit commit --amend` moves the transcript itself.
+        HookMode::PostRewrite
+            if args.args.first().is_some_and(|kind| kind == "amend")
+                && std::env::var_os("AIGIT_STORES_TRANSCRIPT").is_some() =>
+        {
+            read_stdin().map(|_| 0)
+        }
+        HookMode::PostRewrite => post_rewrite(git, &read_stdin()?),
+    }
+}
+
+fn read_stdin() -> Result<Vec<String>> {
+    Ok(std::io::stdin()
+        .lock()
+        .lines()
+        .collect::<std::io::Result<Vec<_>>>()?)
+}
+
+/// Blocks a plain `git commit`; `aigit commit` sets `AIGIT_ALLOW_COMMIT` for the commit it runs.
+fn pre_commit() -> u8 {
+    if std::env::var_os("AIGIT_ALLOW_COMMIT").is_some_and(|v| !v.is_empty()) {
+        return 0;
+    }
+    eprintln!("aigit: commit blocked. Use: aigit commit");
+    1
+}
+
+/// Verifies every commit a push would add to `remote`'s branches, like `aigit verify`. Deleted
+/// refs and refs outside `refs/heads/` are not checked.
+fn pre_push(git: &Git, remote: &str, updates: &[String]) -> Result<u8> {
+    let policy = Policy::load_from_repo(&git.repo)?;
+    let mut commits: Vec<String> = Vec::new();
+    for line in updates {
+        let [_, local_sha, remote_ref, remote_sha] =
+            line.split_whitespace().collect::<Vec<_>>()[..]
+        else {
+            continue;
+        };
+        if local_sha == NULL_SHA || !remote_ref.starts_with("refs/heads/") {
+            continue;
+        }
+        let new = if remote_sha == NULL_SHA {
+            git.commits_not_on_remote(local_sha, remote)?
+        } else {
+            git.rev_list(&format!("{remote_sha}..{local_sha}"))?
+        };
+        for sha in new {
+            if !commits.contains(&sha) {
+                commits.push(sha);
+            }
+        }
+    }
+
+    let mut code = 0;
+    for commit in &commits {
+        let verdict = verify_commit(git, &policy, commit, false)?;
+        let short = &commit[..commit.len().min(12)];
+        match &verdict {
+            Verdict::Pass | Verdict::Exempt(_) => continue,
+            Verdict::Fail => eprintln!("aigit: pre-push: {short}: transcript fails the policy"),
+            Verdict::Unapproved(reasons) => {
+                for reason in reasons {
+                    eprintln!("aigit: pre-push: {short}: {reason}");
+                }
+            }
+            Verdict::Rejected(err) | Verdict::Unavailable(err) => {
+                eprintln!("aigit: pre-push: {short}: {err}")
+            }
+        }
+        code = code.max(verdict.code());
+    }
+    if code != 0 {
+        eprintln!(
+            "aigit: push blocked; examine the commits above (`aigit exam --range <commit>^..<commit>`) or push with --no-verify"
+        );
+    }
+    Ok(code)
+}
+
+/// Moves transcripts from rewritten commits to their rewrites (`<old> <new>` per stdin line), as
+/// long as the rewrite has the same diff; a changed diff needs a new exam.
+fn post_rewrite(git: &Git, rewrites: &[String]) -> Result<u8> {
+    let policy = Policy::load_from_repo(&git.repo)?;
+    let store = TranscriptStore::from_policy(&policy)?;
+    for line in rewrites {
+        let mut fields = line.split_whitespace();
+        let (Some(old), Some(new)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        if old == new || store.load(&git.repo, new).is_ok() {
+            continue;
+        }
+        let Ok(mut transcript) = store.load(&git.repo, old) else {
+            continue;
+        };
+        let short = &new[..new.len().min(12)];
+        let patch_id = git.patch_id_for_commit(new)?;
+        if transcript.diff_fingerprint.patch_id != patch_id {
+            eprintln!(
+                "aigit: post-rewrite: {short}: the diff changed, so the transcript of {} was not carried over",
+                &old[..old.len().min(12)]
+            );
+            continue;
+        }
+        transcript.commit = Some(new.to_string());
+        crate::signing::sign_if_enabled(git, &policy, &mut transcript)?;
+        store.store(&git.repo, new, &transcript)?;
+    }
+    Ok(0)
+}
@@ -1,8 +1,23 @@
+pub(crate) mod appeal;
+pub(crate) mod bench;
+pub(crate) mod bundle;
+pub(crate) mod cache;
+pub(crate) mod calibrate;
 pub(crate) mod common;
 pub(crate) mod commit;
 pub(crate) mod config;
+pub(crate) mod countersign;
 pub(crate) mod dashboard;
 pub(crate) mod exam;
+pub(crate) mod grade;
+pub(crate) mod hook_run;
 pub(crate) mod install_hook;
+pub(crate) mod notes;
+pub(crate) mod org_export;
+pub(crate) mod packs;
 pub(crate) mod policy;
+pub(crate) mod report;
+pub(crate) mod show;
+pub(crate) mod stats;
+pub(crate) mod transcript;
 pub(crate) mod verify;
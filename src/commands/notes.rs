@@ -0,0 +1,96 @@
+use anyhow::Result;
+
+use crate::cli::NotesArgs;
+use crate::config::Policy;
+use crate::git::Git;
+use crate::notes_sync::{self, FetchReport, MergeStrategy, SyncState};
+
+fn plural(n: usize) -> &'static str {
+    if n == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+/// One line on what merging the remote's transcripts did.
+fn describe_merge(remote: &str, report: &FetchReport) -> String {
+    let mut line = format!(
+        "merged {} new transcript{} from {remote}",
+        report.added,
+        plural(report.added)
+    );
+    if report.conflicts > 0 {
+        line.push_str(&format!(
+            "; {} commit{} had different transcripts on both sides, {} resolved in favour of {remote}",
+            report.conflicts,
+            plural(report.conflicts),
+            report.theirs_won
+        ));
+    }
+    line
+}
+
+/// Pushes `refs/notes/aigit`; a remote that has moved on is fetched and merged first.
+pub(crate) fn cmd_notes_push(git: &Git, args: NotesArgs) -> Result<u8> {
+    let policy = Policy::load_from_repo(&git.repo)?;
+    let remote = notes_sync::remote(&policy, args.remote.as_deref());
+    let report = notes_sync::push(&git.repo, &remote, MergeStrategy::from_policy(&policy))?;
+    if let Some(merged) = &report.merged {
+        eprintln!(
+            "aigit notes: {remote} had moved on: {}",
+            describe_merge(&remote, merged)
+        );
+    }
+    if report.up_to_date {
+        eprintln!("aigit notes: {remote} already has every transcript");
+    } else {
+        eprintln!("aigit notes: pushed transcripts to {remote}");
+    }
+    Ok(0)
+}
+
+/// Fetches the remote's transcripts and merges them into `refs/notes/aigit`.
+pub(crate) fn cmd_notes_fetch(git: &Git, args: NotesArgs) -> Result<u8> {
+    let policy = Policy::load_from_repo(&git.repo)?;
+    let remote = notes_sync::remote(&policy, args.remote.as_deref());
+    let report = notes_sync::fetch(&git.repo, &remote, MergeStrategy::from_policy(&policy))?;
+    if report.found {
+        eprintln!("aigit notes: {}", describe_merge(&remote, &report));
+    } else {
+        eprintln!("aigit notes: {remote} has no transcripts yet");
+    }
+    Ok(0)
+}
+
+/// Counts transcripts here and on the remote and says which way they need to go.
+pub(crate) fn cmd_notes_status(git: &Git, args: NotesArgs) -> Result<u8> {
+    let policy = Policy::load_from_repo(&git.repo)?;
+    let remote = notes_sync::remote(&policy, args.remote.as_deref());
+    let status = notes_sync::status(&git.repo, &remote)?;
+    let mut line = format!(
+        "aigit notes: {} transcript{} here",
+        status.local,
+        plural(status.local)
+    );
+    if let Some(theirs) = status.remote {
+        line.push_str(&format!(
+            ", {theirs} on {remote} ({} only here, {} only on {remote})",
+            status.only_local, status.only_remote
+        ));
+    }
+    println!("{line}");
+    let state = match status.state {
+        SyncState::Empty => "no transcripts on either side".to_string(),
+        SyncState::InSync => "in sync".to_string(),
+        SyncState::NotPushed => format!("{remote} has none yet; run `aigit notes push`"),
+        SyncState::Ahead => "ahead; run `aigit notes push`".to_string(),
+        SyncState::Behind => "behind; run `aigit notes fetch`".to_string(),
+        SyncState::Diverged => "diverged; run `aigit notes push` to merge and push".to_string(),
+        SyncState::Unknown => {
+            format!("{remote} has transcripts not fetched yet; run `aigit notes fetch`")
+        }
+    };
+    println!("aigit notes: {remote}: {state}");
+    Ok(0)
+}
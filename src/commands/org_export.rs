@@ -0,0 +1,262 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::cli::OrgExportArgs;
+use crate::config::Policy;
+use crate::git::{CommitMeta, Git, GitRepo};
+use crate::transcript::{Decision, Transcript, TranscriptStore, NOTES_REF};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct OrgEntry {
+    pub(crate) commit: CommitMeta,
+    pub(crate) transcript: Transcript,
+}
+
+/// Pass rates, scores and transcript coverage over a set of commits.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Coverage {
+    /// Commits reachable from HEAD.
+    pub(crate) commits: usize,
+    /// Of those, commits with a transcript.
+    pub(crate) covered: usize,
+    pub(crate) coverage: f64,
+    pub(crate) transcripts: usize,
+    pub(crate) passes: usize,
+    pub(crate) pass_rate: f64,
+    pub(crate) avg_total_score: f64,
+    pub(crate) category_scores: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct OrgRepo {
+    /// Where the transcripts were read from: local paths and remote URLs from the repos file.
+    pub(crate) sources: Vec<String>,
+    pub(crate) stats: Coverage,
+    pub(crate) entries: Vec<OrgEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct OrgExport {
+    pub(crate) schema_version: String,
+    pub(crate) generated_at: DateTime<Utc>,
+    pub(crate) totals: Coverage,
+    /// Keyed by repo fingerprint (`repo_id`); clones of the same repository are merged.
+    pub(crate) repos: BTreeMap<String, OrgRepo>,
+}
+
+pub(crate) const ORG_EXPORT_SCHEMA: &str = "aigit-org-export/0.1";
+
+/// Everything read for one `repo_id`, from one or more clones.
+#[derive(Default)]
+struct Merged {
+    sources: Vec<String>,
+    history: BTreeSet<String>,
+    entries: Vec<OrgEntry>,
+}
+
+/// Commits and transcripts read from one repository, before merging by `repo_id`.
+struct Collected {
+    repo_id: String,
+    source: String,
+    history: BTreeSet<String>,
+    entries: Vec<OrgEntry>,
+}
+
+/// Reads the transcripts of every repository in `--repos-file` into one dataset keyed by repo
+/// fingerprint, with coverage and score statistics per repository and in total.
+pub(crate) fn cmd_org_export(_git: &Git, args: OrgExportArgs) -> Result<u8> {
+    let repos_file = Path::new(&args.repos_file);
+    let raw = std::fs::read_to_string(repos_file)
+        .with_context(|| format!("failed to read {}", repos_file.display()))?;
+    let base = repos_file.parent().unwrap_or(Path::new("."));
+
+    let mut collected = Vec::new();
+    let mut failed = 0;
+    for source in raw
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+    {
+        match collect(base, source, &args) {
+            Ok(c) => collected.push(c),
+            Err(err) => {
+                eprintln!("aigit: org-export: skipping {source}: {err:#}");
+                failed += 1;
+            }
+        }
+    }
+    if collected.is_empty() && failed > 0 {
+        return Err(anyhow!(
+            "no repository in {} could be read",
+            repos_file.display()
+        ));
+    }
+
+    let mut merged: BTreeMap<String, Merged> = BTreeMap::new();
+    for c in collected {
+        let m = merged.entry(c.repo_id).or_default();
+        m.sources.push(c.source);
+        m.history.extend(c.history);
+        for e in c.entries {
+            if !m.entries.iter().any(|seen| seen.commit.sha == e.commit.sha) {
+                m.entries.push(e);
+            }
+        }
+    }
+
+    let totals = coverage(
+        merged.values().map(|m| m.history.len()).sum(),
+        merged
+            .values()
+            .flat_map(|m| m.entries.iter().map(move |e| (&m.history, e))),
+    );
+    let repos = merged
+        .into_iter()
+        .map(|(repo_id, mut m)| {
+            m.entries
+                .sort_by(|a, b| b.commit.author_date_iso.cmp(&a.commit.author_date_iso));
+            let stats = coverage(m.history.len(), m.entries.iter().map(|e| (&m.history, e)));
+            let repo = OrgRepo {
+                sources: m.sources,
+                stats,
+                entries: m.entries,
+            };
+            (repo_id, repo)
+        })
+        .collect();
+
+    let export = OrgExport {
+        schema_version: ORG_EXPORT_SCHEMA.to_string(),
+        generated_at: Utc::now(),
+        totals,
+        repos,
+    };
+    let json = serde_json::to_string_pretty(&export)?;
+    match &args.out {
+        Some(out) => {
+            std::fs::write(out, json).with_context(|| format!("failed to write {out}"))?;
+            eprintln!(
+                "aigit: org-export: wrote {} repositories to {out}",
+                export.repos.len()
+            );
+        }
+        None => println!("{json}"),
+    }
+    Ok(0)
+}
+
+/// Whether a repos-file entry names a remote rather than a local path.
+fn is_remote(source: &str) -> bool {
+    source.contains("://")
+        || source
+            .split_once(':')
+            .is_some_and(|(host, _)| host.contains('@') && !host.contains('/'))
+}
+
+fn collect(base: &Path, source: &str, args: &OrgExportArgs) -> Result<Collected> {
+    // Remotes are cloned (history and notes only) into a directory dropped after reading.
+    let scratch;
+    let git = if is_remote(source) {
+        scratch = tempfile::tempdir().context("failed to create a temporary directory")?;
+        let git = Git::new(GitRepo::clone_history(source, scratch.path())?);
+        fetch_notes(&git)?;
+        // The repository's own policy decides its repo_id (e.g. hashing).
+        if let Ok(policy) = git.show_file("HEAD", ".aigit.toml") {
+            std::fs::write(git.repo.workdir.join(".aigit.toml"), policy)?;
+        }
+        git
+    } else {
+        let git = Git::new(GitRepo::open(&base.join(source))?);
+        if args.fetch_notes {
+            if let Err(err) = fetch_notes(&git) {
+                eprintln!("aigit: org-export: {source}: {err:#}");
+            }
+        }
+        git
+    };
+
+    let policy = Policy::load_from_repo(&git.repo)?;
+    let store = TranscriptStore::from_policy(&policy)?;
+    let mut entries = Vec::new();
+    for sha in store.list(&git.repo)? {
+        let Ok(commit) = git.commit_meta(&sha) else {
+            continue;
+        };
+        let mut transcript = match store.load(&git.repo, &sha) {
+            Ok(t) => t,
+            Err(e) => {
+                eprintln!(
+                    "aigit: org-export: {source}: skipping {sha}: failed to load transcript: {e}"
+                );
+                continue;
+            }
+        };
+        transcript.commit = Some(sha.clone());
+        if !args.include_answers {
+            transcript.answers.answers.clear();
+        }
+        entries.push(OrgEntry { commit, transcript });
+    }
+    Ok(Collected {
+        repo_id: crate::repo_id::resolve(&git, &policy)?,
+        source: source.to_string(),
+        history: git
+            .rev_list("HEAD")
+            .unwrap_or_default()
+            .into_iter()
+            .collect(),
+        entries,
+    })
+}
+
+fn fetch_notes(git: &Git) -> Result<()> {
+    git.fetch_ref(
+        "origin",
+        &format!("+refs/notes/{NOTES_REF}:refs/notes/{NOTES_REF}"),
+    )
+}
+
+/// Coverage of `commits` reachable commits by transcripts, each paired with the history of the
+/// repository it was read from.
+pub(crate) fn coverage<'a>(
+    commits: usize,
+    entries: impl Iterator<Item = (&'a BTreeSet<String>, &'a OrgEntry)>,
+) -> Coverage {
+    let mut out = Coverage {
+        commits,
+        ..Coverage::default()
+    };
+    let mut total_score = 0.0;
+    let mut categories: BTreeMap<String, (u32, f64)> = BTreeMap::new();
+    for (history, e) in entries {
+        out.transcripts += 1;
+        if history.contains(&e.commit.sha) {
+            out.covered += 1;
+        }
+        if e.transcript.decision == Decision::Pass {
+            out.passes += 1;
+        }
+        total_score += e.transcript.score.total_score;
+        for q in &e.transcript.score.per_question {
+            let entry = categories.entry(q.category.clone()).or_default();
+            entry.0 += 1;
+            entry.1 += q.score;
+        }
+    }
+    if out.commits > 0 {
+        out.coverage = out.covered as f64 / out.commits as f64;
+    }
+    if out.transcripts > 0 {
+        out.pass_rate = out.passes as f64 / out.transcripts as f64;
+        out.avg_total_score = total_score / out.transcripts as f64;
+    }
+    out.category_scores = categories
+        .into_iter()
+        .map(|(cat, (n, sum))| (cat, sum / n as f64))
+        .collect();
+    out
+}
@@ -0,0 +1,112 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+
+use crate::cli::{PacksAddArgs, PacksRemoveArgs};
+use crate::config::Policy;
+use crate::git::Git;
+use crate::packs::{PackManifest, PackSource};
+
+/// Installs a pack into `.aigit/packs/<name>/` after validating its manifest.
+pub(crate) fn cmd_packs_add(git: &Git, args: PacksAddArgs) -> Result<u8> {
+    let (raw, commit) = crate::packs::fetch(&args.source, args.git_ref.as_deref())?;
+    let pack = PackManifest::parse(&raw, &args.source)?;
+    let previous = crate::packs::load(&git.repo, &pack.name)
+        .ok()
+        .flatten()
+        .map(|(p, _)| p.version);
+    crate::packs::install(
+        &git.repo,
+        &raw,
+        &pack,
+        PackSource {
+            source: args.source.clone(),
+            commit,
+            installed_at: Utc::now(),
+        },
+    )?;
+    match previous {
+        Some(old) if old != pack.version => eprintln!(
+            "aigit: packs: updated {} {old} -> {}",
+            pack.name, pack.version
+        ),
+        _ => eprintln!(
+            "aigit: packs: installed {} {} ({} questions, {} categories) in {}",
+            pack.name,
+            pack.version,
+            pack.questions.len(),
+            pack.categories.len(),
+            crate::packs::packs_dir(&git.repo)
+                .join(&pack.name)
+                .display()
+        ),
+    }
+
+    let uses = policy_references(git)?;
+    match uses
+        .iter()
+        .find(|e| crate::packs::parse_ref(e).0 == pack.name)
+    {
+        None => eprintln!(
+            "aigit: packs: add \"{}@{}\" to `packs` in .aigit.toml to ask its questions",
+            pack.name, pack.version
+        ),
+        Some(entry) => {
+            if let (_, Some(pinned)) = crate::packs::parse_ref(entry) {
+                if pinned != pack.version {
+                    eprintln!(
+                        "aigit: packs: warning: .aigit.toml pins {entry}; update it to {}@{}",
+                        pack.name, pack.version
+                    );
+                }
+            }
+        }
+    }
+    Ok(0)
+}
+
+pub(crate) fn cmd_packs_list(git: &Git) -> Result<u8> {
+    let uses = policy_references(git)?;
+    let packs = crate::packs::installed(&git.repo)?;
+    if packs.is_empty() {
+        eprintln!("aigit: packs: none installed");
+    }
+    for (pack, source) in packs {
+        let used = uses
+            .iter()
+            .any(|e| crate::packs::parse_ref(e).0 == pack.name);
+        println!(
+            "{}\t{}\t{} questions, {} categories\t{}\t{}",
+            pack.name,
+            pack.version,
+            pack.questions.len(),
+            pack.categories.len(),
+            source.map(|s| s.source).unwrap_or_else(|| "-".to_string()),
+            if used { "in use" } else { "unused" }
+        );
+    }
+    Ok(0)
+}
+
+pub(crate) fn cmd_packs_remove(git: &Git, args: PacksRemoveArgs) -> Result<u8> {
+    let dir = crate::packs::packs_dir(&git.repo).join(&args.name);
+    if crate::packs::load(&git.repo, &args.name)?.is_none() {
+        return Err(anyhow!("no pack named {} is installed", args.name));
+    }
+    std::fs::remove_dir_all(&dir).with_context(|| format!("failed to remove {}", dir.display()))?;
+    eprintln!("aigit: packs: removed {}", args.name);
+    if policy_references(git)?
+        .iter()
+        .any(|e| crate::packs::parse_ref(e).0 == args.name)
+    {
+        eprintln!(
+            "aigit: packs: warning: .aigit.toml still lists {} in `packs`; remove it there too",
+            args.name
+        );
+    }
+    Ok(0)
+}
+
+/// The `packs` entries of the repo's `.aigit.toml`.
+fn policy_references(git: &Git) -> Result<Vec<String>> {
+    Ok(Policy::load_repo_file(&git.repo)?.packs)
+}
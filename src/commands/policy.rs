@@ -11,3 +11,21 @@ pub(crate) fn cmd_policy_validate(git: &Git, verbose: bool) -> Result<u8> {
     Ok(0)
 }
 
+pub(crate) fn cmd_policy_pull(git: &Git) -> Result<u8> {
+    // The source is read from the repo's own file so a pulled bundle cannot redirect itself.
+    let policy = Policy::load_repo_file(&git.repo)?;
+    let installed = crate::central_policy::pull(git, &policy.policy_source)?;
+    eprintln!(
+        "aigit: policy: installed {} (sha256 {}{})",
+        installed.source,
+        &installed.sha256[..12],
+        installed
+            .signed_by
+            .as_deref()
+            .map(|s| format!(", signed by {s}"))
+            .unwrap_or_default()
+    );
+    // Fail now rather than on the next commit if the layered result does not load.
+    Policy::load_from_repo(&git.repo)?;
+    Ok(0)
+}
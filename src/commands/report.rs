@@ -0,0 +1,255 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::json;
+
+use base64::Engine;
+
+use crate::cli::{ReportFormat, ReportGerritArgs, ReportMrArgs};
+use crate::commands::verify::Verdict;
+use crate::config::Policy;
+use crate::git::Git;
+use crate::transcript::{Decision, TranscriptStore};
+
+/// Environment variable holding the GitLab token used by `--post` (sent as `PRIVATE-TOKEN`).
+const GITLAB_TOKEN_ENV: &str = "GITLAB_TOKEN";
+const POST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Summarizes the transcripts of a merge request's commits as a Markdown note: plain Markdown,
+/// or the JSON body of GitLab's merge request notes API, optionally posted there.
+pub(crate) fn cmd_report_mr(git: &Git, args: ReportMrArgs) -> Result<u8> {
+    let range = match args.range.clone().or_else(|| {
+        std::env::var("CI_MERGE_REQUEST_DIFF_BASE_SHA")
+            .ok()
+            .map(|base| format!("{base}..HEAD"))
+    }) {
+        Some(range) => range,
+        None => {
+            return Err(anyhow!(
+                "no --range given and CI_MERGE_REQUEST_DIFF_BASE_SHA is not set"
+            ))
+        }
+    };
+    let body = render_note(git, &range)?;
+
+    match args.format {
+        ReportFormat::Markdown => print!("{body}"),
+        ReportFormat::Gitlab => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&json!({ "body": body }))?
+            )
+        }
+    }
+    if args.post {
+        return post_gitlab_note(&args, &body);
+    }
+    Ok(0)
+}
+
+fn render_note(git: &Git, range: &str) -> Result<String> {
+    let policy = Policy::load_from_repo(&git.repo)?;
+    let store = TranscriptStore::from_policy(&policy)?;
+    let commits = git.rev_list(range)?;
+
+    let mut rows = Vec::new();
+    let mut passed = 0;
+    for sha in &commits {
+        let meta = git.commit_meta(sha)?;
+        let subject = meta.subject.replace('|', "\\|");
+        let short = &sha[..sha.len().min(10)];
+        match store.load(&git.repo, sha) {
+            Ok(t) => {
+                let decision = match t.decision {
+                    Decision::Pass => {
+                        passed += 1;
+                        "✅ pass"
+                    }
+                    Decision::Fail => "❌ fail",
+                };
+                rows.push(format!(
+                    "| `{short}` | {subject} | {decision} | {:.2} | {} |",
+                    t.score.total_score,
+                    t.score.hallucination_flags.len()
+                ));
+            }
+            Err(_) => rows.push(format!(
+                "| `{short}` | {subject} | ⚠️ no transcript | — | — |"
+            )),
+        }
+    }
+
+    let mut out = format!(
+        "### aigit: {passed}/{} commit{} passed the proof-of-understanding exam\n\n",
+        commits.len(),
+        if commits.len() == 1 { "" } else { "s" }
+    );
+    if rows.is_empty() {
+        out.push_str(&format!("No commits in `{range}`.\n"));
+        return Ok(out);
+    }
+    out.push_str("| Commit | Subject | Decision | Score | Flags |\n");
+    out.push_str("|---|---|---|---|---|\n");
+    for row in rows {
+        out.push_str(&row);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// POSTs the note to `/projects/:id/merge_requests/:iid/notes`. Settings left out on the command
+/// line come from the GitLab CI environment.
+fn post_gitlab_note(args: &ReportMrArgs, body: &str) -> Result<u8> {
+    let setting = |flag: &Option<String>, env: &str, name: &str| {
+        flag.clone()
+            .or_else(|| std::env::var(env).ok())
+            .ok_or_else(|| anyhow!("--post needs {name} (or {env})"))
+    };
+    let api = setting(&args.gitlab_url, "CI_API_V4_URL", "--gitlab-url")?;
+    let project = setting(&args.project, "CI_PROJECT_ID", "--project")?;
+    let mr = setting(&args.mr, "CI_MERGE_REQUEST_IID", "--mr")?;
+    let token = std::env::var(GITLAB_TOKEN_ENV)
+        .map_err(|_| anyhow!("--post needs a token in {GITLAB_TOKEN_ENV}"))?;
+
+    let url = format!(
+        "{}/projects/{}/merge_requests/{mr}/notes",
+        api.trim_end_matches('/'),
+        project.replace('/', "%2F")
+    );
+    let agent = ureq::AgentBuilder::new().timeout(POST_TIMEOUT).build();
+    match agent
+        .post(&url)
+        .set("PRIVATE-TOKEN", &token)
+        .send_json(json!({ "body": body }))
+    {
+        Ok(resp) => {
+            let note: serde_json::Value = resp
+                .into_json()
+                .context("GitLab returned an unreadable response")?;
+            eprintln!(
+                "aigit: report: posted note {} to merge request !{mr}",
+                note["id"]
+            );
+            Ok(0)
+        }
+        Err(ureq::Error::Status(code, resp)) => {
+            let detail = resp.into_string().unwrap_or_default();
+            eprintln!(
+                "aigit: report: GitLab rejected the note ({code}): {}",
+                detail.trim()
+            );
+            Ok(3)
+        }
+        Err(err) => {
+            eprintln!("aigit: report: failed to reach GitLab: {err}");
+            Ok(3)
+        }
+    }
+}
+
+/// Verifies a commit like `aigit verify` and turns the verdict into a Gerrit `ReviewInput`: a
+/// message plus a +1/-1 vote on `--label` (no vote when the check could not run). The review is
+/// printed, and posted to the change's revision with `--post`.
+pub(crate) fn cmd_report_gerrit(git: &Git, args: ReportGerritArgs) -> Result<u8> {
+    let policy = Policy::load_from_repo(&git.repo)?;
+    let commit = git.resolve_commitish(&args.commitish)?;
+    let verdict = crate::commands::verify::verify_commit(git, &policy, &commit, !args.no_fetch)?;
+
+    let score = || {
+        TranscriptStore::git_notes()
+            .load(&git.repo, &commit)
+            .map(|t| {
+                format!(
+                    " (score {:.2}, {} hallucination flag{})",
+                    t.score.total_score,
+                    t.score.hallucination_flags.len(),
+                    if t.score.hallucination_flags.len() == 1 {
+                        ""
+                    } else {
+                        "s"
+                    }
+                )
+            })
+            .unwrap_or_default()
+    };
+    let (message, vote) = match &verdict {
+        Verdict::Pass => (format!("aigit verify: PASS{}", score()), Some(1)),
+        Verdict::Exempt(reason) => (format!("aigit verify: PASS (exempt: {reason})"), Some(1)),
+        Verdict::Fail => (format!("aigit verify: FAIL{}", score()), Some(-1)),
+        Verdict::Unapproved(reasons) => {
+            (format!("aigit verify: FAIL: {}", reasons.join("; ")), Some(-1))
+        }
+        Verdict::Rejected(reason) => (format!("aigit verify: FAIL: {reason}"), Some(-1)),
+        Verdict::Unavailable(reason) => (format!("aigit verify: could not verify: {reason}"), None),
+    };
+    let mut review = json!({ "message": message, "tag": "autogenerated:aigit" });
+    if let Some(vote) = vote {
+        review["labels"] = json!({ args.label.as_str(): vote });
+    }
+    println!("{}", serde_json::to_string_pretty(&review)?);
+
+    if args.post {
+        return post_gerrit_review(git, &args, &commit, &review);
+    }
+    Ok(0)
+}
+
+/// POSTs the review to `/a/changes/:change/revisions/:commit/review` with HTTP basic auth.
+fn post_gerrit_review(
+    git: &Git,
+    args: &ReportGerritArgs,
+    commit: &str,
+    review: &serde_json::Value,
+) -> Result<u8> {
+    let base = args
+        .gerrit_url
+        .clone()
+        .or_else(|| std::env::var("GERRIT_URL").ok())
+        .ok_or_else(|| anyhow!("--post needs --gerrit-url (or GERRIT_URL)"))?;
+    let change = args
+        .change
+        .clone()
+        .or_else(|| std::env::var("GERRIT_CHANGE_NUMBER").ok())
+        .or_else(|| git.trailer(commit, "Change-Id"))
+        .ok_or_else(|| {
+            anyhow!("--post needs --change (or GERRIT_CHANGE_NUMBER, or a Change-Id trailer)")
+        })?;
+    let (Ok(user), Ok(password)) = (
+        std::env::var("GERRIT_USER"),
+        std::env::var("GERRIT_HTTP_PASSWORD"),
+    ) else {
+        return Err(anyhow!(
+            "--post needs credentials in GERRIT_USER and GERRIT_HTTP_PASSWORD"
+        ));
+    };
+
+    let url = format!(
+        "{}/a/changes/{}/revisions/{commit}/review",
+        base.trim_end_matches('/'),
+        change.replace('/', "%2F")
+    );
+    let auth = base64::engine::general_purpose::STANDARD.encode(format!("{user}:{password}"));
+    let agent = ureq::AgentBuilder::new().timeout(POST_TIMEOUT).build();
+    match agent
+        .post(&url)
+        .set("Authorization", &format!("Basic {auth}"))
+        .send_json(review)
+    {
+        Ok(_) => {
+            eprintln!("aigit: report: posted review to Gerrit change {change}");
+            Ok(0)
+        }
+        Err(ureq::Error::Status(code, resp)) => {
+            let detail = resp.into_string().unwrap_or_default();
+            eprintln!(
+                "aigit: report: Gerrit rejected the review ({code}): {}",
+                detail.trim()
+            );
+            Ok(3)
+        }
+        Err(err) => {
+            eprintln!("aigit: report: failed to reach Gerrit: {err}");
+            Ok(3)
+        }
+    }
+}
@@ -0,0 +1,334 @@
+use std::fmt::Write as _;
+
+use anyhow::{Context, Result};
+
+use crate::cli::{ShowArgs, ShowFormat};
+use crate::config::Policy;
+use crate::git::Git;
+use crate::signing::SignatureFormat;
+use crate::transcript::{Decision, Transcript, TranscriptStore};
+
+/// Prints a commit's stored transcript, as JSON or as a single HTML file for people who review
+/// changes outside git (auditors, change-advisory boards).
+pub(crate) fn cmd_show(git: &Git, args: ShowArgs) -> Result<u8> {
+    let policy = Policy::load_from_repo(&git.repo)?;
+    let commit = git.resolve_commitish(&args.commit)?;
+    let store = TranscriptStore::from_policy(&policy)?;
+    let transcript = store
+        .load(&git.repo, &commit)
+        .with_context(|| format!("no transcript stored for {commit}"))?;
+
+    let mut out = match args.format {
+        ShowFormat::Json => serde_json::to_string_pretty(&transcript)?,
+        ShowFormat::Html => render_html(git, &policy, &commit, &transcript)?,
+        ShowFormat::InToto => {
+            serde_json::to_string_pretty(&crate::attestation::statement(&transcript, &commit)?)?
+        }
+    };
+    if !out.ends_with('\n') {
+        out.push('\n');
+    }
+    match &args.out {
+        Some(path) => {
+            std::fs::write(path, out).with_context(|| format!("failed to write {path}"))?;
+            eprintln!("aigit: wrote the transcript of {commit} to {path}");
+        }
+        None => print!("{out}"),
+    }
+    Ok(0)
+}
+
+const STYLE: &str = "body{font-family:system-ui,-apple-system,sans-serif;max-width:960px;margin:2em auto;padding:0 1em;color:#1f2328;line-height:1.45}
+h1{font-size:1.5em;margin-bottom:.2em}h2{font-size:1.15em;border-bottom:1px solid #d0d7de;padding-bottom:.2em;margin-top:1.6em}
+table{border-collapse:collapse;width:100%}th,td{text-align:left;vertical-align:top;padding:.3em .6em;border-bottom:1px solid #eaeef2}
+th{width:14em;font-weight:600}code,pre{font-family:ui-monospace,monospace;font-size:.9em}
+pre{background:#f6f8fa;padding:.6em;overflow-x:auto;white-space:pre-wrap;word-break:break-all}
+.pass{color:#1a7f37;font-weight:600}.fail{color:#cf222e;font-weight:600}.muted{color:#656d76}
+.question{border:1px solid #d0d7de;border-radius:6px;padding:.6em 1em;margin:1em 0}
+.question h3{font-size:1em;margin:.2em 0}.answer{white-space:pre-wrap;background:#f6f8fa;padding:.6em;border-radius:4px}
+.scores span{margin-right:1.2em}ul.notes{margin:.4em 0}";
+
+/// Escapes text for HTML element content and attribute values.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// `Name <email>`, or the bare email.
+fn person(name: Option<&str>, email: &str) -> String {
+    match name {
+        Some(name) => format!("{name} <{email}>"),
+        None => email.to_string(),
+    }
+}
+
+fn decision_html(decision: Decision) -> &'static str {
+    match decision {
+        Decision::Pass => "<span class=\"pass\">PASS</span>",
+        Decision::Fail => "<span class=\"fail\">FAIL</span>",
+    }
+}
+
+/// A self-contained report: the change, the decision and thresholds, every question with its
+/// answer, score and notes, flags, and the signatures and chain link that vouch for it.
+fn render_html(git: &Git, policy: &Policy, commit: &str, t: &Transcript) -> Result<String> {
+    let meta = git.commit_meta(commit)?;
+    let short = &commit[..commit.len().min(12)];
+    let mut h = String::new();
+    writeln!(
+        h,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>aigit transcript {short}: {}</title>\n<style>\n{STYLE}\n</style>\n</head>\n<body>",
+        escape(&meta.subject)
+    )?;
+    writeln!(
+        h,
+        "<h1>aigit transcript: {}</h1>\n<p class=\"muted\">Commit <code>{commit}</code> by {} &lt;{}&gt;, {}</p>",
+        escape(&meta.subject),
+        escape(&meta.author_name),
+        escape(&meta.author_email),
+        escape(&meta.author_date_iso)
+    )?;
+
+    h.push_str("<h2>Decision</h2>\n<table>\n");
+    let mut row = |label: &str, value: String| {
+        writeln!(h, "<tr><th>{label}</th><td>{value}</td></tr>").map(drop)
+    };
+    row("Decision", decision_html(t.decision).to_string())?;
+    row(
+        "Total score",
+        format!(
+            "{:.2} (minimum {:.2})",
+            t.score.total_score, t.thresholds.min_total_score
+        ),
+    )?;
+    if !t.thresholds.required_categories.is_empty() {
+        row(
+            "Required categories",
+            escape(&t.thresholds.required_categories.join(", ")),
+        )?;
+    }
+    row("Exam taken", t.timestamp.to_rfc3339())?;
+    let mut examiner = format!("{} / {}", t.provider.provider, t.provider.model);
+    if let Some(grader) = &t.provider.grader {
+        examiner.push_str(&format!(", graded by {grader}"));
+    }
+    row("Examiner", escape(&examiner))?;
+    row("Repository", escape(&t.repo_id))?;
+    row(
+        "Repository fingerprint",
+        format!("<code>{}</code>", escape(&t.repo_fingerprint)),
+    )?;
+    row(
+        "Diff fingerprint (patch-id)",
+        format!("<code>{}</code>", escape(&t.diff_fingerprint.patch_id)),
+    )?;
+    if let Some(risk) = &t.risk {
+        row(
+            "Risk",
+            escape(&format!(
+                "{} ({:.2}){}",
+                risk.level,
+                risk.score,
+                if risk.factors.is_empty() {
+                    String::new()
+                } else {
+                    format!(": {}", risk.factors.join(", "))
+                }
+            )),
+        )?;
+    }
+    if let Some(appeal) = &t.appeal {
+        row(
+            "Appeal",
+            format!(
+                "re-graded by {} / {}: {} ({:.2}){}",
+                escape(&appeal.arbiter),
+                escape(&appeal.model),
+                decision_html(appeal.arbiter_decision),
+                appeal.arbiter_score.total_score,
+                if appeal.overturned {
+                    ", overturned"
+                } else {
+                    ""
+                }
+            ),
+        )?;
+    }
+    if let Some(reused) = &t.reused_from {
+        row(
+            "Reused from",
+            format!(
+                "<code>{}</code> ({})",
+                escape(&reused.commit),
+                escape(&reused.repo_id)
+            ),
+        )?;
+    }
+    if let Some(amended) = &t.amended {
+        row(
+            "Amended from",
+            format!(
+                "<code>{}</code>{}",
+                escape(&amended.from),
+                if amended.reexamined {
+                    " (re-examined)"
+                } else {
+                    ""
+                }
+            ),
+        )?;
+    }
+    h.push_str("</table>\n");
+
+    if let Some(report) = &t.failure_report {
+        h.push_str("<h2>Why it failed</h2>\n<ul>\n");
+        for check in &report.checks {
+            writeln!(h, "<li>{}</li>", escape(&check.detail))?;
+        }
+        h.push_str("</ul>\n");
+    }
+
+    h.push_str("<h2>Questions and answers</h2>\n");
+    for q in &t.exam.questions {
+        let score = t.score.per_question.iter().find(|s| s.id == q.id);
+        writeln!(
+            h,
+            "<div class=\"question\">\n<h3>{} <span class=\"muted\">[{}]</span></h3>\n<p>{}</p>",
+            escape(&q.id),
+            escape(&q.category),
+            escape(&q.prompt)
+        )?;
+        if let Some(choices) = q.choices.as_ref().filter(|c| !c.is_empty()) {
+            h.push_str("<ol type=\"A\">\n");
+            for choice in choices {
+                writeln!(h, "<li>{}</li>", escape(choice))?;
+            }
+            h.push_str("</ol>\n");
+        }
+        let answer = t.answers.answers.get(&q.id).map(String::as_str);
+        let missing = match t.answers.withheld.get(&q.id) {
+            Some(Some(digest)) => format!("(withheld, SHA-256 {})", escape(digest)),
+            Some(None) => "(withheld)".to_string(),
+            None => "(no answer)".to_string(),
+        };
+        writeln!(
+            h,
+            "<div class=\"answer\">{}</div>",
+            answer.map_or(format!("<span class=\"muted\">{missing}</span>"), escape)
+        )?;
+        if let Some(respondent) = t.answers.respondents.get(&q.id) {
+            writeln!(
+                h,
+                "<p class=\"muted\">Answered by {}</p>",
+                escape(&person(respondent.name.as_deref(), &respondent.email))
+            )?;
+        }
+        if let Some(s) = score {
+            writeln!(
+                h,
+                "<p class=\"scores\"><span>score {:.2}</span><span>completeness {:.2}</span><span>specificity {:.2}</span></p>",
+                s.score, s.completeness, s.specificity
+            )?;
+            if !s.notes.is_empty() {
+                h.push_str("<ul class=\"notes\">\n");
+                for note in &s.notes {
+                    writeln!(h, "<li>{}</li>", escape(&note.to_string()))?;
+                }
+                h.push_str("</ul>\n");
+            }
+        }
+        h.push_str("</div>\n");
+    }
+
+    if !t.score.hallucination_flags.is_empty() || !t.score.integrity_flags.is_empty() {
+        h.push_str("<h2>Flags</h2>\n<ul>\n");
+        for flag in &t.score.hallucination_flags {
+            writeln!(h, "<li>hallucination: {}</li>", escape(&flag.to_string()))?;
+        }
+        for flag in &t.score.integrity_flags {
+            writeln!(h, "<li>copied answer: {}</li>", escape(&flag.to_string()))?;
+        }
+        h.push_str("</ul>\n");
+    }
+
+    h.push_str("<h2>Signatures</h2>\n");
+    match &t.signature {
+        Some(signature) => {
+            let status = match (
+                policy.signing.allowed_signers.as_deref(),
+                crate::signing::check(&git.repo, policy, t),
+            ) {
+                (None, _) => "not checked (no signing.allowed_signers)".to_string(),
+                (Some(allowed), None) => format!("verified against {}", escape(allowed)),
+                (Some(_), Some(reason)) => {
+                    format!("<span class=\"fail\">{}</span>", escape(&reason))
+                }
+            };
+            writeln!(
+                h,
+                "<p>Signed by {} ({} key): {}</p>\n<pre>{}</pre>",
+                escape(&signature.signer),
+                match signature.format {
+                    SignatureFormat::Ssh => "SSH",
+                    SignatureFormat::Openpgp => "OpenPGP",
+                },
+                status,
+                escape(signature.signature.trim())
+            )?;
+        }
+        None => h.push_str("<p class=\"muted\">The transcript is not signed.</p>\n"),
+    }
+    if !t.countersignatures.is_empty() {
+        h.push_str("<p>Countersigned by:</p>\n<ul>\n");
+        for c in &t.countersignatures {
+            let status = match crate::signing::check_countersignature(&git.repo, policy, t, c) {
+                Ok(()) => "verified".to_string(),
+                Err(err) => format!(
+                    "<span class=\"fail\">{}</span>",
+                    escape(&format!("{err:#}"))
+                ),
+            };
+            writeln!(
+                h,
+                "<li>{} on {}{}: {}</li>",
+                escape(&person(c.name.as_deref(), &c.email)),
+                c.timestamp.to_rfc3339(),
+                if c.groups.is_empty() {
+                    String::new()
+                } else {
+                    format!(" for {}", escape(&c.groups.join(", ")))
+                },
+                status
+            )?;
+        }
+        h.push_str("</ul>\n");
+    }
+    if let Some(link) = &t.chain {
+        h.push_str("<h2>Transcript chain</h2>\n");
+        match &link.previous {
+            Some(previous) => writeln!(
+                h,
+                "<p>Follows the transcript of <code>{}</code> (hash <code>{}</code>).</p>",
+                escape(&previous.commit),
+                escape(&previous.hash)
+            )?,
+            None => h.push_str("<p>First transcript of the chain.</p>\n"),
+        }
+    }
+    writeln!(
+        h,
+        "<h2>Transcript hash</h2>\n<p><code>sha256:{}</code></p>\n</body>\n</html>",
+        t.content_hash()?
+    )?;
+    Ok(h)
+}
@@ -0,0 +1,217 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::cli::{StatsArgs, StatsBy, StatsFormat};
+use crate::config::Policy;
+use crate::git::Git;
+use crate::transcript::{Decision, NoteCode, TranscriptStore};
+
+#[derive(Debug, Clone, Serialize)]
+struct AuthorStats {
+    author_name: String,
+    author_email: String,
+    exams: u32,
+    passes: u32,
+    pass_rate: f64,
+    avg_total_score: f64,
+    category_scores: BTreeMap<String, f64>,
+    /// Grader notes per deficiency code, most common first.
+    deficiencies: Vec<DeficiencyCount>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct DeficiencyCount {
+    code: NoteCode,
+    notes: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct StatsReport {
+    schema_version: String,
+    generated_at: DateTime<Utc>,
+    by: String,
+    since: Option<String>,
+    until: Option<String>,
+    groups: Vec<AuthorStats>,
+    /// Deficiency notes across every group, most common first.
+    deficiencies: Vec<DeficiencyCount>,
+}
+
+#[derive(Default)]
+struct Accumulator {
+    author_name: String,
+    exams: u32,
+    passes: u32,
+    total_score: f64,
+    categories: BTreeMap<String, (u32, f64)>,
+    deficiencies: BTreeMap<NoteCode, u32>,
+}
+
+pub(crate) fn cmd_stats(git: &Git, args: StatsArgs) -> Result<u8> {
+    let policy = Policy::load_from_repo(&git.repo)?;
+    match args.by {
+        StatsBy::Author => {
+            if !policy.stats.by_author.unwrap_or(false) {
+                return Err(anyhow!(
+                    "per-author stats are disabled; opt in with `stats.by_author = true` in .aigit.toml"
+                ));
+            }
+        }
+    }
+    let since = args.since.as_deref().map(parse_date).transpose()?;
+    let until = args.until.as_deref().map(parse_date).transpose()?;
+
+    let store = TranscriptStore::from_policy(&policy)?;
+    let mut groups: BTreeMap<String, Accumulator> = BTreeMap::new();
+    for sha in store.list(&git.repo)? {
+        let Ok(meta) = git.commit_meta(&sha) else {
+            continue;
+        };
+        let authored = DateTime::parse_from_rfc3339(&meta.author_date_iso)
+            .map(|d| d.date_naive())
+            .ok();
+        if let (Some(since), Some(date)) = (since, authored) {
+            if date < since {
+                continue;
+            }
+        }
+        if let (Some(until), Some(date)) = (until, authored) {
+            if date >= until {
+                continue;
+            }
+        }
+        let t = match store.load(&git.repo, &sha) {
+            Ok(t) => t,
+            Err(e) => {
+                eprintln!("aigit: stats: skipping {sha}: failed to load transcript: {e}");
+                continue;
+            }
+        };
+
+        let acc = groups
+            .entry(meta.author_email.to_ascii_lowercase())
+            .or_default();
+        acc.author_name = meta.author_name;
+        acc.exams += 1;
+        if t.decision == Decision::Pass {
+            acc.passes += 1;
+        }
+        acc.total_score += t.score.total_score;
+        for q in &t.score.per_question {
+            let entry = acc.categories.entry(q.category.clone()).or_default();
+            entry.0 += 1;
+            entry.1 += q.score;
+            for note in q.notes.iter().filter(|n| n.code.is_deficiency()) {
+                *acc.deficiencies.entry(note.code).or_default() += 1;
+            }
+        }
+    }
+
+    let mut totals: BTreeMap<NoteCode, u32> = BTreeMap::new();
+    for acc in groups.values() {
+        for (code, n) in &acc.deficiencies {
+            *totals.entry(*code).or_default() += n;
+        }
+    }
+    let report = StatsReport {
+        schema_version: "aigit-stats/0.1".to_string(),
+        generated_at: Utc::now(),
+        by: "author".to_string(),
+        since: args.since,
+        until: args.until,
+        groups: groups
+            .into_iter()
+            .map(|(email, acc)| AuthorStats {
+                author_name: acc.author_name,
+                author_email: email,
+                exams: acc.exams,
+                passes: acc.passes,
+                pass_rate: acc.passes as f64 / acc.exams as f64,
+                avg_total_score: acc.total_score / acc.exams as f64,
+                category_scores: acc
+                    .categories
+                    .into_iter()
+                    .map(|(cat, (n, sum))| (cat, sum / n as f64))
+                    .collect(),
+                deficiencies: ranked(acc.deficiencies),
+            })
+            .collect(),
+        deficiencies: ranked(totals),
+    };
+
+    match args.format {
+        StatsFormat::Json => {
+            serde_json::to_writer_pretty(std::io::stdout(), &report)?;
+            println!();
+        }
+        StatsFormat::Md => print!("{}", render_markdown(&report)),
+    }
+    Ok(0)
+}
+
+fn ranked(counts: BTreeMap<NoteCode, u32>) -> Vec<DeficiencyCount> {
+    let mut out: Vec<DeficiencyCount> = counts
+        .into_iter()
+        .map(|(code, notes)| DeficiencyCount { code, notes })
+        .collect();
+    out.sort_by(|a, b| b.notes.cmp(&a.notes).then(a.code.cmp(&b.code)));
+    out
+}
+
+fn parse_date(raw: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+        .map_err(|_| anyhow!("invalid date {raw:?} (expected YYYY-MM-DD)"))
+}
+
+fn render_markdown(report: &StatsReport) -> String {
+    let categories = report
+        .groups
+        .iter()
+        .flat_map(|g| g.category_scores.keys().cloned())
+        .collect::<BTreeSet<_>>();
+
+    let mut out = String::new();
+    out.push_str("| Author | Exams | Pass rate | Avg score | Top deficiency |");
+    for c in &categories {
+        out.push_str(&format!(" {c} |"));
+    }
+    out.push('\n');
+    out.push_str("|---|---:|---:|---:|---|");
+    for _ in &categories {
+        out.push_str("---:|");
+    }
+    out.push('\n');
+    for g in &report.groups {
+        out.push_str(&format!(
+            "| {} <{}> | {} | {:.0}% | {:.2} | {} |",
+            g.author_name,
+            g.author_email,
+            g.exams,
+            g.pass_rate * 100.0,
+            g.avg_total_score,
+            g.deficiencies
+                .first()
+                .map(|d| format!("{} ({})", d.code, d.notes))
+                .unwrap_or_else(|| "-".to_string())
+        ));
+        for c in &categories {
+            match g.category_scores.get(c) {
+                Some(v) => out.push_str(&format!(" {v:.2} |")),
+                None => out.push_str(" - |"),
+            }
+        }
+        out.push('\n');
+    }
+    if !report.deficiencies.is_empty() {
+        let counts: Vec<String> = report
+            .deficiencies
+            .iter()
+            .map(|d| format!("{} {}", d.code, d.notes))
+            .collect();
+        out.push_str(&format!("\nDeficiency notes: {}\n", counts.join(", ")));
+    }
+    out
+}
@@ -0,0 +1,200 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::cli::{TranscriptFindArgs, TranscriptFormat, TranscriptMigrateArgs};
+use crate::config::Policy;
+use crate::git::{Git, GitRepo};
+use crate::transcript::{
+    Decision, MigrationOutcome, ReusedFrom, Transcript, TranscriptStore, SCHEMA_VERSION,
+};
+
+#[derive(Debug, Clone, Serialize)]
+struct TranscriptMatch {
+    /// Working directory of the repository the transcript was found in.
+    repo: String,
+    commit: String,
+    repo_id: String,
+    timestamp: DateTime<Utc>,
+    decision: Decision,
+    total_score: f64,
+    subject: String,
+    #[serde(skip)]
+    transcript: Transcript,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct FindReport {
+    schema_version: String,
+    patch_id: String,
+    matches: Vec<TranscriptMatch>,
+}
+
+pub(crate) fn cmd_transcript_find(git: &Git, args: TranscriptFindArgs) -> Result<u8> {
+    let policy = Policy::load_from_repo(&git.repo)?;
+    let patch_id = match (&args.patch_id, &args.commit) {
+        (Some(id), _) => id.clone(),
+        (None, Some(commit)) => {
+            let commit = git.resolve_commitish(commit)?;
+            git.patch_id_for_commit(&commit)?
+        }
+        (None, None) => {
+            let (diff, _) = git.diff_staged()?;
+            if diff.trim().is_empty() {
+                return Err(anyhow!(
+                    "no staged changes; pass --patch-id or --commit to choose the change"
+                ));
+            }
+            git.patch_id_from_diff_text(&diff)?
+        }
+    };
+    let exclude = args
+        .commit
+        .as_deref()
+        .map(|c| git.resolve_commitish(c))
+        .transpose()?;
+
+    let store = TranscriptStore::from_policy(&policy)?;
+    let mut matches = find_in(git, &store, &patch_id, exclude.as_deref())?;
+    for path in &args.repos {
+        let other = Git::new(GitRepo::open(Path::new(path))?);
+        matches.extend(find_in(&other, &store, &patch_id, None)?);
+    }
+    matches.sort_by_key(|m| std::cmp::Reverse(m.timestamp));
+
+    match args.format {
+        TranscriptFormat::Json => {
+            let report = FindReport {
+                schema_version: "aigit-transcript-find/0.1".to_string(),
+                patch_id: patch_id.clone(),
+                matches: matches.clone(),
+            };
+            serde_json::to_writer_pretty(std::io::stdout(), &report)?;
+            println!();
+        }
+        TranscriptFormat::Table => {
+            if matches.is_empty() {
+                eprintln!("aigit: no transcripts found for patch-id {patch_id}");
+            }
+            for m in &matches {
+                println!(
+                    "{}  {}  {:.2}  {}  {}  {}",
+                    &m.commit[..m.commit.len().min(12)],
+                    match m.decision {
+                        Decision::Pass => "PASS",
+                        Decision::Fail => "FAIL",
+                    },
+                    m.total_score,
+                    m.timestamp.format("%Y-%m-%d"),
+                    m.repo,
+                    m.subject
+                );
+            }
+        }
+    }
+
+    let Some(target) = &args.attach else {
+        return Ok(0);
+    };
+    let target = git.resolve_commitish(target)?;
+    let target_patch_id = git.patch_id_for_commit(&target)?;
+    if target_patch_id != patch_id {
+        return Err(anyhow!(
+            "{target} introduces a different change (patch-id {target_patch_id}); refusing to attach"
+        ));
+    }
+    let Some(source) = matches
+        .iter()
+        .find(|m| m.decision == Decision::Pass && m.commit != target)
+    else {
+        eprintln!("aigit: no passing transcript to reuse for patch-id {patch_id}");
+        return Ok(4);
+    };
+    let mut transcript = source.transcript.clone();
+    transcript.reused_from = Some(ReusedFrom {
+        commit: source.commit.clone(),
+        repo_id: source.repo_id.clone(),
+        timestamp: source.timestamp,
+    });
+    transcript.commit = Some(target.clone());
+    transcript.timestamp = Utc::now();
+    transcript.chain = None;
+    store.link(&git.repo, &mut transcript)?;
+    crate::signing::sign_if_enabled(git, &policy, &mut transcript)?;
+    store.store(&git.repo, &target, &transcript)?;
+    eprintln!(
+        "aigit: attached the transcript of {} to {target}",
+        source.commit
+    );
+    Ok(0)
+}
+
+/// Transcripts in `git`'s store whose diff fingerprint is `patch_id`.
+fn find_in(
+    git: &Git,
+    store: &TranscriptStore,
+    patch_id: &str,
+    exclude: Option<&str>,
+) -> Result<Vec<TranscriptMatch>> {
+    let mut out = Vec::new();
+    for sha in store.list(&git.repo)? {
+        if exclude == Some(sha.as_str()) {
+            continue;
+        }
+        let Ok(transcript) = store.load(&git.repo, &sha) else {
+            continue;
+        };
+        if transcript.diff_fingerprint.patch_id != patch_id {
+            continue;
+        }
+        let subject = git.commit_meta(&sha).map(|m| m.subject).unwrap_or_default();
+        out.push(TranscriptMatch {
+            repo: git.repo.workdir.display().to_string(),
+            commit: sha,
+            repo_id: transcript.repo_id.clone(),
+            timestamp: transcript.timestamp,
+            decision: transcript.decision,
+            total_score: transcript.score.total_score,
+            subject,
+            transcript,
+        });
+    }
+    Ok(out)
+}
+
+/// Rewrites transcripts stored in git notes in an older schema to `SCHEMA_VERSION`, leaving
+/// the ones whose hash something pins; exit code 2 when some could not be read.
+pub(crate) fn cmd_transcript_migrate(git: &Git, args: TranscriptMigrateArgs) -> Result<u8> {
+    let policy = Policy::load_from_repo(&git.repo)?;
+    let store = TranscriptStore::from_policy(&policy)?;
+    let outcomes = store.migrate_notes(&git.repo, args.dry_run)?;
+    let (mut upgraded, mut pinned, mut unreadable) = (0, 0, 0);
+    for (commit, outcome) in &outcomes {
+        match outcome {
+            MigrationOutcome::Current => {}
+            MigrationOutcome::Upgraded { from } => {
+                upgraded += 1;
+                println!(
+                    "{commit}  {from} -> {SCHEMA_VERSION}{}",
+                    if args.dry_run { " (dry run)" } else { "" }
+                );
+            }
+            MigrationOutcome::Pinned { from, by } => {
+                pinned += 1;
+                println!("{commit}  kept at {from}: {by} covers it as stored");
+            }
+            MigrationOutcome::Unreadable(reason) => {
+                unreadable += 1;
+                println!("{commit}  unreadable: {reason}");
+            }
+        }
+    }
+    eprintln!(
+        "aigit transcript migrate: {} {upgraded} of {} transcript(s) to {SCHEMA_VERSION}, kept {pinned} pinned, {unreadable} unreadable",
+        if args.dry_run { "would upgrade" } else { "upgraded" },
+        outcomes.len()
+    );
+    Ok(if unreadable > 0 { 2 } else { 0 })
+}
@@ -1,43 +1,343 @@
 use anyhow::Result;
+use serde::Serialize;
 
-use crate::cli::VerifyArgs;
+use crate::cli::{VerifyArgs, VerifyFormat};
 use crate::config::Policy;
 use crate::git::Git;
-use crate::transcript::TranscriptStore;
+use crate::transcript::{Decision, TranscriptStore};
+
+/// What verifying one commit found.
+pub(crate) enum Verdict {
+    Pass,
+    /// The transcript does not meet the policy.
+    Fail,
+    /// No usable transcript for this commit (missing, or for a different repo or diff).
+    Rejected(String),
+    /// The transcript meets the policy but lacks a required approver's countersignature.
+    Unapproved(Vec<String>),
+    /// The check itself could not run (e.g. history missing from a shallow clone).
+    Unavailable(String),
+    /// The commit is of a class `[verify]` exempts (merges, bots, reverts); not checked.
+    Exempt(String),
+}
+
+impl Verdict {
+    /// Exit code of `aigit verify` for this verdict.
+    pub(crate) fn code(&self) -> u8 {
+        match self {
+            Verdict::Pass | Verdict::Exempt(_) => 0,
+            Verdict::Fail | Verdict::Unapproved(_) | Verdict::Rejected(_) => 4,
+            Verdict::Unavailable(_) => 3,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Verdict::Pass => "pass",
+            Verdict::Fail => "fail",
+            Verdict::Rejected(_) => "rejected",
+            Verdict::Unapproved(_) => "unapproved",
+            Verdict::Unavailable(_) => "unavailable",
+            Verdict::Exempt(_) => "exempt",
+        }
+    }
+
+    fn reasons(&self) -> Vec<String> {
+        match self {
+            Verdict::Pass => Vec::new(),
+            Verdict::Fail => vec!["transcript fails the policy".to_string()],
+            Verdict::Unapproved(reasons) => reasons.clone(),
+            Verdict::Rejected(err) | Verdict::Unavailable(err) | Verdict::Exempt(err) => {
+                vec![err.clone()]
+            }
+        }
+    }
+}
+
+/// One commit's line in `aigit verify --range` / `--all`.
+#[derive(Serialize)]
+pub(crate) struct CommitVerdict {
+    commit: String,
+    subject: String,
+    result: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    reasons: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct BatchReport {
+    commits: Vec<CommitVerdict>,
+    pub(crate) passed: usize,
+    /// Commits `[verify]` exempts from the check.
+    pub(crate) exempt: usize,
+    /// Commits without a valid transcript, in the order verified (newest first).
+    pub(crate) failed: Vec<String>,
+}
 
 pub(crate) fn cmd_verify(git: &Git, args: VerifyArgs, _verbose: bool) -> Result<u8> {
-    let policy = Policy::load_from_repo(&git.repo)?;
-    let store = TranscriptStore::git_notes();
+    let mut policy = Policy::load_from_repo(&git.repo)?;
+    if let Some(basis) = args.policy {
+        policy.verify.policy = Some(basis.as_str().to_string());
+    }
+    let mut chain_code = 0;
+    if args.chain {
+        chain_code = verify_chain(git, &policy)?;
+        if args.commitish.is_none() && args.range.is_none() && !args.all {
+            return Ok(chain_code);
+        }
+    }
+    let commits = match (&args.commitish, &args.range) {
+        (Some(commitish), _) => vec![git.resolve_commitish(commitish)?],
+        (None, Some(range)) => git.rev_list(range)?,
+        (None, None) => git.rev_list("HEAD")?,
+    };
+    if args.commitish.is_none() || matches!(args.format, VerifyFormat::Json) {
+        return Ok(verify_batch(git, &policy, &commits, &args)?.max(chain_code));
+    }
+    let commit = &commits[0];
+    let verdict = verify_commit(git, &policy, commit, !args.no_fetch)?;
+    match &verdict {
+        Verdict::Pass => println!("aigit verify: PASS ({commit})"),
+        Verdict::Exempt(reason) => println!("aigit verify: EXEMPT ({commit}): {reason}"),
+        Verdict::Fail => println!("aigit verify: FAIL ({commit})"),
+        Verdict::Unapproved(reasons) => {
+            println!("aigit verify: FAIL ({commit})");
+            for reason in reasons {
+                eprintln!("aigit verify: {reason}");
+            }
+        }
+        Verdict::Rejected(err) | Verdict::Unavailable(err) => eprintln!("aigit verify: {err}"),
+    }
+    Ok(verdict.code().max(chain_code))
+}
 
-    let commit = git.resolve_commitish(&args.commitish)?;
-    let transcript = match store.load(&git.repo, &commit) {
-        Ok(t) => t,
-        Err(err) => {
-            eprintln!("aigit verify: {err}");
-            return Ok(4);
+/// Walks the transcript chain back from `refs/aigit/chain`; 4 when a transcript in it was
+/// deleted or replaced.
+fn verify_chain(git: &Git, policy: &Policy) -> Result<u8> {
+    let store = TranscriptStore::from_policy(policy)?;
+    let Some(report) = crate::chain::walk(&git.repo, &store)? else {
+        eprintln!(
+            "aigit verify: no transcript chain ({} is missing; set chain.enabled)",
+            crate::chain::CHAIN_REF
+        );
+        return Ok(4);
+    };
+    match report.broken {
+        None => {
+            println!(
+                "aigit verify: chain intact: {} transcript(s) back from {}",
+                report.commits.len(),
+                &report.commits[0][..report.commits[0].len().min(12)]
+            );
+            Ok(0)
+        }
+        Some((commit, reason)) => {
+            println!(
+                "aigit verify: chain broken at {}: {reason} ({} transcript(s) checked before it)",
+                &commit[..commit.len().min(12)],
+                report.commits.len()
+            );
+            Ok(4)
+        }
+    }
+}
+
+/// Verifies each of `commits` and prints one line per commit (or JSON); exits with the worst
+/// verdict's code, listing the commits that failed.
+fn verify_batch(git: &Git, policy: &Policy, commits: &[String], args: &VerifyArgs) -> Result<u8> {
+    let (report, code) = verify_all(git, policy, commits, !args.no_fetch)?;
+    match args.format {
+        VerifyFormat::Json => {
+            serde_json::to_writer_pretty(std::io::stdout(), &report)?;
+            println!();
         }
+        VerifyFormat::Table => {
+            println!("{:<12}  {:<11}  subject", "commit", "result");
+            for c in &report.commits {
+                println!(
+                    "{:<12}  {:<11}  {}",
+                    &c.commit[..c.commit.len().min(12)],
+                    c.result.to_uppercase(),
+                    c.subject
+                );
+                for reason in &c.reasons {
+                    println!("{:<12}  {:<11}  - {reason}", "", "");
+                }
+            }
+            println!(
+                "aigit verify: {} commit(s), {} passed, {} exempt, {} failed",
+                report.commits.len(),
+                report.passed,
+                report.exempt,
+                report.failed.len()
+            );
+        }
+    }
+    if !report.failed.is_empty() {
+        let short: Vec<&str> = report
+            .failed
+            .iter()
+            .map(|c| &c[..c.len().min(12)])
+            .collect();
+        eprintln!("aigit verify: commits without a valid transcript: {}", short.join(" "));
+    }
+    Ok(code)
+}
+
+/// Verifies each of `commits`; the report and the worst verdict's exit code.
+pub(crate) fn verify_all(
+    git: &Git,
+    policy: &Policy,
+    commits: &[String],
+    allow_fetch: bool,
+) -> Result<(BatchReport, u8)> {
+    let mut code = 0;
+    let mut report = BatchReport {
+        commits: Vec::new(),
+        passed: 0,
+        exempt: 0,
+        failed: Vec::new(),
+    };
+    for commit in commits {
+        let verdict = verify_commit(git, policy, commit, allow_fetch)?;
+        code = code.max(verdict.code());
+        match verdict {
+            Verdict::Pass => report.passed += 1,
+            Verdict::Exempt(_) => report.exempt += 1,
+            _ => report.failed.push(commit.clone()),
+        }
+        report.commits.push(CommitVerdict {
+            commit: commit.clone(),
+            subject: git
+                .commit_meta(commit)
+                .map(|m| m.subject)
+                .unwrap_or_default(),
+            result: verdict.label(),
+            reasons: verdict.reasons(),
+        });
+    }
+    Ok((report, code))
+}
+
+/// Checks that `commit` (a full sha) has a transcript for this repository and exactly this diff,
+/// that it meets the policy, and that the approvers it needs countersigned it.
+pub(crate) fn verify_commit(
+    git: &Git,
+    policy: &Policy,
+    commit: &str,
+    allow_fetch: bool,
+) -> Result<Verdict> {
+    if let Some(reason) = exemption(git, policy, commit)? {
+        return Ok(Verdict::Exempt(reason));
+    }
+    let store = TranscriptStore::from_policy(policy)?;
+    let transcript = match store.load(&git.repo, commit) {
+        Ok(t) => t,
+        Err(err) => return Ok(Verdict::Rejected(err.to_string())),
     };
 
     if let Some(t_commit) = &transcript.commit {
-        if t_commit != &commit {
-            eprintln!("aigit verify: transcript commit mismatch");
-            return Ok(4);
+        if t_commit != commit {
+            return Ok(Verdict::Rejected("transcript commit mismatch".to_string()));
+        }
+    }
+    if let Some(reason) = crate::signing::check(&git.repo, policy, &transcript) {
+        return Ok(Verdict::Rejected(reason));
+    }
+    if let Some(reason) = transcript.withheld_answers_issue() {
+        return Ok(Verdict::Rejected(reason));
+    }
+
+    if policy.repo_id.hash.unwrap_or(false) {
+        let expected_repo_id = crate::repo_id::resolve(git, policy)?;
+        if !crate::repo_id::is_hashed(&transcript.repo_id) || transcript.repo_id != expected_repo_id
+        {
+            return Ok(Verdict::Rejected(
+                "repo identity mismatch (hashed repo_id differs)".to_string(),
+            ));
         }
     }
 
-    let expected_patch_id = git.patch_id_for_commit(&commit)?;
+    if let Err(err) = git.ensure_parents_available(commit, allow_fetch) {
+        return Ok(Verdict::Unavailable(err.to_string()));
+    }
+    let expected_patch_id = git.patch_id_for_commit(commit)?;
     if transcript.diff_fingerprint.patch_id != expected_patch_id {
-        eprintln!("aigit verify: diff fingerprint mismatch");
-        return Ok(4);
+        return Ok(Verdict::Rejected("diff fingerprint mismatch".to_string()));
     }
 
-    let ok = transcript.verify_against_policy(&policy);
-    if ok {
-        println!("aigit verify: PASS ({commit})");
-        Ok(0)
+    let recorded;
+    let policy = if policy.verify.policy.as_deref() == Some("recorded") {
+        // The thresholds come from the note, so only a signed one is believed.
+        if policy.signing.allowed_signers.is_none() || transcript.signature.is_none() {
+            return Ok(Verdict::Rejected(
+                "--policy recorded needs a transcript signed by a key in signing.allowed_signers"
+                    .to_string(),
+            ));
+        }
+        recorded = transcript.recorded_policy(policy);
+        &recorded
     } else {
-        println!("aigit verify: FAIL ({commit})");
-        Ok(4)
+        policy
+    };
+    let changed_files = git.changed_files_for_commit(commit)?;
+    if crate::decision_engine::enabled(policy) {
+        let meta = git.commit_meta(commit)?;
+        let change = crate::decision_engine::change(
+            Some(meta.author_name),
+            Some(meta.author_email),
+            &changed_files,
+        );
+        let verdict = crate::decision_engine::verify(&git.repo, policy, &transcript, &changed_files, change)?;
+        if transcript.decision != Decision::Pass || !verdict.allow {
+            return Ok(Verdict::Fail);
+        }
+    } else if !transcript.verify_against_policy(policy, &changed_files) {
+        return Ok(Verdict::Fail);
     }
+    if policy.approvals.is_empty() {
+        return Ok(Verdict::Pass);
+    }
+    let author = git.commit_meta(commit)?.author_email;
+    let missing =
+        crate::approvals::missing(&git.repo, policy, &transcript, &author, &changed_files)?;
+    Ok(if missing.is_empty() {
+        Verdict::Pass
+    } else {
+        Verdict::Unapproved(missing)
+    })
 }
 
+/// Why `[verify]` exempts `commit` from the transcript check, if it does.
+fn exemption(git: &Git, policy: &Policy, commit: &str) -> Result<Option<String>> {
+    let rules = &policy.verify;
+    if git.parent_count(commit)? > 1 {
+        if rules.skip_merges.unwrap_or(false) {
+            return Ok(Some("merge commit".to_string()));
+        }
+        // A merge that leaves the first parent's tree as it was has nothing to examine.
+        if git
+            .changed_files_for_commit(commit)
+            .is_ok_and(|files| files.is_empty())
+        {
+            return Ok(Some("merge introduces no changes".to_string()));
+        }
+    }
+    if rules.skip_authors.is_empty() && rules.skip_subject_patterns.is_empty() {
+        return Ok(None);
+    }
+    let meta = git.commit_meta(commit)?;
+    if let Some(author) = rules
+        .skip_authors
+        .iter()
+        .find(|a| **a == meta.author_name || a.eq_ignore_ascii_case(&meta.author_email))
+    {
+        return Ok(Some(format!("author {author}")));
+    }
+    for pattern in &rules.skip_subject_patterns {
+        if regex::Regex::new(pattern)?.is_match(&meta.subject) {
+            return Ok(Some(format!("subject matches {pattern:?}")));
+        }
+    }
+    Ok(None)
+}
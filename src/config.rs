@@ -2,7 +2,10 @@ use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
+use crate::calibration::GraderCalibration;
+use crate::diff_stats::DiffStats;
 use crate::git::GitRepo;
+use crate::transcript::{QuestionScore, Severity};
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CodexCliPolicy {
@@ -29,6 +32,1063 @@ pub struct CodexCliPolicy {
     /// Timeout for the Codex process in seconds.
     #[serde(default)]
     pub timeout_secs: Option<u64>,
+
+    /// How many times a failed or unparseable Codex run is retried (default 1).
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+
+    /// Delay before the first retry in milliseconds; doubles on each further retry (default 500).
+    #[serde(default)]
+    pub retry_backoff_ms: Option<u64>,
+
+    /// When a response cannot be parsed, retry with a prompt that includes the parse error
+    /// (default true). When false the original prompt is re-sent unchanged.
+    #[serde(default)]
+    pub repair_prompt: Option<bool>,
+
+    /// Stream `codex exec` stderr and elapsed time to the terminal while it runs (default true).
+    /// `--quiet` on `aigit exam` / `aigit commit` turns it off for one run.
+    #[serde(default)]
+    pub progress: Option<bool>,
+
+    /// Minimum delay between forwarded stderr lines in milliseconds (default 1000).
+    #[serde(default)]
+    pub progress_interval_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClaudeCliPolicy {
+    /// Base command used to invoke the claude CLI (no flags).
+    ///
+    /// Examples:
+    /// - "claude"
+    /// - "npx -y @anthropic-ai/claude-code"
+    #[serde(default)]
+    pub command: Option<String>,
+
+    /// Optional model override (passed to `claude --model`).
+    #[serde(default)]
+    pub model: Option<String>,
+
+    /// Permission mode passed to `claude --permission-mode` (default "plan", i.e. read-only).
+    #[serde(default)]
+    pub permission_mode: Option<String>,
+
+    /// Tools the examiner may use (default ["Read", "Grep", "Glob"]).
+    #[serde(default)]
+    pub allowed_tools: Option<Vec<String>>,
+
+    /// Timeout for the claude process in seconds.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExecPolicy {
+    /// Command that reads an `aigit-exec/0.1` request on stdin and prints an `Exam` as JSON.
+    #[serde(default)]
+    pub exam_command: Option<String>,
+
+    /// Command that reads an `aigit-exec/0.1` request (with answers) on stdin and prints a
+    /// `Score` as JSON.
+    #[serde(default)]
+    pub grade_command: Option<String>,
+
+    /// Timeout for each command in seconds.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HttpApiPolicy {
+    /// Base URL of an OpenAI-compatible API; `/chat/completions` is appended
+    /// (default "https://api.openai.com/v1").
+    #[serde(default)]
+    pub base_url: Option<String>,
+
+    /// Environment variable holding the bearer token (default "OPENAI_API_KEY"; no
+    /// `Authorization` header is sent when it is unset).
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+
+    /// Model name sent with each request (falls back to `model`).
+    #[serde(default)]
+    pub model: Option<String>,
+
+    /// "json_schema" (default) or "json_object" for servers without structured outputs.
+    #[serde(default)]
+    pub response_format: Option<String>,
+
+    /// Request timeout in seconds.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GradingPolicy {
+    /// Grade each exam this many times and average the results (default 1). With more than one
+    /// sample, transcripts record per-question and total confidence intervals.
+    #[serde(default)]
+    pub samples: Option<u32>,
+
+    /// Confidence level of the recorded intervals: 0.80, 0.90, 0.95 (default) or 0.99.
+    #[serde(default)]
+    pub confidence_level: Option<f64>,
+
+    /// Compare the lower bound of the total score's interval, not the mean, against
+    /// `min_total_score`, so borderline passes need a confident grade.
+    #[serde(default)]
+    pub require_lower_bound: Option<bool>,
+}
+
+impl GradingPolicy {
+    pub fn confidence_level(&self) -> f64 {
+        self.confidence_level.unwrap_or(0.95)
+    }
+
+    /// Two-sided normal quantile for the configured confidence level.
+    pub fn z_score(&self) -> Result<f64> {
+        let level = self.confidence_level();
+        [(0.80, 1.2816), (0.90, 1.6449), (0.95, 1.9600), (0.99, 2.5758)]
+            .iter()
+            .find(|(l, _)| (l - level).abs() < 1e-9)
+            .map(|(_, z)| *z)
+            .ok_or_else(|| {
+                anyhow!("grading.confidence_level must be 0.80, 0.90, 0.95 or 0.99 (got {level})")
+            })
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExamQualityPolicy {
+    /// Validate exams written by a non-static provider before showing them (default true).
+    #[serde(default)]
+    pub enabled: Option<bool>,
+
+    /// Ask the provider for a second exam when the first has problems (default true). Whatever
+    /// problems remain are patched by dropping questions or adding built-in ones.
+    #[serde(default)]
+    pub regenerate: Option<bool>,
+
+    /// Word-overlap (Jaccard, 0..1) at which two questions count as duplicates (default 0.8).
+    #[serde(default)]
+    pub near_duplicate_threshold: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExamPolicy {
+    /// Exam sizes by change size and area, keyed by name (`[exam.tiers.small]`). Without tiers
+    /// every change gets the full exam.
+    #[serde(default)]
+    pub tiers: BTreeMap<String, ExamTier>,
+
+    /// Give changes that only touch test files the short `tests_only` exam, on what the tests
+    /// pin down and why they were written (default true). A tier limited to `paths` that fits
+    /// the change still wins.
+    #[serde(default)]
+    pub tests_only: Option<bool>,
+}
+
+/// One exam size. A change fits a tier when it is within every limit the tier sets.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExamTier {
+    /// Most added plus removed lines (default: no limit).
+    #[serde(default)]
+    pub max_lines: Option<usize>,
+
+    /// Most changed files (default: no limit).
+    #[serde(default)]
+    pub max_files: Option<usize>,
+
+    /// Every changed file must match one of these globs, e.g. `docs/**` (empty = any files).
+    #[serde(default)]
+    pub paths: Vec<String>,
+
+    /// Questions to ask (default: as many as the full exam).
+    #[serde(default)]
+    pub questions: Option<usize>,
+
+    /// Categories to ask about (empty = all); required categories are always asked.
+    #[serde(default)]
+    pub categories: Vec<String>,
+}
+
+impl ExamTier {
+    pub fn fits(&self, stats: &DiffStats, changed_files: &[String]) -> bool {
+        self.max_lines.is_none_or(|max| stats.lines() <= max)
+            && self.max_files.is_none_or(|max| stats.files <= max)
+            && (self.paths.is_empty()
+                || build_globset(&self.paths)
+                    .is_ok_and(|set| changed_files.iter().all(|f| set.is_match(f))))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EvidencePolicy {
+    /// Ask at least one "point to the line" question, answered by citing a `path:line` or hunk
+    /// from the diff and graded on whether that location is in the diff (default false).
+    #[serde(default)]
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SelfCheckPolicy {
+    /// Append an objective question answered by the diff itself (e.g. "How many files does this
+    /// change touch?"), graded locally whatever the provider (default false).
+    #[serde(default)]
+    pub enabled: Option<bool>,
+
+    /// A wrong self-check answer fails the exam regardless of the score (default true).
+    #[serde(default)]
+    pub required: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TokenizerPolicy {
+    /// tiktoken-format BPE rank file (e.g. `cl100k_base.tiktoken`) for exact token counts;
+    /// relative to the repository root. Without it tokens are estimated.
+    #[serde(default)]
+    pub bpe_file: Option<String>,
+
+    /// Context limits in tokens keyed by model (or provider) name, overriding
+    /// `max_tokens_context` for that model.
+    #[serde(default)]
+    pub context_limits: BTreeMap<String, usize>,
+}
+
+/// Normalizing total scores across graders with offsets learned by `aigit calibrate`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CalibrationPolicy {
+    /// Directory of the calibration suite: reference cases in `cases/*.json` and the learned
+    /// `offsets.json`, relative to the repository root (default ".aigit/calibration").
+    #[serde(default)]
+    pub suite: Option<String>,
+
+    /// Add the grader's learned offset to its total score before thresholds apply (default
+    /// true).
+    #[serde(default)]
+    pub normalize: Option<bool>,
+
+    /// Offsets read from the suite's `offsets.json` when the policy is loaded.
+    #[serde(skip)]
+    pub learned: BTreeMap<String, GraderCalibration>,
+}
+
+/// Local checks of the answers before they go to the grader.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PrecheckPolicy {
+    /// Warn about empty, too short and copied answers before grading (default true).
+    #[serde(default)]
+    pub enabled: Option<bool>,
+
+    /// Words an open answer needs to not count as too short (default 5).
+    #[serde(default)]
+    pub min_words: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TuiPolicy {
+    /// Show the part of the diff each question is about above it (default true).
+    #[serde(default)]
+    pub show_diff: Option<bool>,
+
+    /// Diff lines shown per question, after hunk headers (default 12).
+    #[serde(default)]
+    pub diff_lines: Option<usize>,
+
+    /// Color the diff, feedback and the score table after grading (default: when stdout, or
+    /// stderr for the table, is a terminal and `NO_COLOR` is unset).
+    #[serde(default)]
+    pub color: Option<bool>,
+
+    /// After each line of an open answer, show its word count, the category signal words it
+    /// uses and whether it names a changed file or symbol (default true).
+    #[serde(default)]
+    pub live_feedback: Option<bool>,
+
+    /// Run the exam in a full-screen terminal UI when stdin and stdout are terminals (default
+    /// true); off, or without a terminal, questions are asked line by line.
+    #[serde(default)]
+    pub full_screen: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AppealPolicy {
+    /// Provider that re-grades stored answers on `aigit appeal` (e.g. "anthropic"). Required to
+    /// appeal.
+    #[serde(default)]
+    pub arbiter: Option<String>,
+
+    /// Model for the arbiter (default: the top-level `model`).
+    #[serde(default)]
+    pub model: Option<String>,
+
+    /// Whether the arbiter's decision replaces the original one (default false: both grades are
+    /// recorded but the original decision stands).
+    #[serde(default)]
+    pub can_overturn: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HealthPolicy {
+    /// Ping the provider before an interactive exam starts (default true).
+    #[serde(default)]
+    pub check: Option<bool>,
+
+    /// Ping timeout in seconds (default 10).
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+
+    /// Consecutive failures (pings or grading) that open the circuit breaker (default 3).
+    #[serde(default)]
+    pub failure_threshold: Option<u32>,
+
+    /// How long an open breaker keeps the provider out of rotation, in seconds (default 900).
+    #[serde(default)]
+    pub cooldown_secs: Option<u64>,
+
+    /// Provider to use while the breaker is open (e.g. "local" or "ollama"). Without one, exams
+    /// fail fast until the cooldown expires.
+    #[serde(default)]
+    pub fallback_provider: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnthropicPolicy {
+    /// Model name (falls back to `model`; one of the two is required).
+    #[serde(default)]
+    pub model: Option<String>,
+
+    /// `max_tokens` sent with each request (default 4096).
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+
+    /// Environment variable holding the API key (default "ANTHROPIC_API_KEY").
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+
+    /// API base URL (default "https://api.anthropic.com").
+    #[serde(default)]
+    pub base_url: Option<String>,
+
+    /// Request timeout in seconds.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OllamaPolicy {
+    /// Ollama server URL (default "http://localhost:11434").
+    #[serde(default)]
+    pub host: Option<String>,
+
+    /// Model name (falls back to `model`, then "llama3.1").
+    #[serde(default)]
+    pub model: Option<String>,
+
+    /// Sampling temperature (default 0).
+    #[serde(default)]
+    pub temperature: Option<f64>,
+
+    /// Request timeout in seconds (default 300; local models can be slow).
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+
+    /// Use the static examiner when the server is unreachable (default true). Set to false to
+    /// fail instead.
+    #[serde(default)]
+    pub allow_fallback: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LfsPolicy {
+    /// Note in each LFS summary whether the new object is present in the local LFS store.
+    #[serde(default)]
+    pub include_metadata: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RepoIdPolicy {
+    /// "remote:<name>" (default "remote:origin"), "workdir", or "explicit:<string>".
+    #[serde(default)]
+    pub source: Option<String>,
+
+    /// Record only a salted SHA-256 of the identity (`sha256:<hex>`) instead of the raw value.
+    #[serde(default)]
+    pub hash: Option<bool>,
+
+    /// Salt mixed into the hash when `hash = true`.
+    #[serde(default)]
+    pub salt: Option<String>,
+
+    /// Environment variable holding the salt; takes precedence over `salt` when set.
+    #[serde(default)]
+    pub salt_env: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VerifyPolicy {
+    /// Pass merge commits without a transcript check (default false).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_merges: Option<bool>,
+
+    /// Authors whose commits pass unchecked, by name or email (e.g. "dependabot[bot]").
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skip_authors: Vec<String>,
+
+    /// Regexes; commits whose subject matches one pass unchecked (e.g. "^Revert ").
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skip_subject_patterns: Vec<String>,
+
+    /// What transcripts are re-checked against: "current" (default; this policy as it is now,
+    /// so tightened thresholds fail older commits) or "recorded" (the grading thresholds of the
+    /// policy snapshot a signed transcript took at exam time). `aigit verify --policy` overrides
+    /// it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfilesPolicy {
+    /// Bias exams toward the committer's historically weak categories (opt-in).
+    #[serde(default)]
+    pub enabled: Option<bool>,
+
+    /// Categories averaging below this score count as weak (default 0.75).
+    #[serde(default)]
+    pub weak_threshold: Option<f64>,
+
+    /// Maximum number of follow-up categories per exam (default 2).
+    #[serde(default)]
+    pub max_focus: Option<usize>,
+
+    /// Minimum number of past exams before the profile is used (default 3).
+    #[serde(default)]
+    pub min_exams: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatsPolicy {
+    /// Allow `aigit stats --by author` (per-person numbers are opt-in per repo).
+    #[serde(default)]
+    pub by_author: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StaticGraderPolicy {
+    /// Signal words per category id, matched case-insensitively as substrings of an answer.
+    /// Categories without an entry use the built-in English list for that category, then the
+    /// `default` entry. An empty list disables the keyword check for that category.
+    #[serde(default)]
+    pub keywords: BTreeMap<String, Vec<String>>,
+}
+
+const KEYWORDS_RISK: &[&str] = &["risk", "break", "fail", "regress", "error", "panic"];
+const KEYWORDS_TESTING: &[&str] = &["test", "cargo test", "unit", "integration", "ci"];
+const KEYWORDS_ROLLBACK: &[&str] = &["revert", "rollback", "backout", "feature flag", "mitigate"];
+const KEYWORDS_SECURITY: &[&str] = &["auth", "authz", "pii", "secret", "token", "key", "encrypt"];
+const KEYWORDS_MEMORY_SAFETY: &[&str] = &[
+    "unsafe", "invariant", "sound", "alias", "race", "lock", "deadlock", "atomic", "ordering",
+    "thread",
+];
+const KEYWORDS_PERFORMANCE: &[&str] = &[
+    "complexity", "o(", "benchmark", "latency", "throughput", "allocat", "profil", "hot path",
+];
+const KEYWORDS_API_SURFACE: &[&str] = &[
+    "semver", "major", "minor", "patch", "breaking", "downstream", "deprecat", "migrat",
+    "compatib",
+];
+const KEYWORDS_CONFIGURATION: &[&str] = &[
+    "default", "environment", "staging", "production", "prod", "rollout", "roll out", "flag",
+];
+const KEYWORDS_CI_PIPELINE: &[&str] = &[
+    "job", "branch", "deploy", "secret", "token", "fork", "permission", "revert", "rerun",
+];
+const KEYWORDS_DEFAULT: &[&str] = &["file", "module", "function", "line"];
+
+fn builtin_keywords(category: &str) -> Option<&'static [&'static str]> {
+    match category {
+        "risk" => Some(KEYWORDS_RISK),
+        "testing" => Some(KEYWORDS_TESTING),
+        "rollback" => Some(KEYWORDS_ROLLBACK),
+        "security" => Some(KEYWORDS_SECURITY),
+        "memory_safety" => Some(KEYWORDS_MEMORY_SAFETY),
+        "performance" => Some(KEYWORDS_PERFORMANCE),
+        "api_surface" => Some(KEYWORDS_API_SURFACE),
+        "configuration" => Some(KEYWORDS_CONFIGURATION),
+        "ci_pipeline" => Some(KEYWORDS_CI_PIPELINE),
+        _ => None,
+    }
+}
+
+impl StaticGraderPolicy {
+    /// True if `category` has its own keyword list (configured or built in) rather than the
+    /// generic default one.
+    pub fn has_specific_keywords(&self, category: &str) -> bool {
+        self.keywords.contains_key(category) || builtin_keywords(category).is_some()
+    }
+
+    /// Signal words for `category`: the configured list, else the built-in English list with
+    /// its translation into `language` added.
+    pub fn keywords_for(&self, category: &str, language: Option<&str>) -> Vec<String> {
+        if let Some(words) = self.keywords.get(category) {
+            return words.clone();
+        }
+        let builtin = builtin_keywords(category);
+        if let (None, Some(words)) = (builtin, self.keywords.get("default")) {
+            return words.clone();
+        }
+        builtin
+            .unwrap_or(KEYWORDS_DEFAULT)
+            .iter()
+            .chain(crate::i18n::keywords(language, category))
+            .map(|w| w.to_string())
+            .collect()
+    }
+}
+
+/// Where `aigit policy pull` fetches the org-wide policy bundle from.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PolicySource {
+    /// HTTPS URL of the bundle; its SSH signature is fetched from `<url>.sig`.
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Git ref holding the bundle (e.g. "refs/aigit/policy"), fetched from `remote`.
+    #[serde(default)]
+    pub git_ref: Option<String>,
+
+    /// Remote for `git_ref` (default "origin").
+    #[serde(default)]
+    pub remote: Option<String>,
+
+    /// Bundle path within `git_ref` (default "aigit.toml"); signature at `<path>.sig`.
+    #[serde(default)]
+    pub path: Option<String>,
+
+    /// ssh-keygen allowed-signers file, relative to the repo root.
+    #[serde(default)]
+    pub allowed_signers: Option<String>,
+
+    /// Principal the bundle must be signed by.
+    #[serde(default)]
+    pub signer: Option<String>,
+
+    /// Refuse unsigned bundles (default true).
+    #[serde(default)]
+    pub require_signature: Option<bool>,
+}
+
+/// Who may answer which questions when an exam is split between several people.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RespondentsPolicy {
+    /// Categories only the committer may answer (e.g. "summary", "intent").
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub committer_only: Vec<String>,
+
+    /// Categories delegated to a set of people (`[[respondents.roles]]`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<RespondentRole>,
+}
+
+impl RespondentsPolicy {
+    pub fn is_empty(&self) -> bool {
+        self.committer_only.is_empty() && self.roles.is_empty()
+    }
+}
+
+/// Signing transcripts with the committer's SSH or GPG key, so that a transcript written into
+/// git notes by hand does not pass `aigit verify`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SigningPolicy {
+    /// Sign transcripts on `aigit commit` with `user.signingkey` in the `gpg.format` git uses
+    /// for commits (default false).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+
+    /// Allowed-signers file, relative to the repo root, that `aigit verify` checks signatures
+    /// against: ssh-keygen's format for SSH keys, `<email> <fingerprint>` lines for OpenPGP.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_signers: Option<String>,
+
+    /// Reject unsigned transcripts in `aigit verify` (default false).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required: Option<bool>,
+}
+
+/// The `store = "trailer"` backend.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrailerStorePolicy {
+    /// Also embed the whole transcript in the commit message, base64-encoded, so it survives
+    /// where git notes do not (default false: only its hash, decision and score).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embed: Option<bool>,
+}
+
+/// The `store = "remote"` backend: a central audit service reached over HTTP.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RemoteStorePolicy {
+    /// Base URL of the service; transcripts are sent to and read from
+    /// `<url>/transcripts/<commit>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// Environment variable holding the bearer token (default "AIGIT_STORE_TOKEN"; no
+    /// `Authorization` header is sent when it is unset).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_env: Option<String>,
+
+    /// Request timeout in seconds (default 10).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+/// What makes the final pass/fail decision (`[decision]`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DecisionPolicy {
+    /// "builtin" (default: the thresholds in this file), "opa": a Rego module, evaluated by
+    /// `opa eval` against the transcript JSON, decides, with the built-in decision as
+    /// `input.decision`, or "command": `command` decides from the same JSON (the default when
+    /// `command` is set).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub engine: Option<String>,
+
+    /// The Rego module, relative to the repository root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rego: Option<String>,
+
+    /// Query whose value decides: a boolean, or an object with `allow` and a `deny` set of
+    /// reasons (default "data.aigit").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+
+    /// Command that runs OPA (default "opa").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opa_command: Option<String>,
+
+    /// Command given the transcript JSON on stdin, printing its decision as JSON: a boolean, or
+    /// an object with `allow` and `deny` reasons. A relative path is relative to the
+    /// repository root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+
+    /// How long `command` may take (default 30).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+/// Sharing `refs/notes/aigit` with a remote (`aigit notes push|fetch|status`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NotesPolicy {
+    /// Remote transcripts are pushed to and fetched from (default "origin").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+
+    /// Push the notes after `aigit commit` stores a transcript (default false). A failed push
+    /// only warns; `aigit notes push` retries it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_push: Option<bool>,
+
+    /// How a commit with different transcripts on both sides is resolved when notes are
+    /// merged: "newest" (default; the later exam, then the one with more countersignatures),
+    /// "ours" or "theirs".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merge: Option<String>,
+}
+
+/// Limits on calls to the exam provider (`[rate_limit]`), shared by every aigit process using
+/// the same `state_dir`: generation, grading, consensus samples, appeals and `aigit bench` /
+/// `aigit calibrate` runs queue for them instead of tripping the provider's own limits.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitPolicy {
+    /// Calls started in any 60 seconds (default unlimited).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests_per_minute: Option<u32>,
+
+    /// Calls running at once (default unlimited).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent: Option<u32>,
+
+    /// How long a call may wait in the queue before it fails (default 600).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_wait_secs: Option<u64>,
+
+    /// Where the shared counters live (default `$XDG_STATE_HOME/aigit/rate-limit`, else
+    /// `~/.local/state/aigit/rate-limit`), so limits hold across repositories. A relative path
+    /// is relative to the repository root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_dir: Option<String>,
+}
+
+/// Provider responses reused for identical requests (`[response_cache]`), kept in
+/// `.git/aigit/cache/responses/` and addressed by provider, model, output schema and prompt.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResponseCachePolicy {
+    /// Cache every provider call (`true`) or none (`false`). Unset, `aigit calibrate` and
+    /// consensus grading (`grading.samples > 1`) use the cache and other commands do not;
+    /// `aigit bench` never does, since it times the calls.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+
+    /// Responses older than this many days are asked for again (default: kept until
+    /// `aigit cache clear`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u32>,
+}
+
+/// The hash chain that makes deleted or replaced transcripts detectable.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChainPolicy {
+    /// Record in each new transcript the hash of the one stored before it, and keep the newest
+    /// in `refs/aigit/chain`; `aigit verify --chain` walks it (default false).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+}
+
+/// Changes that need a countersignature from a named approver group before `aigit verify`
+/// accepts them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ApprovalsPolicy {
+    /// `[[approvals.groups]]`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<ApproverGroup>,
+}
+
+impl ApprovalsPolicy {
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ApproverGroup {
+    /// Group name shown in failure reasons (e.g. "security").
+    pub name: String,
+
+    /// The group must countersign exams with a question in one of these categories...
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
+
+    /// ...about a change touching one of these paths (globs). Either may be left out, not both.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+
+    /// Email globs of the approvers.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub approvers: Vec<String>,
+
+    /// File in the repository listing more approvers, one email or glob per line (`#` starts a
+    /// comment), e.g. "security-team.txt".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approvers_file: Option<String>,
+}
+
+impl ApproverGroup {
+    /// Whether a change touching `changed_files` whose exam asks about `categories` needs this
+    /// group's countersignature.
+    pub fn applies_to(&self, categories: &[&str], changed_files: &[String]) -> bool {
+        (self.categories.is_empty()
+            || self.categories.iter().any(|c| categories.contains(&c.as_str())))
+            && (self.paths.is_empty() || any_path_matches(&self.paths, changed_files))
+    }
+
+    /// Whether `email` is one of the approvers, from `approvers` or `approvers_file` (read from
+    /// the working tree). Emails are compared case-insensitively.
+    pub fn allows(&self, repo: &GitRepo, email: &str) -> Result<bool> {
+        let mut patterns = self.approvers.clone();
+        if let Some(file) = &self.approvers_file {
+            let path = repo.workdir.join(file);
+            let raw = std::fs::read_to_string(&path).with_context(|| {
+                format!("approvals.groups.{}: failed to read {}", self.name, path.display())
+            })?;
+            patterns.extend(
+                raw.lines()
+                    .map(|l| l.split('#').next().unwrap_or_default().trim())
+                    .filter(|l| !l.is_empty())
+                    .map(str::to_string),
+            );
+        }
+        let patterns: Vec<String> = patterns.iter().map(|p| p.to_ascii_lowercase()).collect();
+        let set = build_globset(&patterns)
+            .with_context(|| format!("approvals.groups.{}: invalid approver pattern", self.name))?;
+        Ok(set.is_match(email.to_ascii_lowercase()))
+    }
+}
+
+/// Minimum time spent answering, for exams taken interactively (`exam_mode = "tui"`). Answers
+/// given from a file or in the editor are not timed and never flagged.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TimingPolicy {
+    /// Seconds each answered question must have been worked on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_seconds_per_question: Option<f64>,
+
+    /// Seconds the whole exam must have taken.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_total_seconds: Option<f64>,
+
+    /// "flag" (default) warns about a too-fast exam and records it in the transcript; "fail"
+    /// fails it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+}
+
+impl TimingPolicy {
+    pub fn is_empty(&self) -> bool {
+        self.min_seconds_per_question.is_none() && self.min_total_seconds.is_none()
+    }
+
+    /// Whether a too-fast exam fails rather than being flagged.
+    pub fn fails(&self) -> bool {
+        self.action.as_deref() == Some("fail")
+    }
+}
+
+/// Heuristic risk pre-assessment of a change, done before the exam is written (see `risk` in the
+/// transcript). Each level can make the exam longer and stricter.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RiskPolicy {
+    /// Globs of sensitive paths on top of the built-in ones (auth, crypto, migrations, CI, ...).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+
+    /// How medium-risk changes are examined (`[risk.medium]`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub medium: Option<RiskLevelPolicy>,
+
+    /// How high-risk changes are examined (`[risk.high]`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub high: Option<RiskLevelPolicy>,
+}
+
+/// What changes at one risk level.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RiskLevelPolicy {
+    /// Risk score (0..1) from which a change is at this level (default 0.3 for medium, 0.6 for
+    /// high).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub at: Option<f64>,
+
+    /// Questions to ask at least, lifting a smaller `exam.tiers` count (LLM examiners are asked
+    /// for this many).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub questions: Option<usize>,
+
+    /// `min_total_score` at this level, when stricter than the policy's own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_total_score: Option<f64>,
+
+    /// Installed question packs (`name` or `name@version`) asked only at this level.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packs: Vec<String>,
+}
+
+/// The soundness question asked when a diff adds unsafe code, `static mut`s, locks or atomics to
+/// Rust files.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemorySafetyPolicy {
+    /// Ask the question (default true).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+
+    /// Specificity (0..1) its answer needs, or the exam fails (default 0.7: the answer must name
+    /// the changed code).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_specificity: Option<f64>,
+}
+
+impl MemorySafetyPolicy {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The question asked when a diff changes configuration files, environment variable reads or
+/// feature flags.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfigChangesPolicy {
+    /// Ask the question (default true).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+
+    /// More configuration files (globs), on top of the built-in ones (`*.ini`, `.env`,
+    /// `config/**`, ...).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+}
+
+/// The question asked when a diff edits CI pipeline files.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CiPipelinePolicy {
+    /// Ask the question (default true).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+
+    /// More pipeline files (globs), on top of the built-in ones (`.github/workflows/**`,
+    /// `.gitlab-ci.yml`, `Jenkinsfile`, ...).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+}
+
+/// The semver question asked when a diff adds, removes or changes public Rust items.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ApiSurfacePolicy {
+    /// Ask the question (default true; turn off for binaries without a library API).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+}
+
+/// Answers copied from the commit message or from each other (see `integrity_flags` in the
+/// score).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IntegrityPolicy {
+    /// Compare answers with the commit message and each other (default true).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+
+    /// Share (0..1) of an answer's word pairs found in the other text at which it is flagged
+    /// (default 0.8).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_similarity: Option<f64>,
+
+    /// Answers and commit messages shorter than this many words are not compared (default 8).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_words: Option<usize>,
+
+    /// "flag" (default) warns about copied answers and records them in the transcript; "fail"
+    /// fails the exam.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+}
+
+impl IntegrityPolicy {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether a copied answer fails the exam rather than being flagged.
+    pub fn fails(&self) -> bool {
+        self.action.as_deref() == Some("fail")
+    }
+}
+
+/// Second chances after a failed exam (`aigit exam --retake`, or the prompt in `aigit commit`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetakePolicy {
+    /// Ask the examiner for a harder follow-up to each retaken question (default false).
+    #[serde(default)]
+    pub follow_ups: Option<bool>,
+
+    /// Attempts allowed at one exam, the first included, before it must be taken again in
+    /// full (default 3).
+    #[serde(default)]
+    pub max_attempts: Option<usize>,
+}
+
+impl RetakePolicy {
+    pub fn max_attempts(&self) -> usize {
+        self.max_attempts.unwrap_or(3)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RespondentRole {
+    /// Role name shown in failure reasons (e.g. "on-call").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// Categories this role answers.
+    pub categories: Vec<String>,
+
+    /// Email globs of the people who may answer them (e.g. "*@sre.example.com"); "committer"
+    /// allows the committer too.
+    pub allowed: Vec<String>,
+}
+
+impl RespondentRole {
+    /// Whether `email` may answer for this role; `is_committer` when it is the committer's.
+    pub fn allows(&self, email: &str, is_committer: bool) -> bool {
+        let globs: Vec<String> = self
+            .allowed
+            .iter()
+            .filter(|p| p.as_str() != "committer")
+            .cloned()
+            .collect();
+        (is_committer && self.allowed.iter().any(|p| p == "committer"))
+            || build_globset(&globs).is_ok_and(|set| set.is_match(email))
+    }
+}
+
+/// A policy-defined exam category beyond the built-in eight.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CustomCategory {
+    pub id: String,
+
+    /// What the category covers; shown to LLM examiners and used for the default prompt.
+    #[serde(default)]
+    pub description: String,
+
+    /// Question asked by the static examiner.
+    #[serde(default)]
+    pub prompt: Option<String>,
+
+    /// Only applies when a changed file matches one of these globs (empty = always applies).
+    #[serde(default)]
+    pub paths: Vec<String>,
+
+    /// When the category applies, its questions must be answered (like `required_categories`).
+    #[serde(default)]
+    pub required: bool,
+}
+
+impl CustomCategory {
+    pub fn applies_to(&self, changed_files: &[String]) -> bool {
+        self.paths.is_empty() || any_path_matches(&self.paths, changed_files)
+    }
+
+    pub fn question_prompt(&self) -> String {
+        self.prompt.clone().unwrap_or_else(|| {
+            let topic = if self.description.trim().is_empty() {
+                self.id.as_str()
+            } else {
+                self.description.trim()
+            };
+            format!("{topic}: how does this change affect it? If it does not, explain why.")
+        })
+    }
+}
+
+/// A repository-defined question asked when matching paths change (`[[questions]]`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BankQuestion {
+    /// Question id in the exam (default `bank_<n>`, numbered by position in the bank).
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Category the answer is graded under: a built-in one or a `[[categories]]` id.
+    pub category: String,
+    /// Prompt template; `{files}` and `{dirs}` expand to the matching changed files and their
+    /// directories, alongside the placeholders every repo-defined prompt supports.
+    pub prompt: String,
+    /// Asked when a changed file matches one of these globs (empty = every change).
+    #[serde(default)]
+    pub paths: Vec<String>,
+}
+
+impl BankQuestion {
+    /// Changed files that match `paths`; every changed file when `paths` is empty.
+    pub fn matching_files(&self, changed_files: &[String]) -> Vec<String> {
+        if self.paths.is_empty() {
+            return changed_files.to_vec();
+        }
+        match build_globset(&self.paths) {
+            Ok(set) => changed_files
+                .iter()
+                .filter(|f| set.is_match(f.as_str()))
+                .cloned()
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,22 +1099,108 @@ pub struct Policy {
     pub required_categories: Vec<String>,
     #[serde(default)]
     pub max_hallucination_flags: u32,
+    /// Only hallucination flags at or above this severity count toward
+    /// `max_hallucination_flags` (default: all flags count).
+    #[serde(default)]
+    pub hallucination_min_severity: Option<Severity>,
+    /// Every answered question must reach this `category_relevance` (0..1), so an off-topic
+    /// answer cannot pass on length alone (default: not enforced).
+    #[serde(default)]
+    pub min_category_relevance: Option<f64>,
+
+    /// How much each category counts in `total_score` (`[score_weights]`, e.g. `risk = 3.0`);
+    /// the total is the weighted mean of question scores. Unlisted categories weigh 1.0.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub score_weights: BTreeMap<String, f64>,
+
+    /// Lowest mean score each listed category's questions may get (`[min_category_score]`,
+    /// e.g. `rollback = 0.6`), so a strong total cannot hide one weak category.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub min_category_score: BTreeMap<String, f64>,
+
+    /// Policy-defined categories (`[[categories]]`).
+    #[serde(default)]
+    pub categories: Vec<CustomCategory>,
+
+    /// Domain-specific questions asked when matching paths change (`[[questions]]`).
+    #[serde(default)]
+    pub questions: Vec<BankQuestion>,
+
+    /// Question packs installed under `.aigit/packs/` (see `aigit packs add`) whose categories
+    /// and questions join the bank: "name", or "name@version" to pin the installed version.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packs: Vec<String>,
 
     #[serde(default)]
     pub provider: Option<String>,
+    /// Provider that grades answers when it should differ from the one writing the exam
+    /// (e.g. `provider = "codex-cli"`, `grader = "static"`).
+    #[serde(default)]
+    pub grader: Option<String>,
     #[serde(default)]
     pub model: Option<String>,
     #[serde(default)]
     pub exam_mode: Option<String>,
+    /// Language tag exams are written and graded in (e.g. "ja"; default English). The built-in
+    /// questions are translated for "ja"; LLM providers are told to use the language.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
 
+    /// Where transcripts are kept: "git-notes" (default), "trailer" (a hashed reference in
+    /// the commit message's trailers, plus git notes), "tree" (`.aigit/transcripts/` in the
+    /// commit itself, plus git notes) or "remote" (an HTTP audit service, plus git notes).
     #[serde(default)]
     pub store: Option<String>,
 
+    /// How much of each answer stored transcripts keep: "full" (default), "hashed" (its SHA-256
+    /// instead of its text) or "none". Answers may describe internal systems, and git notes
+    /// get pushed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub store_answers: Option<String>,
+
+    /// The trailer store (`[trailer_store]`).
+    #[serde(default)]
+    pub trailer_store: TrailerStorePolicy,
+
+    /// The remote store (`[remote_store]`).
+    #[serde(default)]
+    pub remote_store: RemoteStorePolicy,
+
+    /// The decision engine (`[decision]`).
+    #[serde(default)]
+    pub decision: DecisionPolicy,
+
+    /// Sharing transcripts with a remote (`[notes]`).
+    #[serde(default)]
+    pub notes: NotesPolicy,
+
+    /// Limits on provider calls (`[rate_limit]`).
+    #[serde(default)]
+    pub rate_limit: RateLimitPolicy,
+
+    /// Reusing provider responses (`[response_cache]`).
+    #[serde(default)]
+    pub response_cache: ResponseCachePolicy,
+
     #[serde(default)]
     pub redactions: Vec<String>,
     #[serde(default)]
     pub max_tokens_context: Option<usize>,
 
+    /// Lines of unchanged code around each change shown to the examiner (default 0). Patch-ids
+    /// are unaffected.
+    #[serde(default)]
+    pub context_lines: Option<u32>,
+
+    /// Show each change's whole enclosing function (`git diff --function-context`) to the
+    /// examiner, per file while it fits the token budget (default false).
+    #[serde(default)]
+    pub include_function_context: Option<bool>,
+
+    /// How diff and prompt sizes are measured against the context limit.
+    #[serde(default)]
+    pub tokenizer: TokenizerPolicy,
+
     #[serde(default)]
     pub hooks: Hooks,
 
@@ -62,6 +1208,144 @@ pub struct Policy {
     #[serde(default)]
     pub codex_cli: CodexCliPolicy,
 
+    /// Settings used when `provider = "claude-cli"`.
+    #[serde(default)]
+    pub claude_cli: ClaudeCliPolicy,
+
+    /// Settings used when `provider = "exec"`.
+    #[serde(default)]
+    pub exec: ExecPolicy,
+
+    /// Settings used when `provider = "http-api"`.
+    #[serde(default)]
+    pub http_api: HttpApiPolicy,
+
+    /// Settings used when `provider = "anthropic"`.
+    #[serde(default)]
+    pub anthropic: AnthropicPolicy,
+
+    /// Settings used when `provider = "ollama"`.
+    #[serde(default)]
+    pub ollama: OllamaPolicy,
+
+    /// Provider health-check and circuit breaker.
+    #[serde(default)]
+    pub health: HealthPolicy,
+
+    /// Multi-sample grading and confidence requirements.
+    #[serde(default)]
+    pub grading: GradingPolicy,
+
+    /// The diff-derived attention-check question.
+    #[serde(default)]
+    pub self_check: SelfCheckPolicy,
+
+    /// Questions answered by citing a location in the diff.
+    #[serde(default)]
+    pub evidence: EvidencePolicy,
+
+    /// Exam size by change size (`[exam.tiers.<name>]`).
+    #[serde(default)]
+    pub exam: ExamPolicy,
+
+    /// Local validation of generated exams.
+    #[serde(default)]
+    pub exam_quality: ExamQualityPolicy,
+
+    /// Re-grading by a second model on `aigit appeal`.
+    #[serde(default)]
+    pub appeal: AppealPolicy,
+
+    /// The interactive (`exam_mode = "tui"`) exam.
+    #[serde(default)]
+    pub tui: TuiPolicy,
+
+    /// Answer checks run before grading.
+    #[serde(default)]
+    pub precheck: PrecheckPolicy,
+
+    /// Per-grader score normalization.
+    #[serde(default)]
+    pub calibration: CalibrationPolicy,
+
+    /// Rules for exams answered by more than one person.
+    #[serde(default)]
+    pub respondents: RespondentsPolicy,
+
+    /// Minimum answer times for interactive exams.
+    #[serde(default)]
+    pub timing: TimingPolicy,
+
+    /// Detection of answers copied from the commit message or from each other.
+    #[serde(default)]
+    pub integrity: IntegrityPolicy,
+
+    /// Unsafe and concurrent Rust code in the diff (`[memory_safety]`).
+    #[serde(default)]
+    pub memory_safety: MemorySafetyPolicy,
+
+    /// Hot paths (globs); a change touching one gets a `performance` question on expected
+    /// complexity and benchmarks, as do diffs adding nested loops or complexity notes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub perf_paths: Vec<String>,
+
+    /// Public API changes in Rust library files (`[api_surface]`).
+    #[serde(default)]
+    pub api_surface: ApiSurfacePolicy,
+
+    /// Configuration, environment variable and feature-flag changes (`[config_changes]`).
+    #[serde(default)]
+    pub config_changes: ConfigChangesPolicy,
+
+    /// CI pipeline changes (`[ci_pipeline]`).
+    #[serde(default)]
+    pub ci_pipeline: CiPipelinePolicy,
+
+    /// Risk pre-assessment of the diff, and how risky changes are examined.
+    #[serde(default)]
+    pub risk: RiskPolicy,
+
+    /// Retakes of failed exams.
+    #[serde(default)]
+    pub retake: RetakePolicy,
+
+    /// Approver groups that must countersign some changes.
+    #[serde(default)]
+    pub approvals: ApprovalsPolicy,
+
+    /// Transcript signatures (`[signing]`).
+    #[serde(default)]
+    pub signing: SigningPolicy,
+
+    /// Transcript hash chain (`[chain]`).
+    #[serde(default)]
+    pub chain: ChainPolicy,
+
+    /// How Git LFS pointer changes are presented to the examiner.
+    #[serde(default)]
+    pub lfs: LfsPolicy,
+
+    #[serde(default)]
+    pub verify: VerifyPolicy,
+
+    /// How `repo_id` is derived for exams and transcripts.
+    #[serde(default)]
+    pub repo_id: RepoIdPolicy,
+
+    /// Per-author performance profiles used to tailor exams.
+    #[serde(default)]
+    pub profiles: ProfilesPolicy,
+
+    #[serde(default)]
+    pub stats: StatsPolicy,
+
+    /// Tuning for the built-in (`provider = "local"`) grader.
+    #[serde(default)]
+    pub static_grader: StaticGraderPolicy,
+
+    #[serde(default)]
+    pub policy_source: PolicySource,
+
     #[serde(flatten)]
     pub extra: BTreeMap<String, toml::Value>,
 }
@@ -70,6 +1354,16 @@ pub struct Policy {
 pub struct Hooks {
     #[serde(default)]
     pub enforce: Option<bool>,
+
+    /// What aigit does about your recent commits that have no transcript (made with
+    /// `--no-verify` or a hand-set `AIGIT_ALLOW_COMMIT`): "nag" (default; warn on every aigit run
+    /// and record them in `.git/aigit/bypasses.json`), "record" (only record) or "off".
+    #[serde(default)]
+    pub track_bypasses: Option<String>,
+
+    /// How many recent commits on HEAD are checked for bypasses (default 50).
+    #[serde(default)]
+    pub bypass_lookback: Option<usize>,
 }
 
 impl Default for Policy {
@@ -82,32 +1376,517 @@ impl Default for Policy {
                 "testing".to_string(),
             ],
             max_hallucination_flags: 0,
+            hallucination_min_severity: None,
+            min_category_relevance: None,
+            score_weights: BTreeMap::new(),
+            min_category_score: BTreeMap::new(),
+            categories: vec![],
+            questions: vec![],
+            packs: vec![],
             provider: Some("local".to_string()),
+            grader: None,
             model: Some("static".to_string()),
             exam_mode: Some("tui".to_string()),
+            language: None,
             store: Some("git-notes".to_string()),
+            store_answers: None,
+            trailer_store: TrailerStorePolicy::default(),
+            remote_store: RemoteStorePolicy::default(),
+            decision: DecisionPolicy::default(),
+            notes: NotesPolicy::default(),
+            rate_limit: RateLimitPolicy::default(),
+            response_cache: ResponseCachePolicy::default(),
             redactions: vec![],
             max_tokens_context: Some(4096),
-            hooks: Hooks { enforce: None },
+            context_lines: None,
+            include_function_context: None,
+            tokenizer: TokenizerPolicy::default(),
+            hooks: Hooks::default(),
             codex_cli: CodexCliPolicy::default(),
+            claude_cli: ClaudeCliPolicy::default(),
+            exec: ExecPolicy::default(),
+            http_api: HttpApiPolicy::default(),
+            anthropic: AnthropicPolicy::default(),
+            ollama: OllamaPolicy::default(),
+            health: HealthPolicy::default(),
+            grading: GradingPolicy::default(),
+            self_check: SelfCheckPolicy::default(),
+            evidence: EvidencePolicy::default(),
+            exam: ExamPolicy::default(),
+            exam_quality: ExamQualityPolicy::default(),
+            appeal: AppealPolicy::default(),
+            tui: TuiPolicy::default(),
+            precheck: PrecheckPolicy::default(),
+            calibration: CalibrationPolicy::default(),
+            respondents: RespondentsPolicy::default(),
+            timing: TimingPolicy::default(),
+            integrity: IntegrityPolicy::default(),
+            memory_safety: MemorySafetyPolicy::default(),
+            perf_paths: vec![],
+            api_surface: ApiSurfacePolicy::default(),
+            config_changes: ConfigChangesPolicy::default(),
+            ci_pipeline: CiPipelinePolicy::default(),
+            risk: RiskPolicy::default(),
+            retake: RetakePolicy::default(),
+            approvals: ApprovalsPolicy::default(),
+            signing: SigningPolicy::default(),
+            chain: ChainPolicy::default(),
+            lfs: LfsPolicy::default(),
+            verify: VerifyPolicy::default(),
+            repo_id: RepoIdPolicy::default(),
+            profiles: ProfilesPolicy::default(),
+            stats: StatsPolicy::default(),
+            static_grader: StaticGraderPolicy::default(),
+            policy_source: PolicySource::default(),
             extra: BTreeMap::new(),
         }
     }
 }
 
 impl Policy {
+    /// Effective policy: the installed central bundle (see `aigit policy pull`), if any, with the
+    /// repo's `.aigit.toml` layered on top; the bundle's thresholds stay floors the repo can only
+    /// tighten (see `apply_org_floors`). Each file may pull in bases via `extends`/`include`
+    /// (see `load_layered`).
     pub fn load_from_repo(repo: &GitRepo) -> Result<Self> {
+        let path = repo.workdir.join(".aigit.toml");
+        let central = crate::central_policy::installed_path(repo);
+        if !path.exists() && !central.exists() {
+            let mut policy = Self::default();
+            crate::calibration::apply(repo, &mut policy)?;
+            return Ok(policy);
+        }
+        let mut table = toml::Table::new();
+        let mut org = None;
+        for layer in [&central, &path] {
+            if layer.exists() {
+                let layer_table = load_layered(repo, layer, &mut Vec::new())?;
+                if layer == &central {
+                    org = Some(layer_table.clone());
+                } else if org.is_some() || has_policy_source(&layer_table) {
+                    check_unlocked(&path, &layer_table)?;
+                }
+                merge_toml(&mut table, layer_table);
+            }
+        }
+        let mut policy: Self = toml::Value::Table(table)
+            .try_into()
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        crate::packs::apply(repo, &mut policy)?;
+        // Offsets live in the repo, so under a central policy only the bundle can turn them on.
+        let org_normalizes = org.as_ref().is_some_and(|org| {
+            org.get("calibration")
+                .and_then(|c| c.get("normalize"))
+                .and_then(toml::Value::as_bool)
+                == Some(true)
+        });
+        if org.is_some() && !org_normalizes {
+            policy.calibration.normalize = Some(false);
+        }
+        crate::calibration::apply(repo, &mut policy)?;
+        if let Some(org) = org {
+            policy
+                .apply_org_floors(org)
+                .with_context(|| format!("failed to parse {}", central.display()))?;
+        }
+        policy.validate()?;
+        Ok(policy.with_defaults())
+    }
+
+    /// Keeps the central bundle's thresholds as minimums: a repo may raise `min_total_score` and
+    /// `min_category_score`, lower `max_hallucination_flags` and add `required_categories`, but
+    /// not the reverse. Only keys the bundle sets are enforced.
+    fn apply_org_floors(&mut self, org: toml::Table) -> Result<()> {
+        let set = |key: &str| org.contains_key(key);
+        let (total, required, flags, category) = (
+            set("min_total_score"),
+            set("required_categories"),
+            set("max_hallucination_flags"),
+            set("min_category_score"),
+        );
+        let floor: Self = toml::Value::Table(org).try_into()?;
+        if total {
+            self.min_total_score = self.min_total_score.max(floor.min_total_score);
+        }
+        if required {
+            for cat in floor.required_categories {
+                if !self.required_categories.contains(&cat) {
+                    self.required_categories.push(cat);
+                }
+            }
+        }
+        if flags {
+            self.max_hallucination_flags = self
+                .max_hallucination_flags
+                .min(floor.max_hallucination_flags);
+        }
+        if category {
+            for (cat, min) in floor.min_category_score {
+                let ours = self.min_category_score.entry(cat).or_insert(min);
+                *ours = ours.max(min);
+            }
+        }
+        Ok(())
+    }
+
+    /// Only the repo's own `.aigit.toml`, without any central layer; used when rewriting it.
+    pub fn load_repo_file(repo: &GitRepo) -> Result<Self> {
         let path = repo.workdir.join(".aigit.toml");
         if !path.exists() {
             return Ok(Self::default());
         }
-        let raw = std::fs::read_to_string(&path)
-            .with_context(|| format!("failed to read {}", path.display()))?;
-        let policy: Self =
-            toml::from_str(&raw).with_context(|| format!("failed to parse {}", path.display()))?;
+        let policy: Self = toml::Value::Table(read_toml_table(&path)?)
+            .try_into()
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        policy.validate()?;
         Ok(policy.with_defaults())
     }
 
+    fn validate(&self) -> Result<()> {
+        for (category, weight) in &self.score_weights {
+            if !weight.is_finite() || *weight < 0.0 {
+                return Err(anyhow!(
+                    "score_weights.{category} must be a number of at least 0"
+                ));
+            }
+        }
+        for (category, min) in &self.min_category_score {
+            if !(0.0..=1.0).contains(min) {
+                return Err(anyhow!("min_category_score.{category}: must be between 0 and 1"));
+            }
+        }
+        let mut seen = std::collections::BTreeSet::new();
+        for cat in &self.categories {
+            if cat.id.trim().is_empty() {
+                return Err(anyhow!("categories: id must not be empty"));
+            }
+            if !seen.insert(cat.id.as_str()) {
+                return Err(anyhow!("categories: duplicate id {}", cat.id));
+            }
+            build_globset(&cat.paths)
+                .with_context(|| format!("categories.{}: invalid paths", cat.id))?;
+        }
+        let mut ids = std::collections::BTreeSet::new();
+        for (i, q) in self.questions.iter().enumerate() {
+            let id = bank_question_id(i, q);
+            if q.category.trim().is_empty() {
+                return Err(anyhow!("questions[{i}]: category must not be empty"));
+            }
+            if q.prompt.trim().is_empty() {
+                return Err(anyhow!("questions[{i}]: prompt must not be empty"));
+            }
+            if !ids.insert(id.clone()) {
+                return Err(anyhow!("questions[{i}]: duplicate id {id}"));
+            }
+            build_globset(&q.paths)
+                .with_context(|| format!("questions[{i}]: invalid paths"))?;
+        }
+        for (i, role) in self.respondents.roles.iter().enumerate() {
+            build_globset(&role.allowed)
+                .with_context(|| format!("respondents.roles[{i}]: invalid allowed pattern"))?;
+        }
+        let mut groups = std::collections::BTreeSet::new();
+        for (i, group) in self.approvals.groups.iter().enumerate() {
+            if group.name.trim().is_empty() {
+                return Err(anyhow!("approvals.groups[{i}]: name must not be empty"));
+            }
+            if !groups.insert(group.name.as_str()) {
+                return Err(anyhow!("approvals.groups: duplicate name {}", group.name));
+            }
+            if group.categories.is_empty() && group.paths.is_empty() {
+                return Err(anyhow!(
+                    "approvals.groups.{}: set categories, paths or both",
+                    group.name
+                ));
+            }
+            if group.approvers.is_empty() && group.approvers_file.is_none() {
+                return Err(anyhow!(
+                    "approvals.groups.{}: set approvers or approvers_file",
+                    group.name
+                ));
+            }
+            build_globset(&group.paths)
+                .with_context(|| format!("approvals.groups.{}: invalid paths", group.name))?;
+            build_globset(&group.approvers)
+                .with_context(|| format!("approvals.groups.{}: invalid approvers", group.name))?;
+        }
+        if let Some(mode) = self.store_answers.as_deref() {
+            if !matches!(mode, "full" | "hashed" | "none") {
+                return Err(anyhow!(
+                    "store_answers must be \"full\", \"hashed\" or \"none\", not {mode:?}"
+                ));
+            }
+        }
+        if self.store.as_deref() == Some("remote") && self.remote_store.url.is_none() {
+            return Err(anyhow!("store = \"remote\" needs remote_store.url"));
+        }
+        if let Some(url) = self.remote_store.url.as_deref() {
+            crate::remote_store::check_url(url)?;
+        }
+        match self.decision.engine.as_deref() {
+            None | Some("builtin") => {}
+            Some("opa") if self.decision.rego.is_none() => {
+                return Err(anyhow!("decision.engine = \"opa\" needs decision.rego"));
+            }
+            Some("opa") => {}
+            Some("command") if self.decision.command.is_none() => {
+                return Err(anyhow!("decision.engine = \"command\" needs decision.command"));
+            }
+            Some("command") => {}
+            Some(other) => {
+                return Err(anyhow!(
+                    "decision.engine must be \"builtin\", \"opa\" or \"command\", not {other:?}"
+                ));
+            }
+        }
+        if let Some(merge) = self.notes.merge.as_deref() {
+            if !matches!(merge, "newest" | "ours" | "theirs") {
+                return Err(anyhow!(
+                    "notes.merge must be \"newest\", \"ours\" or \"theirs\", not {merge:?}"
+                ));
+            }
+        }
+        if self.signing.required.unwrap_or(false) && self.signing.allowed_signers.is_none() {
+            return Err(anyhow!(
+                "signing.required needs signing.allowed_signers to check signatures against"
+            ));
+        }
+        for (name, tier) in &self.exam.tiers {
+            build_globset(&tier.paths)
+                .with_context(|| format!("exam.tiers.{name}: invalid paths"))?;
+            if tier.questions == Some(0) {
+                return Err(anyhow!("exam.tiers.{name}: questions must be at least 1"));
+            }
+        }
+        self.grading.z_score()?;
+        if let Some(action) = &self.timing.action {
+            if !matches!(action.as_str(), "flag" | "fail") {
+                return Err(anyhow!(
+                    "timing.action: expected \"flag\" or \"fail\", got {action:?}"
+                ));
+            }
+        }
+        for (key, value) in [
+            ("min_seconds_per_question", self.timing.min_seconds_per_question),
+            ("min_total_seconds", self.timing.min_total_seconds),
+        ] {
+            if value.is_some_and(|v| v.is_nan() || v < 0.0) {
+                return Err(anyhow!("timing.{key}: must be a non-negative number"));
+            }
+        }
+        if let Some(language) = &self.language {
+            if language.is_empty()
+                || !language
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+            {
+                return Err(anyhow!(
+                    "language: expected a language tag such as \"ja\" or \"pt-BR\", got {language:?}"
+                ));
+            }
+        }
+        if let Some(action) = &self.integrity.action {
+            if !matches!(action.as_str(), "flag" | "fail") {
+                return Err(anyhow!(
+                    "integrity.action: expected \"flag\" or \"fail\", got {action:?}"
+                ));
+            }
+        }
+        if self
+            .integrity
+            .max_similarity
+            .is_some_and(|v| !(0.0..=1.0).contains(&v))
+        {
+            return Err(anyhow!("integrity.max_similarity: must be between 0 and 1"));
+        }
+        if self
+            .memory_safety
+            .min_specificity
+            .is_some_and(|v| !(0.0..=1.0).contains(&v))
+        {
+            return Err(anyhow!("memory_safety.min_specificity: must be between 0 and 1"));
+        }
+        build_globset(&self.perf_paths).context("perf_paths: invalid pattern")?;
+        if let Some(basis) = self.verify.policy.as_deref() {
+            if !matches!(basis, "current" | "recorded") {
+                return Err(anyhow!(
+                    "verify.policy must be \"current\" or \"recorded\", not {basis:?}"
+                ));
+            }
+        }
+        for pattern in &self.verify.skip_subject_patterns {
+            regex::Regex::new(pattern).with_context(|| {
+                format!("verify.skip_subject_patterns: invalid regex {pattern:?}")
+            })?;
+        }
+        build_globset(&self.config_changes.paths)
+            .context("config_changes.paths: invalid pattern")?;
+        build_globset(&self.ci_pipeline.paths).context("ci_pipeline.paths: invalid pattern")?;
+        build_globset(&self.risk.paths).context("risk.paths: invalid pattern")?;
+        for (name, level) in [("medium", &self.risk.medium), ("high", &self.risk.high)] {
+            let Some(level) = level else {
+                continue;
+            };
+            if level.at.is_some_and(|v| !(0.0..=1.0).contains(&v)) {
+                return Err(anyhow!("risk.{name}.at: must be between 0 and 1"));
+            }
+            if level
+                .min_total_score
+                .is_some_and(|v| !(0.0..=1.0).contains(&v))
+            {
+                return Err(anyhow!("risk.{name}.min_total_score: must be between 0 and 1"));
+            }
+            if level.questions == Some(0) {
+                return Err(anyhow!("risk.{name}.questions: must be at least 1"));
+            }
+        }
+        if self.retake.max_attempts == Some(0) {
+            return Err(anyhow!("retake.max_attempts: must be at least 1"));
+        }
+        if self.rate_limit.requests_per_minute == Some(0) {
+            return Err(anyhow!("rate_limit.requests_per_minute: must be at least 1"));
+        }
+        if self.rate_limit.max_concurrent == Some(0) {
+            return Err(anyhow!("rate_limit.max_concurrent: must be at least 1"));
+        }
+        if self.response_cache.max_age_days == Some(0) {
+            return Err(anyhow!("response_cache.max_age_days: must be at least 1"));
+        }
+        if let Some(mode) = &self.hooks.track_bypasses {
+            if !matches!(mode.as_str(), "nag" | "record" | "off") {
+                return Err(anyhow!(
+                    "hooks.track_bypasses: expected \"nag\", \"record\" or \"off\", got {mode:?}"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The `exam.tiers` entry that sizes the exam for a change: the most specific one it fits,
+    /// tiers limited to `paths` first, then the smallest `max_lines` and `max_files`.
+    pub fn exam_tier(
+        &self,
+        stats: &DiffStats,
+        changed_files: &[String],
+    ) -> Option<(&str, &ExamTier)> {
+        self.exam
+            .tiers
+            .iter()
+            .filter(|(_, t)| t.fits(stats, changed_files))
+            .min_by_key(|(_, t)| {
+                (
+                    t.paths.is_empty(),
+                    t.max_lines.unwrap_or(usize::MAX),
+                    t.max_files.unwrap_or(usize::MAX),
+                )
+            })
+            .map(|(name, t)| (name.as_str(), t))
+    }
+
+    /// Custom categories that apply to a change touching `changed_files`.
+    pub fn applicable_categories(&self, changed_files: &[String]) -> Vec<&CustomCategory> {
+        self.categories
+            .iter()
+            .filter(|c| c.applies_to(changed_files))
+            .collect()
+    }
+
+    /// Bank questions asked for a change touching `changed_files`, each with its exam id and the
+    /// changed files that triggered it.
+    pub fn bank_questions(
+        &self,
+        changed_files: &[String],
+    ) -> Vec<(String, &BankQuestion, Vec<String>)> {
+        self.questions
+            .iter()
+            .enumerate()
+            .filter_map(|(i, q)| {
+                let files = q.matching_files(changed_files);
+                (!files.is_empty()).then(|| (bank_question_id(i, q), q, files))
+            })
+            .collect()
+    }
+
+    /// Weight of a category's questions in `total_score` (`score_weights`, default 1.0).
+    pub fn score_weight(&self, category: &str) -> f64 {
+        self.score_weights.get(category).copied().unwrap_or(1.0)
+    }
+
+    /// Mean of the question scores, each weighted by its category's `score_weights` entry
+    /// (0 when nothing carries weight).
+    pub fn weighted_total(&self, per_question: &[QuestionScore]) -> f64 {
+        let weights: f64 = per_question
+            .iter()
+            .map(|q| self.score_weight(&q.category))
+            .sum();
+        if weights <= 0.0 {
+            return 0.0;
+        }
+        per_question
+            .iter()
+            .map(|q| self.score_weight(&q.category) * q.score)
+            .sum::<f64>()
+            / weights
+    }
+
+    /// `required_categories` plus every applicable custom category marked `required`.
+    pub fn required_categories_for(&self, changed_files: &[String]) -> Vec<String> {
+        let mut out = self.required_categories.clone();
+        for cat in self.applicable_categories(changed_files) {
+            if cat.required && !out.contains(&cat.id) {
+                out.push(cat.id.clone());
+            }
+        }
+        out
+    }
+
+    pub fn require_lower_bound(&self) -> bool {
+        self.grading.require_lower_bound.unwrap_or(false)
+    }
+
+    /// Whether a wrong self-check answer fails the exam.
+    pub fn self_check_required(&self) -> bool {
+        self.self_check.enabled.unwrap_or(false) && self.self_check.required.unwrap_or(true)
+    }
+
+    /// Who grades answers, as `<provider>/<model>`; learned calibration offsets are keyed by it.
+    pub fn grader_identity(&self) -> String {
+        let grader = self
+            .grader
+            .as_deref()
+            .or(self.provider.as_deref())
+            .unwrap_or("local");
+        self.provider_identity(grader)
+    }
+
+    /// `<provider>/<model>` for a provider name, with the model its own section (or `model`)
+    /// configures.
+    pub fn provider_identity(&self, provider: &str) -> String {
+        let model = match provider {
+            "codex-cli" => self.codex_cli.model.as_deref(),
+            "claude-cli" => self.claude_cli.model.as_deref(),
+            "http-api" => self.http_api.model.as_deref(),
+            "anthropic" => self.anthropic.model.as_deref(),
+            "ollama" => self.ollama.model.as_deref(),
+            "exec" => None,
+            _ => return "local/static".to_string(),
+        };
+        let model = model
+            .or(self.model.as_deref())
+            .filter(|m| *m != "static")
+            .unwrap_or("default");
+        format!("{provider}/{model}")
+    }
+
+    /// The learned offset for the configured grader, if it was calibrated and normalization is
+    /// on.
+    pub fn grader_offset(&self) -> Option<f64> {
+        self.calibration
+            .learned
+            .get(&self.grader_identity())
+            .map(|c| c.offset)
+    }
+
     fn with_defaults(mut self) -> Self {
         let d = Self::default();
         if self.min_total_score == 0.0 {
@@ -134,9 +1913,14 @@ impl Policy {
         self
     }
 
-    pub fn max_context_chars(&self) -> usize {
-        // very rough, deterministic token->chars estimate (4 chars/token)
-        self.max_tokens_context.unwrap_or(4096) * 4
+    /// Context window in tokens for the configured model: `tokenizer.context_limits` keyed by
+    /// model, then by provider, else `max_tokens_context`.
+    pub fn max_context_tokens(&self) -> usize {
+        [self.model.as_deref(), self.provider.as_deref()]
+            .into_iter()
+            .flatten()
+            .find_map(|key| self.tokenizer.context_limits.get(key).copied())
+            .unwrap_or_else(|| self.max_tokens_context.unwrap_or(4096))
     }
 
     pub fn set_key(&mut self, key: &str, value: &str) -> Result<()> {
@@ -153,10 +1937,51 @@ impl Policy {
                     .map_err(|_| anyhow!("max_hallucination_flags must be an integer"))?;
                 Ok(())
             }
+            key if key.starts_with("score_weights.") => {
+                let weight = value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|w| w.is_finite() && *w >= 0.0)
+                    .ok_or_else(|| anyhow!("{key} must be a number of at least 0"))?;
+                self.score_weights
+                    .insert(key.trim_start_matches("score_weights.").to_string(), weight);
+                Ok(())
+            }
+            key if key.starts_with("min_category_score.") => {
+                let min = value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|v| (0.0..=1.0).contains(v))
+                    .ok_or_else(|| anyhow!("{key} must be a number between 0 and 1"))?;
+                self.min_category_score
+                    .insert(key.trim_start_matches("min_category_score.").to_string(), min);
+                Ok(())
+            }
+            "min_category_relevance" => {
+                self.min_category_relevance = Some(
+                    value
+                        .parse::<f64>()
+                        .map_err(|_| anyhow!("min_category_relevance must be a number"))?,
+                );
+                Ok(())
+            }
+            "hallucination_min_severity" => {
+                self.hallucination_min_severity = Some(
+                    serde_json::from_value(serde_json::Value::String(value.to_string()))
+                        .map_err(|_| {
+                            anyhow!("hallucination_min_severity must be low, medium, high or critical")
+                        })?,
+                );
+                Ok(())
+            }
             "exam_mode" => {
                 self.exam_mode = Some(value.to_string());
                 Ok(())
             }
+            "language" => {
+                self.language = Some(value.to_string());
+                Ok(())
+            }
             "provider" => {
                 self.provider = Some(value.to_string());
                 Ok(())
@@ -165,10 +1990,30 @@ impl Policy {
                 self.model = Some(value.to_string());
                 Ok(())
             }
+            "grader" => {
+                self.grader = Some(value.to_string());
+                Ok(())
+            }
             "store" => {
                 self.store = Some(value.to_string());
                 Ok(())
             }
+            "store_answers" => {
+                self.store_answers = Some(value.to_string());
+                Ok(())
+            }
+            "repo_id.source" => {
+                self.repo_id.source = Some(value.to_string());
+                Ok(())
+            }
+            "repo_id.hash" => {
+                self.repo_id.hash = Some(
+                    value
+                        .parse::<bool>()
+                        .map_err(|_| anyhow!("repo_id.hash must be true or false"))?,
+                );
+                Ok(())
+            }
             _ => Err(anyhow!("unsupported key: {key}")),
         }
     }
@@ -177,3 +2022,166 @@ impl Policy {
         Ok(toml::to_string_pretty(self)?)
     }
 }
+
+/// Loads a policy file with its bases resolved. Layers apply in a fixed order, later ones
+/// overriding earlier ones: the `extends` base, then each `include` in list order, then the file
+/// itself. `extends = "<name>"` refers to `.aigit/bases/<name>.toml` in the repo; anything
+/// containing `/` or ending in `.toml` (and every `include` entry) is a path relative to the
+/// including file.
+fn load_layered(
+    repo: &GitRepo,
+    path: &std::path::Path,
+    stack: &mut Vec<std::path::PathBuf>,
+) -> Result<toml::Table> {
+    let canonical = path
+        .canonicalize()
+        .with_context(|| format!("failed to resolve policy file {}", path.display()))?;
+    if stack.contains(&canonical) {
+        let chain = stack
+            .iter()
+            .chain(std::iter::once(&canonical))
+            .map(|p| p.display().to_string())
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        return Err(anyhow!("policy include cycle: {chain}"));
+    }
+    stack.push(canonical.clone());
+
+    let mut own = read_toml_table(&canonical)?;
+    let dir = canonical
+        .parent()
+        .map(|p| p.to_path_buf())
+        .unwrap_or_default();
+    let mut bases = Vec::new();
+    match own.remove("extends") {
+        Some(toml::Value::String(name)) => {
+            bases.push(if name.contains('/') || name.ends_with(".toml") {
+                dir.join(name)
+            } else {
+                repo.workdir
+                    .join(".aigit")
+                    .join("bases")
+                    .join(format!("{name}.toml"))
+            });
+        }
+        Some(_) => {
+            return Err(anyhow!(
+                "{}: extends must be a string",
+                canonical.display()
+            ))
+        }
+        None => {}
+    }
+    match own.remove("include") {
+        Some(toml::Value::Array(items)) => {
+            for item in items {
+                let toml::Value::String(inc) = item else {
+                    return Err(anyhow!(
+                        "{}: include entries must be strings",
+                        canonical.display()
+                    ));
+                };
+                bases.push(dir.join(inc));
+            }
+        }
+        Some(toml::Value::String(inc)) => bases.push(dir.join(inc)),
+        Some(_) => {
+            return Err(anyhow!(
+                "{}: include must be a list of paths",
+                canonical.display()
+            ))
+        }
+        None => {}
+    }
+
+    let mut table = toml::Table::new();
+    for base in bases {
+        merge_toml(&mut table, load_layered(repo, &base, stack)?);
+    }
+    merge_toml(&mut table, own);
+    stack.pop();
+    Ok(table)
+}
+
+fn read_toml_table(path: &std::path::Path) -> Result<toml::Table> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    toml::from_str(&raw).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+/// Layers `overlay` onto `base`: tables merge key by key, anything else (scalars, arrays) in
+/// `overlay` replaces the base value.
+fn merge_toml(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(b)), toml::Value::Table(o)) => merge_toml(b, o),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Keys that change how a change is judged rather than how high the bar is: under a
+/// `policy_source` only the central bundle may set them.
+const LOCKED_BY_POLICY_SOURCE: &[&str] = &[
+    "decision",
+    "score_weights",
+    "calibration",
+    "verify.skip_merges",
+    "verify.skip_authors",
+    "verify.skip_subject_patterns",
+];
+
+/// Whether the policy sets where `aigit policy pull` fetches a central bundle from.
+fn has_policy_source(table: &toml::Table) -> bool {
+    table
+        .get("policy_source")
+        .and_then(toml::Value::as_table)
+        .is_some_and(|source| source.contains_key("url") || source.contains_key("git_ref"))
+}
+
+/// Rejects a repo policy (`path`, with what it extends) that sets a key locked by
+/// `policy_source`.
+fn check_unlocked(path: &std::path::Path, repo_table: &toml::Table) -> Result<()> {
+    for key in LOCKED_BY_POLICY_SOURCE {
+        let set = match key.split_once('.') {
+            Some((table, field)) => repo_table
+                .get(table)
+                .and_then(toml::Value::as_table)
+                .is_some_and(|t| t.contains_key(field)),
+            None => repo_table.contains_key(*key),
+        };
+        if set {
+            return Err(anyhow!(
+                "{key} is set by the central policy (policy_source); remove it from {}",
+                path.display()
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn bank_question_id(index: usize, question: &BankQuestion) -> String {
+    match &question.id {
+        Some(id) if !id.trim().is_empty() => id.clone(),
+        _ => format!("bank_{}", index + 1),
+    }
+}
+
+pub(crate) fn build_globset(patterns: &[String]) -> Result<globset::GlobSet> {
+    let mut builder = globset::GlobSetBuilder::new();
+    for p in patterns {
+        builder.add(globset::Glob::new(p)?);
+    }
+    Ok(builder.build()?)
+}
+
+/// True when any of `files` matches any of the glob `patterns` (invalid patterns match nothing;
+/// they are rejected when the policy is loaded).
+fn any_path_matches(patterns: &[String], files: &[String]) -> bool {
+    match build_globset(patterns) {
+        Ok(set) => files.iter().any(|f| set.is_match(f)),
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,230 @@
+use serde::{Deserialize, Serialize};
+
+use crate::examiner::{Exam, ExamContext, ExamQuestion, QuestionKind};
+
+/// Id and category of the question asked when a diff changes configuration.
+pub const QUESTION_ID: &str = "configuration";
+pub const CATEGORY: &str = "configuration";
+
+/// Configuration files, on top of `config_changes.paths`.
+const CONFIG_PATHS: &[&str] = &[
+    "**/*.ini",
+    "**/*.cfg",
+    "**/*.conf",
+    "**/*.properties",
+    "**/.env",
+    "**/.env.*",
+    "**/*.env",
+    "**/config/**",
+    "**/configs/**",
+    "**/settings.*",
+    "**/*.config.*",
+    "**/application*.yml",
+    "**/application*.yaml",
+    "**/values*.yaml",
+    "**/appsettings*.json",
+];
+
+/// Reads of environment variables; a quoted name or identifier may follow.
+const ENV_READS: &[&str] = &[
+    "env::var(",
+    "env::var_os(",
+    "env!(",
+    "option_env!(",
+    "os.environ[",
+    "os.environ.get(",
+    "os.getenv(",
+    "process.env.",
+    "process.env[",
+    "getenv(",
+    "System.getenv(",
+    "ENV[",
+    "ENV.fetch(",
+];
+
+/// Feature-flag definitions and checks.
+const FLAG_NEEDLES: &[&str] = &[
+    "feature_flag",
+    "featureFlag",
+    "FeatureFlag",
+    "feature_enabled",
+    "isFeatureEnabled",
+    "is_feature_enabled",
+    "cfg(feature",
+    "variation(",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSignalKind {
+    /// A configuration file changed.
+    File,
+    /// A changed line reads an environment variable.
+    EnvVar,
+    /// A changed line defines or checks a feature flag.
+    FeatureFlag,
+}
+
+/// A configuration file, environment variable read or feature flag the diff touches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigSignal {
+    pub kind: ConfigSignalKind,
+    pub path: String,
+    /// The environment variable's name, when it is a literal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The hunk, as `<path> <hunk header>`; absent for a whole configuration file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hunk: Option<String>,
+}
+
+impl std::fmt::Display for ConfigSignal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.kind, &self.name) {
+            (ConfigSignalKind::File, _) => write!(f, "config file {}", self.path),
+            (ConfigSignalKind::EnvVar, Some(name)) => {
+                write!(f, "environment variable {name} in {}", self.path)
+            }
+            (ConfigSignalKind::EnvVar, None) => {
+                write!(f, "environment variables in {}", self.path)
+            }
+            (ConfigSignalKind::FeatureFlag, _) => write!(f, "feature flags in {}", self.path),
+        }
+    }
+}
+
+/// The variable named right after an environment read: `"NAME"`, `'NAME'` or `NAME`.
+fn env_var_name(rest: &str) -> Option<String> {
+    let rest = rest.trim_start_matches(['"', '\'', '`']);
+    let name: String = rest
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+        .collect();
+    (!name.is_empty()).then_some(name)
+}
+
+/// Configuration files among the changed files (dependency manifests aside), and environment
+/// reads and feature flags in added or removed lines.
+pub fn detect(extra_paths: &[String], diff: &str, changed_files: &[String]) -> Vec<ConfigSignal> {
+    let mut out: Vec<ConfigSignal> = Vec::new();
+    let patterns: Vec<String> = CONFIG_PATHS
+        .iter()
+        .map(|p| p.to_string())
+        .chain(extra_paths.iter().cloned())
+        .collect();
+    if let Ok(set) = crate::config::build_globset(&patterns) {
+        for f in changed_files {
+            let name = f.rsplit('/').next().unwrap_or(f);
+            if set.is_match(f.as_str()) && !crate::risk::is_dependency_file(name) {
+                out.push(ConfigSignal {
+                    kind: ConfigSignalKind::File,
+                    path: f.clone(),
+                    name: None,
+                    hunk: None,
+                });
+            }
+        }
+    }
+    for hunk in crate::question_links::hunks(diff) {
+        if out
+            .iter()
+            .any(|s| s.kind == ConfigSignalKind::File && s.path == hunk.path)
+        {
+            continue;
+        }
+        for line in hunk.text.iter().skip(1) {
+            let Some(code) = line.strip_prefix(['+', '-']) else {
+                continue;
+            };
+            let code = code.trim_start();
+            if code.starts_with("//") || code.starts_with('#') {
+                continue;
+            }
+            let mut found: Vec<(ConfigSignalKind, Option<String>)> = Vec::new();
+            for needle in ENV_READS {
+                if let Some((_, rest)) = code.split_once(needle) {
+                    found.push((ConfigSignalKind::EnvVar, env_var_name(rest)));
+                    break;
+                }
+            }
+            if FLAG_NEEDLES.iter().any(|n| code.contains(n)) {
+                found.push((ConfigSignalKind::FeatureFlag, None));
+            }
+            for (kind, name) in found {
+                let signal = ConfigSignal {
+                    kind,
+                    path: hunk.path.to_string(),
+                    name,
+                    hunk: Some(hunk.reference()),
+                };
+                if !out
+                    .iter()
+                    .any(|s| s.kind == kind && s.path == signal.path && s.name == signal.name)
+                {
+                    out.push(signal);
+                }
+            }
+        }
+    }
+    out
+}
+
+fn enabled(ctx: &ExamContext) -> bool {
+    ctx.policy.config_changes.enabled.unwrap_or(true)
+}
+
+fn describe(signals: &[ConfigSignal]) -> String {
+    signals
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The line the generate prompt adds when the diff changes configuration.
+pub fn prompt_requirement(ctx: &ExamContext) -> Option<String> {
+    if !enabled(ctx) || ctx.config_changes.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "- The diff changes configuration ({}). Ask one question with id `{QUESTION_ID}` and category `{CATEGORY}` on the default values, the order it rolls out in, and which environments it affects.\n",
+        describe(&ctx.config_changes)
+    ))
+}
+
+/// Adds the configuration question when the diff changes configuration and the exam has none.
+pub fn ensure_question(ctx: &ExamContext, exam: &mut Exam) {
+    if !enabled(ctx)
+        || ctx.config_changes.is_empty()
+        || exam.questions.iter().any(|q| q.category == CATEGORY)
+    {
+        return;
+    }
+    let what = describe(&ctx.config_changes);
+    let prompt = crate::i18n::configuration_question(ctx.policy.language.as_deref(), &what)
+        .unwrap_or_else(|| {
+            format!(
+                "This change touches configuration ({what}). What are the default values when nothing is set, in what order does it roll out, and which environments (development, staging, production) does it affect?"
+            )
+        });
+    let mut paths: Vec<String> = Vec::new();
+    let mut hunks: Vec<String> = Vec::new();
+    for s in &ctx.config_changes {
+        if !paths.contains(&s.path) {
+            paths.push(s.path.clone());
+        }
+        if let Some(h) = s.hunk.as_ref().filter(|h| !hunks.contains(h)) {
+            hunks.push(h.clone());
+        }
+    }
+    exam.questions.push(ExamQuestion {
+        id: QUESTION_ID.to_string(),
+        category: CATEGORY.to_string(),
+        prompt,
+        choices: None,
+        related_paths: paths,
+        related_hunks: hunks,
+        correct_choice: None,
+        kind: Some(QuestionKind::Open),
+    });
+}
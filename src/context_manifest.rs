@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::examiner::TokenBudget;
+use crate::lfs::LfsChange;
+
+/// What of the change made it into the provider context, so "the grader never saw my change"
+/// can be checked against the transcript.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextManifest {
+    /// "bpe" (counted with `tokenizer.bpe_file`) or "estimate".
+    pub tokenizer: String,
+    pub max_tokens: usize,
+    /// Tokens of the diff as included in the context.
+    pub total_tokens: usize,
+    /// The diff was cut to fit `max_tokens`.
+    pub truncated: bool,
+    /// Number of redacted spans.
+    #[serde(default)]
+    pub redactions: usize,
+    pub files: Vec<FileContext>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileContext {
+    pub path: String,
+    /// Added plus removed lines in the full diff.
+    pub lines_changed: usize,
+    /// Added plus removed lines that made it into the context.
+    pub lines_included: usize,
+    /// "full", "partial" (cut by truncation), "omitted" (cut entirely) or "summarized" (an LFS
+    /// pointer change described instead of shown).
+    pub status: String,
+}
+
+impl ContextManifest {
+    /// Compares the full redacted diff with the diff placed in the context.
+    pub fn build(
+        full_diff: &str,
+        included_diff: &str,
+        changed_files: &[String],
+        lfs_changes: &[LfsChange],
+        redactions: usize,
+        budget: &TokenBudget,
+    ) -> Self {
+        let full = per_file_lines(full_diff);
+        let included = per_file_lines(included_diff);
+        let files = changed_files
+            .iter()
+            .map(|path| {
+                let lines_changed = full.get(path).copied().unwrap_or_default();
+                let lines_included = included.get(path).copied().unwrap_or_default();
+                let status = if lfs_changes.iter().any(|c| c.path == *path) {
+                    "summarized"
+                } else if !included.contains_key(path) {
+                    "omitted"
+                } else if lines_included < lines_changed {
+                    "partial"
+                } else {
+                    "full"
+                };
+                FileContext {
+                    path: path.clone(),
+                    lines_changed,
+                    lines_included,
+                    status: status.to_string(),
+                }
+            })
+            .collect();
+        Self {
+            tokenizer: budget.tokenizer_name().to_string(),
+            max_tokens: budget.max_tokens,
+            total_tokens: budget.count(included_diff),
+            truncated: included_diff.ends_with("[aigit: diff truncated]\n"),
+            redactions,
+            files,
+        }
+    }
+}
+
+/// Added plus removed lines per file, keyed by the path after `b/` in `diff --git` headers.
+fn per_file_lines(diff: &str) -> BTreeMap<String, usize> {
+    let mut out = BTreeMap::new();
+    let mut current: Option<String> = None;
+    for line in diff.lines() {
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            let path = header
+                .rsplit_once(" b/")
+                .map(|(_, p)| p)
+                .unwrap_or(header)
+                .to_string();
+            out.entry(path.clone()).or_insert(0);
+            current = Some(path);
+            continue;
+        }
+        if line.starts_with("+++") || line.starts_with("---") {
+            continue;
+        }
+        if line.starts_with('+') || line.starts_with('-') {
+            if let Some(path) = &current {
+                *out.entry(path.clone()).or_insert(0) += 1;
+            }
+        }
+    }
+    out
+}
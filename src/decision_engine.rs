@@ -0,0 +1,264 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::codex_cli::{split_command_line, wait_with_input};
+use crate::config::Policy;
+use crate::git::GitRepo;
+use crate::transcript::{Decision, Transcript};
+
+const DEFAULT_QUERY: &str = "data.aigit";
+const DEFAULT_COMMAND: &str = "opa";
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// The external engine's call on a transcript (`decision.engine = "opa"` or "command"),
+/// recorded with it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineDecision {
+    pub engine: String,
+    /// `decision.rego`, or `decision.command`.
+    pub module: String,
+    /// SHA-256 of the module, or of the command's program when it is a file in the repository,
+    /// as it was when it decided.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module_sha256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    /// The threshold decision the engine saw as `input.decision`.
+    pub builtin: Decision,
+    pub allow: bool,
+    /// Reasons the engine gave for denying the change.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+}
+
+impl EngineDecision {
+    pub fn decision(&self) -> Decision {
+        if self.allow {
+            Decision::Pass
+        } else {
+            Decision::Fail
+        }
+    }
+}
+
+/// The engine making the final decision: `decision.engine`, else "command" when
+/// `decision.command` is set, else "builtin".
+pub fn engine(policy: &Policy) -> &str {
+    let cfg = &policy.decision;
+    match cfg.engine.as_deref() {
+        Some(engine) => engine,
+        None if cfg.command.is_some() => "command",
+        None => "builtin",
+    }
+}
+
+/// Whether a Rego module or an external command makes the final decision.
+pub fn enabled(policy: &Policy) -> bool {
+    engine(policy) != "builtin"
+}
+
+/// Who made the change and what it touches, given to the engine as `input.change` next to the
+/// transcript's own fields.
+pub fn change(
+    author_name: Option<String>,
+    author_email: Option<String>,
+    files: &[String],
+) -> Value {
+    json!({
+        "author_name": author_name,
+        "author_email": author_email,
+        "changed_files": files,
+    })
+}
+
+/// Asks the configured engine about `t` (whose `decision` is the built-in one) plus `change`.
+pub fn evaluate(
+    repo: &GitRepo,
+    policy: &Policy,
+    t: &Transcript,
+    change: Value,
+) -> Result<EngineDecision> {
+    let mut input = serde_json::to_value(t)?;
+    if let Some(map) = input.as_object_mut() {
+        map.remove("policy_engine");
+        map.insert("change".to_string(), change);
+    }
+    match engine(policy) {
+        "command" => run_command(repo, policy, t, &input),
+        _ => run_opa(repo, policy, t, &input),
+    }
+}
+
+/// Evaluates `decision.query` with `opa eval` against the input.
+fn run_opa(
+    repo: &GitRepo,
+    policy: &Policy,
+    t: &Transcript,
+    input: &Value,
+) -> Result<EngineDecision> {
+    let cfg = &policy.decision;
+    let module = cfg
+        .rego
+        .as_deref()
+        .ok_or_else(|| anyhow!("decision.engine = \"opa\" needs decision.rego"))?;
+    let module_path = repo.workdir.join(module);
+    let source = std::fs::read(&module_path)
+        .with_context(|| format!("failed to read decision.rego {}", module_path.display()))?;
+    let query = cfg.query.as_deref().unwrap_or(DEFAULT_QUERY);
+
+    let command = cfg.opa_command.as_deref().unwrap_or(DEFAULT_COMMAND);
+    let parts = shlex::split(command)
+        .filter(|p| !p.is_empty())
+        .ok_or_else(|| anyhow!("invalid decision.opa_command: {command}"))?;
+    let mut child = Command::new(&parts[0])
+        .args(&parts[1..])
+        .args(["eval", "--format", "json", "--stdin-input", "--data"])
+        .arg(&module_path)
+        .arg(query)
+        .current_dir(&repo.workdir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to run {command} (decision.engine = \"opa\")"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(&serde_json::to_vec(input)?)?;
+    }
+    let out = child.wait_with_output()?;
+    if !out.status.success() {
+        return Err(anyhow!(
+            "opa eval of {module} failed: {}",
+            String::from_utf8_lossy(&out.stderr).trim()
+        ));
+    }
+    let result: Value = serde_json::from_slice(&out.stdout)
+        .with_context(|| format!("opa eval of {module} printed invalid JSON"))?;
+    let value = result
+        .pointer("/result/0/expressions/0/value")
+        .ok_or_else(|| anyhow!("{query} is undefined in {module}"))?;
+    let (allow, deny) = verdict(value)
+        .map_err(|other| anyhow!("{query} in {module} must be {VERDICT_SHAPE}, not {other}"))?;
+    Ok(EngineDecision {
+        engine: "opa".to_string(),
+        module: module.to_string(),
+        module_sha256: Some(hex::encode(Sha256::digest(&source))),
+        query: Some(query.to_string()),
+        builtin: t.decision,
+        allow,
+        deny,
+    })
+}
+
+/// Runs `decision.command` with the input JSON on stdin; it prints its verdict as JSON.
+fn run_command(
+    repo: &GitRepo,
+    policy: &Policy,
+    t: &Transcript,
+    input: &Value,
+) -> Result<EngineDecision> {
+    let cfg = &policy.decision;
+    let command = cfg
+        .command
+        .as_deref()
+        .filter(|c| !c.trim().is_empty())
+        .ok_or_else(|| anyhow!("decision.engine = \"command\" needs decision.command"))?;
+    let (program, args) = split_command_line(command)?;
+    let program = in_repo(&repo.workdir, &program);
+    let child = Command::new(&program)
+        .args(&args)
+        .current_dir(&repo.workdir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to run decision.command {command}"))?;
+    let timeout = Duration::from_secs(cfg.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+    let stdout = wait_with_input(
+        child,
+        &serde_json::to_string(input)?,
+        timeout,
+        &format!("decision.command ({command})"),
+    )?;
+    let value: Value = serde_json::from_str(stdout.trim())
+        .with_context(|| format!("decision.command {command} printed invalid JSON"))?;
+    let (allow, deny) = verdict(&value).map_err(|other| {
+        anyhow!("decision.command {command} must print {VERDICT_SHAPE}, not {other}")
+    })?;
+    Ok(EngineDecision {
+        engine: "command".to_string(),
+        module: command.to_string(),
+        module_sha256: std::fs::read(&program)
+            .ok()
+            .filter(|_| program.starts_with(&repo.workdir))
+            .map(|bytes| hex::encode(Sha256::digest(bytes))),
+        query: None,
+        builtin: t.decision,
+        allow,
+        deny,
+    })
+}
+
+/// A program given as a relative path (`./scripts/pou-decide`) is in the repository; a bare
+/// name is looked up on `PATH`.
+fn in_repo(workdir: &Path, program: &str) -> PathBuf {
+    let path = Path::new(program);
+    if path.is_relative() && path.components().count() > 1 {
+        workdir.join(path)
+    } else {
+        PathBuf::from(program)
+    }
+}
+
+const VERDICT_SHAPE: &str = "a boolean or an object with allow/deny";
+
+/// `true`/`false`, or `{"allow": bool, "deny": [reason, ...]}`; any reason denies the change.
+fn verdict(value: &Value) -> std::result::Result<(bool, Vec<String>), &Value> {
+    let (mut allow, deny) = match value {
+        Value::Bool(allow) => (*allow, Vec::new()),
+        Value::Object(map) => (
+            map.get("allow").and_then(Value::as_bool).unwrap_or(false),
+            map.get("deny")
+                .and_then(Value::as_array)
+                .map(|reasons| {
+                    reasons
+                        .iter()
+                        .map(|r| r.as_str().map_or_else(|| r.to_string(), str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        ),
+        other => return Err(other),
+    };
+    if !deny.is_empty() {
+        allow = false;
+    }
+    Ok((allow, deny))
+}
+
+/// Re-evaluates the engine for a stored transcript, as `aigit verify` does under the current
+/// policy: the built-in decision it is given is the recorded one, re-checked against today's
+/// thresholds.
+pub fn verify(
+    repo: &GitRepo,
+    policy: &Policy,
+    t: &Transcript,
+    changed_files: &[String],
+    change: Value,
+) -> Result<EngineDecision> {
+    let mut base = t.clone();
+    base.decision = t.policy_engine.as_ref().map_or(t.decision, |e| e.builtin);
+    base.policy_engine = None;
+    base.decision = if base.verify_against_policy(policy, changed_files) {
+        Decision::Pass
+    } else {
+        Decision::Fail
+    };
+    evaluate(repo, policy, &base, change)
+}
@@ -0,0 +1,229 @@
+use crate::tokenizer::Tokenizer;
+
+/// Lines of each file's first hunk that are always kept, after its header.
+const MIN_HUNK_LINES: usize = 3;
+
+struct Line<'a> {
+    text: &'a str,
+    tokens: usize,
+}
+
+struct Hunk<'a> {
+    header: Line<'a>,
+    lines: Vec<Line<'a>>,
+}
+
+/// One file's section of a unified diff: the `diff --git`/`index`/`---`/`+++` lines, then hunks.
+struct FileDiff<'a> {
+    header: Vec<Line<'a>>,
+    hunks: Vec<Hunk<'a>>,
+}
+
+impl FileDiff<'_> {
+    fn tokens(&self) -> usize {
+        self.header.iter().map(|l| l.tokens).sum::<usize>()
+            + self
+                .hunks
+                .iter()
+                .map(|h| h.header.tokens + h.lines.iter().map(|l| l.tokens).sum::<usize>())
+                .sum::<usize>()
+    }
+}
+
+/// How much of one file is kept: lines of each hunk, in order (`None` = hunk dropped).
+struct Kept {
+    per_hunk: Vec<Option<usize>>,
+}
+
+/// Cuts a unified diff to `max_tokens` without hiding any file: every file keeps its header, its
+/// first hunk header and a few lines, and the rest of the budget is shared across files in
+/// proportion to their size. Returns `None` when even that minimum does not fit.
+pub fn fit(tokenizer: &Tokenizer, diff: &str, max_tokens: usize) -> Option<String> {
+    let files = parse(tokenizer, diff);
+    let mut kept: Vec<Kept> = files
+        .iter()
+        .map(|f| Kept {
+            per_hunk: f.hunks.iter().map(|_| None).collect(),
+        })
+        .collect();
+
+    // The floor every file gets.
+    let mut used = 0;
+    for (file, kept) in files.iter().zip(kept.iter_mut()) {
+        used += file.header.iter().map(|l| l.tokens).sum::<usize>();
+        if let Some(first) = file.hunks.first() {
+            let n = first.lines.len().min(MIN_HUNK_LINES);
+            used += first.header.tokens + first.lines[..n].iter().map(|l| l.tokens).sum::<usize>();
+            kept.per_hunk[0] = Some(n);
+        }
+    }
+    if used > max_tokens {
+        return None;
+    }
+
+    // Proportional shares of what is left, then any unspent share to files in order.
+    let left = max_tokens - used;
+    let remaining: Vec<usize> = files
+        .iter()
+        .zip(&kept)
+        .map(|(f, k)| f.tokens() - kept_tokens(f, k))
+        .collect();
+    let total_remaining: usize = remaining.iter().sum();
+    let mut spare = left;
+    for ((file, kept), rest) in files.iter().zip(kept.iter_mut()).zip(&remaining) {
+        if let Some(share) = (left * rest).checked_div(total_remaining) {
+            spare -= grow(file, kept, share);
+        }
+    }
+    for (file, kept) in files.iter().zip(kept.iter_mut()) {
+        if spare == 0 {
+            break;
+        }
+        spare -= grow(file, kept, spare);
+    }
+
+    let mut out = String::new();
+    for (file, kept) in files.iter().zip(&kept) {
+        for line in &file.header {
+            out.push_str(line.text);
+        }
+        for (hunk, n) in file.hunks.iter().zip(&kept.per_hunk) {
+            let Some(n) = n else {
+                continue;
+            };
+            out.push_str(hunk.header.text);
+            for line in &hunk.lines[..*n] {
+                out.push_str(line.text);
+            }
+        }
+    }
+    Some(out)
+}
+
+fn kept_tokens(file: &FileDiff, kept: &Kept) -> usize {
+    file.header.iter().map(|l| l.tokens).sum::<usize>()
+        + file
+            .hunks
+            .iter()
+            .zip(&kept.per_hunk)
+            .filter_map(|(h, n)| {
+                n.map(|n| h.header.tokens + h.lines[..n].iter().map(|l| l.tokens).sum::<usize>())
+            })
+            .sum::<usize>()
+}
+
+/// Adds whole lines to `kept`, hunk by hunk, spending at most `budget` tokens; returns what was
+/// spent.
+fn grow(file: &FileDiff, kept: &mut Kept, budget: usize) -> usize {
+    let mut spent = 0;
+    for (hunk, n) in file.hunks.iter().zip(kept.per_hunk.iter_mut()) {
+        let start = match n {
+            Some(n) => *n,
+            None => {
+                if spent + hunk.header.tokens > budget {
+                    return spent;
+                }
+                spent += hunk.header.tokens;
+                *n = Some(0);
+                0
+            }
+        };
+        for line in &hunk.lines[start..] {
+            if spent + line.tokens > budget {
+                return spent;
+            }
+            spent += line.tokens;
+            *n = n.map(|k| k + 1);
+        }
+    }
+    spent
+}
+
+fn parse<'a>(tokenizer: &Tokenizer, diff: &'a str) -> Vec<FileDiff<'a>> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    for text in diff.split_inclusive('\n') {
+        let line = Line {
+            text,
+            tokens: tokenizer.count(text),
+        };
+        if text.starts_with("diff --git ") || files.is_empty() {
+            files.push(FileDiff {
+                header: Vec::new(),
+                hunks: Vec::new(),
+            });
+        }
+        let file = files.last_mut().expect("a file was pushed");
+        if text.starts_with("@@") {
+            file.hunks.push(Hunk {
+                header: line,
+                lines: Vec::new(),
+            });
+        } else if let Some(hunk) = file.hunks.last_mut() {
+            hunk.lines.push(line);
+        } else {
+            file.header.push(line);
+        }
+    }
+    files
+}
+
+/// `minimal` with each file's section swapped for its section in `enriched` (the same change with
+/// surrounding code), file by file in diff order, as long as the total stays within `max_tokens`.
+pub fn with_context(
+    tokenizer: &Tokenizer,
+    minimal: &str,
+    enriched: &str,
+    max_tokens: usize,
+) -> String {
+    let enriched: Vec<(&str, &str)> = sections(enriched);
+    let mut total = tokenizer.count(minimal);
+    let mut out = String::with_capacity(minimal.len());
+    for (path, section) in sections(minimal) {
+        let richer = enriched
+            .iter()
+            .find(|(p, _)| *p == path)
+            .map(|(_, s)| *s)
+            .filter(|s| !path.is_empty() && *s != section);
+        match richer {
+            Some(richer) => {
+                let extra = tokenizer
+                    .count(richer)
+                    .saturating_sub(tokenizer.count(section));
+                if total + extra <= max_tokens {
+                    total += extra;
+                    out.push_str(richer);
+                } else {
+                    out.push_str(section);
+                }
+            }
+            None => out.push_str(section),
+        }
+    }
+    out
+}
+
+/// A diff split at `diff --git` lines, with the path after `b/` (empty for any preamble).
+fn sections(diff: &str) -> Vec<(&str, &str)> {
+    let mut out: Vec<(&str, &str)> = Vec::new();
+    let mut start = 0;
+    let mut path = "";
+    let mut offset = 0;
+    for line in diff.split_inclusive('\n') {
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            if offset > start {
+                out.push((path, &diff[start..offset]));
+            }
+            start = offset;
+            path = header
+                .trim_end()
+                .rsplit_once(" b/")
+                .map(|(_, p)| p)
+                .unwrap_or_default();
+        }
+        offset += line.len();
+    }
+    if offset > start {
+        out.push((path, &diff[start..offset]));
+    }
+    out
+}
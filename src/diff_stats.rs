@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+/// How big a change is and where it lands; sizes the exam (see `exam.tiers`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffStats {
+    pub files: usize,
+    /// Added/removed lines in the full diff.
+    pub insertions: usize,
+    pub deletions: usize,
+    /// Top-level directories the change touches (`src/`, `docs/`), in first-seen order; files at
+    /// the repository root count as `./`.
+    pub areas: Vec<String>,
+}
+
+impl DiffStats {
+    pub fn new(diff: &str, changed_files: &[String]) -> Self {
+        let (insertions, deletions) = crate::prompt_template::line_counts(diff);
+        let mut areas: Vec<String> = Vec::new();
+        for file in changed_files {
+            let area = match file.split_once('/') {
+                Some((top, _)) => format!("{top}/"),
+                None => "./".to_string(),
+            };
+            if !areas.contains(&area) {
+                areas.push(area);
+            }
+        }
+        Self {
+            files: changed_files.len(),
+            insertions,
+            deletions,
+            areas,
+        }
+    }
+
+    /// Added plus removed lines.
+    pub fn lines(&self) -> usize {
+        self.insertions + self.deletions
+    }
+
+    /// `git diff --shortstat`-style summary.
+    pub fn shortstat(&self) -> String {
+        crate::prompt_template::diffstat(self.files, self.insertions, self.deletions)
+    }
+}
@@ -0,0 +1,150 @@
+use regex::Regex;
+
+use crate::examiner::{Exam, ExamContext, ExamQuestion};
+use crate::question_links::DiffHunk;
+use crate::transcript::{Answers, Note, NoteCode, Score};
+
+/// A location an answer points to: a file and a line range on the new side of the diff (the old
+/// side for hunks that only remove lines), as cited.
+pub struct Citation {
+    pub text: String,
+    pub path: String,
+    pub start: u32,
+    pub end: u32,
+}
+
+/// The locations an answer cites, in order: `path:line`, `path:start-end`, or a hunk written as
+/// the path followed by its header (`src/lib.rs @@ -10,2 +10,3 @@`).
+pub fn citations(answer: &str) -> Vec<Citation> {
+    let hunk = Regex::new(r"([\w./-]+)\s+@@\s*-(\d+)(?:,(\d+))?\s+\+(\d+)(?:,(\d+))?\s*@@")
+        .expect("valid regex");
+    let line = Regex::new(r"([\w./-]*\w):(\d+)(?:-(\d+))?\b").expect("valid regex");
+    let number = |m: Option<regex::Match>| m.and_then(|m| m.as_str().parse::<u32>().ok());
+
+    let mut out: Vec<(usize, Citation)> = Vec::new();
+    for c in hunk.captures_iter(answer) {
+        let whole = c.get(0).expect("match");
+        let (start, len) = match (number(c.get(4)), number(c.get(5)).unwrap_or(1)) {
+            (Some(start), 0) => (
+                number(c.get(2)).unwrap_or(start),
+                number(c.get(3)).unwrap_or(1),
+            ),
+            (Some(start), len) => (start, len),
+            (None, _) => continue,
+        };
+        out.push((
+            whole.start(),
+            Citation {
+                text: whole.as_str().to_string(),
+                path: c[1].to_string(),
+                start,
+                end: start + len.max(1) - 1,
+            },
+        ));
+    }
+    for c in line.captures_iter(answer) {
+        let whole = c.get(0).expect("match");
+        let Some(start) = number(c.get(2)) else {
+            continue;
+        };
+        let end = number(c.get(3)).unwrap_or(start).max(start);
+        out.push((
+            whole.start(),
+            Citation {
+                text: whole.as_str().to_string(),
+                path: c[1].to_string(),
+                start,
+                end,
+            },
+        ));
+    }
+    out.sort_by_key(|(at, _)| *at);
+    out.into_iter().map(|(_, c)| c).collect()
+}
+
+/// The hunks of `diff` a citation points into, as `<path> <header>` references; empty when the
+/// location is not part of the diff. The path may be given in full or as a path suffix.
+pub fn resolve(diff: &str, citation: &Citation) -> Vec<String> {
+    crate::question_links::hunks(diff)
+        .iter()
+        .filter(|h| h.path == citation.path || h.path.ends_with(&format!("/{}", citation.path)))
+        .filter(|h| {
+            let (start, end) = lines(h);
+            citation.start <= end && citation.end >= start
+        })
+        .map(DiffHunk::reference)
+        .collect()
+}
+
+/// Lines a hunk covers: its new-side range, or the old-side range when it only removes lines.
+fn lines(hunk: &DiffHunk) -> (u32, u32) {
+    let range = |side: char| -> Option<(u32, u32)> {
+        let spec = hunk
+            .header
+            .split_whitespace()
+            .find_map(|w| w.strip_prefix(side))?;
+        let (start, len) = match spec.split_once(',') {
+            Some((start, len)) => (start.parse().ok()?, len.parse().ok()?),
+            None => (spec.parse().ok()?, 1),
+        };
+        Some((start, len))
+    };
+    match (range('+'), range('-')) {
+        (Some((start, len)), _) if len > 0 => (start, start + len - 1),
+        (_, Some((start, len))) if len > 0 => (start, start + len - 1),
+        _ => (0, 0),
+    }
+}
+
+/// Grades every evidence question deterministically: full credit when the answer cites a location
+/// inside the hunks the question is about (anywhere in the diff when it is linked to none),
+/// nothing otherwise. The total moves with it.
+pub fn apply(ctx: &ExamContext, exam: &Exam, answers: &Answers, score: &mut Score) {
+    let mut shift = 0.0;
+    for q in exam.questions.iter().filter(|q| q.is_evidence()) {
+        let Some(question_score) = score.per_question.iter_mut().find(|s| s.id == q.id) else {
+            continue;
+        };
+        let answer = answers.get(&q.id).unwrap_or_default();
+        let (right, note) = check(&ctx.diff, q, answer);
+        shift += ctx.policy.score_weight(&q.category)
+            * crate::answer_key::set_deterministic(question_score, right, answer);
+        question_score.evidence_valid = Some(right);
+        question_score
+            .notes
+            .extend(note.map(|message| Note::new(NoteCode::Inaccuracy, message)));
+    }
+    crate::answer_key::shift_total(&ctx.policy, score, shift);
+}
+
+/// Whether `answer` cites a location the question accepts, and why not when it does not.
+fn check(diff: &str, question: &ExamQuestion, answer: &str) -> (bool, Option<String>) {
+    let cited = citations(answer);
+    let Some(first) = cited.first() else {
+        return (
+            false,
+            Some("evidence: no location cited (expected path:line or a hunk header)".to_string()),
+        );
+    };
+    let mut outside = None;
+    for c in &cited {
+        let hunks = resolve(diff, c);
+        if hunks.is_empty() {
+            continue;
+        }
+        if question.related_hunks.is_empty()
+            || hunks.iter().any(|h| question.related_hunks.contains(h))
+        {
+            return (true, None);
+        }
+        outside.get_or_insert(c);
+    }
+    let note = match outside {
+        Some(c) => format!(
+            "evidence: {} is in the diff but not in the hunks this question is about",
+            c.text
+        ),
+        None => format!("evidence: {} is not part of the diff", first.text),
+    };
+    (false, Some(note))
+}
@@ -0,0 +1,219 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::examiner::{Exam, ExamContext, ExamQuestion, Examiner, StaticExaminer};
+
+/// Source extensions that make a bare word (no `/`) look like a file name.
+const FILE_EXTENSIONS: &[&str] = &[
+    "c", "cc", "cfg", "cpp", "cs", "css", "go", "h", "hpp", "html", "ini", "java", "js", "json",
+    "kt", "lock", "md", "proto", "py", "rb", "rs", "sh", "sql", "swift", "toml", "ts", "tsx",
+    "txt", "xml", "yaml", "yml",
+];
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "does", "for", "how", "in", "is", "it", "of", "on", "the", "this",
+    "to", "what", "which", "why", "with", "would", "you",
+];
+
+/// A problem found in a generated exam and fixed before it was shown.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QualityIssue {
+    /// "unknown_file", "unknown_symbol", "duplicate" or "missing_category".
+    pub kind: String,
+    /// The dropped question, or the question added for a missing category.
+    pub question_id: String,
+    pub detail: String,
+}
+
+/// What local validation did to a generated exam.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExamQuality {
+    /// The first exam had issues and a second one was requested.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub regenerated: bool,
+    pub issues: Vec<QualityIssue>,
+}
+
+/// Finds questions that reference files or symbols outside the diff, duplicate an earlier
+/// question, and required categories nobody asks about.
+pub fn check(ctx: &ExamContext, exam: &Exam) -> Vec<QualityIssue> {
+    let threshold = ctx
+        .policy
+        .exam_quality
+        .near_duplicate_threshold
+        .unwrap_or(0.8);
+    let mut issues = Vec::new();
+    let mut kept: Vec<(&ExamQuestion, BTreeSet<String>)> = Vec::new();
+    for q in &exam.questions {
+        if let Some(file) = file_tokens(&q.prompt)
+            .into_iter()
+            .find(|f| !in_diff_files(ctx, f))
+        {
+            issues.push(issue(
+                "unknown_file",
+                q,
+                format!("mentions {file}, which is not in the diff"),
+            ));
+            continue;
+        }
+        if let Some(symbol) = symbol_tokens(&q.prompt)
+            .into_iter()
+            .find(|s| !ctx.diff.contains(s.as_str()))
+        {
+            issues.push(issue(
+                "unknown_symbol",
+                q,
+                format!("mentions `{symbol}`, which does not appear in the diff"),
+            ));
+            continue;
+        }
+        let words = prompt_words(&q.prompt);
+        if let Some((earlier, _)) = kept
+            .iter()
+            .find(|(_, other)| jaccard(&words, other) >= threshold)
+        {
+            issues.push(issue(
+                "duplicate",
+                q,
+                format!("repeats question {}", earlier.id),
+            ));
+            continue;
+        }
+        kept.push((q, words));
+    }
+    for cat in ctx.policy.required_categories_for(&ctx.changed_files) {
+        if !kept.iter().any(|(q, _)| q.category == cat) {
+            issues.push(QualityIssue {
+                kind: "missing_category".to_string(),
+                question_id: cat.clone(),
+                detail: format!("no question covers required category {cat}"),
+            });
+        }
+    }
+    issues
+}
+
+/// Drops the questions `issues` name and adds the built-in question for each missing category.
+pub fn patch(ctx: &ExamContext, exam: &mut Exam, issues: &mut [QualityIssue]) {
+    let dropped: BTreeSet<&str> = issues
+        .iter()
+        .filter(|i| i.kind != "missing_category")
+        .map(|i| i.question_id.as_str())
+        .collect();
+    exam.questions.retain(|q| !dropped.contains(q.id.as_str()));
+
+    let builtin = StaticExaminer::new()
+        .generate_exam(ctx)
+        .map(|e| e.questions)
+        .unwrap_or_default();
+    for issue in issues.iter_mut().filter(|i| i.kind == "missing_category") {
+        let cat = issue.question_id.clone();
+        let mut question = builtin
+            .iter()
+            .find(|q| q.category == cat)
+            .cloned()
+            .unwrap_or_else(|| ExamQuestion {
+                id: cat.clone(),
+                category: cat.clone(),
+                prompt: crate::i18n::missing_category_question(
+                    ctx.policy.language.as_deref(),
+                    &cat,
+                )
+                .unwrap_or_else(|| {
+                    format!("Explain the {cat} aspects of this change, citing the changed files.")
+                }),
+                choices: None,
+                related_paths: Vec::new(),
+                related_hunks: Vec::new(),
+                correct_choice: None,
+                kind: None,
+            });
+        while exam.questions.iter().any(|q| q.id == question.id) {
+            question.id.push_str("_added");
+        }
+        issue.question_id = question.id.clone();
+        exam.questions.push(question);
+    }
+}
+
+fn issue(kind: &str, q: &ExamQuestion, detail: String) -> QualityIssue {
+    QualityIssue {
+        kind: kind.to_string(),
+        question_id: q.id.clone(),
+        detail,
+    }
+}
+
+pub(crate) fn in_diff_files(ctx: &ExamContext, token: &str) -> bool {
+    ctx.changed_files.iter().any(|f| {
+        f == token || f.ends_with(&format!("/{token}")) || token.ends_with(&format!("/{f}"))
+    })
+}
+
+fn trim_token(token: &str) -> &str {
+    token.trim_matches(|c: char| ",.;:!?()[]{}\"'`".contains(c))
+}
+
+/// Words that look like paths: `dir/name.ext`, or a bare `name.ext` with a known extension.
+pub(crate) fn file_tokens(prompt: &str) -> Vec<String> {
+    prompt
+        .split_whitespace()
+        .map(trim_token)
+        .filter(|t| {
+            let Some((stem, ext)) = t.rsplit_once('.') else {
+                return false;
+            };
+            let stem = stem.rsplit('/').next().unwrap_or(stem);
+            !stem.is_empty()
+                && !ext.is_empty()
+                && (t.contains('/') || FILE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+/// Code identifiers quoted in backticks (`parse_config`, `Foo::bar()`, `retryCount`), the way
+/// generated questions name code. Plain words in backticks (`cargo`) are not treated as symbols.
+fn symbol_tokens(prompt: &str) -> Vec<String> {
+    prompt
+        .split('`')
+        .skip(1)
+        .step_by(2)
+        .map(str::trim)
+        .filter(|s| {
+            let name = s.trim_end_matches("()");
+            let code_like = name.len() < s.len()
+                || name.contains('_')
+                || name.contains("::")
+                || name.chars().skip(1).any(|c| c.is_uppercase());
+            code_like
+                && name.len() >= 3
+                && name
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == ':')
+                && name
+                    .chars()
+                    .next()
+                    .is_some_and(|c| c.is_alphabetic() || c == '_')
+        })
+        .map(|s| s.trim_end_matches("()"))
+        .map(|s| s.rsplit("::").next().unwrap_or(s).to_string())
+        .collect()
+}
+
+fn prompt_words(prompt: &str) -> BTreeSet<String> {
+    prompt
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|w| !w.is_empty() && !STOPWORDS.contains(w))
+        .map(str::to_string)
+        .collect()
+}
+
+fn jaccard(a: &BTreeSet<String>, b: &BTreeSet<String>) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    a.intersection(b).count() as f64 / a.union(b).count() as f64
+}
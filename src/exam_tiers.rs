@@ -0,0 +1,185 @@
+use std::sync::LazyLock;
+
+use crate::config::ExamTier;
+use crate::examiner::{ExamContext, ExamQuestion};
+
+/// The categories every full exam covers.
+pub const BUILTIN_CATEGORIES: &[&str] = &[
+    "summary",
+    "intent",
+    "invariants",
+    "risk",
+    "testing",
+    "rollback",
+    "alternatives",
+    "security",
+];
+
+/// Name of the built-in exam size for changes that only touch tests.
+pub const TESTS_ONLY: &str = "tests_only";
+
+/// Test directories and the usual test file names.
+const TEST_PATHS: &[&str] = &[
+    "tests/**",
+    "**/tests/**",
+    "test/**",
+    "**/test/**",
+    "**/__tests__/**",
+    "spec/**",
+    "**/spec/**",
+    "**/testdata/**",
+    "**/*_test.*",
+    "**/*_tests.*",
+    "**/test_*.py",
+    "**/*.test.*",
+    "**/*.spec.*",
+    "**/*Test.java",
+    "**/*Tests.java",
+    "**/*Test.kt",
+    "**/*Tests.cs",
+];
+
+/// Two questions on the tests: what they pin down and what prompted them.
+static TESTS_ONLY_TIER: LazyLock<ExamTier> = LazyLock::new(|| ExamTier {
+    paths: TEST_PATHS.iter().map(|p| p.to_string()).collect(),
+    questions: Some(2),
+    categories: vec!["testing".to_string()],
+    ..ExamTier::default()
+});
+
+/// Whether the change gets the `tests_only` exam: every changed file is a test file,
+/// `exam.tests_only` is not off, and no tier limited to `paths` fits the change.
+pub fn test_lane(ctx: &ExamContext) -> bool {
+    ctx.policy.exam.tests_only.unwrap_or(true)
+        && !ctx.changed_files.is_empty()
+        && TESTS_ONLY_TIER.fits(&ctx.stats, &ctx.changed_files)
+        && ctx
+            .policy
+            .exam_tier(&ctx.stats, &ctx.changed_files)
+            .is_none_or(|(_, t)| t.paths.is_empty())
+}
+
+/// The questions of the `tests_only` exam, in place of the full battery.
+pub fn test_lane_questions() -> Vec<ExamQuestion> {
+    [
+        (
+            "test_behavior",
+            "Which behavior do the new or changed tests pin down? Name the tests and the cases they cover.",
+        ),
+        (
+            "test_gap",
+            "What gap prompted these tests (a bug, a missing case, a refactor to protect)? What would have gone unnoticed without them?",
+        ),
+    ]
+    .into_iter()
+    .map(|(id, prompt)| ExamQuestion {
+        id: id.to_string(),
+        category: "testing".to_string(),
+        prompt: prompt.to_string(),
+        choices: None,
+        related_paths: Vec::new(),
+        related_hunks: Vec::new(),
+        correct_choice: None,
+        kind: None,
+    })
+    .collect()
+}
+
+/// The tier that sizes the exam for this change, with its name: the built-in `tests_only` one,
+/// or the `exam.tiers` entry.
+pub fn select(ctx: &ExamContext) -> Option<(&str, &ExamTier)> {
+    if test_lane(ctx) {
+        return Some((TESTS_ONLY, &TESTS_ONLY_TIER));
+    }
+    ctx.policy.exam_tier(&ctx.stats, &ctx.changed_files)
+}
+
+/// Categories whose first question a trimmed exam keeps: the required ones, or in the
+/// `tests_only` exam only the applicable custom categories marked `required`.
+fn required(ctx: &ExamContext) -> Vec<String> {
+    if test_lane(ctx) {
+        return ctx
+            .policy
+            .applicable_categories(&ctx.changed_files)
+            .into_iter()
+            .filter(|c| c.required)
+            .map(|c| c.id.clone())
+            .collect();
+    }
+    ctx.policy.required_categories_for(&ctx.changed_files)
+}
+
+/// Questions the change's risk level asks for at least (`[risk.<level>] questions`).
+pub fn risk_questions(ctx: &ExamContext) -> Option<usize> {
+    let risk = ctx.risk.as_ref()?;
+    crate::risk::level_policy(&ctx.policy, risk.level)?.questions
+}
+
+/// Categories the exam must cover: the tier's categories plus the required ones, or every
+/// built-in category when no tier narrows them.
+pub fn categories(ctx: &ExamContext) -> Vec<String> {
+    let Some((_, tier)) = select(ctx).filter(|(_, t)| !t.categories.is_empty()) else {
+        return BUILTIN_CATEGORIES.iter().map(|c| c.to_string()).collect();
+    };
+    let mut out = tier.categories.clone();
+    for cat in required(ctx) {
+        if !out.contains(&cat) {
+            out.push(cat);
+        }
+    }
+    out
+}
+
+/// Cuts a question list down to the selected tier: questions outside its categories go, then the
+/// list is shortened to its question count, raised to the risk level's. The first question of each required category and
+/// evidence questions are always kept, even past the count.
+pub fn trim(ctx: &ExamContext, questions: &mut Vec<ExamQuestion>) {
+    let Some((_, tier)) = select(ctx) else {
+        return;
+    };
+    let required = required(ctx);
+    if !tier.categories.is_empty() {
+        questions.retain(|q| {
+            tier.categories.contains(&q.category)
+                || required.contains(&q.category)
+                || q.is_evidence()
+        });
+    }
+    let Some(max) = tier
+        .questions
+        .map(|n| n.max(risk_questions(ctx).unwrap_or(0)))
+    else {
+        return;
+    };
+
+    let mut covered: Vec<&str> = Vec::new();
+    let essential: Vec<bool> = questions
+        .iter()
+        .map(|q| {
+            if q.is_evidence() {
+                return true;
+            }
+            let first = required.contains(&q.category) && !covered.contains(&q.category.as_str());
+            if first {
+                covered.push(&q.category);
+            }
+            first
+        })
+        .collect();
+    let mut room = max.saturating_sub(essential.iter().filter(|e| **e).count());
+    let keep: Vec<bool> = essential
+        .iter()
+        .map(|&e| {
+            if e {
+                return true;
+            }
+            if room > 0 {
+                room -= 1;
+                return true;
+            }
+            false
+        })
+        .collect();
+    let mut keep = keep.into_iter();
+    questions.retain(|_| keep.next().unwrap_or(false));
+}
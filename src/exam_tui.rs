@@ -0,0 +1,865 @@
+use std::io::IsTerminal;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Position, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::examiner::{Exam, ExamContext, ExamQuestion};
+use crate::precheck::Issue;
+use crate::session::ExamSession;
+use crate::transcript::Answers;
+
+/// How long to wait for a key before saving draft answers typed since the last save.
+const IDLE_SAVE: Duration = Duration::from_millis(500);
+
+const KEYS_EDIT: &str =
+    "Tab focus  Ctrl-N/Ctrl-P next/prev question  d full diff  Ctrl-S review  Ctrl-C abort";
+const KEYS_REVIEW: &str = "↑/↓ scroll  Enter submit  Esc back to editing  Ctrl-C abort";
+const KEYS_REVIEW_ISSUES: &str =
+    "↑/↓ scroll  Enter submit anyway  Esc edit the flagged answers  Ctrl-C abort";
+
+/// Whether the full-screen exam can run: `tui.full_screen` is not turned off and both stdin and
+/// stdout are terminals.
+pub fn available(ctx: &ExamContext) -> bool {
+    ctx.policy.tui.full_screen.unwrap_or(true)
+        && std::io::stdin().is_terminal()
+        && std::io::stdout().is_terminal()
+}
+
+/// Runs the full-screen exam: a question list, an answer editor and a diff pane, then a review
+/// of every answer. Returns the answers, with the time spent on each question on the editing
+/// screen, once they are submitted. With a `session`, editing starts from its drafts and times
+/// and saves to it as answers are typed.
+pub fn run(exam: &Exam, ctx: &ExamContext, session: Option<&mut ExamSession>) -> Result<Answers> {
+    let mut terminal = ratatui::try_init()?;
+    let result = App::new(exam, ctx, session).run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    List,
+    Answer,
+    Diff,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Screen {
+    Edit,
+    Review,
+}
+
+struct App<'a> {
+    exam: &'a Exam,
+    ctx: &'a ExamContext,
+    session: Option<&'a mut ExamSession>,
+    answers: Vec<TextArea>,
+    /// Seconds spent on each question, and when they were last added to.
+    seconds: Vec<f64>,
+    clock: Instant,
+    selected: usize,
+    focus: Focus,
+    screen: Screen,
+    full_diff: bool,
+    diff_scroll: u16,
+    review_scroll: u16,
+    /// Precheck issues found when the review screen was opened.
+    issues: Vec<Issue>,
+}
+
+impl<'a> App<'a> {
+    fn new(exam: &'a Exam, ctx: &'a ExamContext, session: Option<&'a mut ExamSession>) -> Self {
+        let answers = exam
+            .questions
+            .iter()
+            .map(|q| match session.as_deref().and_then(|s| s.draft(&q.id)) {
+                Some(draft) => TextArea::from_text(draft),
+                None => TextArea::default(),
+            })
+            .collect();
+        let seconds = exam
+            .questions
+            .iter()
+            .map(|q| {
+                session
+                    .as_deref()
+                    .and_then(|s| s.answer_seconds.get(&q.id).copied())
+                    .unwrap_or(0.0)
+            })
+            .collect();
+        Self {
+            exam,
+            ctx,
+            session,
+            answers,
+            seconds,
+            clock: Instant::now(),
+            selected: 0,
+            focus: Focus::Answer,
+            screen: Screen::Edit,
+            full_diff: false,
+            diff_scroll: 0,
+            review_scroll: 0,
+            issues: Vec::new(),
+        }
+    }
+
+    fn run(mut self, terminal: &mut DefaultTerminal) -> Result<Answers> {
+        loop {
+            terminal.draw(|f| self.draw(f))?;
+            let idle = !event::poll(IDLE_SAVE)?;
+            self.tick();
+            if idle {
+                if let Some(session) = self.session.as_deref_mut() {
+                    session.flush();
+                }
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                if let Some(session) = self.session.as_deref_mut() {
+                    session.flush();
+                }
+                return Err(anyhow!("exam aborted"));
+            }
+            let submitted = match self.screen {
+                Screen::Edit => {
+                    self.on_edit_key(key);
+                    false
+                }
+                Screen::Review => self.on_review_key(key),
+            };
+            if let Some(session) = self.session.as_deref_mut() {
+                let q = &self.exam.questions[self.selected];
+                session.record(&q.id, self.answers[self.selected].text().trim_end());
+            }
+            if submitted {
+                let mut answers = Answers::new(
+                    self.exam
+                        .questions
+                        .iter()
+                        .zip(&self.answers)
+                        .map(|(q, a)| (q.id.clone(), a.text().trim_end().to_string()))
+                        .collect(),
+                );
+                answers.answer_seconds = self
+                    .exam
+                    .questions
+                    .iter()
+                    .zip(&self.seconds)
+                    .map(|(q, s)| (q.id.clone(), *s))
+                    .collect();
+                return Ok(answers);
+            }
+        }
+    }
+
+    /// Adds the time since the last tick to the question being edited; time on the review
+    /// screen counts for none.
+    fn tick(&mut self) {
+        let elapsed = self.clock.elapsed().as_secs_f64();
+        self.clock = Instant::now();
+        if self.screen != Screen::Edit {
+            return;
+        }
+        self.seconds[self.selected] += elapsed;
+        if let Some(session) = self.session.as_deref_mut() {
+            session.add_seconds(&self.exam.questions[self.selected].id, elapsed);
+        }
+    }
+
+    fn question(&self) -> &ExamQuestion {
+        &self.exam.questions[self.selected]
+    }
+
+    fn select(&mut self, index: usize) {
+        self.selected = index.min(self.exam.questions.len().saturating_sub(1));
+        self.diff_scroll = 0;
+    }
+
+    fn on_edit_key(&mut self, key: KeyEvent) {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('s') if ctrl => {
+                self.screen = Screen::Review;
+                self.review_scroll = 0;
+                let answers = self
+                    .exam
+                    .questions
+                    .iter()
+                    .zip(&self.answers)
+                    .map(|(q, a)| (q.id.clone(), a.text()))
+                    .collect();
+                self.issues = crate::precheck::check(&self.ctx.policy, self.exam, &answers);
+                return;
+            }
+            KeyCode::Char('n') if ctrl => return self.select(self.selected + 1),
+            KeyCode::Char('p') if ctrl => return self.select(self.selected.saturating_sub(1)),
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    Focus::List => Focus::Answer,
+                    Focus::Answer => Focus::Diff,
+                    Focus::Diff => Focus::List,
+                };
+                return;
+            }
+            KeyCode::BackTab => {
+                self.focus = match self.focus {
+                    Focus::List => Focus::Diff,
+                    Focus::Answer => Focus::List,
+                    Focus::Diff => Focus::Answer,
+                };
+                return;
+            }
+            _ => {}
+        }
+        match self.focus {
+            Focus::List => match key.code {
+                KeyCode::Up | KeyCode::Char('k') => self.select(self.selected.saturating_sub(1)),
+                KeyCode::Down | KeyCode::Char('j') => self.select(self.selected + 1),
+                KeyCode::Enter => self.focus = Focus::Answer,
+                KeyCode::Char('d') => self.full_diff = !self.full_diff,
+                _ => {}
+            },
+            Focus::Diff => match key.code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.diff_scroll = self.diff_scroll.saturating_sub(1)
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.diff_scroll = self.diff_scroll.saturating_add(1)
+                }
+                KeyCode::PageUp => self.diff_scroll = self.diff_scroll.saturating_sub(10),
+                KeyCode::PageDown => self.diff_scroll = self.diff_scroll.saturating_add(10),
+                KeyCode::Char('d') => {
+                    self.full_diff = !self.full_diff;
+                    self.diff_scroll = 0;
+                }
+                KeyCode::Esc => self.focus = Focus::Answer,
+                _ => {}
+            },
+            Focus::Answer => {
+                if key.code == KeyCode::Esc {
+                    self.focus = Focus::List;
+                } else {
+                    self.answers[self.selected].on_key(key);
+                }
+            }
+        }
+    }
+
+    /// Handles a key on the review screen; true when the answers are submitted.
+    fn on_review_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Enter => return true,
+            KeyCode::Esc => {
+                self.screen = Screen::Edit;
+                let flagged = crate::precheck::flagged(self.exam, &self.issues);
+                if let Some(first) = flagged.first() {
+                    let index = self.exam.questions.iter().position(|q| &q.id == first);
+                    self.select(index.unwrap_or(self.selected));
+                }
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.review_scroll = self.review_scroll.saturating_sub(1)
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.review_scroll = self.review_scroll.saturating_add(1)
+            }
+            KeyCode::PageUp => self.review_scroll = self.review_scroll.saturating_sub(10),
+            KeyCode::PageDown => self.review_scroll = self.review_scroll.saturating_add(10),
+            _ => {}
+        }
+        false
+    }
+
+    fn draw(&mut self, f: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).areas(f.area());
+        let keys = match self.screen {
+            Screen::Edit => {
+                self.draw_edit(f, main);
+                KEYS_EDIT
+            }
+            Screen::Review => {
+                self.draw_review(f, main);
+                if self.issues.is_empty() {
+                    KEYS_REVIEW
+                } else {
+                    KEYS_REVIEW_ISSUES
+                }
+            }
+        };
+        f.render_widget(
+            Paragraph::new(keys).style(Style::default().add_modifier(Modifier::DIM)),
+            status,
+        );
+    }
+
+    fn draw_edit(&self, f: &mut Frame, area: Rect) {
+        let [list_area, right] =
+            Layout::horizontal([Constraint::Percentage(28), Constraint::Percentage(72)])
+                .areas(area);
+        let show_diff = self.ctx.policy.tui.show_diff.unwrap_or(true);
+        let [question_area, answer_area, diff_area] = Layout::vertical([
+            Constraint::Length(self.question_height(right.width)),
+            Constraint::Min(6),
+            Constraint::Percentage(if show_diff { 45 } else { 0 }),
+        ])
+        .areas(right);
+
+        let items: Vec<ListItem> = self
+            .exam
+            .questions
+            .iter()
+            .zip(&self.answers)
+            .map(|(q, a)| {
+                let mark = if a.is_empty() { "  " } else { "✓ " };
+                ListItem::new(format!("{mark}{} [{}]", q.id, q.category))
+            })
+            .collect();
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        f.render_stateful_widget(
+            List::new(items)
+                .block(self.pane("Questions", Focus::List))
+                .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+            list_area,
+            &mut state,
+        );
+
+        f.render_widget(
+            Paragraph::new(self.question_lines())
+                .wrap(Wrap { trim: false })
+                .block(Block::default().borders(Borders::ALL).title(format!(
+                    "{}/{}",
+                    self.selected + 1,
+                    self.exam.questions.len()
+                ))),
+            question_area,
+        );
+
+        let answer = &self.answers[self.selected];
+        let block = self.pane("Answer", Focus::Answer);
+        let inner = block.inner(answer_area);
+        let mut title = String::new();
+        if self.ctx.policy.tui.live_feedback.unwrap_or(true) && self.question().choices.is_none() {
+            title = crate::answer_feedback::summarize(self.ctx, self.question(), &answer.text());
+        }
+        let (lines, cursor) = answer.wrapped(inner.width.max(1) as usize);
+        let scroll = cursor
+            .1
+            .saturating_sub(inner.height.saturating_sub(1) as usize);
+        f.render_widget(
+            Paragraph::new(lines.into_iter().map(Line::from).collect::<Vec<_>>())
+                .scroll((scroll as u16, 0))
+                .block(block.title_bottom(title)),
+            answer_area,
+        );
+        if self.focus == Focus::Answer {
+            f.set_cursor_position(Position::new(
+                inner.x + cursor.0 as u16,
+                inner.y + (cursor.1 - scroll) as u16,
+            ));
+        }
+
+        if show_diff {
+            let title = if self.full_diff {
+                "Diff (all)"
+            } else {
+                "Diff (this question)"
+            };
+            f.render_widget(
+                Paragraph::new(self.diff_lines())
+                    .scroll((self.diff_scroll, 0))
+                    .block(self.pane(title, Focus::Diff)),
+                diff_area,
+            );
+        }
+    }
+
+    fn draw_review(&self, f: &mut Frame, area: Rect) {
+        let mut lines = Vec::new();
+        let unanswered = self.answers.iter().filter(|a| a.is_empty()).count();
+        if !self.issues.is_empty() {
+            let warn = Style::default().fg(Color::Yellow);
+            lines.push(Line::styled("Before grading, check:", warn));
+            for issue in &self.issues {
+                lines.push(Line::styled(format!("  - {}", issue.message), warn));
+            }
+            lines.push(Line::default());
+        } else if unanswered > 0 {
+            lines.push(Line::styled(
+                format!("{unanswered} question(s) unanswered"),
+                Style::default().fg(Color::Yellow),
+            ));
+            lines.push(Line::default());
+        }
+        for (q, a) in self.exam.questions.iter().zip(&self.answers) {
+            lines.push(Line::styled(
+                format!("[{}] {}", q.category, q.prompt),
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+            if a.is_empty() {
+                lines.push(Line::styled(
+                    "  (no answer)",
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+            for line in &a.lines {
+                lines.push(Line::from(format!("  {line}")));
+            }
+            lines.push(Line::default());
+        }
+        f.render_widget(
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: false })
+                .scroll((self.review_scroll, 0))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title("Review your answers"),
+                ),
+            area,
+        );
+    }
+
+    fn pane(&self, title: &'static str, focus: Focus) -> Block<'static> {
+        let style = if self.focus == focus {
+            Style::default().fg(Color::Cyan)
+        } else {
+            Style::default()
+        };
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(style)
+            .title(title)
+    }
+
+    fn question_lines(&self) -> Vec<Line<'static>> {
+        let q = self.question();
+        let mut lines = vec![Line::styled(
+            format!("[{}] {}", q.category, q.prompt),
+            Style::default().add_modifier(Modifier::BOLD),
+        )];
+        if let Some(choices) = q.choices.as_ref().filter(|c| !c.is_empty()) {
+            for (idx, choice) in choices.iter().enumerate() {
+                let letter = (b'A' + idx as u8) as char;
+                lines.push(Line::from(format!("  {letter}) {choice}")));
+            }
+            lines.push(Line::styled(
+                "(answer with the letter)",
+                Style::default().add_modifier(Modifier::DIM),
+            ));
+        } else if q.is_evidence() {
+            lines.push(Line::styled(
+                "(cite a line as path:line, a range as path:start-end, or a hunk as path @@ -a,b +c,d @@)",
+                Style::default().add_modifier(Modifier::DIM),
+            ));
+        }
+        lines
+    }
+
+    /// Rows the question pane needs at `width`, borders included.
+    fn question_height(&self, width: u16) -> u16 {
+        let width = width.saturating_sub(2).max(1) as usize;
+        let rows: usize = self
+            .question_lines()
+            .iter()
+            .map(|l| l.width().max(1).div_ceil(width))
+            .sum();
+        (rows as u16 + 2).min(12)
+    }
+
+    fn diff_lines(&self) -> Vec<Line<'static>> {
+        let excerpt = if self.full_diff {
+            Vec::new()
+        } else {
+            crate::question_links::excerpt(&self.ctx.diff, self.question(), usize::MAX)
+        };
+        let lines: Vec<String> = if excerpt.is_empty() {
+            self.ctx.diff.lines().map(str::to_string).collect()
+        } else {
+            excerpt
+        };
+        lines
+            .into_iter()
+            .map(|l| {
+                let color = match l.chars().next() {
+                    Some('+') if !l.starts_with("+++") => Some(Color::Green),
+                    Some('-') if !l.starts_with("---") => Some(Color::Red),
+                    _ if l.starts_with("@@") || l.contains(" @@ ") => Some(Color::Cyan),
+                    _ => None,
+                };
+                match color {
+                    Some(c) => Line::from(Span::styled(l, Style::default().fg(c))),
+                    None => Line::from(l),
+                }
+            })
+            .collect()
+    }
+}
+
+/// A multi-line answer being edited, with the cursor as (line, char column).
+struct TextArea {
+    lines: Vec<String>,
+    row: usize,
+    col: usize,
+}
+
+impl Default for TextArea {
+    fn default() -> Self {
+        Self {
+            lines: vec![String::new()],
+            row: 0,
+            col: 0,
+        }
+    }
+}
+
+impl TextArea {
+    /// A text area holding `text`, with the cursor at its end.
+    fn from_text(text: &str) -> Self {
+        let lines: Vec<String> = text.lines().map(str::to_string).collect();
+        if lines.is_empty() {
+            return Self::default();
+        }
+        let row = lines.len() - 1;
+        let col = lines[row].chars().count();
+        Self { lines, row, col }
+    }
+
+    fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    fn is_empty(&self) -> bool {
+        self.lines.iter().all(|l| l.trim().is_empty())
+    }
+
+    fn byte_index(line: &str, col: usize) -> usize {
+        line.char_indices()
+            .nth(col)
+            .map(|(i, _)| i)
+            .unwrap_or(line.len())
+    }
+
+    fn line_len(&self, row: usize) -> usize {
+        self.lines[row].chars().count()
+    }
+
+    fn on_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char(c) => {
+                let at = Self::byte_index(&self.lines[self.row], self.col);
+                self.lines[self.row].insert(at, c);
+                self.col += 1;
+            }
+            KeyCode::Enter => {
+                let at = Self::byte_index(&self.lines[self.row], self.col);
+                let rest = self.lines[self.row].split_off(at);
+                self.row += 1;
+                self.lines.insert(self.row, rest);
+                self.col = 0;
+            }
+            KeyCode::Backspace => {
+                if self.col > 0 {
+                    let at = Self::byte_index(&self.lines[self.row], self.col - 1);
+                    self.lines[self.row].remove(at);
+                    self.col -= 1;
+                } else if self.row > 0 {
+                    let line = self.lines.remove(self.row);
+                    self.row -= 1;
+                    self.col = self.line_len(self.row);
+                    self.lines[self.row].push_str(&line);
+                }
+            }
+            KeyCode::Delete => {
+                if self.col < self.line_len(self.row) {
+                    let at = Self::byte_index(&self.lines[self.row], self.col);
+                    self.lines[self.row].remove(at);
+                } else if self.row + 1 < self.lines.len() {
+                    let next = self.lines.remove(self.row + 1);
+                    self.lines[self.row].push_str(&next);
+                }
+            }
+            KeyCode::Left => {
+                if self.col > 0 {
+                    self.col -= 1;
+                } else if self.row > 0 {
+                    self.row -= 1;
+                    self.col = self.line_len(self.row);
+                }
+            }
+            KeyCode::Right => {
+                if self.col < self.line_len(self.row) {
+                    self.col += 1;
+                } else if self.row + 1 < self.lines.len() {
+                    self.row += 1;
+                    self.col = 0;
+                }
+            }
+            KeyCode::Up if self.row > 0 => {
+                self.row -= 1;
+                self.col = self.col.min(self.line_len(self.row));
+            }
+            KeyCode::Down if self.row + 1 < self.lines.len() => {
+                self.row += 1;
+                self.col = self.col.min(self.line_len(self.row));
+            }
+            KeyCode::Home => self.col = 0,
+            KeyCode::End => self.col = self.line_len(self.row),
+            _ => {}
+        }
+    }
+
+    /// The text hard-wrapped at `width` columns, and where the cursor lands in it (column, row).
+    fn wrapped(&self, width: usize) -> (Vec<String>, (usize, usize)) {
+        let mut out = Vec::new();
+        let mut cursor = (0, 0);
+        for (row, line) in self.lines.iter().enumerate() {
+            let chars: Vec<char> = line.chars().collect();
+            if row == self.row {
+                // A cursor just past a full row moves to the start of the next one.
+                cursor = (self.col % width, out.len() + self.col / width);
+            }
+            if chars.is_empty() {
+                out.push(String::new());
+            }
+            for chunk in chars.chunks(width) {
+                out.push(chunk.iter().collect());
+            }
+            if row == self.row
+                && self.col == chars.len()
+                && !chars.is_empty()
+                && self.col.is_multiple_of(width)
+            {
+                out.push(String::new());
+            }
+        }
+        (out, cursor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use super::*;
+    use crate::config::Policy;
+    use crate::git::{Git, GitRepo};
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn ctrl(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    fn typed(text: &str) -> TextArea {
+        let mut area = TextArea::default();
+        for c in text.chars() {
+            area.on_key(match c {
+                '\n' => key(KeyCode::Enter),
+                c => key(KeyCode::Char(c)),
+            });
+        }
+        area
+    }
+
+    #[test]
+    fn editing_counts_characters_not_bytes() {
+        let mut area = typed("héllo wörld");
+        assert_eq!(area.col, 11);
+        for _ in 0..5 {
+            area.on_key(key(KeyCode::Left));
+        }
+        area.on_key(key(KeyCode::Backspace));
+        area.on_key(key(KeyCode::Char('_')));
+        assert_eq!(area.text(), "héllo_wörld");
+        area.on_key(key(KeyCode::Delete));
+        assert_eq!(area.text(), "héllo_örld");
+        area.on_key(key(KeyCode::End));
+        area.on_key(key(KeyCode::Char('✓')));
+        assert_eq!(area.text(), "héllo_örld✓");
+        assert_eq!(area.col, 11);
+    }
+
+    #[test]
+    fn cursor_moves_and_joins_across_lines() {
+        let mut area = typed("first line\nab\nthird");
+        assert_eq!((area.row, area.col), (2, 5));
+        // Up clamps the column to the shorter line; Down keeps it.
+        area.on_key(key(KeyCode::Up));
+        assert_eq!((area.row, area.col), (1, 2));
+        area.on_key(key(KeyCode::Up));
+        assert_eq!((area.row, area.col), (0, 2));
+        area.on_key(key(KeyCode::Down));
+        assert_eq!((area.row, area.col), (1, 2));
+        // Right at the end of a line goes to the start of the next, Left comes back.
+        area.on_key(key(KeyCode::Right));
+        assert_eq!((area.row, area.col), (2, 0));
+        area.on_key(key(KeyCode::Left));
+        assert_eq!((area.row, area.col), (1, 2));
+        // Backspace at the start of a line joins it to the one above.
+        area.on_key(key(KeyCode::Down));
+        area.on_key(key(KeyCode::Home));
+        area.on_key(key(KeyCode::Backspace));
+        assert_eq!(area.text(), "first line\nabthird");
+        assert_eq!((area.row, area.col), (1, 2));
+        // Enter splits the line at the cursor.
+        area.on_key(key(KeyCode::Enter));
+        assert_eq!(area.text(), "first line\nab\nthird");
+        assert_eq!((area.row, area.col), (2, 0));
+        // Delete at the end of a line pulls the next one up.
+        area.on_key(key(KeyCode::Up));
+        area.on_key(key(KeyCode::End));
+        area.on_key(key(KeyCode::Delete));
+        assert_eq!(area.text(), "first line\nabthird");
+    }
+
+    #[test]
+    fn from_text_puts_the_cursor_at_the_end() {
+        let area = TextArea::from_text("one\ntwö");
+        assert_eq!((area.row, area.col), (1, 3));
+        assert!(TextArea::from_text("").is_empty());
+        assert!(TextArea::from_text("  \n ").is_empty());
+    }
+
+    #[test]
+    fn wrapped_splits_at_the_width_and_places_the_cursor() {
+        let area = typed("abcdefgh\n\nxy");
+        let (lines, cursor) = area.wrapped(3);
+        assert_eq!(lines, ["abc", "def", "gh", "", "xy"]);
+        assert_eq!(cursor, (2, 4));
+
+        // Wide rows wrap by characters, not bytes.
+        let mut area = typed("ééééé");
+        area.on_key(key(KeyCode::Home));
+        area.on_key(key(KeyCode::Right));
+        area.on_key(key(KeyCode::Right));
+        area.on_key(key(KeyCode::Right));
+        let (lines, cursor) = area.wrapped(2);
+        assert_eq!(lines, ["éé", "éé", "é"]);
+        assert_eq!(cursor, (1, 1));
+
+        // A cursor just past a full row starts the next one.
+        let (lines, cursor) = typed("abcd").wrapped(2);
+        assert_eq!(lines, ["ab", "cd", ""]);
+        assert_eq!(cursor, (0, 2));
+    }
+
+    fn question(id: &str, category: &str) -> ExamQuestion {
+        ExamQuestion {
+            id: id.to_string(),
+            category: category.to_string(),
+            prompt: format!("{id}?"),
+            choices: None,
+            related_paths: Vec::new(),
+            related_hunks: Vec::new(),
+            correct_choice: None,
+            kind: None,
+        }
+    }
+
+    fn context() -> ExamContext {
+        let dir = tempfile::tempdir().unwrap();
+        let status = Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(dir.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let git = Git::new(GitRepo::open(dir.path()).unwrap());
+        ExamContext::new(
+            &git,
+            "0".repeat(40),
+            "",
+            Vec::new(),
+            Vec::new(),
+            &Policy::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn review_flags_weak_answers_and_submits_on_enter() {
+        let exam = Exam {
+            protocol_version: "aigit/0.1".to_string(),
+            questions: vec![question("why", "intent"), question("undo", "rollback")],
+            adaptation: None,
+            quality: None,
+            tier: None,
+        };
+        let ctx = context();
+        let mut app = App::new(&exam, &ctx, None);
+        for c in "It seeds the fixture the hello check reads on startup.".chars() {
+            app.on_edit_key(key(KeyCode::Char(c)));
+        }
+        app.on_edit_key(ctrl('n'));
+        assert_eq!(app.selected, 1);
+        app.on_edit_key(ctrl('n'));
+        assert_eq!(app.selected, 1, "the last question stays selected");
+
+        // Ctrl-S opens the review, which flags the empty answer.
+        app.on_edit_key(ctrl('p'));
+        app.on_edit_key(ctrl('s'));
+        assert!(app.screen == Screen::Review);
+        assert_eq!(crate::precheck::flagged(&exam, &app.issues), ["undo"]);
+        app.on_review_key(key(KeyCode::Down));
+        assert_eq!(app.review_scroll, 1);
+
+        // Esc goes back to editing the first flagged answer.
+        assert!(!app.on_review_key(key(KeyCode::Esc)));
+        assert!(app.screen == Screen::Edit);
+        assert_eq!(app.selected, 1);
+        for c in "Revert the commit; nothing else depends on the fixture.".chars() {
+            app.on_edit_key(key(KeyCode::Char(c)));
+        }
+        app.on_edit_key(ctrl('s'));
+        assert!(app.issues.is_empty());
+        assert_eq!(app.review_scroll, 0);
+        assert!(app.on_review_key(key(KeyCode::Enter)));
+    }
+
+    #[test]
+    fn tab_cycles_focus_and_esc_leaves_the_answer() {
+        let exam = Exam {
+            protocol_version: "aigit/0.1".to_string(),
+            questions: vec![question("why", "intent"), question("undo", "rollback")],
+            adaptation: None,
+            quality: None,
+            tier: None,
+        };
+        let ctx = context();
+        let mut app = App::new(&exam, &ctx, None);
+        assert!(app.focus == Focus::Answer);
+        app.on_edit_key(key(KeyCode::Tab));
+        assert!(app.focus == Focus::Diff);
+        app.on_edit_key(key(KeyCode::Down));
+        assert_eq!(app.diff_scroll, 1);
+        app.on_edit_key(key(KeyCode::Tab));
+        assert!(app.focus == Focus::List);
+        // In the list, j/k pick the question instead of typing.
+        app.on_edit_key(key(KeyCode::Char('j')));
+        assert_eq!(app.selected, 1);
+        assert_eq!(app.diff_scroll, 0);
+        assert!(app.answers[1].is_empty());
+        app.on_edit_key(key(KeyCode::BackTab));
+        assert!(app.focus == Focus::Diff);
+        app.on_edit_key(key(KeyCode::BackTab));
+        assert!(app.focus == Focus::Answer);
+        app.on_edit_key(key(KeyCode::Esc));
+        assert!(app.focus == Focus::List);
+    }
+}
@@ -1,28 +1,79 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
 use crate::config::Policy;
-use crate::codex_cli::CodexCliRunner;
+use crate::anthropic::AnthropicClient;
+use crate::claude_cli::ClaudeCliRunner;
+use crate::codex_cli::{exam_schema_json, score_schema_json, CodexCliRunner};
+use crate::context_manifest::ContextManifest;
+use crate::diff_stats::DiffStats;
+use crate::exam_quality::ExamQuality;
+use crate::exec::{ExecRequest, ExecRunner};
+use crate::http_api::HttpApiClient;
+use crate::ollama::{OllamaClient, Unreachable};
 use crate::git::Git;
+use crate::lfs::LfsChange;
+use crate::api_surface::ApiChange;
+use crate::ci_pipeline::CiChange;
+use crate::config_changes::ConfigSignal;
+use crate::memory_safety::MemorySafetySignal;
+use crate::performance::PerfSignal;
+use crate::rate_limit::{Permit, RateLimiter};
 use crate::redact::RedactionHit;
-use crate::transcript::{Answers, Score};
-
-const KEYWORDS_RISK: &[&str] = &["risk", "break", "fail", "regress", "error", "panic"];
-const KEYWORDS_TESTING: &[&str] = &["test", "cargo test", "unit", "integration", "ci"];
-const KEYWORDS_ROLLBACK: &[&str] = &["revert", "rollback", "backout", "feature flag", "mitigate"];
-const KEYWORDS_SECURITY: &[&str] = &["auth", "authz", "pii", "secret", "token", "key", "encrypt"];
-const KEYWORDS_DEFAULT: &[&str] = &["file", "module", "function", "line"];
+use crate::response_cache;
+use crate::risk::{RiskAssessment, RiskLevel};
+use crate::symbols::ChangedSymbol;
+use crate::tokenizer::Tokenizer;
+use crate::transcript::{
+    Answers, HallucinationFlag, Normalization, Note, NoteCode, QuestionScore, Score,
+    ScoreConfidence, ScoreInterval,
+};
 
 #[derive(Debug, Clone)]
 pub struct ExamContext {
     pub repo_id: String,
     pub workdir: std::path::PathBuf,
+    /// Where aigit keeps its caches and state for this repository.
+    pub git_dir: std::path::PathBuf,
     pub diff_patch_id: String,
     #[allow(dead_code)]
     pub diff: String,
     pub changed_files: Vec<String>,
+    /// Size and areas of the full (untruncated) diff.
+    pub stats: DiffStats,
+    pub branch: Option<String>,
     pub redactions: Vec<RedactionHit>,
-    #[allow(dead_code)]
+    pub lfs_changes: Vec<LfsChange>,
+    /// Functions, types and modules the full diff defines or edits.
+    pub changed_symbols: Vec<ChangedSymbol>,
+    /// Unsafe code, `static mut`s, locks and atomics the full diff adds to Rust files.
+    pub memory_safety: Vec<MemorySafetySignal>,
+    /// Hot paths, nested loops and complexity notes the full diff touches or adds.
+    pub performance: Vec<PerfSignal>,
+    /// Public Rust items the full diff adds, removes or changes.
+    pub api_changes: Vec<ApiChange>,
+    /// Configuration files, environment variable reads and feature flags the full diff touches.
+    pub config_changes: Vec<ConfigSignal>,
+    /// CI pipeline files the full diff edits.
+    pub ci_changes: Vec<CiChange>,
+    /// Paths outside the sparse-checkout cone are absent from `workdir`.
+    pub sparse_checkout: bool,
+    /// Categories to probe with extra questions (from the committer's profile).
+    pub focus_categories: Vec<String>,
+    /// Message of the commit being examined, when known; answers copied from it are flagged.
+    pub commit_message: Option<String>,
+    /// Issue or pull request description the change implements (redacted), so questions can
+    /// probe whether the diff does what it declares.
+    pub work_item: Option<String>,
+    /// Heuristic risk of the change, assessed before the exam is written.
+    pub risk: Option<RiskAssessment>,
+    pub budget: TokenBudget,
+    /// What of the change the context holds; recorded in the transcript.
+    pub context_manifest: ContextManifest,
     pub policy: Policy,
 }
 
@@ -35,27 +86,137 @@ impl ExamContext {
         redactions: Vec<RedactionHit>,
         policy: &Policy,
     ) -> Result<Self> {
-        let repo_id = git
-            .remote_fingerprint()?
-            .unwrap_or_else(|| git.repo.workdir.display().to_string());
-        let mut diff = diff_redacted.to_string();
-        let max_chars = policy.max_context_chars();
-        if diff.len() > max_chars {
-            diff.truncate(max_chars);
-            diff.push_str("\n\n[aigit: diff truncated]\n");
-        }
+        let repo_id = crate::repo_id::resolve(git, policy)?;
+        let stats = DiffStats::new(diff_redacted, &changed_files);
+        let lfs_dir = git.repo.git_dir.join("lfs").join("objects");
+        let (diff, lfs_changes) = crate::lfs::summarize_pointers(
+            diff_redacted,
+            policy
+                .lfs
+                .include_metadata
+                .unwrap_or(false)
+                .then_some(lfs_dir.as_path()),
+        );
+        let changed_symbols = crate::symbols::extract(diff_redacted, &|id| git.blob(id).ok());
+        let memory_safety = crate::memory_safety::detect(diff_redacted);
+        let performance =
+            crate::performance::detect(&policy.perf_paths, diff_redacted, &changed_files);
+        let api_changes = crate::api_surface::detect(diff_redacted);
+        let config_changes = crate::config_changes::detect(
+            &policy.config_changes.paths,
+            diff_redacted,
+            &changed_files,
+        );
+        let ci_changes =
+            crate::ci_pipeline::detect(&policy.ci_pipeline.paths, diff_redacted, &changed_files);
+        let budget = TokenBudget::for_policy(policy, &git.repo.workdir)?;
+        let diff = budget.fit_diff(&diff, budget.max_tokens);
+        let context_manifest = ContextManifest::build(
+            diff_redacted,
+            &diff,
+            &changed_files,
+            &lfs_changes,
+            redactions.len(),
+            &budget,
+        );
         Ok(Self {
             repo_id,
             workdir: git.repo.workdir.clone(),
+            git_dir: git.repo.git_dir.clone(),
             diff_patch_id,
             diff,
             changed_files,
+            stats,
+            branch: git.current_branch(),
             redactions,
+            lfs_changes,
+            changed_symbols,
+            memory_safety,
+            performance,
+            api_changes,
+            config_changes,
+            ci_changes,
+            sparse_checkout: git.clone_shape().sparse_checkout,
+            focus_categories: Vec::new(),
+            commit_message: None,
+            work_item: None,
+            risk: None,
+            budget,
+            context_manifest,
             policy: policy.clone(),
         })
     }
 }
 
+/// Answers with fewer words are noted as short by the built-in grader.
+pub const SHORT_ANSWER_WORDS: usize = 20;
+
+const TRUNCATION_MARKER: &str = "\n\n[aigit: diff truncated]\n";
+
+/// The model's context window in tokens and the tokenizer that measures against it. The diff is
+/// cut to fit the whole window when the context is built, and cut again while a prompt is
+/// assembled so the instructions, questions and answers around it fit too.
+#[derive(Debug, Clone)]
+pub struct TokenBudget {
+    tokenizer: Arc<Tokenizer>,
+    pub max_tokens: usize,
+}
+
+impl TokenBudget {
+    /// `tokenizer.bpe_file` (relative paths are resolved against `workdir`) or the estimate, and
+    /// the context limit for the configured provider/model.
+    pub fn for_policy(policy: &Policy, workdir: &Path) -> Result<Self> {
+        let tokenizer = match policy.tokenizer.bpe_file.as_deref() {
+            Some(file) => Tokenizer::from_bpe_file(&workdir.join(file))?,
+            None => Tokenizer::estimate(),
+        };
+        Ok(Self {
+            tokenizer: Arc::new(tokenizer),
+            max_tokens: policy.max_context_tokens(),
+        })
+    }
+
+    pub fn count(&self, text: &str) -> usize {
+        self.tokenizer.count(text)
+    }
+
+    /// "bpe" when counting with a rank file, else "estimate".
+    pub fn tokenizer_name(&self) -> &'static str {
+        if self.tokenizer.is_exact() {
+            "bpe"
+        } else {
+            "estimate"
+        }
+    }
+
+    /// `diff` cut to fit `max_tokens`, marker included, with `[aigit: diff truncated]` appended
+    /// when anything was cut. Every file keeps its header and the start of its first hunk (see
+    /// `diff_fit`); only when even that does not fit is the diff cut at a line break.
+    pub fn fit_diff(&self, diff: &str, max_tokens: usize) -> String {
+        if self.count(diff) <= max_tokens {
+            return diff.to_string();
+        }
+        let body = diff.strip_suffix(TRUNCATION_MARKER).unwrap_or(diff);
+        let room = max_tokens.saturating_sub(self.count(TRUNCATION_MARKER));
+        let kept = crate::diff_fit::fit(&self.tokenizer, body, room)
+            .filter(|kept| self.count(kept) <= room)
+            .unwrap_or_else(|| self.tokenizer.truncate(body, room).0.to_string());
+        format!("{}{TRUNCATION_MARKER}", kept.trim_end_matches('\n'))
+    }
+
+    /// `minimal` with per-file surrounding code from `enriched` wherever it fits (see
+    /// `diff_fit::with_context`).
+    pub fn with_context(&self, minimal: &str, enriched: &str) -> String {
+        crate::diff_fit::with_context(&self.tokenizer, minimal, enriched, self.max_tokens)
+    }
+
+    /// `ctx.diff` cut so that `before` + diff + `after` fits the window.
+    fn fit_prompt_diff(&self, diff: &str, before: &str, after: &str) -> String {
+        let overhead = self.count(before) + self.count(after);
+        self.fit_diff(diff, self.max_tokens.saturating_sub(overhead))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExamQuestion {
     pub id: String,
@@ -63,14 +224,66 @@ pub struct ExamQuestion {
     pub prompt: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub choices: Option<Vec<String>>,
+    /// Changed files the question is about.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related_paths: Vec<String>,
+    /// Hunks the question is about, each as `<path> <hunk header>` (e.g.
+    /// `src/lib.rs @@ -10,2 +10,3 @@`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related_hunks: Vec<String>,
+    /// Letter of the correct choice ("A", "B", ...) for multiple-choice questions with one right
+    /// answer. Answers are graded against it deterministically; it is never shown to the
+    /// candidate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correct_choice: Option<String>,
+    /// How the question is answered; `None` is the same as `open`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<QuestionKind>,
+}
+
+impl ExamQuestion {
+    /// Whether the answer is a cited location in the diff rather than free text.
+    pub fn is_evidence(&self) -> bool {
+        self.kind == Some(QuestionKind::Evidence)
+    }
+}
+
+/// Kinds of exam questions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuestionKind {
+    /// Free text, or a letter when the question has `choices`.
+    Open,
+    /// "Point to the line": the answer cites a `path:line` or hunk from the diff, and is graded
+    /// on whether that location exists in the diff (see `evidence`).
+    Evidence,
+}
+
+/// Records how an exam was tailored beyond the default question set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExamAdaptation {
+    /// What drove the adaptation (e.g. "author-profile").
+    pub source: String,
+    pub focus_categories: Vec<String>,
+    pub applied: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Exam {
     pub protocol_version: String,
     pub questions: Vec<ExamQuestion>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adaptation: Option<ExamAdaptation>,
+    /// Problems local validation found in a generated exam, and how they were fixed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality: Option<ExamQuality>,
+    /// The `exam.tiers` entry that sized the exam.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tier: Option<String>,
 }
 
+pub const EXAM_PACKET_SCHEMA: &str = "aigit-exam/0.1";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExamPacket {
     pub schema_version: String,
@@ -79,18 +292,43 @@ pub struct ExamPacket {
     pub changed_files: Vec<String>,
     pub diff_redacted: String,
     pub redactions: Vec<RedactionHit>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lfs_changes: Vec<LfsChange>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changed_symbols: Vec<ChangedSymbol>,
+    #[serde(default)]
+    pub diff_stats: DiffStats,
+    /// The issue or pull request description the exam was generated with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub work_item: Option<String>,
+    /// Language tag the exam is written in and answers are expected in (absent = English).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
     pub exam: Exam,
 }
 
 impl ExamPacket {
+    /// The packet handed to the committer: as `from_context`, without the answer key.
+    pub fn for_candidate(ctx: &ExamContext, mut exam: Exam) -> Self {
+        for q in &mut exam.questions {
+            q.correct_choice = None;
+        }
+        Self::from_context(ctx, exam)
+    }
+
     pub fn from_context(ctx: &ExamContext, exam: Exam) -> Self {
         Self {
-            schema_version: "aigit-exam/0.1".to_string(),
+            schema_version: EXAM_PACKET_SCHEMA.to_string(),
             repo_id: ctx.repo_id.clone(),
             diff_patch_id: ctx.diff_patch_id.clone(),
             changed_files: ctx.changed_files.clone(),
             diff_redacted: ctx.diff.clone(),
             redactions: ctx.redactions.clone(),
+            lfs_changes: ctx.lfs_changes.clone(),
+            changed_symbols: ctx.changed_symbols.clone(),
+            diff_stats: ctx.stats.clone(),
+            work_item: ctx.work_item.clone(),
+            language: ctx.policy.language.clone(),
             exam,
         }
     }
@@ -99,6 +337,25 @@ impl ExamPacket {
 pub trait Examiner {
     fn generate_exam(&self, ctx: &ExamContext) -> Result<Exam>;
     fn grade_exam(&self, ctx: &ExamContext, exam: &Exam, answers: &Answers) -> Result<Score>;
+
+    /// Cheap check that the provider is reachable, run before an interactive exam so a dead
+    /// grader is reported before anyone types answers.
+    fn health_check(&self, _timeout: Duration) -> Result<()> {
+        Ok(())
+    }
+
+    /// Harder follow-up questions for a retake, one for each of the `weak` questions, aimed at
+    /// what its graded answer missed. By default the fixed follow-up for its category.
+    fn generate_follow_ups(
+        &self,
+        ctx: &ExamContext,
+        exam: &Exam,
+        _answers: &Answers,
+        _score: &Score,
+        weak: &[String],
+    ) -> Result<Vec<ExamQuestion>> {
+        Ok(fixed_follow_ups(exam, weak, ctx.policy.language.as_deref()))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -111,19 +368,27 @@ impl StaticExaminer {
 }
 
 impl Examiner for StaticExaminer {
-    fn generate_exam(&self, _ctx: &ExamContext) -> Result<Exam> {
-        let questions = vec![
+    fn generate_exam(&self, ctx: &ExamContext) -> Result<Exam> {
+        let mut questions = vec![
             ExamQuestion {
                 id: "change_summary".to_string(),
                 category: "summary".to_string(),
                 prompt: "Summarize what changed (concrete files/modules) and why.".to_string(),
                 choices: None,
+                related_paths: Vec::new(),
+                related_hunks: Vec::new(),
+                correct_choice: None,
+                kind: None,
             },
             ExamQuestion {
                 id: "intent".to_string(),
                 category: "intent".to_string(),
                 prompt: "What user/business requirement does this satisfy?".to_string(),
                 choices: None,
+                related_paths: Vec::new(),
+                related_hunks: Vec::new(),
+                correct_choice: None,
+                kind: None,
             },
             ExamQuestion {
                 id: "invariants".to_string(),
@@ -131,6 +396,10 @@ impl Examiner for StaticExaminer {
                 prompt: "What assumptions does this change rely on? What invariants must remain true?"
                     .to_string(),
                 choices: None,
+                related_paths: Vec::new(),
+                related_hunks: Vec::new(),
+                correct_choice: None,
+                kind: None,
             },
             ExamQuestion {
                 id: "risk".to_string(),
@@ -138,12 +407,20 @@ impl Examiner for StaticExaminer {
                 prompt: "What could break, and where would issues surface first (blast radius)?"
                     .to_string(),
                 choices: None,
+                related_paths: Vec::new(),
+                related_hunks: Vec::new(),
+                correct_choice: None,
+                kind: None,
             },
             ExamQuestion {
                 id: "testing".to_string(),
                 category: "testing".to_string(),
                 prompt: "What tests were run? Which should exist? What coverage is missing?".to_string(),
                 choices: None,
+                related_paths: Vec::new(),
+                related_hunks: Vec::new(),
+                correct_choice: None,
+                kind: None,
             },
             ExamQuestion {
                 id: "rollback".to_string(),
@@ -151,6 +428,10 @@ impl Examiner for StaticExaminer {
                 prompt: "How would you rollback/revert/mitigate if this change causes problems?"
                     .to_string(),
                 choices: None,
+                related_paths: Vec::new(),
+                related_hunks: Vec::new(),
+                correct_choice: None,
+                kind: None,
             },
             ExamQuestion {
                 id: "alternatives".to_string(),
@@ -158,6 +439,10 @@ impl Examiner for StaticExaminer {
                 prompt: "What alternative approach was considered, and why was it rejected?"
                     .to_string(),
                 choices: None,
+                related_paths: Vec::new(),
+                related_hunks: Vec::new(),
+                correct_choice: None,
+                kind: None,
             },
             ExamQuestion {
                 id: "security_privacy".to_string(),
@@ -165,60 +450,173 @@ impl Examiner for StaticExaminer {
                 prompt: "Any security/privacy concerns (auth/authz, PII, secrets, data access)? If not relevant, explain why."
                     .to_string(),
                 choices: None,
+                related_paths: Vec::new(),
+                related_hunks: Vec::new(),
+                correct_choice: None,
+                kind: None,
             },
         ];
+        if crate::exam_tiers::test_lane(ctx) {
+            questions = crate::exam_tiers::test_lane_questions();
+        }
+        if ctx.policy.evidence.enabled.unwrap_or(false) {
+            questions.push(ExamQuestion {
+                id: "evidence".to_string(),
+                category: "evidence".to_string(),
+                prompt: "Which changed line would you point a reviewer to first? Cite it as path:line."
+                    .to_string(),
+                choices: None,
+                related_paths: Vec::new(),
+                related_hunks: Vec::new(),
+                correct_choice: None,
+                kind: Some(QuestionKind::Evidence),
+            });
+        }
+        let language = ctx.policy.language.as_deref();
+        for q in &mut questions {
+            if let Some(prompt) = crate::i18n::question(language, &q.id) {
+                q.prompt = prompt.to_string();
+            }
+        }
+        for cat in ctx.policy.applicable_categories(&ctx.changed_files) {
+            questions.push(ExamQuestion {
+                id: cat.id.clone(),
+                category: cat.id.clone(),
+                prompt: crate::prompt_template::render(&cat.question_prompt(), ctx),
+                choices: None,
+                related_paths: Vec::new(),
+                related_hunks: Vec::new(),
+                correct_choice: None,
+                kind: None,
+            });
+        }
+        for (id, q, files) in ctx.policy.bank_questions(&ctx.changed_files) {
+            questions.push(ExamQuestion {
+                id,
+                category: q.category.clone(),
+                prompt: crate::prompt_template::render_bank(&q.prompt, ctx, &files),
+                choices: None,
+                related_paths: files,
+                related_hunks: Vec::new(),
+                correct_choice: None,
+                kind: None,
+            });
+        }
+        for cat in &ctx.focus_categories {
+            questions.push(ExamQuestion {
+                id: format!("{cat}_followup"),
+                category: cat.clone(),
+                prompt: followup_prompt(cat, language),
+                choices: None,
+                related_paths: Vec::new(),
+                related_hunks: Vec::new(),
+                correct_choice: None,
+                kind: None,
+            });
+        }
+        if let Some(title) = ctx.work_item.as_deref().and_then(work_item_title) {
+            questions.push(ExamQuestion {
+                id: "work_item_alignment".to_string(),
+                category: "intent".to_string(),
+                prompt: crate::i18n::work_item_question(language, &title).unwrap_or_else(|| {
+                    format!(
+                        "The description of this work says: \"{title}\". Does the diff do everything it declares? Name anything it promises that is missing, and anything the diff changes that it does not mention."
+                    )
+                }),
+                choices: None,
+                related_paths: Vec::new(),
+                related_hunks: Vec::new(),
+                correct_choice: None,
+                kind: None,
+            });
+        }
+        let adaptation = (!ctx.focus_categories.is_empty()).then(|| ExamAdaptation {
+            source: "author-profile".to_string(),
+            focus_categories: ctx.focus_categories.clone(),
+            applied: "added one follow-up question per focus category".to_string(),
+        });
+        crate::exam_tiers::trim(ctx, &mut questions);
         Ok(Exam {
             protocol_version: "aigit/0.1".to_string(),
             questions,
+            adaptation,
+            quality: None,
+            tier: crate::exam_tiers::select(ctx).map(|(name, _)| name.to_string()),
         })
     }
 
     fn grade_exam(&self, ctx: &ExamContext, exam: &Exam, answers: &Answers) -> Result<Score> {
         let mut per_question = Vec::new();
         let mut hallucination_flags = Vec::new();
+        let mut symbols: Vec<&str> = Vec::new();
+        for s in &ctx.changed_symbols {
+            if !symbols.contains(&s.name.as_str()) {
+                symbols.push(&s.name);
+            }
+        }
 
         for q in &exam.questions {
             let answer = answers.get(&q.id).unwrap_or_default().trim().to_string();
             let mut notes = Vec::new();
             let completeness = if answer.is_empty() { 0.0 } else { 1.0 };
             if completeness == 0.0 {
-                notes.push("empty answer".to_string());
+                notes.push(Note::new(NoteCode::MissingSpecifics, "empty answer"));
             }
 
             let mentions_changed_file = ctx
                 .changed_files
                 .iter()
                 .any(|f| !f.is_empty() && answer.contains(f));
-            if completeness > 0.0 && !mentions_changed_file && !ctx.changed_files.is_empty() {
-                notes.push("does not mention any changed file path".to_string());
+            let mentions_changed_symbol = symbols
+                .iter()
+                .any(|s| crate::symbols::mentions(&answer, s));
+            if completeness > 0.0
+                && !mentions_changed_file
+                && !mentions_changed_symbol
+                && !ctx.changed_files.is_empty()
+            {
+                notes.push(Note::new(
+                    NoteCode::Boilerplate,
+                    "does not mention any changed file path or symbol",
+                ));
             }
 
-            let word_count = answer.split_whitespace().count();
-            if completeness > 0.0 && word_count < 20 {
-                notes.push(format!("answer is short ({word_count} words)"));
+            let word_count = crate::i18n::word_count(&answer);
+            if completeness > 0.0 && word_count < SHORT_ANSWER_WORDS {
+                notes.push(Note::new(
+                    NoteCode::MissingSpecifics,
+                    format!("answer is short ({word_count} words)"),
+                ));
             }
             let specificity = if answer.is_empty() {
                 0.0
-            } else if mentions_changed_file {
+            } else if mentions_changed_file || mentions_changed_symbol {
                 1.0
-            } else if word_count >= 20 {
+            } else if word_count >= SHORT_ANSWER_WORDS {
                 0.6
             } else {
                 0.3
             };
 
-            let expected_keywords = match q.category.as_str() {
-                "risk" => KEYWORDS_RISK,
-                "testing" => KEYWORDS_TESTING,
-                "rollback" => KEYWORDS_ROLLBACK,
-                "security" => KEYWORDS_SECURITY,
-                _ => KEYWORDS_DEFAULT,
-            };
-            let category_bonus = keyword_score(&answer, expected_keywords);
-            if completeness > 0.0 && category_bonus <= 0.2 {
-                notes.push(format!(
-                    "missing category signals (look for: {})",
-                    expected_keywords.join(", ")
+            let expected_keywords = ctx
+                .policy
+                .static_grader
+                .keywords_for(&q.category, ctx.policy.language.as_deref());
+            let mut category_relevance = keyword_score(&answer, &expected_keywords);
+            // Categories without their own vocabulary (summary, intent, ...) are on topic when
+            // they talk about what actually changed.
+            if !ctx.policy.static_grader.has_specific_keywords(&q.category)
+                && (mentions_changed_file || mentions_changed_symbol)
+            {
+                category_relevance = 1.0;
+            }
+            if completeness > 0.0 && category_relevance <= 0.2 {
+                notes.push(Note::new(
+                    NoteCode::MissingSpecifics,
+                    format!(
+                        "missing category signals (look for: {})",
+                        expected_keywords.join(", ")
+                    ),
                 ));
             }
 
@@ -226,35 +624,37 @@ impl Examiner for StaticExaminer {
                 // very conservative "hallucination": explicit file paths not in changed set
                 for mentioned in extract_file_like_tokens(&answer) {
                     if !ctx.changed_files.iter().any(|f| f == &mentioned) {
-                        hallucination_flags.push(format!(
-                            "{}: mentions file not in diff: {}",
-                            q.id, mentioned
-                        ));
+                        hallucination_flags.push(HallucinationFlag::file_not_in_diff(&q.id, &mentioned));
                     }
                 }
             }
 
-            let score = 0.4 * completeness + 0.4 * specificity + 0.2 * category_bonus;
-            per_question.push(crate::transcript::QuestionScore {
+            let score = 0.4 * completeness + 0.4 * specificity + 0.2 * category_relevance;
+            per_question.push(QuestionScore {
                 id: q.id.clone(),
                 category: q.category.clone(),
                 score,
                 completeness,
                 specificity,
+                category_relevance,
                 notes,
+                interval: None,
+                choice_correct: None,
+                evidence_valid: None,
             });
         }
 
-        let total_score = if per_question.is_empty() {
-            0.0
-        } else {
-            per_question.iter().map(|q| q.score).sum::<f64>() / (per_question.len() as f64)
-        };
+        let total_score = ctx.policy.weighted_total(&per_question);
 
         Ok(Score {
             total_score,
             per_question,
             hallucination_flags,
+            integrity_flags: Vec::new(),
+            confidence: None,
+            self_check: None,
+            grader: None,
+            normalization: None,
         })
     }
 }
@@ -274,101 +674,1060 @@ impl CodexCliExaminer {
 
 impl Examiner for CodexCliExaminer {
     fn generate_exam(&self, ctx: &ExamContext) -> Result<Exam> {
-        let prompt = build_codex_cli_generate_exam_prompt(ctx);
-        let raw = self
-            .runner
-            .run_json_generate_exam(&ctx.workdir, &prompt)?;
-
-        let mut exam: Exam = serde_json::from_str(&raw)?;
-        if exam.protocol_version.trim().is_empty() {
-            exam.protocol_version = "aigit/0.1".to_string();
-        }
-        // Basic sanity: unique ids.
-        let mut ids = std::collections::BTreeSet::new();
-        let mut mcq_count = 0usize;
-        for q in &exam.questions {
-            if q.id.trim().is_empty() {
-                return Err(anyhow::anyhow!("codex exam question id is empty"));
-            }
-            if !ids.insert(q.id.clone()) {
-                return Err(anyhow::anyhow!(
-                    "codex exam contains duplicate question id: {}",
-                    q.id
-                ));
-            }
-            if let Some(choices) = &q.choices {
-                if choices.len() == 4 {
-                    mcq_count += 1;
+        let prompt = build_generate_exam_prompt(ctx, true);
+        let parse = |raw: &str| parse_generated_exam(ctx, raw, "codex");
+        response_cache::call(
+            ctx,
+            "codex-cli",
+            &exam_schema_json(),
+            &prompt,
+            || {
+                self.runner.run_json_generate_exam(&ctx.workdir, &prompt, |raw| {
+                    parse(raw).map(|_| raw.to_string())
+                })
+            },
+            parse,
+        )
+    }
+
+    fn grade_exam(&self, ctx: &ExamContext, exam: &Exam, answers: &Answers) -> Result<Score> {
+        let prompt = build_judge_prompt(ctx, exam, answers, true);
+        let parse = |raw: &str| parse_judged_score(ctx, exam, answers, raw, "codex");
+        response_cache::call(
+            ctx,
+            "codex-cli",
+            &score_schema_json(),
+            &prompt,
+            || {
+                self.runner.run_json_judge(&ctx.workdir, &prompt, |raw| {
+                    parse(raw).map(|_| raw.to_string())
+                })
+            },
+            parse,
+        )
+    }
+
+    fn health_check(&self, _timeout: Duration) -> Result<()> {
+        self.runner.ping()
+    }
+
+    fn generate_follow_ups(
+        &self,
+        ctx: &ExamContext,
+        exam: &Exam,
+        answers: &Answers,
+        score: &Score,
+        weak: &[String],
+    ) -> Result<Vec<ExamQuestion>> {
+        let prompt = build_follow_up_prompt(ctx, exam, answers, score, weak, true);
+        let parse = |raw: &str| parse_follow_ups(raw, "codex", exam, weak);
+        response_cache::call(
+            ctx,
+            "codex-cli",
+            &exam_schema_json(),
+            &prompt,
+            || {
+                self.runner.run_json_generate_exam(&ctx.workdir, &prompt, |raw| {
+                    parse(raw).map(|_| raw.to_string())
+                })
+            },
+            parse,
+        )
+    }
+}
+
+/// Examiner backed by the `claude` CLI in print mode (`provider = "claude-cli"`). Like Codex, it
+/// runs in the repository with read-only tools.
+#[derive(Debug, Clone)]
+pub struct ClaudeCliExaminer {
+    runner: ClaudeCliRunner,
+}
+
+impl ClaudeCliExaminer {
+    pub fn new(policy: &Policy) -> Self {
+        Self {
+            runner: ClaudeCliRunner::from_policy(policy),
+        }
+    }
+}
+
+impl Examiner for ClaudeCliExaminer {
+    fn generate_exam(&self, ctx: &ExamContext) -> Result<Exam> {
+        let prompt = build_generate_exam_prompt(ctx, true);
+        response_cache::call(
+            ctx,
+            "claude-cli",
+            &exam_schema_json(),
+            &prompt,
+            || self.runner.run_json_generate_exam(&ctx.workdir, &prompt),
+            |raw| parse_generated_exam(ctx, raw, "claude"),
+        )
+    }
+
+    fn grade_exam(&self, ctx: &ExamContext, exam: &Exam, answers: &Answers) -> Result<Score> {
+        let prompt = build_judge_prompt(ctx, exam, answers, true);
+        response_cache::call(
+            ctx,
+            "claude-cli",
+            &score_schema_json(),
+            &prompt,
+            || self.runner.run_json_judge(&ctx.workdir, &prompt),
+            |raw| parse_judged_score(ctx, exam, answers, raw, "claude"),
+        )
+    }
+
+    fn health_check(&self, _timeout: Duration) -> Result<()> {
+        self.runner.ping()
+    }
+
+    fn generate_follow_ups(
+        &self,
+        ctx: &ExamContext,
+        exam: &Exam,
+        answers: &Answers,
+        score: &Score,
+        weak: &[String],
+    ) -> Result<Vec<ExamQuestion>> {
+        let prompt = build_follow_up_prompt(ctx, exam, answers, score, weak, true);
+        response_cache::call(
+            ctx,
+            "claude-cli",
+            &exam_schema_json(),
+            &prompt,
+            || self.runner.run_json_generate_exam(&ctx.workdir, &prompt),
+            |raw| parse_follow_ups(raw, "claude", exam, weak),
+        )
+    }
+}
+
+/// Examiner backed by any OpenAI-compatible chat completions endpoint (`provider = "http-api"`).
+#[derive(Debug, Clone)]
+pub struct HttpApiExaminer {
+    client: HttpApiClient,
+}
+
+impl HttpApiExaminer {
+    pub fn new(policy: &Policy) -> Self {
+        Self {
+            client: HttpApiClient::from_policy(policy),
+        }
+    }
+}
+
+impl Examiner for HttpApiExaminer {
+    fn generate_exam(&self, ctx: &ExamContext) -> Result<Exam> {
+        let prompt = build_generate_exam_prompt(ctx, false);
+        response_cache::call(
+            ctx,
+            "http-api",
+            &exam_schema_json(),
+            &prompt,
+            || self.client.run_json_generate_exam(&prompt),
+            |raw| parse_generated_exam(ctx, raw, "http_api"),
+        )
+    }
+
+    fn grade_exam(&self, ctx: &ExamContext, exam: &Exam, answers: &Answers) -> Result<Score> {
+        let prompt = build_judge_prompt(ctx, exam, answers, false);
+        response_cache::call(
+            ctx,
+            "http-api",
+            &score_schema_json(),
+            &prompt,
+            || self.client.run_json_judge(&prompt),
+            |raw| parse_judged_score(ctx, exam, answers, raw, "http_api"),
+        )
+    }
+
+    fn health_check(&self, timeout: Duration) -> Result<()> {
+        self.client.ping(timeout)
+    }
+
+    fn generate_follow_ups(
+        &self,
+        ctx: &ExamContext,
+        exam: &Exam,
+        answers: &Answers,
+        score: &Score,
+        weak: &[String],
+    ) -> Result<Vec<ExamQuestion>> {
+        let prompt = build_follow_up_prompt(ctx, exam, answers, score, weak, false);
+        response_cache::call(
+            ctx,
+            "http-api",
+            &exam_schema_json(),
+            &prompt,
+            || self.client.run_json_generate_exam(&prompt),
+            |raw| parse_follow_ups(raw, "http_api", exam, weak),
+        )
+    }
+}
+
+/// Examiner backed by Anthropic's Messages API (`provider = "anthropic"`).
+#[derive(Debug, Clone)]
+pub struct AnthropicExaminer {
+    client: AnthropicClient,
+}
+
+impl AnthropicExaminer {
+    pub fn new(policy: &Policy) -> Self {
+        Self {
+            client: AnthropicClient::from_policy(policy),
+        }
+    }
+}
+
+impl Examiner for AnthropicExaminer {
+    fn generate_exam(&self, ctx: &ExamContext) -> Result<Exam> {
+        let prompt = build_generate_exam_prompt(ctx, false);
+        response_cache::call(
+            ctx,
+            "anthropic",
+            &exam_schema_json(),
+            &prompt,
+            || self.client.run_json_generate_exam(&prompt),
+            |raw| parse_generated_exam(ctx, raw, "anthropic"),
+        )
+    }
+
+    fn grade_exam(&self, ctx: &ExamContext, exam: &Exam, answers: &Answers) -> Result<Score> {
+        let prompt = build_judge_prompt(ctx, exam, answers, false);
+        response_cache::call(
+            ctx,
+            "anthropic",
+            &score_schema_json(),
+            &prompt,
+            || self.client.run_json_judge(&prompt),
+            |raw| parse_judged_score(ctx, exam, answers, raw, "anthropic"),
+        )
+    }
+
+    fn health_check(&self, timeout: Duration) -> Result<()> {
+        self.client.ping(timeout)
+    }
+
+    fn generate_follow_ups(
+        &self,
+        ctx: &ExamContext,
+        exam: &Exam,
+        answers: &Answers,
+        score: &Score,
+        weak: &[String],
+    ) -> Result<Vec<ExamQuestion>> {
+        let prompt = build_follow_up_prompt(ctx, exam, answers, score, weak, false);
+        response_cache::call(
+            ctx,
+            "anthropic",
+            &exam_schema_json(),
+            &prompt,
+            || self.client.run_json_generate_exam(&prompt),
+            |raw| parse_follow_ups(raw, "anthropic", exam, weak),
+        )
+    }
+}
+
+/// Validates a generated exam locally (see `exam_quality`): asks the generator once more when the
+/// first exam has problems, then drops deficient questions and fills in missing categories.
+pub struct QualityCheckedExaminer {
+    inner: Box<dyn Examiner>,
+    regenerate: bool,
+}
+
+impl QualityCheckedExaminer {
+    pub fn new(inner: Box<dyn Examiner>, policy: &Policy) -> Self {
+        Self {
+            inner,
+            regenerate: policy.exam_quality.regenerate.unwrap_or(true),
+        }
+    }
+}
+
+impl Examiner for QualityCheckedExaminer {
+    fn generate_exam(&self, ctx: &ExamContext) -> Result<Exam> {
+        let mut exam = self.inner.generate_exam(ctx)?;
+        let mut issues = crate::exam_quality::check(ctx, &exam);
+        if issues.is_empty() {
+            return Ok(exam);
+        }
+        let mut regenerated = false;
+        if self.regenerate {
+            // A failed second attempt is not fatal; the first exam can still be patched.
+            if let Ok(second) = self.inner.generate_exam(ctx) {
+                let second_issues = crate::exam_quality::check(ctx, &second);
+                if second_issues.len() < issues.len() {
+                    exam = second;
+                    issues = second_issues;
                 }
+                regenerated = true;
             }
         }
-        if mcq_count < 3 {
-            return Err(anyhow::anyhow!(
-                "codex exam must include at least 3 multiple-choice questions with exactly 4 choices (A-D); got {}",
-                mcq_count
-            ));
+        if !issues.is_empty() {
+            crate::exam_quality::patch(ctx, &mut exam, &mut issues);
         }
+        if exam.questions.is_empty() {
+            return StaticExaminer::new().generate_exam(ctx);
+        }
+        exam.quality = Some(ExamQuality {
+            regenerated,
+            issues,
+        });
         Ok(exam)
     }
 
     fn grade_exam(&self, ctx: &ExamContext, exam: &Exam, answers: &Answers) -> Result<Score> {
-        let prompt = build_codex_cli_judge_prompt(ctx, exam, answers);
-        let raw = self
-            .runner
-            .run_json_judge(&ctx.workdir, &prompt)?;
-
-        let mut score: Score = serde_json::from_str(&raw)?;
-
-        // Validate that the response covers exactly the current exam questions.
-        let expected_ids: std::collections::BTreeSet<&str> =
-            exam.questions.iter().map(|q| q.id.as_str()).collect();
-        let got_ids: std::collections::BTreeSet<&str> =
-            score.per_question.iter().map(|q| q.id.as_str()).collect();
-        if expected_ids != got_ids {
-            return Err(anyhow::anyhow!(
-                "codex judge returned mismatched question ids (expected {:?}, got {:?})",
-                expected_ids,
-                got_ids
-            ));
-        }
+        self.inner.grade_exam(ctx, exam, answers)
+    }
 
-        // Defensive: clamp scores into [0,1] so policy checks behave.
-        score.total_score = clamp01(score.total_score);
-        for q in &mut score.per_question {
-            q.score = clamp01(q.score);
-            q.completeness = clamp01(q.completeness);
-            q.specificity = clamp01(q.specificity);
+    fn health_check(&self, timeout: Duration) -> Result<()> {
+        self.inner.health_check(timeout)
+    }
+
+    fn generate_follow_ups(
+        &self,
+        ctx: &ExamContext,
+        exam: &Exam,
+        answers: &Answers,
+        score: &Score,
+        weak: &[String],
+    ) -> Result<Vec<ExamQuestion>> {
+        self.inner.generate_follow_ups(ctx, exam, answers, score, weak)
+    }
+}
+
+/// Mixes backends: one examiner writes the exam, another grades it (`grader` in policy), e.g. LLM
+/// questions with deterministic static grading.
+pub struct HybridExaminer {
+    generator: Box<dyn Examiner>,
+    grader: Box<dyn Examiner>,
+}
+
+impl HybridExaminer {
+    pub fn new(generator: Box<dyn Examiner>, grader: Box<dyn Examiner>) -> Self {
+        Self { generator, grader }
+    }
+}
+
+impl Examiner for HybridExaminer {
+    fn generate_exam(&self, ctx: &ExamContext) -> Result<Exam> {
+        self.generator.generate_exam(ctx)
+    }
+
+    fn grade_exam(&self, ctx: &ExamContext, exam: &Exam, answers: &Answers) -> Result<Score> {
+        self.grader.grade_exam(ctx, exam, answers)
+    }
+
+    fn health_check(&self, timeout: Duration) -> Result<()> {
+        self.generator.health_check(timeout)?;
+        self.grader.health_check(timeout)
+    }
+
+    fn generate_follow_ups(
+        &self,
+        ctx: &ExamContext,
+        exam: &Exam,
+        answers: &Answers,
+        score: &Score,
+        weak: &[String],
+    ) -> Result<Vec<ExamQuestion>> {
+        self.generator
+            .generate_follow_ups(ctx, exam, answers, score, weak)
+    }
+}
+
+/// Wraps a provider's examiner so each call to it waits its turn under `[rate_limit]`.
+pub struct RateLimitedExaminer {
+    inner: Box<dyn Examiner>,
+    provider: String,
+}
+
+impl RateLimitedExaminer {
+    pub fn new(inner: Box<dyn Examiner>, provider: &str) -> Self {
+        Self {
+            inner,
+            provider: provider.to_string(),
         }
+    }
 
-        // Keep the existing conservative hallucination flags (file mentions not in changed set).
-        // Merge with the model-provided flags.
-        let mut conservative = Vec::new();
-        for q in &exam.questions {
-            let answer = answers.get(&q.id).unwrap_or_default().trim().to_string();
-            if answer.is_empty() {
-                continue;
+    fn permit(&self, ctx: &ExamContext, what: &str) -> Result<Option<Permit>> {
+        RateLimiter::from_policy(&ctx.policy, &ctx.workdir)
+            .map(|limiter| limiter.acquire(&format!("{} {what}", self.provider)))
+            .transpose()
+    }
+}
+
+impl Examiner for RateLimitedExaminer {
+    fn generate_exam(&self, ctx: &ExamContext) -> Result<Exam> {
+        let _permit = self.permit(ctx, "exam generation")?;
+        self.inner.generate_exam(ctx)
+    }
+
+    fn grade_exam(&self, ctx: &ExamContext, exam: &Exam, answers: &Answers) -> Result<Score> {
+        let _permit = self.permit(ctx, "grading")?;
+        self.inner.grade_exam(ctx, exam, answers)
+    }
+
+    fn health_check(&self, timeout: Duration) -> Result<()> {
+        self.inner.health_check(timeout)
+    }
+
+    fn generate_follow_ups(
+        &self,
+        ctx: &ExamContext,
+        exam: &Exam,
+        answers: &Answers,
+        score: &Score,
+        weak: &[String],
+    ) -> Result<Vec<ExamQuestion>> {
+        let _permit = self.permit(ctx, "follow-up questions")?;
+        self.inner.generate_follow_ups(ctx, exam, answers, score, weak)
+    }
+}
+
+/// Wraps another examiner and appends the self-check question (see `self_check`), which it grades
+/// itself; the inner examiner never sees it.
+pub struct SelfCheckExaminer {
+    inner: Box<dyn Examiner>,
+}
+
+impl SelfCheckExaminer {
+    pub fn new(inner: Box<dyn Examiner>) -> Self {
+        Self { inner }
+    }
+}
+
+impl Examiner for SelfCheckExaminer {
+    fn generate_exam(&self, ctx: &ExamContext) -> Result<Exam> {
+        let mut exam = self.inner.generate_exam(ctx)?;
+        exam.questions.retain(|q| q.id != crate::self_check::QUESTION_ID);
+        exam.questions.push(crate::self_check::build(ctx).question);
+        Ok(exam)
+    }
+
+    fn grade_exam(&self, ctx: &ExamContext, exam: &Exam, answers: &Answers) -> Result<Score> {
+        let Some(question) = exam
+            .questions
+            .iter()
+            .find(|q| q.id == crate::self_check::QUESTION_ID)
+        else {
+            return self.inner.grade_exam(ctx, exam, answers);
+        };
+        let mut rest = exam.clone();
+        rest.questions.retain(|q| q.id != question.id);
+        let mut score = self.inner.grade_exam(ctx, &rest, answers)?;
+
+        let (question_score, result) =
+            crate::self_check::grade(ctx, question, answers.get(&question.id).unwrap_or_default());
+        // Fold the self-check into the totals as one more question, weighted by its category.
+        let rest: f64 = score
+            .per_question
+            .iter()
+            .map(|q| ctx.policy.score_weight(&q.category))
+            .sum();
+        let weight = ctx.policy.score_weight(&question_score.category);
+        let fold = |total: f64| {
+            if rest + weight <= 0.0 {
+                total
+            } else {
+                (total * rest + question_score.score * weight) / (rest + weight)
             }
-            for mentioned in extract_file_like_tokens(&answer) {
-                if !ctx.changed_files.iter().any(|f| f == &mentioned) {
-                    conservative.push(format!(
-                        "{}: mentions file not in diff: {}",
-                        q.id, mentioned
-                    ));
+        };
+        score.total_score = fold(score.total_score);
+        if let Some(confidence) = &mut score.confidence {
+            confidence.total.low = fold(confidence.total.low);
+            confidence.total.high = fold(confidence.total.high);
+        }
+        score.per_question.push(question_score);
+        score.self_check = Some(result);
+        Ok(score)
+    }
+
+    fn health_check(&self, timeout: Duration) -> Result<()> {
+        self.inner.health_check(timeout)
+    }
+
+    fn generate_follow_ups(
+        &self,
+        ctx: &ExamContext,
+        exam: &Exam,
+        answers: &Answers,
+        score: &Score,
+        weak: &[String],
+    ) -> Result<Vec<ExamQuestion>> {
+        self.inner.generate_follow_ups(ctx, exam, answers, score, weak)
+    }
+}
+
+/// Wraps another examiner and grades multiple-choice questions that carry a `correct_choice`
+/// against it, and evidence questions against the diff: right is 1.0, wrong or unreadable is
+/// 0.0, whatever the inner grader said. The total moves by the same amount as the overridden
+/// questions. Answers copied from the commit message or from each other are recorded as
+/// integrity flags.
+pub struct AnswerKeyExaminer {
+    inner: Box<dyn Examiner>,
+}
+
+impl AnswerKeyExaminer {
+    pub fn new(inner: Box<dyn Examiner>) -> Self {
+        Self { inner }
+    }
+}
+
+impl Examiner for AnswerKeyExaminer {
+    fn generate_exam(&self, ctx: &ExamContext) -> Result<Exam> {
+        self.inner.generate_exam(ctx)
+    }
+
+    fn grade_exam(&self, ctx: &ExamContext, exam: &Exam, answers: &Answers) -> Result<Score> {
+        let mut score = self.inner.grade_exam(ctx, exam, answers)?;
+        crate::answer_key::apply(&ctx.policy, exam, answers, &mut score);
+        crate::evidence::apply(ctx, exam, answers, &mut score);
+        score.integrity_flags = crate::integrity::check(
+            &ctx.policy.integrity,
+            exam,
+            answers,
+            ctx.commit_message.as_deref(),
+        );
+        Ok(score)
+    }
+
+    fn health_check(&self, timeout: Duration) -> Result<()> {
+        self.inner.health_check(timeout)
+    }
+
+    fn generate_follow_ups(
+        &self,
+        ctx: &ExamContext,
+        exam: &Exam,
+        answers: &Answers,
+        score: &Score,
+        weak: &[String],
+    ) -> Result<Vec<ExamQuestion>> {
+        self.inner.generate_follow_ups(ctx, exam, answers, score, weak)
+    }
+}
+
+/// Wraps another examiner and grades each exam `grading.samples` times, averaging the scores and
+/// recording a confidence interval per question and for the total.
+pub struct SampledExaminer {
+    inner: Box<dyn Examiner>,
+    samples: u32,
+    level: f64,
+    z: f64,
+}
+
+impl SampledExaminer {
+    pub fn new(inner: Box<dyn Examiner>, policy: &Policy) -> Self {
+        Self {
+            inner,
+            samples: policy.grading.samples.unwrap_or(1).max(1),
+            level: policy.grading.confidence_level(),
+            // Validated when the policy was loaded.
+            z: policy.grading.z_score().unwrap_or(1.96),
+        }
+    }
+
+    fn interval(&self, values: &[f64]) -> (f64, ScoreInterval) {
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let variance = if values.len() > 1 {
+            values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+        let stddev = variance.sqrt();
+        let half = self.z * stddev / n.sqrt();
+        (
+            mean,
+            ScoreInterval {
+                low: clamp01(mean - half),
+                high: clamp01(mean + half),
+                stddev,
+            },
+        )
+    }
+}
+
+impl Examiner for SampledExaminer {
+    fn generate_exam(&self, ctx: &ExamContext) -> Result<Exam> {
+        self.inner.generate_exam(ctx)
+    }
+
+    fn grade_exam(&self, ctx: &ExamContext, exam: &Exam, answers: &Answers) -> Result<Score> {
+        let mut runs = Vec::with_capacity(self.samples as usize);
+        for _ in 0..self.samples {
+            runs.push(self.inner.grade_exam(ctx, exam, answers)?);
+        }
+        let mut merged = runs[0].clone();
+        for q in &mut merged.per_question {
+            let pick = |f: fn(&QuestionScore) -> f64| -> Vec<f64> {
+                runs.iter()
+                    .filter_map(|r| r.per_question.iter().find(|p| p.id == q.id).map(f))
+                    .collect()
+            };
+            let (score, interval) = self.interval(&pick(|p| p.score));
+            q.score = score;
+            q.interval = Some(interval);
+            q.completeness = mean(&pick(|p| p.completeness));
+            q.specificity = mean(&pick(|p| p.specificity));
+            q.category_relevance = mean(&pick(|p| p.category_relevance));
+            for r in &runs[1..] {
+                if let Some(p) = r.per_question.iter().find(|p| p.id == q.id) {
+                    for note in &p.notes {
+                        if !q.notes.contains(note) {
+                            q.notes.push(note.clone());
+                        }
+                    }
                 }
             }
         }
-        score.hallucination_flags.extend(conservative);
-        score.hallucination_flags.sort();
-        score.hallucination_flags.dedup();
+        let totals: Vec<f64> = runs.iter().map(|r| r.total_score).collect();
+        let (total, total_interval) = self.interval(&totals);
+        merged.total_score = total;
+        // A flag survives only if most samples raised it.
+        let mut flags: Vec<HallucinationFlag> = Vec::new();
+        for flag in runs.iter().flat_map(|r| r.hallucination_flags.iter()) {
+            let seen = runs
+                .iter()
+                .filter(|r| r.hallucination_flags.contains(flag))
+                .count();
+            if seen * 2 > runs.len() && !flags.contains(flag) {
+                flags.push(flag.clone());
+            }
+        }
+        merged.hallucination_flags = flags;
+        merged.confidence = Some(ScoreConfidence {
+            samples: self.samples,
+            level: self.level,
+            total: total_interval,
+        });
+        Ok(merged)
+    }
+
+    fn health_check(&self, timeout: Duration) -> Result<()> {
+        self.inner.health_check(timeout)
+    }
 
+    fn generate_follow_ups(
+        &self,
+        ctx: &ExamContext,
+        exam: &Exam,
+        answers: &Answers,
+        score: &Score,
+        weak: &[String],
+    ) -> Result<Vec<ExamQuestion>> {
+        self.inner.generate_follow_ups(ctx, exam, answers, score, weak)
+    }
+}
+
+/// Outermost wrapper: records who graded and, when `aigit calibrate` learned an offset for that
+/// grader, shifts the total score by it, so thresholds mean the same whichever model grades.
+pub struct NormalizedExaminer {
+    inner: Box<dyn Examiner>,
+    grader: String,
+    offset: Option<f64>,
+    /// Other graders are calibrated but this one is not.
+    uncalibrated: bool,
+}
+
+impl NormalizedExaminer {
+    pub fn new(inner: Box<dyn Examiner>, policy: &Policy) -> Self {
+        let offset = policy.grader_offset();
+        Self {
+            inner,
+            grader: policy.grader_identity(),
+            offset,
+            uncalibrated: offset.is_none() && !policy.calibration.learned.is_empty(),
+        }
+    }
+}
+
+impl Examiner for NormalizedExaminer {
+    fn generate_exam(&self, ctx: &ExamContext) -> Result<Exam> {
+        self.inner.generate_exam(ctx)
+    }
+
+    fn grade_exam(&self, ctx: &ExamContext, exam: &Exam, answers: &Answers) -> Result<Score> {
+        let mut score = self.inner.grade_exam(ctx, exam, answers)?;
+        score.grader = Some(self.grader.clone());
+        match self.offset {
+            Some(offset) => {
+                let raw_total = score.total_score;
+                score.total_score = clamp01(raw_total + offset);
+                if let Some(confidence) = &mut score.confidence {
+                    confidence.total.low = clamp01(confidence.total.low + offset);
+                    confidence.total.high = clamp01(confidence.total.high + offset);
+                }
+                score.normalization = Some(Normalization { offset, raw_total });
+            }
+            None if self.uncalibrated => eprintln!(
+                "aigit: warning: grader {} has no calibration offset, unlike the others in the suite; its scores are not normalized (run `aigit calibrate`)",
+                self.grader
+            ),
+            None => {}
+        }
         Ok(score)
     }
+
+    fn health_check(&self, timeout: Duration) -> Result<()> {
+        self.inner.health_check(timeout)
+    }
+
+    fn generate_follow_ups(
+        &self,
+        ctx: &ExamContext,
+        exam: &Exam,
+        answers: &Answers,
+        score: &Score,
+        weak: &[String],
+    ) -> Result<Vec<ExamQuestion>> {
+        self.inner.generate_follow_ups(ctx, exam, answers, score, weak)
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Examiner delegated to external commands (`provider = "exec"`): an [`ExecRequest`] goes to the
+/// command's stdin and an `Exam` or `Score` comes back on stdout. Unlike the LLM providers, the
+/// exam's shape (e.g. how many multiple-choice questions) is left to the command.
+#[derive(Debug, Clone)]
+pub struct ExecExaminer {
+    runner: ExecRunner,
+}
+
+impl ExecExaminer {
+    pub fn new(policy: &Policy) -> Self {
+        Self {
+            runner: ExecRunner::from_policy(policy),
+        }
+    }
+
+    fn request<'a>(
+        ctx: &'a ExamContext,
+        request: &'static str,
+        exam: Option<&Exam>,
+        answers: Option<&'a Answers>,
+    ) -> ExecRequest<'a> {
+        let exam = exam.cloned().unwrap_or_else(|| Exam {
+            protocol_version: "aigit/0.1".to_string(),
+            questions: Vec::new(),
+            adaptation: None,
+            quality: None,
+            tier: None,
+        });
+        ExecRequest {
+            schema_version: crate::exec::REQUEST_SCHEMA_VERSION,
+            request,
+            packet: ExamPacket::from_context(ctx, exam),
+            answers,
+            focus_categories: &ctx.focus_categories,
+        }
+    }
+}
+
+impl Examiner for ExecExaminer {
+    fn generate_exam(&self, ctx: &ExamContext) -> Result<Exam> {
+        let request = Self::request(ctx, "generate_exam", None, None);
+        response_cache::call(
+            ctx,
+            "exec",
+            &exam_schema_json(),
+            &serde_json::to_string(&request)?,
+            || self.runner.run_generate_exam(&ctx.workdir, &request),
+            |raw| parse_exam_json(raw, "exec"),
+        )
+    }
+
+    fn grade_exam(&self, ctx: &ExamContext, exam: &Exam, answers: &Answers) -> Result<Score> {
+        let request = Self::request(ctx, "grade_exam", Some(exam), Some(answers));
+        response_cache::call(
+            ctx,
+            "exec",
+            &score_schema_json(),
+            &serde_json::to_string(&request)?,
+            || self.runner.run_grade_exam(&ctx.workdir, &request),
+            |raw| parse_judged_score(ctx, exam, answers, raw, "exec"),
+        )
+    }
+
+    fn health_check(&self, _timeout: Duration) -> Result<()> {
+        self.runner.ping()
+    }
+}
+
+/// Examiner backed by a local Ollama server (`provider = "ollama"`). Falls back to
+/// [`StaticExaminer`] when the server cannot be reached, unless `ollama.allow_fallback = false`.
+#[derive(Debug, Clone)]
+pub struct OllamaExaminer {
+    client: OllamaClient,
+    allow_fallback: bool,
+}
+
+impl OllamaExaminer {
+    pub fn new(policy: &Policy) -> Self {
+        Self {
+            client: OllamaClient::from_policy(policy),
+            allow_fallback: policy.ollama.allow_fallback.unwrap_or(true),
+        }
+    }
+
+    fn fall_back(&self, err: anyhow::Error) -> Result<StaticExaminer> {
+        if !self.allow_fallback || err.downcast_ref::<Unreachable>().is_none() {
+            return Err(err);
+        }
+        eprintln!(
+            "aigit: {err}; falling back to the static examiner (set ollama.allow_fallback = false to fail instead)"
+        );
+        Ok(StaticExaminer::new())
+    }
+}
+
+impl Examiner for OllamaExaminer {
+    fn generate_exam(&self, ctx: &ExamContext) -> Result<Exam> {
+        let prompt = build_generate_exam_prompt(ctx, false);
+        match response_cache::call(
+            ctx,
+            "ollama",
+            &exam_schema_json(),
+            &prompt,
+            || self.client.run_json_generate_exam(&prompt),
+            |raw| parse_generated_exam(ctx, raw, "ollama"),
+        ) {
+            Ok(exam) => Ok(exam),
+            Err(err) => self.fall_back(err)?.generate_exam(ctx),
+        }
+    }
+
+    fn grade_exam(&self, ctx: &ExamContext, exam: &Exam, answers: &Answers) -> Result<Score> {
+        let prompt = build_judge_prompt(ctx, exam, answers, false);
+        match response_cache::call(
+            ctx,
+            "ollama",
+            &score_schema_json(),
+            &prompt,
+            || self.client.run_json_judge(&prompt),
+            |raw| parse_judged_score(ctx, exam, answers, raw, "ollama"),
+        ) {
+            Ok(score) => Ok(score),
+            Err(err) => self.fall_back(err)?.grade_exam(ctx, exam, answers),
+        }
+    }
+
+    fn health_check(&self, timeout: Duration) -> Result<()> {
+        // An unreachable server is fine when the static examiner will step in.
+        match self.client.ping(timeout) {
+            Err(err) if self.allow_fallback && err.downcast_ref::<Unreachable>().is_some() => Ok(()),
+            other => other,
+        }
+    }
+
+    fn generate_follow_ups(
+        &self,
+        ctx: &ExamContext,
+        exam: &Exam,
+        answers: &Answers,
+        score: &Score,
+        weak: &[String],
+    ) -> Result<Vec<ExamQuestion>> {
+        let prompt = build_follow_up_prompt(ctx, exam, answers, score, weak, false);
+        match response_cache::call(
+            ctx,
+            "ollama",
+            &exam_schema_json(),
+            &prompt,
+            || self.client.run_json_generate_exam(&prompt),
+            |raw| parse_follow_ups(raw, "ollama", exam, weak),
+        ) {
+            Ok(questions) => Ok(questions),
+            Err(err) => self.fall_back(err)?.generate_follow_ups(ctx, exam, answers, score, weak),
+        }
+    }
+}
+
+/// Parses and sanity-checks an exam produced by an LLM examiner (`source` names it in errors).
+fn parse_generated_exam(ctx: &ExamContext, raw: &str, source: &str) -> Result<Exam> {
+    let mut exam = parse_exam_json(raw, source)?;
+    let mcq_count = exam
+        .questions
+        .iter()
+        .filter(|q| q.choices.as_ref().is_some_and(|c| c.len() == 4))
+        .count();
+    if mcq_count < 3 {
+        return Err(anyhow::anyhow!(
+            "{source} exam must include at least 3 multiple-choice questions with exactly 4 choices (A-D); got {}",
+            mcq_count
+        ));
+    }
+    if !ctx.focus_categories.is_empty() {
+        exam.adaptation = Some(ExamAdaptation {
+            source: "author-profile".to_string(),
+            focus_categories: ctx.focus_categories.clone(),
+            applied: "asked the generator for an extra question per focus category".to_string(),
+        });
+    }
+    Ok(exam)
+}
+
+/// Parses an exam and checks its question ids are non-empty and unique.
+fn parse_exam_json(raw: &str, source: &str) -> Result<Exam> {
+    let mut exam: Exam = serde_json::from_str(raw)?;
+    if exam.protocol_version.trim().is_empty() {
+        exam.protocol_version = "aigit/0.1".to_string();
+    }
+    let mut ids = std::collections::BTreeSet::new();
+    for q in &exam.questions {
+        if q.id.trim().is_empty() {
+            return Err(anyhow::anyhow!("{source} exam question id is empty"));
+        }
+        if !ids.insert(q.id.clone()) {
+            return Err(anyhow::anyhow!(
+                "{source} exam contains duplicate question id: {}",
+                q.id
+            ));
+        }
+    }
+    if exam.questions.is_empty() {
+        return Err(anyhow::anyhow!("{source} exam has no questions"));
+    }
+    Ok(exam)
+}
+
+/// Parses an LLM judge's score, checks it covers exactly the exam's questions, clamps it, and
+/// merges in the conservative file-mention hallucination flags.
+fn parse_judged_score(
+    ctx: &ExamContext,
+    exam: &Exam,
+    answers: &Answers,
+    raw: &str,
+    source: &str,
+) -> Result<Score> {
+    let mut score: Score = serde_json::from_str(raw)?;
+
+    // Validate that the response covers exactly the current exam questions.
+    let expected_ids: std::collections::BTreeSet<&str> =
+        exam.questions.iter().map(|q| q.id.as_str()).collect();
+    let got_ids: std::collections::BTreeSet<&str> =
+        score.per_question.iter().map(|q| q.id.as_str()).collect();
+    if expected_ids != got_ids {
+        return Err(anyhow::anyhow!(
+            "{source} judge returned mismatched question ids (expected {:?}, got {:?})",
+            expected_ids,
+            got_ids
+        ));
+    }
+
+    // Defensive: clamp scores into [0,1] so policy checks behave.
+    score.total_score = clamp01(score.total_score);
+    for q in &mut score.per_question {
+        q.score = clamp01(q.score);
+        q.completeness = clamp01(q.completeness);
+        q.specificity = clamp01(q.specificity);
+        q.category_relevance = clamp01(q.category_relevance);
+    }
+    // With category weights the total is ours to compute, whatever the judge added up.
+    if !ctx.policy.score_weights.is_empty() {
+        score.total_score = ctx.policy.weighted_total(&score.per_question);
+    }
+
+    // Keep the existing conservative hallucination flags (file mentions not in changed set).
+    // Merge with the model-provided flags.
+    let mut conservative = Vec::new();
+    for q in &exam.questions {
+        let answer = answers.get(&q.id).unwrap_or_default().trim().to_string();
+        if answer.is_empty() {
+            continue;
+        }
+        for mentioned in extract_file_like_tokens(&answer) {
+            if !ctx.changed_files.iter().any(|f| f == &mentioned) {
+                conservative.push(HallucinationFlag::file_not_in_diff(&q.id, &mentioned));
+            }
+        }
+    }
+    score.hallucination_flags.extend(conservative);
+    score.hallucination_flags.sort();
+    score.hallucination_flags.dedup();
+
+    Ok(score)
+}
+
+/// Parses follow-up questions from an LLM examiner: at most one per weak question, renamed where
+/// an id is already taken by the exam.
+fn parse_follow_ups(
+    raw: &str,
+    source: &str,
+    exam: &Exam,
+    weak: &[String],
+) -> Result<Vec<ExamQuestion>> {
+    let generated = parse_exam_json(raw, source)?;
+    let mut out: Vec<ExamQuestion> = Vec::new();
+    for mut q in generated.questions.into_iter().take(weak.len()) {
+        while exam.questions.iter().chain(&out).any(|e| e.id == q.id) {
+            q.id.push_str("_retake");
+        }
+        out.push(q);
+    }
+    Ok(out)
+}
+
+/// The fixed follow-up for the category of each weak question, with id `<id>_retake`.
+pub fn fixed_follow_ups(exam: &Exam, weak: &[String], language: Option<&str>) -> Vec<ExamQuestion> {
+    exam.questions
+        .iter()
+        .filter(|q| weak.contains(&q.id))
+        .map(|q| ExamQuestion {
+            id: format!("{}_retake", q.id),
+            category: q.category.clone(),
+            prompt: followup_prompt(&q.category, language),
+            choices: None,
+            related_paths: q.related_paths.clone(),
+            related_hunks: q.related_hunks.clone(),
+            correct_choice: None,
+            kind: None,
+        })
+        .collect()
+}
+
+fn followup_prompt(category: &str, language: Option<&str>) -> String {
+    if let Some(prompt) = crate::i18n::follow_up(language, category) {
+        return prompt;
+    }
+    match category {
+        "risk" => "Name the most likely failure mode of this change and the first signal (log, metric, failing test) that would reveal it.".to_string(),
+        "testing" => "Which specific test would fail if this change were reverted? If none exists, describe the test you would add.".to_string(),
+        "rollback" => "Walk through the exact rollback steps, including any data or config that `git revert` alone would not undo.".to_string(),
+        "security" => "Which inputs or data paths touched by this change cross a trust boundary, and how are they validated?".to_string(),
+        "invariants" => "Which invariant is most likely to be broken by a future edit to this code, and what guards it today?".to_string(),
+        other => format!(
+            "Follow-up on {other}: answer again with concrete references to the changed files and functions."
+        ),
+    }
+}
+
+/// The first line of a work item description, without Markdown heading marks, cut to a length
+/// that reads well inside a question.
+fn work_item_title(description: &str) -> Option<String> {
+    let line = description
+        .lines()
+        .map(|l| l.trim().trim_start_matches('#').trim())
+        .find(|l| !l.is_empty())?;
+    let mut title: String = line.chars().take(120).collect();
+    if title.len() < line.len() {
+        title.push_str("...");
+    }
+    Some(title)
 }
 
-fn keyword_score(answer: &str, keywords: &[&str]) -> f64 {
+fn keyword_score(answer: &str, keywords: &[String]) -> f64 {
     if answer.trim().is_empty() {
         return 0.0;
     }
+    if keywords.is_empty() {
+        return 1.0;
+    }
     let lower = answer.to_lowercase();
     let hits = keywords
         .iter()
@@ -387,7 +1746,7 @@ fn clamp01(v: f64) -> f64 {
     if v.is_nan() {
         return 0.0;
     }
-    v.max(0.0).min(1.0)
+    v.clamp(0.0, 1.0)
 }
 
 fn extract_file_like_tokens(answer: &str) -> Vec<String> {
@@ -412,22 +1771,89 @@ fn extract_file_like_tokens(answer: &str) -> Vec<String> {
     out
 }
 
-fn build_codex_cli_judge_prompt(ctx: &ExamContext, exam: &Exam, answers: &Answers) -> String {
+fn push_repo_access_note(out: &mut String, repo_access: bool) {
+    if repo_access {
+        out.push_str("You may inspect repository files in a READ-ONLY manner if needed, but do not modify anything.\n");
+    } else {
+        out.push_str("You cannot access the repository; rely only on the changed files and diff below.\n");
+    }
+}
+
+fn push_sparse_checkout_note(out: &mut String, ctx: &ExamContext) {
+    if ctx.sparse_checkout {
+        out.push_str("Note: this is a sparse checkout; changed files outside the checkout cone may be missing from disk. Rely on the diff below for those files.\n\n");
+    }
+}
+
+/// Lists the symbols the whole diff touches, so answers naming code cut from a truncated diff
+/// still count as specific.
+/// Asks for the policy's `language` when it is not English. `what` names the text to write in it.
+fn push_language_note(out: &mut String, ctx: &ExamContext, what: &str) {
+    if let Some(tag) = crate::i18n::non_english(ctx.policy.language.as_deref()) {
+        out.push_str(&format!(
+            "Language: write {what} in {}. Keep ids, category names, file paths and code identifiers exactly as they are.\n\n",
+            crate::i18n::language_name(tag)
+        ));
+    }
+}
+
+fn push_changed_symbols(out: &mut String, ctx: &ExamContext) {
+    if ctx.changed_symbols.is_empty() {
+        return;
+    }
+    out.push_str("changed_symbols:\n");
+    for s in &ctx.changed_symbols {
+        out.push_str(&format!("- {} ({} in {}, {})\n", s.name, s.kind, s.path, s.change));
+    }
+    out.push('\n');
+}
+
+/// `repo_access`: the examiner runs inside the workdir and may read files (Codex CLI); API
+/// examiners only see what is in the prompt.
+fn build_judge_prompt(
+    ctx: &ExamContext,
+    exam: &Exam,
+    answers: &Answers,
+    repo_access: bool,
+) -> String {
     let mut out = String::new();
     out.push_str("You are a strict grader for a git \"Proof-of-Understanding\" exam.\n");
-    out.push_str("You may inspect repository files in a READ-ONLY manner if needed, but do not modify anything.\n");
+    push_repo_access_note(&mut out, repo_access);
     out.push_str("Return ONLY a JSON object matching the provided JSON Schema.\n\n");
+    push_sparse_checkout_note(&mut out, ctx);
+    push_language_note(&mut out, ctx, "the `notes` messages; answers are expected in that language, so judge them in it and never mark one down for not being in English");
 
     out.push_str("Grading rubric:\n");
     out.push_str("- completeness: 0..1 based on how well the answer addresses the question (0 if empty).\n");
-    out.push_str("- specificity: 0..1 based on concrete references to what changed (files/functions/behaviors in the diff), not generic boilerplate.\n");
+    out.push_str("- specificity: 0..1 based on concrete references to what changed (changed_files, changed_symbols, behaviors in the diff), not generic boilerplate. Naming a changed function or type counts as much as naming its file.\n");
     out.push_str("- for multiple-choice questions (choices present): treat answers like A/B/C/D (or matching choice text). Penalize if incorrect or ambiguous.\n");
     out.push_str("- for multiple-choice questions, include the correct choice and a 1-sentence explanation in `notes`.\n");
+    out.push_str("- category_relevance: 0..1 based on whether the answer addresses the question's category (e.g. a rollback answer that never says how to undo the change is off-topic, however long it is).\n");
     out.push_str("- score: 0..1 overall for the question; recommended weighting: 0.45*completeness + 0.45*specificity + 0.10*category_relevance.\n");
-    out.push_str("- notes: short bullet-like strings explaining missing specifics or inaccuracies.\n");
-    out.push_str("- hallucination_flags: conservative flags for claims not supported by the diff (esp. files/modules not in changed_files).\n");
+    if !ctx.policy.score_weights.is_empty() {
+        let weights: Vec<String> = ctx
+            .policy
+            .score_weights
+            .iter()
+            .map(|(category, weight)| format!("{category}={weight}"))
+            .collect();
+        out.push_str(&format!(
+            "- total_score: the weighted mean of the per-question scores, weighting each question by its category ({}; other categories 1).\n",
+            weights.join(", ")
+        ));
+    }
+    out.push_str("- notes: short notes explaining the grade. Each note is an object: code (missing_specifics | inaccuracy | boilerplate | excellent; use excellent only for a precise, complete answer), message (one short sentence), related_path (the changed file the note is about, or null).\n");
+    out.push_str("- hallucination_flags: conservative flags for claims not supported by the diff (esp. files/modules not in changed_files). Each flag is an object: question_id, kind (file_not_in_diff | nonexistent_symbol | unsupported_claim | contradicts_diff), claimed_artifact (the file/symbol/behavior claimed, or null), evidence (why it is unsupported), severity (low | medium | high | critical).\n");
     out.push_str("- if an alternative approach exists, mention one in `notes` on the alternatives question and why it may not have been chosen.\n\n");
 
+    if !ctx.policy.categories.is_empty() {
+        out.push_str("project-specific categories (judge category_relevance against these):\n");
+        for cat in &ctx.policy.categories {
+            out.push_str(&format!("- {}: {}\n", cat.id, cat.description));
+        }
+        out.push('\n');
+    }
+
     out.push_str("changed_files:\n");
     for f in &ctx.changed_files {
         out.push_str("- ");
@@ -435,36 +1861,193 @@ fn build_codex_cli_judge_prompt(ctx: &ExamContext, exam: &Exam, answers: &Answer
         out.push('\n');
     }
     out.push('\n');
+    push_changed_symbols(&mut out, ctx);
 
     out.push_str("diff_redacted (may be truncated):\n");
     out.push_str("-----\n");
-    out.push_str(&ctx.diff);
-    out.push_str("\n-----\n\n");
 
-    out.push_str("questions_and_answers:\n");
+    let mut tail = String::from("\n-----\n\n");
+    tail.push_str("questions_and_answers:\n");
     for q in &exam.questions {
         let a = answers.get(&q.id).unwrap_or_default().trim();
-        out.push_str(&format!("\n[id={}] [category={}] prompt: {}\n", q.id, q.category, q.prompt));
-        out.push_str("answer:\n");
-        out.push_str(a);
+        tail.push_str(&format!("\n[id={}] [category={}] prompt: {}\n", q.id, q.category, q.prompt));
+        if let Some(choices) = q.choices.as_ref().filter(|c| !c.is_empty()) {
+            for (letter, choice) in ('A'..='Z').zip(choices) {
+                tail.push_str(&format!("  {letter}) {choice}\n"));
+            }
+            if let Some(key) = &q.correct_choice {
+                tail.push_str(&format!("correct_choice: {key}\n"));
+            }
+        }
+        if q.is_evidence() {
+            tail.push_str("kind: evidence (the cited location is checked against the diff separately; grade only the explanation, if any)\n");
+        }
+        tail.push_str("answer:\n");
+        tail.push_str(a);
+        tail.push('\n');
+    }
+    let diff = ctx.budget.fit_prompt_diff(&ctx.diff, &out, &tail);
+    out.push_str(&diff);
+    out.push_str(&tail);
+    out
+}
+
+/// Version of the exam-generation prompt; part of the exam cache key, so bump it whenever the
+/// prompt changes.
+pub const GENERATE_PROMPT_VERSION: &str = "aigit-generate/0.6";
+
+fn build_follow_up_prompt(
+    ctx: &ExamContext,
+    exam: &Exam,
+    answers: &Answers,
+    score: &Score,
+    weak: &[String],
+    repo_access: bool,
+) -> String {
+    let mut out = String::new();
+    out.push_str("You write follow-up questions for a git \"Proof-of-Understanding\" exam the committer failed.\n");
+    push_repo_access_note(&mut out, repo_access);
+    out.push_str("Return ONLY a JSON object matching the provided JSON Schema.\n\n");
+    push_sparse_checkout_note(&mut out, ctx);
+    push_language_note(&mut out, ctx, "every question prompt");
+
+    out.push_str("Requirements:\n");
+    out.push_str(&format!(
+        "- Exactly {} question(s): one per weak question below, in the same category, with id `<original id>_retake`.\n",
+        weak.len()
+    ));
+    out.push_str("- Make each harder and more specific than the original: aim it at what the answer missed or got wrong (see the grader's notes), naming concrete files/functions/behaviors in the diff.\n");
+    out.push_str("- Open-ended questions only: set `choices` and `correct_choice` to null and `kind` to \"open\".\n");
+    out.push_str("- List the changed files each question is about in `related_paths` and its hunks in `related_hunks`, as for the original.\n\n");
+
+    out.push_str("weak questions:\n");
+    for q in exam.questions.iter().filter(|q| weak.contains(&q.id)) {
+        out.push_str(&format!("- {} [{}]: {}\n", q.id, q.category, q.prompt));
+        out.push_str(&format!("  answer: {}\n", answers.get(&q.id).unwrap_or_default().trim()));
+        if let Some(graded) = score.per_question.iter().find(|p| p.id == q.id) {
+            out.push_str(&format!("  score: {:.2}\n", graded.score));
+            for note in &graded.notes {
+                out.push_str(&format!("  note: {note}\n"));
+            }
+        }
+    }
+    out.push('\n');
+
+    out.push_str("changed_files:\n");
+    for f in &ctx.changed_files {
+        out.push_str("- ");
+        out.push_str(f);
         out.push('\n');
     }
+    out.push('\n');
+
+    out.push_str("diff_redacted (may be truncated):\n");
+    out.push_str("-----\n");
+    let tail = "\n-----\n";
+    let diff = ctx.budget.fit_prompt_diff(&ctx.diff, &out, tail);
+    out.push_str(&diff);
+    out.push_str(tail);
     out
 }
 
-fn build_codex_cli_generate_exam_prompt(ctx: &ExamContext) -> String {
+fn build_generate_exam_prompt(ctx: &ExamContext, repo_access: bool) -> String {
     let mut out = String::new();
     out.push_str("You generate a git \"Proof-of-Understanding\" exam tailored to a specific diff.\n");
-    out.push_str("You may inspect repository files in a READ-ONLY manner if needed, but do not modify anything.\n");
+    push_repo_access_note(&mut out, repo_access);
     out.push_str("Return ONLY a JSON object matching the provided JSON Schema.\n\n");
+    push_sparse_checkout_note(&mut out, ctx);
+    push_language_note(&mut out, ctx, "every question prompt and choice");
 
     out.push_str("Requirements:\n");
-    out.push_str("- 8 questions total (unless the diff is tiny; then >=3).\n");
-    out.push_str("- Cover these categories at least once each: summary, intent, invariants, risk, testing, rollback, alternatives, security.\n");
+    let risk_questions = crate::exam_tiers::risk_questions(ctx);
+    match crate::exam_tiers::select(ctx).and_then(|(name, t)| Some((name, t.questions?))) {
+        Some((name, n)) => {
+            let n = n.max(risk_questions.unwrap_or(0));
+            out.push_str(&format!(
+                "- {n} question{} total (exam size `{name}`, for a change of {} touching {}).\n",
+                if n == 1 { "" } else { "s" },
+                ctx.stats.shortstat(),
+                ctx.stats.areas.join(", ")
+            ))
+        }
+        None => match risk_questions.filter(|n| *n > 8) {
+            Some(n) => out.push_str(&format!("- {n} questions total.\n")),
+            None => out.push_str("- 8 questions total (unless the diff is tiny; then >=3).\n"),
+        },
+    }
+    if let Some(risk) = ctx.risk.as_ref().filter(|r| r.level != RiskLevel::Low) {
+        out.push_str(&format!(
+            "- This change was assessed as {} risk ({}); spend more questions on what makes it risky.\n",
+            risk.level,
+            risk.factors.join(", ")
+        ));
+    }
+    out.push_str(&format!(
+        "- Cover these categories at least once each: {}.\n",
+        crate::exam_tiers::categories(ctx).join(", ")
+    ));
+    if crate::exam_tiers::test_lane(ctx) {
+        out.push_str("- The change only touches tests. Ask which behavior the new or changed tests pin down and what gap (a bug, a missing case, a refactor to protect) prompted them, not about the rest of the system.\n");
+    }
     out.push_str("- Make questions diff-aware: mention concrete files/functions/behaviors present in the diff.\n");
     out.push_str("- Include at least 3 multiple-choice questions by providing a `choices` array with exactly 4 options (A-D).\n");
-    out.push_str("- Multiple-choice questions should be answerable with A/B/C/D.\n");
-    out.push_str("- At least one question should probe an alternative approach and ask why it was not chosen.\n\n");
+    out.push_str("- Multiple-choice questions should be answerable with A/B/C/D and have exactly one correct option; set `correct_choice` to its letter (null for open-ended questions).\n");
+    out.push_str("- At least one question should probe an alternative approach and ask why it was not chosen.\n");
+    out.push_str("- Set `kind` to \"evidence\" for a question the committer answers by pointing to a location in the diff (e.g. \"Which line introduces the new default value?\"); the answer is a `path:line` or a hunk, checked against `related_hunks`, so link the hunks that contain the right line. Use \"open\" for every other question.\n");
+    if ctx.policy.evidence.enabled.unwrap_or(false) {
+        out.push_str("- Include at least one evidence question.\n");
+    }
+    if let Some(requirement) = crate::memory_safety::prompt_requirement(ctx) {
+        out.push_str(&requirement);
+    }
+    if let Some(requirement) = crate::performance::prompt_requirement(ctx) {
+        out.push_str(&requirement);
+    }
+    if let Some(requirement) = crate::api_surface::prompt_requirement(ctx) {
+        out.push_str(&requirement);
+    }
+    if let Some(requirement) = crate::config_changes::prompt_requirement(ctx) {
+        out.push_str(&requirement);
+    }
+    if let Some(requirement) = crate::ci_pipeline::prompt_requirement(ctx) {
+        out.push_str(&requirement);
+    }
+    out.push_str("- For each question, list the changed files it is about in `related_paths` and the hunks it is about in `related_hunks`, each written as the path, a space, and the hunk header copied from the diff (e.g. `src/lib.rs @@ -10,2 +10,3 @@`). Use empty arrays for questions about the change as a whole.\n");
+    let custom = ctx.policy.applicable_categories(&ctx.changed_files);
+    if !custom.is_empty() {
+        out.push_str("- Also cover these project-specific categories at least once each (use the id as the category):\n");
+        for cat in custom {
+            out.push_str(&format!("  - {}: {}\n", cat.id, cat.description));
+        }
+    }
+    let bank = ctx.policy.bank_questions(&ctx.changed_files);
+    if !bank.is_empty() {
+        out.push_str("- Also ask these repository questions, with the given id and category; you may make them more specific to the diff:\n");
+        for (id, q, files) in bank {
+            out.push_str(&format!(
+                "  - {id} [{}]: {}\n",
+                q.category,
+                crate::prompt_template::render_bank(&q.prompt, ctx, &files)
+            ));
+        }
+    }
+    if !ctx.focus_categories.is_empty() {
+        out.push_str(&format!(
+            "- The committer has historically scored low on: {}. Add one extra, harder question for each of these categories.\n",
+            ctx.focus_categories.join(", ")
+        ));
+    }
+    if ctx.work_item.is_some() {
+        out.push_str("- The work item description below states what this change is meant to do. Ask at least one question that checks the diff against it: something the description promises that the diff does not do, or something the diff changes that the description does not mention.\n");
+    }
+    out.push('\n');
+
+    if let Some(work_item) = &ctx.work_item {
+        out.push_str("work_item_description (issue or pull request, redacted):\n");
+        out.push_str("-----\n");
+        out.push_str(work_item);
+        out.push_str("\n-----\n\n");
+    }
 
     out.push_str("changed_files:\n");
     for f in &ctx.changed_files {
@@ -476,7 +2059,9 @@ fn build_codex_cli_generate_exam_prompt(ctx: &ExamContext) -> String {
 
     out.push_str("diff_redacted (may be truncated):\n");
     out.push_str("-----\n");
-    out.push_str(&ctx.diff);
-    out.push_str("\n-----\n");
+    let tail = "\n-----\n";
+    let diff = ctx.budget.fit_prompt_diff(&ctx.diff, &out, tail);
+    out.push_str(&diff);
+    out.push_str(tail);
     out
 }
@@ -0,0 +1,105 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+
+use crate::codex_cli::{program_exists, split_command_line, wait_with_input};
+use crate::config::{ExecPolicy, Policy};
+use crate::examiner::ExamPacket;
+use crate::transcript::Answers;
+
+pub const REQUEST_SCHEMA_VERSION: &str = "aigit-exec/0.1";
+
+/// What `provider = "exec"` writes to the external command's stdin. The command answers with an
+/// `Exam` (for "generate_exam") or a `Score` (for "grade_exam") as JSON on stdout.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecRequest<'a> {
+    pub schema_version: &'static str,
+    /// "generate_exam" or "grade_exam".
+    pub request: &'static str,
+    /// The diff context; `packet.exam` is empty for "generate_exam".
+    pub packet: ExamPacket,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answers: Option<&'a Answers>,
+    /// Categories the committer's profile asks to probe (may be ignored).
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    pub focus_categories: &'a [String],
+}
+
+/// Runs the user-supplied `exec.exam_command` / `exec.grade_command`.
+#[derive(Debug, Clone)]
+pub struct ExecRunner {
+    exam_command: Option<String>,
+    grade_command: Option<String>,
+    timeout: Duration,
+}
+
+impl ExecRunner {
+    pub fn from_policy(policy: &Policy) -> Self {
+        let cfg: &ExecPolicy = &policy.exec;
+        Self {
+            exam_command: cfg.exam_command.clone(),
+            grade_command: cfg.grade_command.clone(),
+            timeout: Duration::from_secs(cfg.timeout_secs.unwrap_or(120)),
+        }
+    }
+
+    /// The configured commands' programs resolve to executables. Only one of them may be set
+    /// when exec handles just generation or just grading (see `grader`).
+    pub fn ping(&self) -> Result<()> {
+        let mut any = false;
+        for (key, command) in [
+            ("exam_command", &self.exam_command),
+            ("grade_command", &self.grade_command),
+        ] {
+            let Some(command) = command.as_deref().filter(|c| !c.trim().is_empty()) else {
+                continue;
+            };
+            any = true;
+            let (program, _) = split_command_line(command)?;
+            if !program_exists(&program) {
+                return Err(anyhow!("exec.{key} not found: {program}"));
+            }
+        }
+        if !any {
+            return Err(anyhow!(
+                "exec.exam_command or exec.grade_command must be set when using the exec provider"
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn run_generate_exam(&self, cwd: &Path, request: &ExecRequest) -> Result<String> {
+        let command = command_or_err("exam_command", &self.exam_command)?;
+        self.run(cwd, command, request)
+    }
+
+    pub fn run_grade_exam(&self, cwd: &Path, request: &ExecRequest) -> Result<String> {
+        let command = command_or_err("grade_command", &self.grade_command)?;
+        self.run(cwd, command, request)
+    }
+
+    fn run(&self, cwd: &Path, command: &str, request: &ExecRequest) -> Result<String> {
+        let (program, args) = split_command_line(command)?;
+        let child = Command::new(&program)
+            .current_dir(cwd)
+            .args(&args)
+            .env("AIGIT_EXEC_REQUEST", request.request)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("failed to spawn exec examiner: {command}"))?;
+        let input = serde_json::to_string(request)?;
+        wait_with_input(child, &input, self.timeout, &format!("exec examiner ({program})"))
+    }
+}
+
+fn command_or_err<'a>(key: &str, command: &'a Option<String>) -> Result<&'a str> {
+    command
+        .as_deref()
+        .filter(|c| !c.trim().is_empty())
+        .ok_or_else(|| anyhow!("exec.{key} must be set when provider = \"exec\""))
+}
@@ -0,0 +1,299 @@
+use serde::{Deserialize, Serialize};
+
+use crate::transcript::{Decision, NoteCode, QuestionScore, Transcript};
+
+/// Why an exam failed: the checks it tripped, the answers that pulled its score down, and what
+/// to improve. Recorded in the transcript of a failed exam and printed with the result.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FailureReport {
+    pub checks: Vec<FailedCheck>,
+    /// Weakest answers first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub questions: Vec<WeakAnswer>,
+}
+
+/// A policy threshold the exam did not meet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedCheck {
+    /// The policy setting, e.g. "min_total_score" or "integrity.action".
+    pub check: String,
+    pub detail: String,
+    pub advice: String,
+}
+
+/// An answer that cost the exam: it scored under `min_total_score` or tripped a check.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeakAnswer {
+    pub id: String,
+    pub category: String,
+    pub score: f64,
+    /// How much higher `total_score` would be with a perfect answer.
+    pub cost: f64,
+    /// The grader's notes on the answer.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub advice: Vec<String>,
+}
+
+impl FailureReport {
+    /// The report for a failed transcript, from its score and thresholds; `None` for a pass.
+    pub fn build(t: &Transcript) -> Option<Self> {
+        if t.decision == Decision::Pass {
+            return None;
+        }
+        let th = &t.thresholds;
+        let mut checks = Vec::new();
+        let mut check = |check: &str, detail: String, advice: &str| {
+            checks.push(FailedCheck {
+                check: check.to_string(),
+                detail,
+                advice: advice.to_string(),
+            })
+        };
+
+        let gated = t.score.gated_total(th.require_lower_bound);
+        if gated < th.min_total_score {
+            let detail = if gated < t.score.total_score {
+                format!(
+                    "score interval lower bound {:.2} < min_total_score {:.2} (borderline pass not confident enough)",
+                    gated, th.min_total_score
+                )
+            } else {
+                format!(
+                    "total_score {:.2} < min_total_score {:.2}",
+                    t.score.total_score, th.min_total_score
+                )
+            };
+            check(
+                "min_total_score",
+                detail,
+                "expand the weakest answers below, naming the files, functions and behavior the change touches",
+            );
+        }
+        let counted = t.score.counted_flags(th.hallucination_min_severity);
+        if (counted as u32) > th.max_hallucination_flags {
+            check(
+                "max_hallucination_flags",
+                format!(
+                    "hallucination_flags {} > max {}{}",
+                    counted,
+                    th.max_hallucination_flags,
+                    th.hallucination_min_severity
+                        .map(|s| format!(" (counting severity >= {s})"))
+                        .unwrap_or_default()
+                ),
+                "drop claims about code the diff does not contain",
+            );
+        }
+        for q in t.score.off_topic(th.min_category_relevance) {
+            check(
+                "min_category_relevance",
+                format!(
+                    "{} answer is off-topic for {} (category_relevance {:.2} < {:.2})",
+                    q.id,
+                    q.category,
+                    q.category_relevance,
+                    th.min_category_relevance.unwrap_or_default()
+                ),
+                "answer each question about its own category",
+            );
+        }
+        let shortfalls = t.score.below_category_minimums(&th.min_category_score);
+        for shortfall in &shortfalls {
+            check(
+                "min_category_score",
+                shortfall.to_string(),
+                "strengthen every answer in the category, not just the total",
+            );
+        }
+        let vague = crate::memory_safety::shortfalls(&th.memory_safety, &t.score);
+        for reason in &vague {
+            check(
+                "memory_safety.min_specificity",
+                reason.clone(),
+                "name the invariant each unsafe block relies on and what guards shared state",
+            );
+        }
+        if th.self_check_required {
+            match &t.score.self_check {
+                Some(self_check) if !self_check.passed => check(
+                    "self_check",
+                    format!(
+                        "self-check answer is wrong (expected {})",
+                        self_check.expected
+                    ),
+                    "read the diff again before answering the self-check question",
+                ),
+                Some(_) => {}
+                None => check(
+                    "self_check",
+                    "the exam has no self-check question".to_string(),
+                    "retake the exam so it includes the self-check question",
+                ),
+            }
+        }
+        for violation in crate::respondents::violations(&th.respondents, &t.exam, &t.answers) {
+            check(
+                "respondents",
+                violation,
+                "have each category answered by someone allowed to answer it",
+            );
+        }
+        if th.timing.fails() {
+            for reason in crate::timing::violations(&th.timing, &t.exam, &t.answers) {
+                check(
+                    "timing",
+                    format!("answered too fast: {reason}"),
+                    "take the time to read each question and the code it is about",
+                );
+            }
+        }
+        if th.integrity.fails() {
+            for flag in &t.score.integrity_flags {
+                check(
+                    "integrity",
+                    format!("copied answer: {flag}"),
+                    "write each answer in your own words",
+                );
+            }
+        }
+        if let Some(engine) = t.policy_engine.as_ref().filter(|e| !e.allow) {
+            let hint = if engine.engine == "command" {
+                "see the rules of the decision command"
+            } else {
+                "see the rules in the decision module"
+            };
+            if engine.deny.is_empty() {
+                check(
+                    "decision.engine",
+                    format!("{} does not allow the change", engine.module),
+                    hint,
+                );
+            }
+            for reason in &engine.deny {
+                check("decision.engine", format!("{}: {reason}", engine.module), hint);
+            }
+        }
+        for cat in &th.required_categories {
+            let asked = t.exam.questions.iter().any(|q| q.category == *cat);
+            if !asked {
+                check(
+                    "required_categories",
+                    format!("no question in required category: {cat}"),
+                    "retake the exam; the examiner must ask about every applicable required category",
+                );
+            }
+            let answered = t
+                .exam
+                .questions
+                .iter()
+                .filter(|q| q.category == *cat)
+                .all(|q| t.answers.is_answered(&q.id));
+            if !answered {
+                check(
+                    "required_categories",
+                    format!("missing required category answer: {cat}"),
+                    "answer every question in the required categories",
+                );
+            }
+        }
+
+        let weight = |q: &QuestionScore| th.score_weights.get(&q.category).copied().unwrap_or(1.0);
+        let total_weight: f64 = t.score.per_question.iter().map(weight).sum();
+        let off_topic: Vec<&str> = t
+            .score
+            .off_topic(th.min_category_relevance)
+            .iter()
+            .map(|q| q.id.as_str())
+            .collect();
+        let flagged: Vec<&str> = t
+            .score
+            .hallucination_flags
+            .iter()
+            .filter(|f| {
+                th.hallucination_min_severity
+                    .is_none_or(|min| f.severity >= min)
+            })
+            .map(|f| f.question_id.as_str())
+            .collect();
+        let mut questions: Vec<WeakAnswer> = t
+            .score
+            .per_question
+            .iter()
+            .filter(|q| {
+                q.score < th.min_total_score
+                    || q.choice_correct == Some(false)
+                    || q.evidence_valid == Some(false)
+                    || off_topic.contains(&q.id.as_str())
+                    || flagged.contains(&q.id.as_str())
+                    || shortfalls.iter().any(|s| s.category == q.category)
+                    || (q.category == crate::memory_safety::CATEGORY && !vague.is_empty())
+            })
+            .map(|q| WeakAnswer {
+                id: q.id.clone(),
+                category: q.category.clone(),
+                score: q.score,
+                cost: if total_weight > 0.0 {
+                    weight(q) * (1.0 - q.score) / total_weight
+                } else {
+                    0.0
+                },
+                notes: q
+                    .notes
+                    .iter()
+                    .filter(|n| n.code.is_deficiency())
+                    .map(|n| n.to_string())
+                    .collect(),
+                advice: advice(
+                    q,
+                    off_topic.contains(&q.id.as_str()),
+                    flagged.contains(&q.id.as_str()),
+                ),
+            })
+            .collect();
+        questions.sort_by(|a, b| b.cost.total_cmp(&a.cost));
+        Some(Self { checks, questions })
+    }
+}
+
+/// What would most improve an answer, from how it was graded.
+fn advice(q: &QuestionScore, off_topic: bool, flagged: bool) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    let mut add = |text: &str| {
+        if !out.iter().any(|o| o == text) {
+            out.push(text.to_string());
+        }
+    };
+    if q.completeness == 0.0 {
+        add("answer it");
+    } else if q.completeness < 0.5 {
+        add("answer every part of the question");
+    }
+    if q.completeness > 0.0 && q.specificity < 0.5 {
+        add("name the changed files, functions or lines it is about");
+    }
+    if off_topic {
+        add(&format!("keep the answer about {}", q.category));
+    }
+    if q.choice_correct == Some(false) {
+        add("pick the choice the diff supports");
+    }
+    if q.evidence_valid == Some(false) {
+        add("cite a path:line inside the hunks the question is about");
+    }
+    if flagged {
+        add("drop claims about code the diff does not contain");
+    }
+    for note in &q.notes {
+        match note.code {
+            NoteCode::MissingSpecifics => {
+                add("name the changed files, functions or lines it is about")
+            }
+            NoteCode::Inaccuracy => add("correct what it gets wrong about the change"),
+            NoteCode::Boilerplate => add("describe this change, not changes in general"),
+            NoteCode::Excellent => {}
+        }
+    }
+    out
+}
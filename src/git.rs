@@ -2,6 +2,8 @@ use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
 use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone)]
 pub struct GitRepo {
@@ -11,7 +13,30 @@ pub struct GitRepo {
 
 impl GitRepo {
     pub fn discover() -> Result<Self> {
+        Self::open(Path::new("."))
+    }
+
+    /// Clones `url` into `dest` without checking anything out and without blobs (where the server
+    /// supports it): enough to read history and notes.
+    pub fn clone_history(url: &str, dest: &Path) -> Result<Self> {
+        let out = Command::new("git")
+            .args(["clone", "-q", "--no-checkout", "--filter=blob:none", url])
+            .arg(dest)
+            .output()
+            .context("failed to run git clone")?;
+        if !out.status.success() {
+            return Err(anyhow!(
+                "git clone {url} failed: {}",
+                String::from_utf8_lossy(&out.stderr).trim()
+            ));
+        }
+        Self::open(dest)
+    }
+
+    /// The repository containing `path`.
+    pub fn open(path: &Path) -> Result<Self> {
         let out = Command::new("git")
+            .current_dir(path)
             .args(["rev-parse", "--show-toplevel"])
             .output()
             .context("failed to run git")?;
@@ -39,6 +64,70 @@ impl GitRepo {
     }
 }
 
+/// How much of the repository is materialized locally.
+///
+/// Partial clones (`--filter=blob:none`, `--filter=tree:0`) may lack blobs a diff needs, and
+/// sparse checkouts leave paths outside the cone absent from the workdir.
+#[derive(Debug, Clone, Default)]
+pub struct CloneShape {
+    /// Promisor remote missing objects can be fetched from (partial clones only).
+    pub promisor_remote: Option<String>,
+    pub sparse_checkout: bool,
+}
+
+/// Whether `path` is a transcript the tree store commits alongside the change it is about.
+pub fn is_stored_transcript(path: &str) -> bool {
+    path.strip_prefix(crate::transcript::TREE_STORE_DIR)
+        .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// `diff` without the files of stored transcripts, so a commit that carries its own transcript
+/// keeps the patch-id the transcript records.
+fn without_stored_transcripts(diff: &str) -> String {
+    let mut out = String::with_capacity(diff.len());
+    let mut keep = true;
+    for line in diff.split_inclusive('\n') {
+        if let Some(paths) = line.strip_prefix("diff --git ") {
+            keep = !paths
+                .split_whitespace()
+                .any(|p| is_stored_transcript(p.get(2..).unwrap_or("")));
+        }
+        if keep {
+            out.push_str(line);
+        }
+    }
+    out
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitMeta {
+    pub sha: String,
+    pub author_name: String,
+    pub author_email: String,
+    pub author_date_iso: String,
+    pub subject: String,
+}
+
+/// `git show`/`git log` format read by `parse_commit_meta`.
+const COMMIT_META_FORMAT: &str = "--format=%H%x09%an%x09%ae%x09%ad%x09%s";
+
+fn parse_commit_meta(line: &str) -> CommitMeta {
+    let mut parts = line.split('\t');
+    let sha = parts.next().unwrap_or("").to_string();
+    let author_name = parts.next().unwrap_or("").to_string();
+    let author_email = parts.next().unwrap_or("").to_string();
+    let author_date_iso = parts.next().unwrap_or("").to_string();
+    let subject_parts = parts.collect::<Vec<_>>();
+    let subject = subject_parts.join("\t");
+    CommitMeta {
+        sha,
+        author_name,
+        author_email,
+        author_date_iso,
+        subject,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Git {
     pub repo: GitRepo,
@@ -49,9 +138,44 @@ impl Git {
         Self { repo }
     }
 
+    pub fn clone_shape(&self) -> CloneShape {
+        let promisor_remote = self
+            .config_get(&["extensions.partialclone"])
+            .or_else(|| {
+                let raw = self
+                    .git_output(["config", "--get-regexp", r"^remote\..*\.promisor$"])
+                    .ok()?;
+                raw.lines()
+                    .filter(|l| l.trim_end().ends_with(" true"))
+                    .filter_map(|l| l.split_whitespace().next())
+                    .find_map(|k| k.strip_prefix("remote.")?.strip_suffix(".promisor"))
+                    .map(|s| s.to_string())
+            });
+        let sparse_checkout = self.config_get(&["--bool", "core.sparsecheckout"]).as_deref() == Some("true");
+        CloneShape {
+            promisor_remote,
+            sparse_checkout,
+        }
+    }
+
     pub fn diff_staged(&self) -> Result<(String, Vec<String>)> {
-        let diff = self.git_output(["diff", "--staged", "--unified=0"])?;
-        let files_raw = self.git_output(["diff", "--staged", "--name-only"])?;
+        self.diff_staged_since(None)
+    }
+
+    /// The staged changes against `base` instead of HEAD (see [`Git::amend_base`]), leaving out
+    /// the transcripts the tree store committed in HEAD.
+    pub fn diff_staged_since(&self, base: Option<&str>) -> Result<(String, Vec<String>)> {
+        let exclude = format!(":(exclude){}", crate::transcript::TREE_STORE_DIR);
+        let since = match base {
+            Some(base) => vec![base, "--", ".", exclude.as_str()],
+            None => Vec::new(),
+        };
+        let diff = self.git_output_fetching(
+            &[&["diff", "--staged", "--unified=0"], since.as_slice()].concat(),
+            &[&["diff", "--staged", "--raw", "--no-abbrev"], since.as_slice()].concat(),
+        )?;
+        let files_raw =
+            self.git_output([&["diff", "--staged", "--name-only"], since.as_slice()].concat())?;
         let changed_files = files_raw
             .lines()
             .map(|s| s.trim())
@@ -62,7 +186,10 @@ impl Git {
     }
 
     pub fn diff_range(&self, range: &str) -> Result<(String, Vec<String>)> {
-        let diff = self.git_output(["diff", "--unified=0", range])?;
+        let diff = self.git_output_fetching(
+            &["diff", "--unified=0", range],
+            &["diff", "--raw", "--no-abbrev", range],
+        )?;
         let files_raw = self.git_output(["diff", "--name-only", range])?;
         let changed_files = files_raw
             .lines()
@@ -73,16 +200,157 @@ impl Git {
         Ok((diff, changed_files))
     }
 
+    /// What `git commit --amend` diffs the index against: HEAD's first parent, or the empty tree
+    /// when HEAD is a root commit. Staged changes against it are the whole amended commit.
+    pub fn amend_base(&self) -> Result<String> {
+        let parents = self
+            .parent_count("HEAD")
+            .map_err(|_| anyhow!("there is no commit to amend"))?;
+        if parents > 0 {
+            return Ok(self.git_output(["rev-parse", "HEAD^1"])?.trim().to_string());
+        }
+        Ok(self
+            .git_output(["hash-object", "-t", "tree", "--stdin"])?
+            .trim()
+            .to_string())
+    }
+
+    /// The staged diff (against `staged_base`, else HEAD) or `range`'s with `context_lines` of
+    /// context, or with each change's whole enclosing function when `function_context`. Only for
+    /// the examiner's context: patch-ids always come from the `--unified=0` diff.
+    pub fn diff_with_context(
+        &self,
+        range: Option<&str>,
+        staged_base: Option<&str>,
+        context_lines: u32,
+        function_context: bool,
+    ) -> Result<String> {
+        let unified = format!("--unified={context_lines}");
+        let exclude = format!(":(exclude){}", crate::transcript::TREE_STORE_DIR);
+        let mut args = vec!["diff", unified.as_str()];
+        let mut raw_args = vec!["diff", "--raw", "--no-abbrev"];
+        if function_context {
+            args.push("--function-context");
+        }
+        match range {
+            Some(range) => {
+                args.push(range);
+                raw_args.push(range);
+            }
+            None => {
+                args.push("--staged");
+                raw_args.push("--staged");
+                if let Some(base) = staged_base {
+                    args.extend([base, "--", ".", exclude.as_str()]);
+                    raw_args.extend([base, "--", ".", exclude.as_str()]);
+                }
+            }
+        }
+        self.git_output_fetching(&args, &raw_args)
+    }
+
+    /// Makes sure `commit`'s parents are present so its diff (and patch-id) is not computed as a
+    /// root commit. In a shallow clone whose boundary is at `commit`, deepens history by one
+    /// commit from [`Git::fetch_remote`] unless `allow_fetch` is false.
+    pub fn ensure_parents_available(&self, commit: &str, allow_fetch: bool) -> Result<()> {
+        if !self.is_shallow_boundary(commit)? {
+            return Ok(());
+        }
+        let remote = self.fetch_remote();
+        let hint = format!("hint: fetch more history (e.g. `git fetch --deepen=1 {remote}`, or `fetch-depth: 0` in CI checkout)");
+        if !allow_fetch {
+            return Err(anyhow!(
+                "commit {commit} is at the shallow clone boundary (its parents are not available locally)\n{hint}"
+            ));
+        }
+        self.git_output(["fetch", "--no-tags", "--deepen=1", remote.as_str()])
+            .map_err(|err| {
+                anyhow!("commit {commit} is at the shallow clone boundary and deepening failed: {err}\n{hint}")
+            })?;
+        if self.is_shallow_boundary(commit)? {
+            return Err(anyhow!(
+                "commit {commit} is still at the shallow clone boundary after deepening\n{hint}"
+            ));
+        }
+        Ok(())
+    }
+
+    /// The remote to fetch missing history from: the one the current branch tracks, else the
+    /// promisor remote of a partial clone, else "origin".
+    pub fn fetch_remote(&self) -> String {
+        self.git_output(["symbolic-ref", "--quiet", "--short", "HEAD"])
+            .ok()
+            .and_then(|branch| self.config_get(&[&format!("branch.{}.remote", branch.trim())]))
+            .filter(|remote| remote != ".")
+            .or_else(|| self.clone_shape().promisor_remote)
+            .unwrap_or_else(|| "origin".to_string())
+    }
+
+    fn is_shallow_boundary(&self, commit: &str) -> Result<bool> {
+        let shallow = self.git_output(["rev-parse", "--is-shallow-repository"])?;
+        if shallow.trim() != "true" {
+            return Ok(false);
+        }
+        let path = self.git_output(["rev-parse", "--git-path", "shallow"])?;
+        let path = self.repo.workdir.join(path.trim());
+        let raw = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        Ok(raw.lines().any(|l| l.trim() == commit))
+    }
+
+    /// Patch-id of the change a commit introduces. Merge and octopus commits are fingerprinted
+    /// by their diff against the first parent, which is what `aigit commit` examines while
+    /// concluding a merge (`git show` would emit a combined diff that `git patch-id` cannot
+    /// fingerprint).
     pub fn patch_id_for_commit(&self, commit: &str) -> Result<String> {
-        let diff = self.git_output(["show", "--pretty=format:", "--unified=0", commit])?;
+        let diff = if self.parent_count(commit)? <= 1 {
+            self.git_output_fetching(
+                &["show", "--pretty=format:", "--unified=0", commit],
+                &["show", "--pretty=format:", "--raw", "--no-abbrev", commit],
+            )?
+        } else {
+            let first_parent = format!("{commit}^1");
+            self.git_output_fetching(
+                &["diff", "--unified=0", &first_parent, commit],
+                &["diff", "--raw", "--no-abbrev", &first_parent, commit],
+            )?
+        };
+        if diff.trim().is_empty() {
+            return Err(anyhow!(
+                "commit {commit} introduces no changes to fingerprint"
+            ));
+        }
         self.patch_id_from_diff(&diff)
     }
 
+    /// Files touched by `commit`; merges are compared against their first parent. Transcripts
+    /// the tree store committed with it are left out.
+    pub fn changed_files_for_commit(&self, commit: &str) -> Result<Vec<String>> {
+        let raw = if self.parent_count(commit)? <= 1 {
+            self.git_output(["show", "--pretty=format:", "--name-only", commit])?
+        } else {
+            let first_parent = format!("{commit}^1");
+            self.git_output(["diff", "--name-only", &first_parent, commit])?
+        };
+        Ok(raw
+            .lines()
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty() && !is_stored_transcript(s))
+            .map(|s| s.to_string())
+            .collect())
+    }
+
+    pub fn parent_count(&self, commit: &str) -> Result<usize> {
+        let out = self.git_output(["rev-list", "--parents", "-n", "1", commit])?;
+        Ok(out.split_whitespace().count().saturating_sub(1))
+    }
+
     pub fn patch_id_from_diff_text(&self, diff: &str) -> Result<String> {
         self.patch_id_from_diff(diff)
     }
 
     fn patch_id_from_diff(&self, diff: &str) -> Result<String> {
+        let diff = &without_stored_transcripts(diff);
         let mut child = Command::new("git")
             .current_dir(&self.repo.workdir)
             .args(["patch-id", "--stable"])
@@ -107,10 +375,10 @@ impl Git {
         Ok(patch_id.to_string())
     }
 
-    pub fn remote_fingerprint(&self) -> Result<Option<String>> {
+    pub fn remote_url(&self, remote: &str) -> Result<Option<String>> {
         let out = Command::new("git")
             .current_dir(&self.repo.workdir)
-            .args(["remote", "get-url", "origin"])
+            .args(["remote", "get-url", remote])
             .output();
         let out = match out {
             Ok(o) => o,
@@ -130,15 +398,161 @@ impl Git {
         Ok(self.git_output(["rev-parse", "HEAD"])?.trim().to_string())
     }
 
+    /// When HEAD was committed; `None` on an unborn branch.
+    pub fn head_commit_time(&self) -> Option<DateTime<Utc>> {
+        let out = self
+            .git_output(["log", "-1", "--format=%ct", "HEAD"])
+            .ok()?;
+        DateTime::from_timestamp(out.trim().parse().ok()?, 0)
+    }
+
+    /// Full message of `rev`, or of the newest commit in a range such as `A..B`.
+    pub fn commit_message(&self, rev: &str) -> Result<String> {
+        Ok(self
+            .git_output(["log", "-1", "--format=%B", rev])?
+            .trim_end()
+            .to_string())
+    }
+
+    pub fn commit_meta(&self, sha: &str) -> Result<CommitMeta> {
+        let out = self.git_output(["show", "-s", "--date=iso-strict", COMMIT_META_FORMAT, sha])?;
+        Ok(parse_commit_meta(out.trim_end()))
+    }
+
+    /// Non-merge commits on HEAD committed since `since` and not reachable from `not` (when it
+    /// still exists), newest first, at most `limit`. Empty on an unborn branch.
+    pub fn commits_since(
+        &self,
+        since: &DateTime<Utc>,
+        not: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<CommitMeta>> {
+        if self.rev_parse_head().is_err() {
+            return Ok(Vec::new());
+        }
+        let since = format!("--since={}", since.to_rfc3339());
+        let limit = format!("--max-count={limit}");
+        let mut args = vec![
+            "log",
+            "--no-merges",
+            &since,
+            &limit,
+            "--date=iso-strict",
+            COMMIT_META_FORMAT,
+            "HEAD",
+        ];
+        let not = not.filter(|sha| self.resolve_commitish(&format!("{sha}^{{commit}}")).is_ok());
+        if let Some(sha) = not {
+            args.extend(["--not", sha]);
+        }
+        let out = self.git_output(args)?;
+        Ok(out.lines().map(parse_commit_meta).collect())
+    }
+
+    pub fn fetch_ref(&self, remote: &str, git_ref: &str) -> Result<()> {
+        self.git_output(["fetch", "--no-tags", remote, git_ref])
+            .map(|_| ())
+            .with_context(|| format!("failed to fetch {git_ref} from {remote}"))
+    }
+
+    /// Commits reachable from `rev`, newest first.
+    pub fn rev_list(&self, rev: &str) -> Result<Vec<String>> {
+        Ok(self
+            .git_output(["rev-list", rev])?
+            .lines()
+            .map(str::to_string)
+            .collect())
+    }
+
+    pub fn show_file(&self, rev: &str, path: &str) -> Result<String> {
+        self.git_output(["show", &format!("{rev}:{path}")])
+    }
+
+    /// Contents of the blob `id` (full or abbreviated).
+    pub fn blob(&self, id: &str) -> Result<String> {
+        self.git_output(["cat-file", "blob", id])
+    }
+
+    /// Value of the last `key:` trailer in a commit's message (e.g. Gerrit's `Change-Id`).
+    pub fn trailer(&self, commit: &str, key: &str) -> Option<String> {
+        let format = format!("--format=%(trailers:key={key},valueonly)");
+        self.git_output(["log", "-1", &format, commit])
+            .ok()?
+            .lines()
+            .map(str::trim)
+            .rfind(|l| !l.is_empty())
+            .map(str::to_string)
+    }
+
+    /// Short name of the checked-out branch; `None` on a detached HEAD.
+    pub fn current_branch(&self) -> Option<String> {
+        self.git_output(["symbolic-ref", "--short", "-q", "HEAD"])
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    }
+
+    pub fn delete_ref(&self, name: &str) -> Result<()> {
+        self.git_output(["update-ref", "-d", name]).map(|_| ())
+    }
+
+    pub fn user_email(&self) -> Option<String> {
+        self.config_get(&["user.email"])
+    }
+
+    pub fn user_name(&self) -> Option<String> {
+        self.config_get(&["user.name"])
+    }
+
+    /// A git config value (e.g. "gpg.format"), if set.
+    pub fn config_value(&self, key: &str) -> Option<String> {
+        self.config_get(&[key])
+    }
+
+    /// Commits reachable from `tip` that no remote-tracking branch of `remote` has, newest first.
+    pub fn commits_not_on_remote(&self, tip: &str, remote: &str) -> Result<Vec<String>> {
+        Ok(self
+            .git_output([
+                "rev-list",
+                tip,
+                "--not",
+                &format!("--remotes={remote}"),
+            ])?
+            .lines()
+            .map(str::to_string)
+            .collect())
+    }
+
     pub fn resolve_commitish(&self, commitish: &str) -> Result<String> {
         let s = self.git_output(["rev-parse", commitish])?;
         Ok(s.trim().to_string())
     }
 
+    /// Opens `path` in the user's editor, chosen like git does (`GIT_EDITOR`, `core.editor`,
+    /// `VISUAL`, `EDITOR`), and waits for it to exit.
+    pub fn edit_file(&self, path: &Path) -> Result<()> {
+        let editor = self.git_output(["var", "GIT_EDITOR"])?.trim().to_string();
+        // The editor setting is a shell snippet (e.g. "code --wait"), as for git itself.
+        let status = Command::new("sh")
+            .current_dir(&self.repo.workdir)
+            .arg("-c")
+            .arg(format!("{editor} \"$@\""))
+            .arg(&editor)
+            .arg(path)
+            .status()
+            .with_context(|| format!("failed to run editor {editor}"))?;
+        if !status.success() {
+            return Err(anyhow!("editor {editor} exited with {status}"));
+        }
+        Ok(())
+    }
+
     pub fn run_git_commit(&self, message: Option<&str>, extra_args: &[String]) -> Result<()> {
         let mut cmd = Command::new("git");
         cmd.current_dir(&self.repo.workdir)
             .env("AIGIT_ALLOW_COMMIT", "1")
+            // The post-rewrite hook leaves an amend's transcript to `aigit commit`.
+            .env("AIGIT_STORES_TRANSCRIPT", "1")
             .arg("commit");
         if let Some(msg) = message {
             cmd.args(["-m", msg]);
@@ -151,24 +565,18 @@ impl Git {
         Ok(())
     }
 
-    pub fn install_pre_commit_hook(&self, force: bool) -> Result<()> {
+    /// Installs the git hook `name` as a one-line script handing over to `aigit hook-run`.
+    pub fn install_hook(&self, name: &str, force: bool) -> Result<()> {
         let hooks_dir = self.repo.git_dir.join("hooks");
         std::fs::create_dir_all(&hooks_dir)?;
-        let hook_path = hooks_dir.join("pre-commit");
+        let hook_path = hooks_dir.join(name);
         if hook_path.exists() && !force {
             return Err(anyhow!(
                 "hook already exists at {} (use --force to overwrite)",
                 hook_path.display()
             ));
         }
-        let script = r#"#!/bin/sh
-set -e
-
-if [ -z "$AIGIT_ALLOW_COMMIT" ]; then
-  echo "aigit: commit blocked. Use: aigit commit"
-  exit 1
-fi
-"#;
+        let script = format!("#!/bin/sh\nexec aigit hook-run {name} \"$@\"\n");
         std::fs::write(&hook_path, script)?;
         #[cfg(unix)]
         {
@@ -177,10 +585,68 @@ fi
             perms.set_mode(0o755);
             std::fs::set_permissions(&hook_path, perms)?;
         }
-        eprintln!("installed pre-commit hook at {}", hook_path.display());
+        eprintln!("installed {name} hook at {}", hook_path.display());
         Ok(())
     }
 
+    fn config_get(&self, args: &[&str]) -> Option<String> {
+        let mut full = vec!["config", "--get"];
+        full.extend_from_slice(args);
+        let value = self.git_output(full).ok()?.trim().to_string();
+        (!value.is_empty()).then_some(value)
+    }
+
+    /// Runs a diff-producing git command. In a partial clone, a failure (typically missing blobs
+    /// that could not be lazily fetched) triggers one batched fetch of every blob listed by
+    /// `raw_args` from the promisor remote, then a retry.
+    fn git_output_fetching(&self, args: &[&str], raw_args: &[&str]) -> Result<String> {
+        let err = match self.git_output(args) {
+            Ok(out) => return Ok(out),
+            Err(err) => err,
+        };
+        let Some(remote) = self.clone_shape().promisor_remote else {
+            return Err(err);
+        };
+        if let Err(fetch_err) = self.fetch_blobs(&remote, raw_args) {
+            return Err(anyhow!(
+                "{err}\naigit: this is a partial clone and fetching missing objects from '{remote}' failed: {fetch_err}\n\
+                 hint: re-run with network access to '{remote}', or run `git fetch --refetch {remote}` to backfill objects"
+            ));
+        }
+        self.git_output(args).map_err(|err| {
+            anyhow!("{err}\naigit: partial clone is still missing objects after fetching from '{remote}'")
+        })
+    }
+
+    fn fetch_blobs(&self, remote: &str, raw_args: &[&str]) -> Result<()> {
+        let raw = self.git_output(raw_args)?;
+        // `--raw` lines look like ":100644 100644 <old-oid> <new-oid> M\tpath"; all-zero oids
+        // denote the missing side of an add/delete.
+        let mut oids = raw
+            .lines()
+            .filter_map(|l| l.strip_prefix(':'))
+            .flat_map(|l| l.split_whitespace().skip(2).take(2))
+            .filter(|oid| oid.chars().any(|c| c != '0'))
+            .collect::<Vec<_>>();
+        oids.sort();
+        oids.dedup();
+        if oids.is_empty() {
+            return Err(anyhow!("no blobs referenced by the diff"));
+        }
+        let mut args = vec![
+            "-c",
+            "fetch.negotiationAlgorithm=noop",
+            "fetch",
+            remote,
+            "--no-tags",
+            "--no-write-fetch-head",
+            "--recurse-submodules=no",
+            "--filter=blob:none",
+        ];
+        args.extend(oids);
+        self.git_output(args).map(|_| ())
+    }
+
     fn git_output<I, S>(&self, args: I) -> Result<String>
     where
         I: IntoIterator<Item = S>,
@@ -0,0 +1,104 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::HealthPolicy;
+use crate::git::GitRepo;
+
+/// Consecutive-failure counters per provider, persisted so the circuit breaker survives across
+/// `aigit` invocations.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProviderHealth {
+    #[serde(default)]
+    pub providers: BTreeMap<String, ProviderState>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProviderState {
+    pub consecutive_failures: u32,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    /// While set and in the future, the breaker is open and the provider is not tried.
+    #[serde(default)]
+    pub open_until: Option<DateTime<Utc>>,
+}
+
+fn health_path(repo: &GitRepo) -> PathBuf {
+    repo.git_dir.join("aigit").join("provider-health.json")
+}
+
+impl ProviderHealth {
+    /// Missing or unreadable state means "healthy"; the breaker never blocks on its own bookkeeping.
+    pub fn load(repo: &GitRepo) -> Self {
+        std::fs::read_to_string(health_path(repo))
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, repo: &GitRepo) -> Result<()> {
+        let path = health_path(repo);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&path, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// Returns the time the breaker closes again if it is currently open for `provider`.
+    pub fn open_until(&self, provider: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.providers
+            .get(provider)
+            .and_then(|s| s.open_until)
+            .filter(|until| *until > now)
+    }
+
+    pub fn record_success(&mut self, provider: &str) {
+        self.providers.remove(provider);
+    }
+
+    /// Counts a failure; returns true when this failure trips the breaker.
+    pub fn record_failure(
+        &mut self,
+        provider: &str,
+        policy: &HealthPolicy,
+        err: &anyhow::Error,
+        now: DateTime<Utc>,
+    ) -> bool {
+        let state = self.providers.entry(provider.to_string()).or_default();
+        state.consecutive_failures += 1;
+        state.last_error = Some(format!("{err:#}"));
+        if state.consecutive_failures >= policy.failure_threshold.unwrap_or(3) {
+            let cooldown = policy.cooldown_secs.unwrap_or(900);
+            state.open_until = Some(now + Duration::seconds(cooldown as i64));
+            return true;
+        }
+        false
+    }
+
+    /// Records the outcome of a provider call, ignoring bookkeeping errors.
+    pub fn record(
+        repo: &GitRepo,
+        provider: &str,
+        policy: &HealthPolicy,
+        outcome: Result<(), &anyhow::Error>,
+    ) -> bool {
+        let mut health = Self::load(repo);
+        let tripped = match outcome {
+            Ok(()) => {
+                if !health.providers.contains_key(provider) {
+                    return false;
+                }
+                health.record_success(provider);
+                false
+            }
+            Err(err) => health.record_failure(provider, policy, err, Utc::now()),
+        };
+        let _ = health.save(repo);
+        tripped
+    }
+}
@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::codex_cli::{exam_schema_json, score_schema_json, truncate_for_error};
+use crate::config::{HttpApiPolicy, Policy};
+
+pub const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// Client for OpenAI-compatible `/chat/completions` endpoints.
+#[derive(Debug, Clone)]
+pub struct HttpApiClient {
+    base_url: String,
+    endpoint: String,
+    api_key_env: String,
+    model: String,
+    response_format: String,
+    timeout: Duration,
+}
+
+impl HttpApiClient {
+    pub fn from_policy(policy: &Policy) -> Self {
+        let cfg: &HttpApiPolicy = &policy.http_api;
+        let base_url = cfg.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL);
+        let base_url = base_url.trim_end_matches('/').to_string();
+        Self {
+            endpoint: format!("{base_url}/chat/completions"),
+            base_url,
+            api_key_env: cfg
+                .api_key_env
+                .clone()
+                .unwrap_or_else(|| "OPENAI_API_KEY".to_string()),
+            model: cfg
+                .model
+                .clone()
+                .or_else(|| policy.model.clone().filter(|m| m != "static"))
+                .unwrap_or_else(|| "gpt-4o-mini".to_string()),
+            response_format: cfg
+                .response_format
+                .clone()
+                .unwrap_or_else(|| "json_schema".to_string()),
+            timeout: Duration::from_secs(cfg.timeout_secs.unwrap_or(120)),
+        }
+    }
+
+    /// Cheap reachability check: `GET {base_url}/models`. Any HTTP answer other than an auth
+    /// rejection counts as up, since not every compatible server implements that route.
+    pub fn ping(&self, timeout: Duration) -> Result<()> {
+        let url = format!("{}/models", self.base_url);
+        let agent = ureq::AgentBuilder::new().timeout(timeout).build();
+        let mut req = agent.get(&url);
+        if let Ok(key) = std::env::var(&self.api_key_env) {
+            if !key.trim().is_empty() {
+                req = req.set("Authorization", &format!("Bearer {}", key.trim()));
+            }
+        }
+        match req.call() {
+            Ok(_) => Ok(()),
+            Err(ureq::Error::Status(code @ (401 | 403), _)) => Err(anyhow!(
+                "http_api: {url} rejected the credentials in {} (status {code})",
+                self.api_key_env
+            )),
+            Err(ureq::Error::Status(..)) => Ok(()),
+            Err(err) => Err(anyhow!("http_api: {url} is unreachable: {err}")),
+        }
+    }
+
+    pub fn run_json_judge(&self, prompt: &str) -> Result<String> {
+        self.run_json_with_schema(prompt, "aigit_score", &score_schema_json())
+    }
+
+    pub fn run_json_generate_exam(&self, prompt: &str) -> Result<String> {
+        self.run_json_with_schema(prompt, "aigit_exam", &exam_schema_json())
+    }
+
+    fn run_json_with_schema(
+        &self,
+        prompt: &str,
+        schema_name: &str,
+        schema: &serde_json::Value,
+    ) -> Result<String> {
+        let mut schema = schema.clone();
+        if let Some(obj) = schema.as_object_mut() {
+            obj.remove("$schema");
+        }
+        let response_format = match self.response_format.as_str() {
+            "json_schema" => serde_json::json!({
+                "type": "json_schema",
+                "json_schema": { "name": schema_name, "strict": true, "schema": schema },
+            }),
+            "json_object" => serde_json::json!({ "type": "json_object" }),
+            other => {
+                return Err(anyhow!(
+                    "unsupported http_api.response_format: {other} (expected \"json_schema\" or \"json_object\")"
+                ))
+            }
+        };
+        let body = serde_json::json!({
+            "model": self.model,
+            "temperature": 0,
+            "response_format": response_format,
+            "messages": [
+                {
+                    "role": "system",
+                    "content": format!(
+                        "Respond with a single JSON object matching this JSON Schema:\n{}",
+                        serde_json::to_string(&schema)?
+                    ),
+                },
+                { "role": "user", "content": prompt },
+            ],
+        });
+
+        let agent = ureq::AgentBuilder::new().timeout(self.timeout).build();
+        let mut req = agent.post(&self.endpoint);
+        if let Ok(key) = std::env::var(&self.api_key_env) {
+            if !key.trim().is_empty() {
+                req = req.set("Authorization", &format!("Bearer {}", key.trim()));
+            }
+        }
+        let resp = match req.send_json(body) {
+            Ok(resp) => resp,
+            Err(ureq::Error::Status(code, resp)) => {
+                let text = resp.into_string().unwrap_or_default();
+                return Err(anyhow!(
+                    "http_api request to {} failed (status {code}):\n{}",
+                    self.endpoint,
+                    truncate_for_error(&text)
+                ));
+            }
+            Err(err) => {
+                return Err(anyhow!("http_api request to {} failed: {err}", self.endpoint))
+            }
+        };
+        let value: serde_json::Value = resp
+            .into_json()
+            .with_context(|| format!("http_api: invalid JSON response from {}", self.endpoint))?;
+        value["choices"][0]["message"]["content"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| {
+                anyhow!(
+                    "http_api: response has no choices[0].message.content:\n{}",
+                    truncate_for_error(&value.to_string())
+                )
+            })
+    }
+}
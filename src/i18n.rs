@@ -0,0 +1,207 @@
+//! Built-in questions and signal words in other languages than English. Japanese (`ja`) is
+//! translated; any other `language` still reaches the LLM prompts, while the built-in examiner
+//! keeps its English questions.
+
+/// The `language` policy value when it asks for something other than English.
+pub fn non_english(language: Option<&str>) -> Option<&str> {
+    language.filter(|l| !l.eq_ignore_ascii_case("en") && !l.to_lowercase().starts_with("en-"))
+}
+
+/// Name of a language tag for the LLM prompts ("Japanese (ja)"); unknown tags as they are.
+pub fn language_name(tag: &str) -> String {
+    let base = tag.split(['-', '_']).next().unwrap_or(tag).to_lowercase();
+    let name = match base.as_str() {
+        "ja" => "Japanese",
+        "zh" => "Chinese",
+        "ko" => "Korean",
+        "de" => "German",
+        "fr" => "French",
+        "es" => "Spanish",
+        "pt" => "Portuguese",
+        "it" => "Italian",
+        "en" => "English",
+        _ => return tag.to_string(),
+    };
+    format!("{name} ({tag})")
+}
+
+fn is_japanese(language: Option<&str>) -> bool {
+    non_english(language).is_some_and(|l| l.eq_ignore_ascii_case("ja") || l.starts_with("ja-"))
+}
+
+/// A built-in question's prompt in `language`, or `None` to keep the English one.
+pub fn question(language: Option<&str>, id: &str) -> Option<&'static str> {
+    if !is_japanese(language) {
+        return None;
+    }
+    Some(match id {
+        "change_summary" => "何を変更したか(具体的なファイルやモジュール)と、その理由を要約してください。",
+        "intent" => "この変更はどのようなユーザー要件・業務要件を満たしますか?",
+        "invariants" => "この変更はどのような前提に依存していますか?どの不変条件が保たれ続ける必要がありますか?",
+        "risk" => "何が壊れる可能性があり、問題はどこで最初に表面化しますか(影響範囲)?",
+        "testing" => "どのテストを実行しましたか?どのテストが存在すべきですか?不足しているカバレッジは何ですか?",
+        "rollback" => "この変更が問題を引き起こした場合、どのようにロールバック・リバート・緩和しますか?",
+        "alternatives" => "どのような代替案を検討し、なぜそれを採用しなかったのですか?",
+        "security_privacy" => "セキュリティやプライバシー上の懸念(認証・認可、個人情報、シークレット、データアクセス)はありますか?関係ない場合は、その理由を説明してください。",
+        "test_behavior" => "追加・変更したテストはどの振る舞いを固定しますか?テスト名と対象のケースを挙げてください。",
+        "test_gap" => "これらのテストを書くきっかけとなった不足(バグ、欠けていたケース、保護したいリファクタリング)は何ですか?テストがなければ何が見逃されていましたか?",
+        "evidence" => "レビュアーに最初に見てほしい変更行はどれですか?path:line の形式で示してください。",
+        _ => return None,
+    })
+}
+
+/// The work item alignment question about a description titled `title`.
+pub fn work_item_question(language: Option<&str>, title: &str) -> Option<String> {
+    is_japanese(language).then(|| {
+        format!(
+            "この作業の説明には「{title}」とあります。差分は説明どおりのことをすべて行っていますか?説明で約束されているのに欠けているものと、説明に書かれていない差分の変更点を挙げてください。"
+        )
+    })
+}
+
+/// The soundness question for a diff adding `kinds` (unsafe code, locks, ...) to `files`.
+pub fn memory_safety_question(language: Option<&str>, kinds: &str, files: &str) -> Option<String> {
+    is_japanese(language).then(|| {
+        format!(
+            "この変更は {files} に {kinds} を追加します。なぜ健全なのですか?各 unsafe ブロックが依存する不変条件、共有された可変状態をデータ競合から守るもの、ロックがデッドロックしない理由、またはアトミック操作のメモリオーダリングで十分な理由を挙げてください。"
+        )
+    })
+}
+
+/// The performance question for a change that looks performance-sensitive (`what`).
+pub fn performance_question(language: Option<&str>, what: &str) -> Option<String> {
+    is_japanese(language).then(|| {
+        format!(
+            "この変更は性能に影響しそうです({what})。変更したコードの時間計算量とメモリ使用量はどの程度で、以前と比べてどう変わりますか?実行したベンチマークや計測を挙げてください。"
+        )
+    })
+}
+
+/// The semver question for a diff changing the public API (`what`).
+pub fn api_surface_question(language: Option<&str>, what: &str) -> Option<String> {
+    is_japanese(language).then(|| {
+        format!(
+            "この変更は公開 API を変更します({what})。semver ではメジャー、マイナー、パッチのどれに当たり、どの利用者が壊れ、どのように移行すればよいですか?"
+        )
+    })
+}
+
+/// The question for a diff changing configuration (`what`).
+pub fn configuration_question(language: Option<&str>, what: &str) -> Option<String> {
+    is_japanese(language).then(|| {
+        format!(
+            "この変更は設定に触れます({what})。何も設定されていないときのデフォルト値は何で、どの順序で展開し、どの環境(開発、ステージング、本番)に影響しますか?"
+        )
+    })
+}
+
+/// The question for a diff editing CI pipeline files (`what`).
+pub fn ci_pipeline_question(language: Option<&str>, what: &str) -> Option<String> {
+    is_japanese(language).then(|| {
+        format!(
+            "この変更は CI パイプラインを編集します({what})。どのジョブ、ブランチ、リリース、デプロイに影響し、各ステップはどのシークレットやトークンにアクセスでき(フォークやプルリクエストのコードから読めますか)、パイプラインが壊れたときにどう検知して元に戻しますか?"
+        )
+    })
+}
+
+/// A harder follow-up question for `category`.
+pub fn follow_up(language: Option<&str>, category: &str) -> Option<String> {
+    if !is_japanese(language) {
+        return None;
+    }
+    Some(match category {
+        "risk" => "この変更で最も起こりやすい障害モードと、それを最初に明らかにするシグナル(ログ、メトリクス、失敗するテスト)を挙げてください。".to_string(),
+        "testing" => "この変更をリバートした場合に失敗する具体的なテストはどれですか?存在しない場合は、追加するテストを説明してください。".to_string(),
+        "rollback" => "`git revert` だけでは元に戻らないデータや設定も含めて、正確なロールバック手順を説明してください。".to_string(),
+        "security" => "この変更が触れる入力やデータ経路のうち、信頼境界をまたぐものはどれで、どのように検証されていますか?".to_string(),
+        "invariants" => "今後このコードを編集したときに最も壊れやすい不変条件はどれで、現在それを何が守っていますか?".to_string(),
+        other => format!(
+            "{other} についての追加質問:変更されたファイルや関数を具体的に挙げて、もう一度答えてください。"
+        ),
+    })
+}
+
+/// The question asked for a required category the generated exam left out.
+pub fn missing_category_question(language: Option<&str>, category: &str) -> Option<String> {
+    is_japanese(language).then(|| {
+        format!(
+            "この変更の {category} に関する側面を、変更されたファイルを挙げて説明してください。"
+        )
+    })
+}
+
+/// Signal words the built-in grader looks for in `language`, next to the English ones.
+pub fn keywords(language: Option<&str>, category: &str) -> &'static [&'static str] {
+    if !is_japanese(language) {
+        return &[];
+    }
+    match category {
+        "risk" => &["リスク", "壊れ", "障害", "失敗", "エラー", "不具合", "影響"],
+        "testing" => &["テスト", "試験", "単体", "結合"],
+        "rollback" => &[
+            "リバート",
+            "ロールバック",
+            "切り戻",
+            "元に戻",
+            "緩和",
+            "フラグ",
+        ],
+        "security" => &[
+            "認証",
+            "認可",
+            "個人情報",
+            "シークレット",
+            "トークン",
+            "鍵",
+            "暗号",
+        ],
+        "memory_safety" => &[
+            "不変条件",
+            "健全",
+            "競合",
+            "ロック",
+            "デッドロック",
+            "アトミック",
+            "スレッド",
+        ],
+        "api_surface" => &["互換", "破壊的", "メジャー", "マイナー", "移行", "利用者", "非推奨"],
+        "configuration" => &["デフォルト", "環境", "本番", "ステージング", "展開", "フラグ"],
+        "ci_pipeline" => &["ジョブ", "ブランチ", "デプロイ", "シークレット", "トークン", "フォーク"],
+        "performance" => &["計算量", "ベンチマーク", "計測", "性能", "レイテンシ", "メモリ"],
+        _ => &["ファイル", "モジュール", "関数", "行"],
+    }
+}
+
+/// Chinese, Japanese and Korean script, written without spaces between words.
+pub fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{AC00}'..='\u{D7AF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{FF66}'..='\u{FF9F}')
+}
+
+/// Words in `text`: whitespace-separated tokens, with CJK text counted as one word per two
+/// characters so answers in those languages are not all "one word" long.
+pub fn word_count(text: &str) -> usize {
+    text.split_whitespace()
+        .map(|token| {
+            let cjk = token.chars().filter(|c| is_cjk(*c)).count();
+            if cjk == 0 {
+                return 1;
+            }
+            let mut other_runs = 0;
+            let mut in_run = false;
+            for c in token.chars() {
+                let other = c.is_alphanumeric() && !is_cjk(c);
+                if other && !in_run {
+                    other_runs += 1;
+                }
+                in_run = other;
+            }
+            cjk.div_ceil(2) + other_runs
+        })
+        .sum()
+}
@@ -0,0 +1,143 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::IntegrityPolicy;
+use crate::examiner::{Exam, ExamQuestion};
+use crate::transcript::Answers;
+
+/// Answers and texts with fewer words are too short to tell copying from coincidence.
+const DEFAULT_MIN_WORDS: usize = 8;
+
+/// Share of an answer's word pairs found in another text above which it counts as copied.
+const DEFAULT_MAX_SIMILARITY: f64 = 0.8;
+
+/// Where a copied answer came from.
+pub const COMMIT_MESSAGE: &str = "commit message";
+
+/// An answer that is a near-duplicate of the commit message or of another answer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntegrityFlag {
+    pub question_id: String,
+    /// "commit message", or the id of the question whose answer it repeats.
+    pub source: String,
+    /// Share (0..1) of the shorter text's word pairs that appear in the other.
+    pub similarity: f64,
+}
+
+impl std::fmt::Display for IntegrityFlag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let source = if self.source == COMMIT_MESSAGE {
+            "the commit message".to_string()
+        } else {
+            format!("the answer to {}", self.source)
+        };
+        write!(
+            f,
+            "{} repeats {source} ({:.0}% similar)",
+            self.question_id,
+            self.similarity * 100.0
+        )
+    }
+}
+
+/// Open answers copied from the commit message (the pending one for `aigit commit`, the range's
+/// last for `--range`) or from another open answer: word pairs are compared after lowercasing
+/// and dropping punctuation, so reflowed or recased copies still match.
+pub fn check(
+    rules: &IntegrityPolicy,
+    exam: &Exam,
+    answers: &Answers,
+    commit_message: Option<&str>,
+) -> Vec<IntegrityFlag> {
+    let mut out = Vec::new();
+    if !rules.enabled.unwrap_or(true) {
+        return out;
+    }
+    let min_words = rules.min_words.unwrap_or(DEFAULT_MIN_WORDS);
+    let max = rules.max_similarity.unwrap_or(DEFAULT_MAX_SIMILARITY);
+    let long_enough = |text: &str| (word_count(text) >= min_words).then(|| shingles(text));
+    let message = commit_message.and_then(long_enough);
+    let open: Vec<(&ExamQuestion, BTreeSet<String>)> = exam
+        .questions
+        .iter()
+        .filter(|q| is_open(q))
+        .filter_map(|q| Some((q, long_enough(answers.get(&q.id)?)?)))
+        .collect();
+    for (i, (q, words)) in open.iter().enumerate() {
+        if let Some(message) = &message {
+            let similarity = overlap(words, message);
+            if similarity >= max {
+                out.push(IntegrityFlag {
+                    question_id: q.id.clone(),
+                    source: COMMIT_MESSAGE.to_string(),
+                    similarity,
+                });
+                continue;
+            }
+        }
+        if let Some((other, similarity)) = open[..i]
+            .iter()
+            .map(|(other, s)| (other, overlap(words, s)))
+            .find(|(_, similarity)| *similarity >= max)
+        {
+            out.push(IntegrityFlag {
+                question_id: q.id.clone(),
+                source: other.id.clone(),
+                similarity,
+            });
+        }
+    }
+    out
+}
+
+/// Free-text questions; choices, cited locations and the self-check are short by design.
+fn is_open(q: &ExamQuestion) -> bool {
+    q.choices.as_ref().is_none_or(|c| c.is_empty())
+        && !q.is_evidence()
+        && q.id != crate::self_check::QUESTION_ID
+}
+
+/// Lowercased words; CJK text, written without spaces, is split into single characters.
+fn words(text: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    for word in text
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|w| !w.is_empty())
+    {
+        let mut rest = String::new();
+        for c in word.chars() {
+            if crate::i18n::is_cjk(c) {
+                if !rest.is_empty() {
+                    out.push(std::mem::take(&mut rest));
+                }
+                out.push(c.to_string());
+            } else {
+                rest.push(c);
+            }
+        }
+        if !rest.is_empty() {
+            out.push(rest);
+        }
+    }
+    out
+}
+
+fn word_count(text: &str) -> usize {
+    crate::i18n::word_count(text)
+}
+
+/// Adjacent word pairs of the normalized text.
+fn shingles(text: &str) -> BTreeSet<String> {
+    words(text).windows(2).map(|w| w.join(" ")).collect()
+}
+
+/// Overlap coefficient: an answer pasted from a longer message still scores 1.
+fn overlap(a: &BTreeSet<String>, b: &BTreeSet<String>) -> f64 {
+    let smaller = a.len().min(b.len());
+    if smaller == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / smaller as f64
+}
@@ -0,0 +1,195 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+const POINTER_VERSION: &str = "version https://git-lfs.github.com/spec/v1";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LfsChange {
+    pub path: String,
+    /// "added", "deleted" or "replaced".
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_oid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_size: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_oid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_size: Option<u64>,
+}
+
+#[derive(Default)]
+struct PointerSide {
+    oid: Option<String>,
+    size: Option<u64>,
+}
+
+/// Replaces hunks that only touch Git LFS pointer files with a one-line summary, so the examiner
+/// sees "LFS object replaced (size, oid)" instead of pointer text.
+///
+/// With `lfs_dir` set, each summary also notes whether the new object is present in the local
+/// LFS store.
+pub fn summarize_pointers(diff: &str, lfs_dir: Option<&Path>) -> (String, Vec<LfsChange>) {
+    let mut out = String::with_capacity(diff.len());
+    let mut changes = Vec::new();
+    for section in split_file_sections(diff) {
+        match parse_pointer_section(section) {
+            Some((header, change)) => {
+                out.push_str(header);
+                out.push_str(&describe(&change, lfs_dir));
+                out.push('\n');
+                changes.push(change);
+            }
+            None => out.push_str(section),
+        }
+    }
+    (out, changes)
+}
+
+fn split_file_sections(diff: &str) -> Vec<&str> {
+    let mut starts = Vec::new();
+    let mut offset = 0;
+    for line in diff.split_inclusive('\n') {
+        if line.starts_with("diff --git ") {
+            starts.push(offset);
+        }
+        offset += line.len();
+    }
+    if starts.first() != Some(&0) {
+        starts.insert(0, 0);
+    }
+    starts.push(diff.len());
+    starts
+        .windows(2)
+        .map(|w| &diff[w[0]..w[1]])
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Returns the section header (everything before the first hunk) and the parsed change when every
+/// changed line of the section is an LFS pointer field.
+fn parse_pointer_section(section: &str) -> Option<(&str, LfsChange)> {
+    let first = section.lines().next()?;
+    let path = first
+        .strip_prefix("diff --git ")?
+        .rsplit_once(" b/")
+        .map(|(_, b)| b.to_string())?;
+    let hunk_start = section.find("\n@@").map(|i| i + 1)?;
+    let (header, hunks) = section.split_at(hunk_start);
+
+    let mut old = PointerSide::default();
+    let mut new = PointerSide::default();
+    for line in hunks.lines() {
+        if line.starts_with("@@") || line.starts_with('\\') {
+            continue;
+        }
+        let (side, field) = if let Some(rest) = line.strip_prefix('-') {
+            (&mut old, rest)
+        } else if let Some(rest) = line.strip_prefix('+') {
+            (&mut new, rest)
+        } else if let Some(rest) = line.strip_prefix(' ') {
+            // context lines (only present with --unified > 0) must be pointer fields too
+            if !is_pointer_field(rest) {
+                return None;
+            }
+            continue;
+        } else {
+            return None;
+        };
+        if field == POINTER_VERSION {
+            continue;
+        } else if let Some(oid) = field.strip_prefix("oid sha256:") {
+            if oid.len() != 64 || !oid.chars().all(|c| c.is_ascii_hexdigit()) {
+                return None;
+            }
+            side.oid = Some(oid.to_string());
+        } else if let Some(size) = field.strip_prefix("size ") {
+            side.size = Some(size.parse().ok()?);
+        } else {
+            return None;
+        }
+    }
+    if old.oid.is_none() && new.oid.is_none() {
+        return None;
+    }
+
+    let kind = if header.contains("\nnew file mode") {
+        "added"
+    } else if header.contains("\ndeleted file mode") {
+        "deleted"
+    } else {
+        "replaced"
+    };
+    Some((
+        header,
+        LfsChange {
+            path,
+            kind: kind.to_string(),
+            old_oid: old.oid,
+            old_size: old.size,
+            new_oid: new.oid,
+            new_size: new.size,
+        },
+    ))
+}
+
+fn is_pointer_field(line: &str) -> bool {
+    line == POINTER_VERSION || line.starts_with("oid sha256:") || line.starts_with("size ")
+}
+
+fn describe(change: &LfsChange, lfs_dir: Option<&Path>) -> String {
+    let side = |oid: &Option<String>, size: Option<u64>| {
+        let oid = oid
+            .as_deref()
+            .map(|o| format!("sha256:{}", &o[..12]))
+            .unwrap_or_else(|| "sha256:?".to_string());
+        match size {
+            Some(size) => format!("{oid} ({})", human_size(size)),
+            None => oid,
+        }
+    };
+    let mut out = match change.kind.as_str() {
+        "added" => format!(
+            "[aigit: LFS object added: {} {}",
+            change.path,
+            side(&change.new_oid, change.new_size)
+        ),
+        "deleted" => format!(
+            "[aigit: LFS object deleted: {} {}",
+            change.path,
+            side(&change.old_oid, change.old_size)
+        ),
+        _ => format!(
+            "[aigit: LFS object replaced: {} {} -> {}",
+            change.path,
+            side(&change.old_oid, change.old_size),
+            side(&change.new_oid, change.new_size)
+        ),
+    };
+    if let (Some(dir), Some(oid)) = (lfs_dir, &change.new_oid) {
+        let local = dir.join(&oid[..2]).join(&oid[2..4]).join(oid).exists();
+        out.push_str(if local {
+            "; object present locally"
+        } else {
+            "; object not fetched locally"
+        });
+    }
+    out.push(']');
+    out
+}
+
+fn human_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
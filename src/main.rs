@@ -1,12 +1,68 @@
+mod answer_feedback;
+mod answer_key;
+mod answer_template;
+mod anthropic;
+mod api_surface;
 mod app;
+mod approvals;
+mod attestation;
+mod bypass;
+mod cache;
+mod calibration;
+mod central_policy;
+mod claude_cli;
 mod cli;
 mod config;
+mod chain;
+mod ci_pipeline;
+mod config_changes;
+mod context_manifest;
 mod codex_cli;
 mod commands;
+mod decision_engine;
+mod diff_fit;
+mod diff_stats;
+mod exam_quality;
+mod exam_tiers;
+mod exam_tui;
+mod evidence;
 mod examiner;
+mod exec;
+mod failure_report;
 mod git;
+mod health;
+mod http_api;
+mod i18n;
+mod integrity;
+mod lfs;
+mod memory_safety;
+mod notes_sync;
+mod ollama;
+mod packs;
+mod performance;
+mod pending;
+mod precheck;
+mod profile;
+mod progress;
+mod prompt_template;
+mod question_links;
+mod rate_limit;
 mod redact;
+mod remote_store;
+mod repo_id;
+mod respondents;
+mod response_cache;
+mod retake;
+mod risk;
+mod self_check;
+mod session;
+mod signing;
+mod summary;
+mod symbols;
+mod timing;
+mod tokenizer;
 mod transcript;
+mod yaml;
 
 use std::process::ExitCode;
 
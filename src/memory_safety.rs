@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::{MemorySafetyPolicy, Policy};
+use crate::examiner::{Exam, ExamContext, ExamQuestion, QuestionKind};
+use crate::transcript::Score;
+
+/// Id and category of the question asked when Rust code adds unsafe code, locks or atomics.
+pub const QUESTION_ID: &str = "memory_safety";
+pub const CATEGORY: &str = "memory_safety";
+
+/// Specificity a memory-safety answer needs unless `memory_safety.min_specificity` is set.
+const DEFAULT_MIN_SPECIFICITY: f64 = 0.7;
+
+/// A construct whose soundness an exam should ask about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalKind {
+    Unsafe,
+    StaticMut,
+    Lock,
+    Atomic,
+}
+
+impl std::fmt::Display for SignalKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SignalKind::Unsafe => "unsafe code",
+            SignalKind::StaticMut => "static mut",
+            SignalKind::Lock => "locks",
+            SignalKind::Atomic => "atomics",
+        })
+    }
+}
+
+/// Unsafe code, a `static mut`, a lock or an atomic added in a Rust file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemorySafetySignal {
+    pub kind: SignalKind,
+    pub path: String,
+    /// The hunk, as `<path> <hunk header>`.
+    pub hunk: String,
+}
+
+/// What an added line of Rust introduces, if anything worth a soundness question.
+fn classify(line: &str) -> Option<SignalKind> {
+    let code = line.trim_start();
+    if code.starts_with("//") {
+        return None;
+    }
+    if code.contains("static mut ") {
+        Some(SignalKind::StaticMut)
+    } else if ["unsafe {", "unsafe fn", "unsafe impl", "unsafe trait"]
+        .iter()
+        .any(|n| code.contains(n))
+    {
+        Some(SignalKind::Unsafe)
+    } else if ["Mutex", "RwLock", "Condvar", ".lock()"]
+        .iter()
+        .any(|n| code.contains(n))
+    {
+        Some(SignalKind::Lock)
+    } else if code.contains("Atomic") || code.contains("Ordering::") {
+        Some(SignalKind::Atomic)
+    } else {
+        None
+    }
+}
+
+/// Every kind of signal in each hunk of a `.rs` file, from the added lines of `diff`.
+pub fn detect(diff: &str) -> Vec<MemorySafetySignal> {
+    let mut out: Vec<MemorySafetySignal> = Vec::new();
+    for hunk in crate::question_links::hunks(diff) {
+        if !hunk.path.ends_with(".rs") {
+            continue;
+        }
+        for line in hunk.text.iter().skip(1) {
+            let Some(kind) = line.strip_prefix('+').and_then(classify) else {
+                continue;
+            };
+            let signal = MemorySafetySignal {
+                kind,
+                path: hunk.path.to_string(),
+                hunk: hunk.reference(),
+            };
+            if !out.contains(&signal) {
+                out.push(signal);
+            }
+        }
+    }
+    out
+}
+
+/// The kinds found, in order, and the files they are in.
+fn summary(signals: &[MemorySafetySignal]) -> (Vec<SignalKind>, Vec<String>) {
+    let mut kinds: Vec<SignalKind> = signals.iter().map(|s| s.kind).collect();
+    kinds.sort();
+    kinds.dedup();
+    let mut paths: Vec<String> = Vec::new();
+    for s in signals {
+        if !paths.contains(&s.path) {
+            paths.push(s.path.clone());
+        }
+    }
+    (kinds, paths)
+}
+
+fn kinds_text(kinds: &[SignalKind]) -> String {
+    kinds
+        .iter()
+        .map(|k| k.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The line the generate prompt adds when the diff needs a memory-safety question.
+pub fn prompt_requirement(ctx: &ExamContext) -> Option<String> {
+    if !enabled(&ctx.policy) || ctx.memory_safety.is_empty() {
+        return None;
+    }
+    let (kinds, paths) = summary(&ctx.memory_safety);
+    Some(format!(
+        "- The diff adds {} in {}. Ask one question with id `{QUESTION_ID}` and category `{CATEGORY}` on why it is sound: the invariants that make each unsafe block safe, what guards shared mutable state, lock ordering and deadlocks, and the memory ordering atomics rely on. Link the hunks that add them.\n",
+        kinds_text(&kinds),
+        paths.join(", ")
+    ))
+}
+
+/// Adds the memory-safety question when the diff needs one and the exam has none.
+pub fn ensure_question(ctx: &ExamContext, exam: &mut Exam) {
+    if !enabled(&ctx.policy)
+        || ctx.memory_safety.is_empty()
+        || exam.questions.iter().any(|q| q.category == CATEGORY)
+    {
+        return;
+    }
+    let (kinds, paths) = summary(&ctx.memory_safety);
+    let kinds = kinds_text(&kinds);
+    let files = paths.join(", ");
+    let prompt = crate::i18n::memory_safety_question(ctx.policy.language.as_deref(), &kinds, &files)
+        .unwrap_or_else(|| {
+            format!(
+                "This change adds {kinds} in {files}. Why is it sound? Name the invariant each unsafe block relies on, what guards shared mutable state against data races, and why the locks cannot deadlock or the atomics' memory ordering is enough."
+            )
+        });
+    let mut hunks: Vec<String> = Vec::new();
+    for s in &ctx.memory_safety {
+        if !hunks.contains(&s.hunk) {
+            hunks.push(s.hunk.clone());
+        }
+    }
+    exam.questions.push(ExamQuestion {
+        id: QUESTION_ID.to_string(),
+        category: CATEGORY.to_string(),
+        prompt,
+        choices: None,
+        related_paths: paths,
+        related_hunks: hunks,
+        correct_choice: None,
+        kind: Some(QuestionKind::Open),
+    });
+}
+
+fn enabled(policy: &Policy) -> bool {
+    policy.memory_safety.enabled.unwrap_or(true)
+}
+
+/// Memory-safety answers less specific than `min_specificity`, as failure reasons.
+pub fn shortfalls(policy: &MemorySafetyPolicy, score: &Score) -> Vec<String> {
+    if !policy.enabled.unwrap_or(true) {
+        return Vec::new();
+    }
+    let min_specificity = policy.min_specificity.unwrap_or(DEFAULT_MIN_SPECIFICITY);
+    score
+        .per_question
+        .iter()
+        .filter(|q| q.category == CATEGORY && q.specificity < min_specificity)
+        .map(|q| {
+            format!(
+                "{} answer is not specific enough about the unsafe or concurrent code (specificity {:.2} < {:.2})",
+                q.id, q.specificity, min_specificity
+            )
+        })
+        .collect()
+}
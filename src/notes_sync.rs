@@ -0,0 +1,343 @@
+use std::collections::BTreeMap;
+use std::process::{Command, Output};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::config::Policy;
+use crate::git::GitRepo;
+use crate::transcript::NOTES_REF;
+
+const DEFAULT_REMOTE: &str = "origin";
+
+/// Pushes that lose a race with another one are retried (after a merge) this many times.
+const PUSH_ATTEMPTS: usize = 3;
+
+/// How a commit with different transcripts on both sides is resolved (`notes.merge`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MergeStrategy {
+    /// The later exam, then the one with more countersignatures; ours on a tie.
+    Newest,
+    Ours,
+    Theirs,
+}
+
+impl MergeStrategy {
+    pub fn from_policy(policy: &Policy) -> Self {
+        match policy.notes.merge.as_deref() {
+            Some("ours") => Self::Ours,
+            Some("theirs") => Self::Theirs,
+            _ => Self::Newest,
+        }
+    }
+}
+
+/// The remote to sync with: `--remote`, else `notes.remote`, else "origin".
+pub fn remote(policy: &Policy, flag: Option<&str>) -> String {
+    flag.or(policy.notes.remote.as_deref())
+        .unwrap_or(DEFAULT_REMOTE)
+        .to_string()
+}
+
+fn local_ref() -> String {
+    format!("refs/notes/{NOTES_REF}")
+}
+
+/// Where the remote's notes are fetched to before they are merged.
+pub fn tracking_ref(remote: &str) -> String {
+    format!("refs/notes/remotes/{remote}/{NOTES_REF}")
+}
+
+/// What a fetch brought in.
+#[derive(Debug, Default)]
+pub struct FetchReport {
+    /// Whether the remote has any transcripts.
+    pub found: bool,
+    /// Commits that had no transcript here before.
+    pub added: usize,
+    /// Commits whose transcript changed on both sides.
+    pub conflicts: usize,
+    /// Conflicts resolved in favour of the remote's transcript.
+    pub theirs_won: usize,
+}
+
+/// Fetches the remote's transcripts and merges them into `refs/notes/aigit`.
+pub fn fetch(repo: &GitRepo, remote: &str, strategy: MergeStrategy) -> Result<FetchReport> {
+    let tracking = tracking_ref(remote);
+    let out = git(
+        repo,
+        &[
+            "fetch",
+            "--no-tags",
+            remote,
+            &format!("+{}:{tracking}", local_ref()),
+        ],
+    )?;
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        if stderr.contains("couldn't find remote ref") {
+            return Ok(FetchReport::default());
+        }
+        return Err(anyhow!(
+            "git fetch {remote} {} failed: {}",
+            local_ref(),
+            stderr.trim()
+        ));
+    }
+    let mut report = FetchReport {
+        found: true,
+        ..FetchReport::default()
+    };
+    let theirs = notes_at(repo, &tracking)?;
+    let Some(local) = rev_parse(repo, &local_ref()) else {
+        git_ok(repo, &["update-ref", &local_ref(), &tracking])?;
+        report.added = theirs.len();
+        return Ok(report);
+    };
+    let remote_sha = rev_parse(repo, &tracking).unwrap_or_default();
+    if remote_sha == local || is_ancestor(repo, &remote_sha, &local) {
+        return Ok(report);
+    }
+
+    let ours = notes_at(repo, &local)?;
+    let base = match git_ok(repo, &["merge-base", &local, &remote_sha]) {
+        Ok(sha) => notes_at(repo, sha.trim())?,
+        Err(_) => BTreeMap::new(),
+    };
+    report.added = theirs.keys().filter(|c| !ours.contains_key(*c)).count();
+    let conflicts: Vec<(&String, &String)> = theirs
+        .iter()
+        .filter(|(commit, blob)| {
+            let before = base.get(*commit);
+            ours.get(*commit)
+                .is_some_and(|mine| mine != *blob && Some(mine) != before && Some(*blob) != before)
+        })
+        .collect();
+    report.conflicts = conflicts.len();
+
+    let side = match strategy {
+        MergeStrategy::Theirs => "theirs",
+        MergeStrategy::Newest | MergeStrategy::Ours => "ours",
+    };
+    git_ok(
+        repo,
+        &[
+            "notes",
+            &format!("--ref={NOTES_REF}"),
+            "merge",
+            "--quiet",
+            "-s",
+            side,
+            &tracking,
+        ],
+    )
+    .with_context(|| format!("failed to merge the transcripts of {remote}"))?;
+    match strategy {
+        MergeStrategy::Ours => {}
+        MergeStrategy::Theirs => report.theirs_won = conflicts.len(),
+        MergeStrategy::Newest => {
+            for (commit, blob) in conflicts {
+                if recency(repo, blob) > recency(repo, &ours[commit]) {
+                    git_ok(
+                        repo,
+                        &[
+                            "notes",
+                            &format!("--ref={NOTES_REF}"),
+                            "add",
+                            "-f",
+                            "-C",
+                            blob,
+                            commit,
+                        ],
+                    )?;
+                    report.theirs_won += 1;
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// What a push did.
+#[derive(Debug)]
+pub struct PushReport {
+    /// The remote already had everything.
+    pub up_to_date: bool,
+    /// The remote had moved on; its transcripts were merged in before pushing again.
+    pub merged: Option<FetchReport>,
+}
+
+/// Pushes `refs/notes/aigit`, fetching and merging first whenever the remote has moved on.
+pub fn push(repo: &GitRepo, remote: &str, strategy: MergeStrategy) -> Result<PushReport> {
+    if rev_parse(repo, &local_ref()).is_none() {
+        return Err(anyhow!(
+            "no transcripts to push ({} does not exist)",
+            local_ref()
+        ));
+    }
+    let mut merged: Option<FetchReport> = None;
+    for _ in 0..PUSH_ATTEMPTS {
+        let out = git(
+            repo,
+            &[
+                "push",
+                "--porcelain",
+                remote,
+                &format!("{0}:{0}", local_ref()),
+            ],
+        )?;
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let flag = stdout
+            .lines()
+            .find(|l| l.contains(&local_ref()))
+            .and_then(|l| l.chars().next());
+        if out.status.success() {
+            return Ok(PushReport {
+                up_to_date: flag == Some('='),
+                merged,
+            });
+        }
+        if flag != Some('!') {
+            return Err(anyhow!(
+                "git push {remote} {} failed: {}",
+                local_ref(),
+                String::from_utf8_lossy(&out.stderr).trim()
+            ));
+        }
+        let report = fetch(repo, remote, strategy)?;
+        merged = Some(match merged {
+            Some(before) => FetchReport {
+                found: true,
+                added: before.added + report.added,
+                conflicts: before.conflicts + report.conflicts,
+                theirs_won: before.theirs_won + report.theirs_won,
+            },
+            None => report,
+        });
+    }
+    Err(anyhow!(
+        "{remote} kept rejecting {} after {PUSH_ATTEMPTS} merges; try again",
+        local_ref()
+    ))
+}
+
+/// How the local transcripts compare with a remote's.
+#[derive(Debug)]
+pub struct Status {
+    pub local: usize,
+    /// The remote's transcript count, when its notes are known here (after a fetch).
+    pub remote: Option<usize>,
+    pub only_local: usize,
+    pub only_remote: usize,
+    pub state: SyncState,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SyncState {
+    Empty,
+    InSync,
+    NotPushed,
+    Ahead,
+    Behind,
+    Diverged,
+    /// The remote's notes moved to a commit not fetched yet.
+    Unknown,
+}
+
+/// Compares `refs/notes/aigit` with the remote's (asked with `git ls-remote`).
+pub fn status(repo: &GitRepo, remote: &str) -> Result<Status> {
+    let out = git_ok(repo, &["ls-remote", remote, &local_ref()])
+        .with_context(|| format!("failed to list the notes of {remote}"))?;
+    let remote_sha = out.split_whitespace().next().map(str::to_string);
+    let local_sha = rev_parse(repo, &local_ref());
+    let ours = match &local_sha {
+        Some(sha) => notes_at(repo, sha)?,
+        None => BTreeMap::new(),
+    };
+    let theirs = match &remote_sha {
+        Some(sha) if rev_parse(repo, &format!("{sha}^{{commit}}")).is_some() => {
+            Some(notes_at(repo, sha)?)
+        }
+        _ => None,
+    };
+    let state = match (&local_sha, &remote_sha) {
+        (None, None) => SyncState::Empty,
+        (Some(_), None) => SyncState::NotPushed,
+        (Some(l), Some(r)) if l == r => SyncState::InSync,
+        (_, Some(_)) if theirs.is_none() => SyncState::Unknown,
+        (None, Some(_)) => SyncState::Behind,
+        (Some(l), Some(r)) if is_ancestor(repo, r, l) => SyncState::Ahead,
+        (Some(l), Some(r)) if is_ancestor(repo, l, r) => SyncState::Behind,
+        _ => SyncState::Diverged,
+    };
+    Ok(Status {
+        local: ours.len(),
+        remote: theirs.as_ref().map(BTreeMap::len),
+        only_local: ours
+            .keys()
+            .filter(|c| theirs.as_ref().is_some_and(|t| !t.contains_key(*c)))
+            .count(),
+        only_remote: theirs
+            .as_ref()
+            .map_or(0, |t| t.keys().filter(|c| !ours.contains_key(*c)).count()),
+        state,
+    })
+}
+
+/// Annotated commit → note blob in the notes commit `rev`.
+fn notes_at(repo: &GitRepo, rev: &str) -> Result<BTreeMap<String, String>> {
+    let out = git_ok(repo, &["ls-tree", "-r", rev])?;
+    Ok(out
+        .lines()
+        .filter_map(|line| {
+            let (meta, path) = line.split_once('\t')?;
+            let blob = meta.split_whitespace().nth(2)?;
+            Some((path.replace('/', ""), blob.to_string()))
+        })
+        .collect())
+}
+
+/// What "newest" compares: when the exam was taken, then how many countersigned it.
+fn recency(repo: &GitRepo, blob: &str) -> Option<(DateTime<Utc>, usize)> {
+    #[derive(Deserialize)]
+    struct Stamp {
+        timestamp: DateTime<Utc>,
+        #[serde(default)]
+        countersignatures: Vec<serde_json::Value>,
+    }
+    let raw = git_ok(repo, &["cat-file", "blob", blob]).ok()?;
+    let stamp: Stamp = serde_json::from_str(&raw).ok()?;
+    Some((stamp.timestamp, stamp.countersignatures.len()))
+}
+
+fn rev_parse(repo: &GitRepo, rev: &str) -> Option<String> {
+    git_ok(repo, &["rev-parse", "--verify", "--quiet", rev])
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+fn is_ancestor(repo: &GitRepo, ancestor: &str, descendant: &str) -> bool {
+    git(repo, &["merge-base", "--is-ancestor", ancestor, descendant])
+        .is_ok_and(|out| out.status.success())
+}
+
+fn git(repo: &GitRepo, args: &[&str]) -> Result<Output> {
+    Command::new("git")
+        .current_dir(&repo.workdir)
+        .args(args)
+        .output()
+        .context("failed to run git")
+}
+
+fn git_ok(repo: &GitRepo, args: &[&str]) -> Result<String> {
+    let out = git(repo, args)?;
+    if !out.status.success() {
+        return Err(anyhow!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&out.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8(out.stdout)?)
+}
@@ -0,0 +1,119 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::codex_cli::{exam_schema_json, score_schema_json, truncate_for_error};
+use crate::config::{OllamaPolicy, Policy};
+
+pub const DEFAULT_HOST: &str = "http://localhost:11434";
+
+/// The Ollama server could not be reached at all (as opposed to answering with an error).
+#[derive(Debug)]
+pub struct Unreachable(pub String);
+
+impl std::fmt::Display for Unreachable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ollama server unreachable: {}", self.0)
+    }
+}
+
+impl std::error::Error for Unreachable {}
+
+/// Client for a local Ollama server's `/api/chat` endpoint, constrained to the exam/score JSON
+/// schemas through Ollama's `format` field.
+#[derive(Debug, Clone)]
+pub struct OllamaClient {
+    host: String,
+    model: String,
+    temperature: f64,
+    timeout: Duration,
+}
+
+impl OllamaClient {
+    pub fn from_policy(policy: &Policy) -> Self {
+        let cfg: &OllamaPolicy = &policy.ollama;
+        Self {
+            host: cfg
+                .host
+                .as_deref()
+                .unwrap_or(DEFAULT_HOST)
+                .trim_end_matches('/')
+                .to_string(),
+            model: cfg
+                .model
+                .clone()
+                .or_else(|| policy.model.clone().filter(|m| m != "static"))
+                .unwrap_or_else(|| "llama3.1".to_string()),
+            temperature: cfg.temperature.unwrap_or(0.0),
+            timeout: Duration::from_secs(cfg.timeout_secs.unwrap_or(300)),
+        }
+    }
+
+    /// Cheap reachability check: `GET /api/tags`.
+    pub fn ping(&self, timeout: Duration) -> Result<()> {
+        let url = format!("{}/api/tags", self.host);
+        let agent = ureq::AgentBuilder::new().timeout(timeout).build();
+        match agent.get(&url).call() {
+            Ok(_) => Ok(()),
+            Err(ureq::Error::Status(code, _)) => {
+                Err(anyhow!("ollama: {url} answered with status {code}"))
+            }
+            Err(err) => Err(anyhow::Error::new(Unreachable(format!("{url}: {err}")))),
+        }
+    }
+
+    pub fn run_json_judge(&self, prompt: &str) -> Result<String> {
+        self.run_json_with_schema(prompt, &score_schema_json())
+    }
+
+    pub fn run_json_generate_exam(&self, prompt: &str) -> Result<String> {
+        self.run_json_with_schema(prompt, &exam_schema_json())
+    }
+
+    fn run_json_with_schema(&self, prompt: &str, schema: &serde_json::Value) -> Result<String> {
+        let mut schema = schema.clone();
+        if let Some(obj) = schema.as_object_mut() {
+            obj.remove("$schema");
+        }
+        let body = serde_json::json!({
+            "model": self.model,
+            "stream": false,
+            "format": schema,
+            "options": { "temperature": self.temperature },
+            "messages": [{ "role": "user", "content": prompt }],
+        });
+        let endpoint = format!("{}/api/chat", self.host);
+        let agent = ureq::AgentBuilder::new().timeout(self.timeout).build();
+        let resp = match agent.post(&endpoint).send_json(body) {
+            Ok(resp) => resp,
+            Err(ureq::Error::Status(code, resp)) => {
+                let text = resp.into_string().unwrap_or_default();
+                return Err(anyhow!(
+                    "ollama request to {endpoint} failed (status {code}):\n{}",
+                    truncate_for_error(&text)
+                ));
+            }
+            Err(ureq::Error::Transport(t))
+                if matches!(
+                    t.kind(),
+                    ureq::ErrorKind::ConnectionFailed | ureq::ErrorKind::Dns
+                ) =>
+            {
+                return Err(anyhow::Error::new(Unreachable(format!("{endpoint}: {t}"))));
+            }
+            Err(err) => return Err(anyhow!("ollama request to {endpoint} failed: {err}")),
+        };
+        let value: serde_json::Value = resp
+            .into_json()
+            .with_context(|| format!("ollama: invalid JSON response from {endpoint}"))?;
+        value["message"]["content"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| {
+                anyhow!(
+                    "ollama: response has no message.content:\n{}",
+                    truncate_for_error(&value.to_string())
+                )
+            })
+    }
+}
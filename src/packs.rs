@@ -0,0 +1,237 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{BankQuestion, CustomCategory, Policy};
+use crate::git::{Git, GitRepo};
+
+/// Schema every pack manifest declares.
+pub const PACK_SCHEMA: &str = "aigit-pack/0.1";
+
+/// File at the root of a pack (its repository or directory) describing it.
+pub const MANIFEST: &str = "aigit-pack.toml";
+
+/// A question pack: reusable `[[categories]]` and `[[questions]]` published by domain experts
+/// and installed with `aigit packs add`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PackManifest {
+    pub schema: String,
+    /// Pack name, referenced from `packs` in the policy.
+    pub name: String,
+    /// Dotted version numbers, e.g. "1.2.0".
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub categories: Vec<CustomCategory>,
+    #[serde(default)]
+    pub questions: Vec<BankQuestion>,
+}
+
+/// Where an installed pack came from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackSource {
+    /// Git URL or local path given to `aigit packs add`.
+    pub source: String,
+    /// Commit the manifest was read from, for packs installed from git.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    pub installed_at: DateTime<Utc>,
+}
+
+impl PackManifest {
+    /// Parses and validates a manifest; `origin` names it in errors.
+    pub fn parse(raw: &str, origin: &str) -> Result<Self> {
+        let pack: Self = toml::from_str(raw)
+            .map_err(|err| anyhow!("{origin}: not a valid pack manifest: {err}"))?;
+        pack.validate()
+            .map_err(|err| anyhow!("{origin}: invalid pack: {err}"))?;
+        Ok(pack)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.schema != PACK_SCHEMA {
+            return Err(anyhow!(
+                "unsupported schema {:?} (expected {PACK_SCHEMA:?})",
+                self.schema
+            ));
+        }
+        if self.name.is_empty()
+            || self.name.starts_with('.')
+            || !self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            return Err(anyhow!(
+                "name {:?} must be letters, digits, '-', '_' or '.'",
+                self.name
+            ));
+        }
+        if !self
+            .version
+            .split('.')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+        {
+            return Err(anyhow!(
+                "version {:?} must be dotted numbers, e.g. \"1.2.0\"",
+                self.version
+            ));
+        }
+        if self.questions.is_empty() && self.categories.is_empty() {
+            return Err(anyhow!("a pack needs at least one question or category"));
+        }
+        let mut ids = std::collections::BTreeSet::new();
+        for (i, q) in self.questions.iter().enumerate() {
+            if q.category.trim().is_empty() || q.prompt.trim().is_empty() {
+                return Err(anyhow!(
+                    "questions[{i}]: category and prompt must not be empty"
+                ));
+            }
+            if !ids.insert(question_id(i, q)) {
+                return Err(anyhow!(
+                    "questions[{i}]: duplicate id {}",
+                    question_id(i, q)
+                ));
+            }
+            for p in &q.paths {
+                globset::Glob::new(p)
+                    .with_context(|| format!("questions[{i}]: invalid path glob {p:?}"))?;
+            }
+        }
+        for cat in &self.categories {
+            if cat.id.trim().is_empty() {
+                return Err(anyhow!("categories: id must not be empty"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Id of a pack question within its pack (default `q<n>`).
+fn question_id(index: usize, q: &BankQuestion) -> String {
+    match &q.id {
+        Some(id) if !id.trim().is_empty() => id.clone(),
+        _ => format!("q{}", index + 1),
+    }
+}
+
+pub fn packs_dir(repo: &GitRepo) -> PathBuf {
+    repo.workdir.join(".aigit").join("packs")
+}
+
+/// Reads a pack manifest from a local directory (or manifest file), or from a git repository
+/// at `git_ref` (default its HEAD). Returns the manifest text and, for git, the commit.
+pub fn fetch(source: &str, git_ref: Option<&str>) -> Result<(String, Option<String>)> {
+    let path = Path::new(source);
+    if path.exists() && git_ref.is_none() {
+        let file = if path.is_dir() {
+            path.join(MANIFEST)
+        } else {
+            path.to_path_buf()
+        };
+        let raw = std::fs::read_to_string(&file)
+            .with_context(|| format!("failed to read {}", file.display()))?;
+        return Ok((raw, None));
+    }
+    let tmp = tempfile::tempdir().context("failed to create a temp dir for the pack")?;
+    let git = Git::new(GitRepo::clone_history(source, &tmp.path().join("pack"))?);
+    let rev = git_ref.unwrap_or("HEAD");
+    let commit = git
+        .resolve_commitish(&format!("{rev}^{{commit}}"))
+        .or_else(|_| git.resolve_commitish(&format!("origin/{rev}^{{commit}}")))
+        .with_context(|| format!("{source} has no branch, tag or commit {rev}"))?;
+    let raw = git
+        .show_file(&commit, MANIFEST)
+        .with_context(|| format!("{source} has no {MANIFEST} at {rev}"))?;
+    Ok((raw, Some(commit)))
+}
+
+/// Writes a validated pack to `.aigit/packs/<name>/`, replacing any installed version.
+pub fn install(repo: &GitRepo, raw: &str, pack: &PackManifest, source: PackSource) -> Result<()> {
+    let dir = packs_dir(repo).join(&pack.name);
+    std::fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let manifest = dir.join(MANIFEST);
+    std::fs::write(&manifest, raw)
+        .with_context(|| format!("failed to write {}", manifest.display()))?;
+    let meta = dir.join("source.json");
+    std::fs::write(&meta, serde_json::to_vec_pretty(&source)?)
+        .with_context(|| format!("failed to write {}", meta.display()))
+}
+
+/// The installed pack `name`, if any, with where it came from.
+pub fn load(repo: &GitRepo, name: &str) -> Result<Option<(PackManifest, Option<PackSource>)>> {
+    let dir = packs_dir(repo).join(name);
+    let manifest = dir.join(MANIFEST);
+    let raw = match std::fs::read_to_string(&manifest) {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read {}", manifest.display()))
+        }
+    };
+    let pack = PackManifest::parse(&raw, &manifest.display().to_string())?;
+    let source = std::fs::read(dir.join("source.json"))
+        .ok()
+        .and_then(|raw| serde_json::from_slice(&raw).ok());
+    Ok(Some((pack, source)))
+}
+
+/// Every installed pack, by name.
+pub fn installed(repo: &GitRepo) -> Result<Vec<(PackManifest, Option<PackSource>)>> {
+    let Ok(dir) = std::fs::read_dir(packs_dir(repo)) else {
+        return Ok(Vec::new());
+    };
+    let mut names: Vec<String> = dir
+        .flatten()
+        .filter(|e| e.path().join(MANIFEST).is_file())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    let mut out = Vec::new();
+    for name in names {
+        out.extend(load(repo, &name)?);
+    }
+    Ok(out)
+}
+
+/// Splits a `packs` entry into the pack name and the pinned version, if any.
+pub fn parse_ref(entry: &str) -> (&str, Option<&str>) {
+    match entry.split_once('@') {
+        Some((name, version)) => (name, Some(version)),
+        None => (entry, None),
+    }
+}
+
+/// Adds the categories and questions of every pack the policy lists in `packs`. Pack questions
+/// get `<pack>.<id>` exam ids.
+pub fn apply(repo: &GitRepo, policy: &mut Policy) -> Result<()> {
+    apply_entries(repo, policy, &policy.packs.clone())
+}
+
+/// Adds the categories and questions of the packs named by `entries` ("name" or
+/// "name@version").
+pub fn apply_entries(repo: &GitRepo, policy: &mut Policy, entries: &[String]) -> Result<()> {
+    for entry in entries {
+        let (name, want) = parse_ref(entry);
+        let (pack, _) = load(repo, name)?.ok_or_else(|| {
+            anyhow!("policy uses pack {name}, which is not installed; run `aigit packs add <url>`")
+        })?;
+        if want.is_some_and(|v| v != pack.version) {
+            return Err(anyhow!(
+                "policy pins pack {entry}, but {name} {} is installed",
+                pack.version
+            ));
+        }
+        policy.categories.extend(pack.categories.iter().cloned());
+        for (i, q) in pack.questions.iter().enumerate() {
+            let mut q = q.clone();
+            q.id = Some(format!("{name}.{}", question_id(i, &q)));
+            policy.questions.push(q);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::examiner::Exam;
+use crate::git::GitRepo;
+use crate::transcript::Answers;
+
+/// An answered exam saved before grading, so a provider timeout or crash does not lose the
+/// typed answers. `aigit grade --resume` picks it up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingExam {
+    pub created_at: DateTime<Utc>,
+    /// "commit" or "exam": what to do once grading succeeds.
+    pub command: String,
+    /// Diff range examined (`None` = staged changes).
+    #[serde(default)]
+    pub range: Option<String>,
+    pub diff_patch_id: String,
+    pub exam: Exam,
+    pub answers: Answers,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub git_args: Vec<String>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+fn pending_path(repo: &GitRepo) -> PathBuf {
+    repo.git_dir.join("aigit").join("pending-exam.json")
+}
+
+impl PendingExam {
+    pub fn save(&self, repo: &GitRepo) -> Result<()> {
+        let path = pending_path(repo);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&path, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    pub fn load(repo: &GitRepo) -> Result<Self> {
+        let path = pending_path(repo);
+        let raw = match std::fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                return Err(anyhow!("no saved exam to resume"));
+            }
+            Err(err) => return Err(err).with_context(|| format!("failed to read {}", path.display())),
+        };
+        serde_json::from_str(&raw).with_context(|| format!("failed to parse {}", path.display()))
+    }
+
+    pub fn clear(repo: &GitRepo) {
+        let _ = std::fs::remove_file(pending_path(repo));
+    }
+}
@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+
+use crate::examiner::{Exam, ExamContext, ExamQuestion, QuestionKind};
+
+/// Id and category of the question asked about performance-sensitive changes. The category is
+/// not part of the full exam; it is asked only when a change needs it.
+pub const QUESTION_ID: &str = "performance";
+pub const CATEGORY: &str = "performance";
+
+/// Files whose loops are not code (docs, data).
+const NOT_CODE: &[&str] = &[
+    ".md", ".txt", ".adoc", ".rst", ".json", ".yaml", ".yml", ".toml",
+];
+
+/// Complexity notes in added lines.
+const COMPLEXITY_HINTS: &[&str] = &["O(n", "O(m", "O(log", "O(1)", "quadratic", "exponential"];
+
+/// Why a change looks performance-sensitive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PerfSignalKind {
+    /// A file matching `perf_paths` changed.
+    HotPath,
+    /// A loop was added inside another loop.
+    NestedLoop,
+    /// An added line mentions complexity ("O(n^2)", "quadratic", ...).
+    ComplexityHint,
+}
+
+impl std::fmt::Display for PerfSignalKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            PerfSignalKind::HotPath => "hot path",
+            PerfSignalKind::NestedLoop => "nested loop",
+            PerfSignalKind::ComplexityHint => "complexity note",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PerfSignal {
+    pub kind: PerfSignalKind,
+    pub path: String,
+    /// The hunk, as `<path> <hunk header>`; absent for a hot path changed as a whole.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hunk: Option<String>,
+}
+
+fn is_loop(code: &str) -> bool {
+    ["for ", "while ", "loop {", "loop{"]
+        .iter()
+        .any(|k| code.starts_with(k))
+        || code.contains(".for_each(")
+}
+
+/// Hot paths among the changed files, and nested loops and complexity notes in added code.
+pub fn detect(perf_paths: &[String], diff: &str, changed_files: &[String]) -> Vec<PerfSignal> {
+    let mut out: Vec<PerfSignal> = Vec::new();
+    if let Ok(set) = crate::config::build_globset(perf_paths) {
+        for f in changed_files.iter().filter(|f| set.is_match(f.as_str())) {
+            out.push(PerfSignal {
+                kind: PerfSignalKind::HotPath,
+                path: f.clone(),
+                hunk: None,
+            });
+        }
+    }
+    for hunk in crate::question_links::hunks(diff) {
+        if NOT_CODE.iter().any(|ext| hunk.path.ends_with(ext)) {
+            continue;
+        }
+        // Indentation of the loops still open at each line, judged by indentation alone.
+        let mut open: Vec<usize> = Vec::new();
+        for line in hunk.text.iter().skip(1) {
+            let (added, text) = match line.split_at_checked(1) {
+                Some(("+", text)) => (true, text),
+                Some((" ", text)) => (false, text),
+                _ => continue,
+            };
+            let code = text.trim_start();
+            if code.is_empty() || code.starts_with("//") || code.starts_with('#') {
+                continue;
+            }
+            let indent = text.len() - code.len();
+            while open.last().is_some_and(|&i| i >= indent) {
+                open.pop();
+            }
+            let mut push = |kind| {
+                let signal = PerfSignal {
+                    kind,
+                    path: hunk.path.to_string(),
+                    hunk: Some(hunk.reference()),
+                };
+                if !out.contains(&signal) {
+                    out.push(signal);
+                }
+            };
+            if added && COMPLEXITY_HINTS.iter().any(|h| code.contains(h)) {
+                push(PerfSignalKind::ComplexityHint);
+            }
+            if is_loop(code) {
+                if added && !open.is_empty() {
+                    push(PerfSignalKind::NestedLoop);
+                }
+                open.push(indent);
+            }
+        }
+    }
+    out
+}
+
+/// "nested loop in src/rank.rs, hot path src/index.rs".
+fn describe(signals: &[PerfSignal]) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    for s in signals {
+        let part = format!("{} in {}", s.kind, s.path);
+        if !parts.contains(&part) {
+            parts.push(part);
+        }
+    }
+    parts.join(", ")
+}
+
+/// The line the generate prompt adds when the diff needs a performance question.
+pub fn prompt_requirement(ctx: &ExamContext) -> Option<String> {
+    if ctx.performance.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "- The change looks performance-sensitive ({}). Ask one question with id `{QUESTION_ID}` and category `{CATEGORY}` on the expected time and memory complexity of the changed code compared with before, and which benchmarks or measurements back it up.\n",
+        describe(&ctx.performance)
+    ))
+}
+
+/// Adds the performance question when the change needs one and the exam has none.
+pub fn ensure_question(ctx: &ExamContext, exam: &mut Exam) {
+    if ctx.performance.is_empty() || exam.questions.iter().any(|q| q.category == CATEGORY) {
+        return;
+    }
+    let what = describe(&ctx.performance);
+    let prompt = crate::i18n::performance_question(ctx.policy.language.as_deref(), &what)
+        .unwrap_or_else(|| {
+            format!(
+                "This change looks performance-sensitive ({what}). What is the expected time and memory complexity of the changed code, how does it compare with before, and which benchmarks or measurements did you run?"
+            )
+        });
+    let mut paths: Vec<String> = Vec::new();
+    let mut hunks: Vec<String> = Vec::new();
+    for s in &ctx.performance {
+        if !paths.contains(&s.path) {
+            paths.push(s.path.clone());
+        }
+        if let Some(h) = s.hunk.as_ref().filter(|h| !hunks.contains(h)) {
+            hunks.push(h.clone());
+        }
+    }
+    exam.questions.push(ExamQuestion {
+        id: QUESTION_ID.to_string(),
+        category: CATEGORY.to_string(),
+        prompt,
+        choices: None,
+        related_paths: paths,
+        related_hunks: hunks,
+        correct_choice: None,
+        kind: Some(QuestionKind::Open),
+    });
+}
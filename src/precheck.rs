@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+
+use crate::config::Policy;
+use crate::examiner::{Exam, ExamQuestion};
+
+/// Open answers shorter than this many words are reported, unless `precheck.min_words` is set.
+const DEFAULT_MIN_WORDS: usize = 5;
+
+/// A problem found in the answers before grading.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    /// Questions to answer again to fix it.
+    pub question_ids: Vec<String>,
+    pub message: String,
+}
+
+/// Empty answers, open answers under `precheck.min_words`, and open answers given word for word
+/// to more than one question: cheap to spot locally, and a grader call wasted if submitted.
+pub fn check(policy: &Policy, exam: &Exam, answers: &BTreeMap<String, String>) -> Vec<Issue> {
+    let mut out = Vec::new();
+    if !policy.precheck.enabled.unwrap_or(true) {
+        return out;
+    }
+    let min_words = policy.precheck.min_words.unwrap_or(DEFAULT_MIN_WORDS);
+    let answer = |q: &ExamQuestion| answers.get(&q.id).map(|a| a.trim()).unwrap_or_default();
+    let mut seen: BTreeMap<String, Vec<&ExamQuestion>> = BTreeMap::new();
+    for q in &exam.questions {
+        let text = answer(q);
+        if text.is_empty() {
+            out.push(Issue {
+                question_ids: vec![q.id.clone()],
+                message: format!("{} ({}) has no answer", q.id, q.category),
+            });
+            continue;
+        }
+        if !is_open(q) {
+            continue;
+        }
+        let words = crate::i18n::word_count(text);
+        if words < min_words {
+            out.push(Issue {
+                question_ids: vec![q.id.clone()],
+                message: format!(
+                    "{} ({}) is {words} word(s), under precheck.min_words {min_words}",
+                    q.id, q.category
+                ),
+            });
+        }
+        let normalized = text
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
+        seen.entry(normalized).or_default().push(q);
+    }
+    for same in seen.values().filter(|qs| qs.len() > 1) {
+        let ids: Vec<String> = same.iter().map(|q| q.id.clone()).collect();
+        out.push(Issue {
+            message: format!("{} have the same answer", ids.join(", ")),
+            question_ids: ids,
+        });
+    }
+    out
+}
+
+/// Free-text questions; choices, cited locations and the self-check are short by design.
+fn is_open(q: &ExamQuestion) -> bool {
+    q.choices.as_ref().is_none_or(|c| c.is_empty())
+        && !q.is_evidence()
+        && q.id != crate::self_check::QUESTION_ID
+}
+
+/// Prints the issues on stderr.
+pub fn warn(issues: &[Issue]) {
+    for issue in issues {
+        eprintln!("aigit: precheck: {}", issue.message);
+    }
+}
+
+/// Every question some issue asks to answer again, in exam order.
+pub fn flagged(exam: &Exam, issues: &[Issue]) -> Vec<String> {
+    exam.questions
+        .iter()
+        .filter(|q| issues.iter().any(|i| i.question_ids.contains(&q.id)))
+        .map(|q| q.id.clone())
+        .collect()
+}
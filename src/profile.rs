@@ -0,0 +1,130 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::git::Git;
+use crate::transcript::TranscriptStore;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CategoryStats {
+    pub answered: u32,
+    pub mean_score: f64,
+}
+
+/// An author's historical per-category performance, aggregated from stored transcripts of
+/// commits they authored. Cached under `.git/aigit/profiles/` (never committed or pushed).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorProfile {
+    pub author_email: String,
+    pub updated_at: DateTime<Utc>,
+    pub exams: u32,
+    pub categories: BTreeMap<String, CategoryStats>,
+    /// Commits whose notes are already counted (or belong to other authors), so a refresh only
+    /// reads notes added since.
+    #[serde(default)]
+    pub seen: BTreeSet<String>,
+}
+
+impl AuthorProfile {
+    /// Updates the cached profile for `author_email` with the notes stored since it was last
+    /// refreshed. The profile is rebuilt from every note when there is no usable cache or a
+    /// counted note has since disappeared (an amend or a pruned note).
+    pub fn refresh(git: &Git, store: &TranscriptStore, author_email: &str) -> Result<Self> {
+        let path = profile_path(git, author_email);
+        let listed: BTreeSet<String> = store.list(&git.repo)?.into_iter().collect();
+        let cached = std::fs::read(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_slice::<Self>(&raw).ok())
+            .filter(|p| {
+                p.author_email.eq_ignore_ascii_case(author_email)
+                    && !p.seen.is_empty()
+                    && p.seen.iter().all(|sha| listed.contains(sha))
+            });
+        let (mut exams, mut sums, mut seen) = match cached {
+            Some(p) => (
+                p.exams,
+                p.categories
+                    .into_iter()
+                    .map(|(cat, s)| (cat, (s.answered, s.mean_score * s.answered as f64)))
+                    .collect::<BTreeMap<_, _>>(),
+                p.seen,
+            ),
+            None => (0u32, BTreeMap::new(), BTreeSet::new()),
+        };
+        for sha in listed {
+            if seen.contains(&sha) {
+                continue;
+            }
+            let Ok(meta) = git.commit_meta(&sha) else {
+                continue;
+            };
+            if !meta.author_email.eq_ignore_ascii_case(author_email) {
+                seen.insert(sha);
+                continue;
+            }
+            let Ok(t) = store.load(&git.repo, &sha) else {
+                continue;
+            };
+            seen.insert(sha);
+            exams += 1;
+            for q in &t.score.per_question {
+                let entry = sums.entry(q.category.clone()).or_default();
+                entry.0 += 1;
+                entry.1 += q.score;
+            }
+        }
+        let categories = sums
+            .into_iter()
+            .map(|(cat, (answered, total))| {
+                (
+                    cat,
+                    CategoryStats {
+                        answered,
+                        mean_score: total / answered as f64,
+                    },
+                )
+            })
+            .collect();
+        let profile = Self {
+            author_email: author_email.to_string(),
+            updated_at: Utc::now(),
+            exams,
+            categories,
+            seen,
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&path, serde_json::to_vec_pretty(&profile)?)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(profile)
+    }
+
+    /// Categories whose mean score is below `threshold`, weakest first.
+    pub fn weak_categories(&self, threshold: f64, max: usize) -> Vec<String> {
+        let mut weak = self
+            .categories
+            .iter()
+            .filter(|(_, s)| s.mean_score < threshold)
+            .collect::<Vec<_>>();
+        weak.sort_by(|a, b| a.1.mean_score.total_cmp(&b.1.mean_score).then(a.0.cmp(b.0)));
+        weak.into_iter().take(max).map(|(c, _)| c.clone()).collect()
+    }
+}
+
+fn profile_path(git: &Git, author_email: &str) -> PathBuf {
+    let mut hasher = Sha256::new();
+    hasher.update(author_email.to_ascii_lowercase().as_bytes());
+    let name = hex::encode(hasher.finalize());
+    git.repo
+        .git_dir
+        .join("aigit")
+        .join("profiles")
+        .join(format!("{}.json", &name[..16]))
+}
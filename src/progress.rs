@@ -0,0 +1,112 @@
+use std::io::{IsTerminal, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const SPINNER: &[char] = &['|', '/', '-', '\\'];
+
+/// Live feedback on stderr while a slow provider command runs: its stderr lines (at most one per
+/// `min_interval`), elapsed time against the timeout, and a spinner when stderr is a terminal.
+/// Cloning shares the same reporter, so the stderr reader thread and the waiting thread can both
+/// feed it.
+#[derive(Clone)]
+pub struct Progress {
+    inner: Arc<Mutex<State>>,
+}
+
+struct State {
+    label: String,
+    timeout: Duration,
+    started: Instant,
+    min_interval: Duration,
+    heartbeat: Duration,
+    last_output: Option<Instant>,
+    tty: bool,
+    spinner_shown: bool,
+    frame: usize,
+}
+
+impl Progress {
+    pub fn start(label: &str, timeout: Duration, min_interval: Duration) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(State {
+                label: label.to_string(),
+                timeout,
+                started: Instant::now(),
+                min_interval,
+                heartbeat: Duration::from_secs(15),
+                last_output: None,
+                tty: std::io::stderr().is_terminal(),
+                spinner_shown: false,
+                frame: 0,
+            })),
+        }
+    }
+
+    /// Forwards one stderr line from the child, unless another was shown less than
+    /// `min_interval` ago. Blank lines are skipped.
+    pub fn line(&self, line: &str) {
+        let line = line.trim();
+        if line.is_empty() {
+            return;
+        }
+        let Ok(mut state) = self.inner.lock() else {
+            return;
+        };
+        let now = Instant::now();
+        if state
+            .last_output
+            .is_some_and(|t| now.duration_since(t) < state.min_interval)
+        {
+            return;
+        }
+        state.clear_spinner();
+        eprintln!("aigit: [{}] {}: {line}", state.elapsed(), state.label);
+        state.last_output = Some(now);
+    }
+
+    /// Called while waiting: redraws the spinner on a terminal; elsewhere prints a heartbeat when
+    /// nothing has been shown for a while.
+    pub fn tick(&self) {
+        let Ok(mut state) = self.inner.lock() else {
+            return;
+        };
+        if state.tty {
+            let frame = SPINNER[state.frame % SPINNER.len()];
+            state.frame += 1;
+            eprint!("\r{frame} {} running [{}]", state.label, state.elapsed());
+            let _ = std::io::stderr().flush();
+            state.spinner_shown = true;
+            return;
+        }
+        let quiet_since = state.last_output.unwrap_or(state.started);
+        if quiet_since.elapsed() >= state.heartbeat {
+            eprintln!("aigit: [{}] {} still running", state.elapsed(), state.label);
+            state.last_output = Some(Instant::now());
+        }
+    }
+
+    /// Removes the spinner line so later output starts on a clean line.
+    pub fn finish(&self) {
+        if let Ok(mut state) = self.inner.lock() {
+            state.clear_spinner();
+        }
+    }
+}
+
+impl State {
+    fn elapsed(&self) -> String {
+        format!(
+            "{}s/{}s",
+            self.started.elapsed().as_secs(),
+            self.timeout.as_secs()
+        )
+    }
+
+    fn clear_spinner(&mut self) {
+        if self.spinner_shown {
+            eprint!("\r\x1b[2K");
+            let _ = std::io::stderr().flush();
+            self.spinner_shown = false;
+        }
+    }
+}
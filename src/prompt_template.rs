@@ -0,0 +1,113 @@
+use crate::examiner::ExamContext;
+
+const MAX_LISTED_FILES: usize = 10;
+const MAX_SYMBOLS: usize = 5;
+
+/// Expands `{changed_files}`, `{top_symbols}`, `{diffstat}` and `{branch}` in a repo-defined
+/// question prompt. Unknown placeholders are left untouched.
+pub fn render(template: &str, ctx: &ExamContext) -> String {
+    if !template.contains('{') {
+        return template.to_string();
+    }
+    let mut out = template.to_string();
+    if out.contains("{changed_files}") {
+        out = out.replace("{changed_files}", &list_files(&ctx.changed_files));
+    }
+    if out.contains("{top_symbols}") {
+        let symbols = top_symbols(ctx);
+        let value = if symbols.is_empty() {
+            "(no symbols detected)".to_string()
+        } else {
+            symbols.join(", ")
+        };
+        out = out.replace("{top_symbols}", &value);
+    }
+    if out.contains("{diffstat}") {
+        out = out.replace("{diffstat}", &ctx.stats.shortstat());
+    }
+    if out.contains("{branch}") {
+        out = out.replace("{branch}", ctx.branch.as_deref().unwrap_or("(detached HEAD)"));
+    }
+    out
+}
+
+/// Renders a `[[questions]]` prompt: `{files}` and `{dirs}` expand to the changed files that
+/// matched the question's paths and their directories, then the placeholders of `render`.
+pub fn render_bank(template: &str, ctx: &ExamContext, files: &[String]) -> String {
+    let mut out = template.to_string();
+    if out.contains("{files}") {
+        out = out.replace("{files}", &list_files(files));
+    }
+    if out.contains("{dirs}") {
+        let mut dirs: Vec<String> = Vec::new();
+        for f in files {
+            let dir = match f.rsplit_once('/') {
+                Some((dir, _)) => format!("{dir}/"),
+                None => "./".to_string(),
+            };
+            if !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+        out = out.replace("{dirs}", &list_files(&dirs));
+    }
+    render(&out, ctx)
+}
+
+/// The first changed symbols (`ExamContext::changed_symbols`, in diff order), each named once,
+/// so prompts name the same symbols grading credits.
+fn top_symbols(ctx: &ExamContext) -> Vec<&str> {
+    let mut out: Vec<&str> = Vec::new();
+    for s in &ctx.changed_symbols {
+        if out.len() == MAX_SYMBOLS {
+            break;
+        }
+        if !out.contains(&s.name.as_str()) {
+            out.push(&s.name);
+        }
+    }
+    out
+}
+
+fn list_files(files: &[String]) -> String {
+    if files.is_empty() {
+        return "(none)".to_string();
+    }
+    let mut out = files
+        .iter()
+        .take(MAX_LISTED_FILES)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if files.len() > MAX_LISTED_FILES {
+        out.push_str(&format!(" and {} more", files.len() - MAX_LISTED_FILES));
+    }
+    out
+}
+
+/// `git diff --shortstat`-style summary of a unified diff.
+pub fn diffstat(files: usize, insertions: usize, deletions: usize) -> String {
+    format!(
+        "{files} file{} changed, {insertions} insertion{}(+), {deletions} deletion{}(-)",
+        if files == 1 { "" } else { "s" },
+        if insertions == 1 { "" } else { "s" },
+        if deletions == 1 { "" } else { "s" },
+    )
+}
+
+/// Added and removed lines in a unified diff.
+pub fn line_counts(diff: &str) -> (usize, usize) {
+    let mut insertions = 0usize;
+    let mut deletions = 0usize;
+    for line in diff.lines() {
+        if line.starts_with("+++") || line.starts_with("---") {
+            continue;
+        }
+        if line.starts_with('+') {
+            insertions += 1;
+        } else if line.starts_with('-') {
+            deletions += 1;
+        }
+    }
+    (insertions, deletions)
+}
@@ -0,0 +1,167 @@
+use crate::examiner::{Exam, ExamContext, ExamQuestion};
+
+/// One hunk of a unified diff: the file it belongs to (the path after `b/`), its `@@ ... @@`
+/// header without the trailing function name, and its lines including the header.
+pub struct DiffHunk<'a> {
+    pub path: &'a str,
+    pub header: &'a str,
+    pub text: Vec<&'a str>,
+}
+
+impl DiffHunk<'_> {
+    /// How questions refer to the hunk: `<path> <header>`.
+    pub fn reference(&self) -> String {
+        format!("{} {}", self.path, self.header)
+    }
+}
+
+/// Splits a unified diff into hunks.
+pub fn hunks(diff: &str) -> Vec<DiffHunk<'_>> {
+    let mut out: Vec<DiffHunk> = Vec::new();
+    let mut path = "";
+    let mut in_hunk = false;
+    for line in diff.lines() {
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            path = header.rsplit_once(" b/").map(|(_, p)| p).unwrap_or(header);
+            in_hunk = false;
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("@@") {
+            let header = match rest.find("@@") {
+                Some(end) => &line[..end + 4],
+                None => line,
+            };
+            out.push(DiffHunk {
+                path,
+                header,
+                text: vec![line],
+            });
+            in_hunk = true;
+            continue;
+        }
+        if in_hunk {
+            if let Some(hunk) = out.last_mut() {
+                hunk.text.push(line);
+            }
+        }
+    }
+    out
+}
+
+/// Checks and completes each question's `related_paths`/`related_hunks` against the diff:
+/// references to files or hunks outside the change are dropped, paths are inferred from the
+/// files a question names when the examiner gave none, and a question linked to paths but no
+/// hunks is linked to every hunk of those paths.
+pub fn link(ctx: &ExamContext, exam: &mut Exam) {
+    let hunks = hunks(&ctx.diff);
+    for q in &mut exam.questions {
+        // Explicit paths win; files the prompt merely mentions are the fallback.
+        let mut paths = changed_paths(&ctx.changed_files, &q.related_paths);
+        if paths.is_empty() {
+            paths = changed_paths(
+                &ctx.changed_files,
+                &crate::exam_quality::file_tokens(&q.prompt),
+            );
+        }
+
+        let mut related: Vec<String> = Vec::new();
+        for r in q.related_hunks.iter().map(|r| normalize(r)) {
+            if hunks.iter().any(|h| h.reference() == r) && !related.contains(&r) {
+                related.push(r);
+            }
+        }
+        for r in &related {
+            if let Some(h) = hunks.iter().find(|h| h.reference() == *r) {
+                if !paths.iter().any(|p| p == h.path) {
+                    paths.push(h.path.to_string());
+                }
+            }
+        }
+        if related.is_empty() {
+            related = hunks
+                .iter()
+                .filter(|h| paths.iter().any(|p| p == h.path))
+                .map(DiffHunk::reference)
+                .collect();
+        }
+        q.related_paths = paths;
+        q.related_hunks = related;
+    }
+}
+
+/// The changed files `names` refer to, by full path or path suffix, in `changed_files` order.
+fn changed_paths(changed_files: &[String], names: &[String]) -> Vec<String> {
+    changed_files
+        .iter()
+        .filter(|f| {
+            names
+                .iter()
+                .any(|n| *f == n || f.ends_with(&format!("/{n}")) || n.ends_with(&format!("/{f}")))
+        })
+        .cloned()
+        .collect()
+}
+
+/// The part of `diff` a question is about, at most `max_lines` lines after the hunk headers:
+/// its `related_hunks`, or, for a question not linked to anything in a change touching a single
+/// file, that file's hunks. Each hunk header is prefixed with its path; a final `...` line counts
+/// what was left out.
+pub fn excerpt(diff: &str, question: &ExamQuestion, max_lines: usize) -> Vec<String> {
+    let all = hunks(diff);
+    let selected: Vec<&DiffHunk> = if !question.related_hunks.is_empty() {
+        question
+            .related_hunks
+            .iter()
+            .filter_map(|r| {
+                let r = normalize(r);
+                all.iter().find(|h| h.reference() == r)
+            })
+            .collect()
+    } else if question.related_paths.is_empty()
+        && all
+            .first()
+            .is_some_and(|f| all.iter().all(|h| h.path == f.path))
+    {
+        all.iter().collect()
+    } else {
+        Vec::new()
+    };
+
+    let mut out: Vec<String> = Vec::new();
+    let mut shown = 0;
+    let mut left_out = 0;
+    for hunk in selected {
+        let body = &hunk.text[1..];
+        if shown >= max_lines {
+            left_out += body.len();
+            continue;
+        }
+        out.push(format!("{} {}", hunk.path, hunk.text[0]));
+        let take = body.len().min(max_lines - shown);
+        out.extend(body[..take].iter().map(|l| l.to_string()));
+        shown += take;
+        left_out += body.len() - take;
+    }
+    if left_out > 0 {
+        out.push(format!("... ({left_out} more lines)"));
+    }
+    out
+}
+
+/// Collapses whitespace and drops any function name after the closing `@@`, so references copied
+/// loosely from the diff still match.
+fn normalize(reference: &str) -> String {
+    let words: Vec<&str> = reference.split_whitespace().collect();
+    let mut out: Vec<&str> = Vec::new();
+    let mut markers = 0;
+    for w in words {
+        out.push(w);
+        if w == "@@" {
+            markers += 1;
+            if markers == 2 {
+                break;
+            }
+        }
+    }
+    out.join(" ")
+}
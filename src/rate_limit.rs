@@ -0,0 +1,202 @@
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+
+use crate::config::Policy;
+
+const DEFAULT_MAX_WAIT_SECS: u64 = 600;
+const WINDOW_MS: i64 = 60_000;
+/// How often a queued call re-checks, and how often it says it is still queued.
+const POLL: Duration = Duration::from_millis(250);
+const HEARTBEAT: Duration = Duration::from_secs(15);
+
+/// `[rate_limit]` as the processes sharing `dir` enforce it: `requests` holds the start time of
+/// each call in the last minute (unix milliseconds, one per line) and `slot-<n>` files are
+/// locked by the calls running.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    dir: PathBuf,
+    per_minute: Option<u32>,
+    concurrent: Option<u32>,
+    max_wait: Duration,
+}
+
+/// A running call's place; dropping it frees the slot.
+pub struct Permit {
+    _slot: Option<File>,
+}
+
+impl RateLimiter {
+    /// The limiter for `policy`, or `None` when it sets no limit. `workdir` anchors a relative
+    /// `state_dir`.
+    pub fn from_policy(policy: &Policy, workdir: &Path) -> Option<Self> {
+        let cfg = &policy.rate_limit;
+        if cfg.requests_per_minute.is_none() && cfg.max_concurrent.is_none() {
+            return None;
+        }
+        Some(Self {
+            dir: state_dir(cfg.state_dir.as_deref(), workdir),
+            per_minute: cfg.requests_per_minute,
+            concurrent: cfg.max_concurrent,
+            max_wait: Duration::from_secs(cfg.max_wait_secs.unwrap_or(DEFAULT_MAX_WAIT_SECS)),
+        })
+    }
+
+    /// Waits for a free slot and room in the per-minute budget, then counts the call. `what`
+    /// names the call in the progress lines printed while it is queued.
+    pub fn acquire(&self, what: &str) -> Result<Permit> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("failed to create {}", self.dir.display()))?;
+        let started = Instant::now();
+        let mut queue = Queue {
+            what,
+            started,
+            last_said: None,
+        };
+
+        let slot = match self.concurrent {
+            Some(limit) => loop {
+                if let Some(slot) = self.free_slot(limit)? {
+                    break Some(slot);
+                }
+                queue.wait(
+                    self.max_wait,
+                    &format!(
+                        "{limit} provider call(s) already running (rate_limit.max_concurrent)"
+                    ),
+                )?;
+            },
+            None => None,
+        };
+        if let Some(limit) = self.per_minute {
+            loop {
+                let Some(ready_in) = self.take_request(limit)? else {
+                    break;
+                };
+                queue.wait(
+                    self.max_wait,
+                    &format!(
+                        "{limit} provider call(s) in the last minute (rate_limit.requests_per_minute), next in {}s",
+                        ready_in.as_secs().max(1)
+                    ),
+                )?;
+            }
+        }
+        if queue.last_said.is_some() {
+            eprintln!(
+                "aigit: rate limit: {what} started after {}s in the queue",
+                started.elapsed().as_secs()
+            );
+        }
+        Ok(Permit { _slot: slot })
+    }
+
+    /// Locks the first free `slot-<n>`, if any is free.
+    fn free_slot(&self, limit: u32) -> Result<Option<File>> {
+        for n in 0..limit {
+            let path = self.dir.join(format!("slot-{n}"));
+            let file = OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(&path)
+                .with_context(|| format!("failed to open {}", path.display()))?;
+            if file.try_lock().is_ok() {
+                return Ok(Some(file));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Records a call starting now if the last minute has room for it; otherwise how long until
+    /// it does.
+    fn take_request(&self, limit: u32) -> Result<Option<Duration>> {
+        let path = self.dir.join("requests");
+        let mut file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .read(true)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        file.lock()
+            .with_context(|| format!("failed to lock {}", path.display()))?;
+        let mut raw = String::new();
+        file.read_to_string(&mut raw)?;
+        let now = Utc::now().timestamp_millis();
+        let mut recent: Vec<i64> = raw
+            .lines()
+            .filter_map(|l| l.trim().parse().ok())
+            .filter(|t| now - t < WINDOW_MS)
+            .collect();
+        recent.sort_unstable();
+        let ready_in = (recent.len() >= limit as usize).then(|| {
+            let oldest = recent[recent.len() - limit as usize];
+            Duration::from_millis((oldest + WINDOW_MS - now).max(0) as u64)
+        });
+        if ready_in.is_none() {
+            recent.push(now);
+        }
+        let out: String = recent.iter().map(|t| format!("{t}\n")).collect();
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(out.as_bytes())?;
+        Ok(ready_in)
+    }
+}
+
+/// A call waiting for its turn, and what it last told the user.
+struct Queue<'a> {
+    what: &'a str,
+    started: Instant,
+    last_said: Option<Instant>,
+}
+
+impl Queue<'_> {
+    fn wait(&mut self, max_wait: Duration, reason: &str) -> Result<()> {
+        let waited = self.started.elapsed();
+        if waited >= max_wait {
+            return Err(anyhow!(
+                "rate limit: {} was still queued after {}s ({reason}; see rate_limit.max_wait_secs)",
+                self.what,
+                waited.as_secs()
+            ));
+        }
+        if self.last_said.is_none() {
+            eprintln!("aigit: rate limit: {} is queued: {reason}", self.what);
+            self.last_said = Some(Instant::now());
+        } else if self
+            .last_said
+            .is_some_and(|said| said.elapsed() >= HEARTBEAT)
+        {
+            eprintln!(
+                "aigit: rate limit: {} still queued after {}s: {reason}",
+                self.what,
+                waited.as_secs()
+            );
+            self.last_said = Some(Instant::now());
+        }
+        std::thread::sleep(POLL.min(max_wait - waited));
+        Ok(())
+    }
+}
+
+fn state_dir(configured: Option<&str>, workdir: &Path) -> PathBuf {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    match configured {
+        Some(dir) => match (dir.strip_prefix("~/"), &home) {
+            (Some(rest), Some(home)) => home.join(rest),
+            _ => workdir.join(dir),
+        },
+        None => std::env::var_os("XDG_STATE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.map(|h| h.join(".local").join("state")))
+            .unwrap_or_else(std::env::temp_dir)
+            .join("aigit")
+            .join("rate-limit"),
+    }
+}
@@ -11,25 +11,25 @@ pub struct RedactionHit {
 }
 
 pub fn redact_diff(policy: &Policy, diff: &str) -> Result<(String, Vec<RedactionHit>)> {
-    let mut patterns: Vec<(String, Regex)> = Vec::new();
-
     // built-in patterns (conservative)
-    patterns.push((
-        "private_key_block".to_string(),
-        Regex::new(r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z ]*PRIVATE KEY-----")?,
-    ));
-    patterns.push((
-        "aws_access_key_id".to_string(),
-        Regex::new(r"AKIA[0-9A-Z]{16}")?,
-    ));
-    patterns.push((
-        "github_pat".to_string(),
-        Regex::new(r"ghp_[A-Za-z0-9]{20,}")?,
-    ));
-    patterns.push((
-        "bearer_token".to_string(),
-        Regex::new(r"(?i)bearer\\s+[A-Za-z0-9\\-\\._=]+")?,
-    ));
+    let mut patterns: Vec<(String, Regex)> = vec![
+        (
+            "private_key_block".to_string(),
+            Regex::new(r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z ]*PRIVATE KEY-----")?,
+        ),
+        (
+            "aws_access_key_id".to_string(),
+            Regex::new(r"AKIA[0-9A-Z]{16}")?,
+        ),
+        (
+            "github_pat".to_string(),
+            Regex::new(r"ghp_[A-Za-z0-9]{20,}")?,
+        ),
+        (
+            "bearer_token".to_string(),
+            Regex::new(r"(?i)bearer\\s+[A-Za-z0-9\\-\\._=]+")?,
+        ),
+    ];
 
     for (i, pat) in policy.redactions.iter().enumerate() {
         patterns.push((format!("policy_redaction_{i}"), Regex::new(pat)?));
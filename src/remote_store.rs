@@ -0,0 +1,162 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::config::Policy;
+use crate::git::GitRepo;
+
+const DEFAULT_TOKEN_ENV: &str = "AIGIT_STORE_TOKEN";
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+
+/// Client for a central audit service keeping transcripts at `<url>/transcripts/<commit>`:
+/// `POST` stores one (JSON body), `GET` returns it or 404.
+#[derive(Debug, Clone)]
+pub struct RemoteStore {
+    url: String,
+    token_env: String,
+    timeout: Duration,
+}
+
+/// Checks that `url` is one the bearer token may be sent to (see [`is_secure_url`]).
+pub fn check_url(url: &str) -> Result<()> {
+    if is_secure_url(url) {
+        return Ok(());
+    }
+    Err(anyhow!(
+        "remote_store.url {url} must use https (plain http only to localhost), so the token is \
+         not sent in the clear"
+    ))
+}
+
+/// Whether `url` is https, or plain http to this machine (`localhost` or a loopback address).
+pub fn is_secure_url(url: &str) -> bool {
+    let local = url.strip_prefix("http://").is_some_and(|rest| {
+        let authority = rest.split(['/', '?', '#']).next().unwrap_or("");
+        let host = authority.rsplit('@').next().unwrap_or("");
+        let host = match host.strip_prefix('[') {
+            Some(v6) => v6.split(']').next().unwrap_or(""),
+            None => host.split(':').next().unwrap_or(""),
+        };
+        host.eq_ignore_ascii_case("localhost")
+            || host
+                .parse::<std::net::IpAddr>()
+                .is_ok_and(|ip| ip.is_loopback())
+    });
+    url.starts_with("https://") || local
+}
+
+/// Transcripts the service could not take yet, one `<commit>.json` each.
+fn queue_dir(repo: &GitRepo) -> PathBuf {
+    repo.git_dir.join("aigit").join("remote-queue")
+}
+
+impl RemoteStore {
+    pub fn from_policy(policy: &Policy) -> Result<Self> {
+        let cfg = &policy.remote_store;
+        let url = cfg
+            .url
+            .as_deref()
+            .ok_or_else(|| anyhow!("store = \"remote\" needs remote_store.url"))?;
+        check_url(url)?;
+        Ok(Self {
+            url: url.trim_end_matches('/').to_string(),
+            token_env: cfg
+                .token_env
+                .clone()
+                .unwrap_or_else(|| DEFAULT_TOKEN_ENV.to_string()),
+            timeout: Duration::from_secs(cfg.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS)),
+        })
+    }
+
+    fn endpoint(&self, commit: &str) -> String {
+        format!("{}/transcripts/{commit}", self.url)
+    }
+
+    fn request(&self, method: &str, url: &str) -> ureq::Request {
+        let agent = ureq::AgentBuilder::new().timeout(self.timeout).build();
+        let mut req = agent.request(method, url);
+        if let Ok(token) = std::env::var(&self.token_env) {
+            if !token.trim().is_empty() {
+                req = req.set("Authorization", &format!("Bearer {}", token.trim()));
+            }
+        }
+        req
+    }
+
+    /// Sends the transcript JSON of `commit` to the service.
+    pub fn upload(&self, commit: &str, json: &str) -> Result<()> {
+        let url = self.endpoint(commit);
+        match self
+            .request("POST", &url)
+            .set("Content-Type", "application/json")
+            .send_string(json)
+        {
+            Ok(_) => Ok(()),
+            Err(ureq::Error::Status(code, resp)) => Err(anyhow!(
+                "remote store: {url} answered {code}: {}",
+                resp.into_string().unwrap_or_default().trim()
+            )),
+            Err(err) => Err(anyhow!("remote store: {url} is unreachable: {err}")),
+        }
+    }
+
+    /// The transcript JSON the service holds for `commit`; `None` when it has none.
+    pub fn download(&self, commit: &str) -> Result<Option<String>> {
+        let url = self.endpoint(commit);
+        match self.request("GET", &url).call() {
+            Ok(resp) => {
+                Ok(Some(resp.into_string().with_context(|| {
+                    format!("remote store: failed to read {url}")
+                })?))
+            }
+            Err(ureq::Error::Status(404, _)) => Ok(None),
+            Err(ureq::Error::Status(code, _)) => {
+                Err(anyhow!("remote store: {url} answered {code}"))
+            }
+            Err(err) => Err(anyhow!("remote store: {url} is unreachable: {err}")),
+        }
+    }
+
+    /// Keeps the transcript of `commit` for a later upload.
+    pub fn enqueue(&self, repo: &GitRepo, commit: &str, json: &str) -> Result<()> {
+        let dir = queue_dir(repo);
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+        let path = dir.join(format!("{commit}.json"));
+        std::fs::write(&path, json).with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// Uploads queued transcripts, oldest first, until one fails; how many were sent.
+    pub fn flush(&self, repo: &GitRepo) -> Result<usize> {
+        let Ok(entries) = std::fs::read_dir(queue_dir(repo)) else {
+            return Ok(0);
+        };
+        let mut queued: Vec<(std::time::SystemTime, PathBuf)> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+            .map(|p| {
+                let modified = p
+                    .metadata()
+                    .and_then(|m| m.modified())
+                    .unwrap_or(std::time::UNIX_EPOCH);
+                (modified, p)
+            })
+            .collect();
+        queued.sort();
+        let mut sent = 0;
+        for (_, path) in queued {
+            let Some(commit) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let json = std::fs::read_to_string(&path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            self.upload(commit, &json)?;
+            std::fs::remove_file(&path)
+                .with_context(|| format!("failed to remove {}", path.display()))?;
+            sent += 1;
+        }
+        Ok(sent)
+    }
+}
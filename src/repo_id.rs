@@ -0,0 +1,102 @@
+use anyhow::{anyhow, Result};
+use sha2::{Digest, Sha256};
+
+use crate::config::Policy;
+use crate::git::Git;
+
+/// Resolves the repository identity recorded in exams and transcripts according to
+/// `repo_id.source`:
+///
+/// - `remote:<name>` (default `remote:origin`): the normalized URL of that remote, falling back
+///   to the workdir path when the remote is not configured
+/// - `workdir`: the absolute workdir path
+/// - `explicit:<string>`: the given string verbatim
+///
+/// With `repo_id.hash = true` only a salted hash of that identity is returned, so internal
+/// hostnames and paths never reach notes or exports.
+pub fn resolve(git: &Git, policy: &Policy) -> Result<String> {
+    let identity = resolve_identity(git, policy)?;
+    if !policy.repo_id.hash.unwrap_or(false) {
+        return Ok(identity);
+    }
+    let salt = policy
+        .repo_id
+        .salt_env
+        .as_deref()
+        .and_then(|name| std::env::var(name).ok())
+        .or_else(|| policy.repo_id.salt.clone())
+        .unwrap_or_default();
+    Ok(hash_identity(&identity, &salt))
+}
+
+pub fn is_hashed(repo_id: &str) -> bool {
+    repo_id.starts_with(HASH_PREFIX)
+}
+
+const HASH_PREFIX: &str = "sha256:";
+
+fn hash_identity(identity: &str, salt: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(identity.as_bytes());
+    format!("{HASH_PREFIX}{}", hex::encode(hasher.finalize()))
+}
+
+fn resolve_identity(git: &Git, policy: &Policy) -> Result<String> {
+    let source = policy.repo_id.source.as_deref().unwrap_or("remote:origin");
+    let workdir = || git.repo.workdir.display().to_string();
+    if source == "workdir" {
+        return Ok(workdir());
+    }
+    if let Some(value) = source.strip_prefix("explicit:") {
+        if value.trim().is_empty() {
+            return Err(anyhow!("repo_id.source = \"explicit:\" requires a value"));
+        }
+        return Ok(value.trim().to_string());
+    }
+    if let Some(remote) = source.strip_prefix("remote:") {
+        return Ok(git
+            .remote_url(remote)?
+            .map(|url| normalize_remote_url(&url))
+            .unwrap_or_else(workdir));
+    }
+    Err(anyhow!(
+        "unsupported repo_id.source: {source} (expected remote:<name>, workdir, or explicit:<string>)"
+    ))
+}
+
+/// Maps the different spellings of a remote to one form, `host/path`: scheme, credentials,
+/// ports, scp-style `:` separators and a trailing `.git` are dropped and the host is lowercased,
+/// so `git@github.com:org/repo.git` and `https://github.com/org/repo` agree.
+pub fn normalize_remote_url(url: &str) -> String {
+    let url = url.trim();
+    let (rest, had_scheme) = match url.split_once("://") {
+        Some((_, rest)) => (rest, true),
+        None => (url, false),
+    };
+    // Local paths (no scheme, no scp-style host) are kept as-is apart from trailing cleanup.
+    let scp_like = !had_scheme && rest.split('/').next().is_some_and(|h| h.contains(':'));
+    if !had_scheme && !scp_like {
+        return trim_repo_suffix(rest).to_string();
+    }
+
+    let (authority, path) = if had_scheme {
+        rest.split_once('/').unwrap_or((rest, ""))
+    } else {
+        rest.split_once(':').unwrap_or((rest, ""))
+    };
+    let host = authority.rsplit('@').next().unwrap_or(authority);
+    let host = if had_scheme {
+        host.split(':').next().unwrap_or(host)
+    } else {
+        host
+    };
+    let path = trim_repo_suffix(path.trim_start_matches('/'));
+    format!("{}/{}", host.to_ascii_lowercase(), path)
+}
+
+fn trim_repo_suffix(path: &str) -> &str {
+    let path = path.trim_end_matches('/');
+    path.strip_suffix(".git").unwrap_or(path)
+}
@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Policy, RespondentsPolicy};
+use crate::examiner::Exam;
+use crate::git::Git;
+use crate::transcript::Answers;
+
+/// The person who answered a question.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Respondent {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub email: String,
+}
+
+/// Combines answers files from several respondents. Each file's `respondent` is recorded for
+/// every answer in it that does not name its own; a question answered in two files is an error.
+pub fn merge(parts: Vec<(String, Answers)>) -> Result<Answers> {
+    let mut answers = BTreeMap::new();
+    let mut respondents = BTreeMap::new();
+    let mut source: BTreeMap<String, String> = BTreeMap::new();
+    for (path, part) in parts {
+        for (id, text) in part.answers {
+            if let Some(first) = source.get(&id) {
+                return Err(anyhow!(
+                    "question {id} is answered in both {first} and {path}"
+                ));
+            }
+            if let Some(who) = part.respondents.get(&id).or(part.respondent.as_ref()) {
+                respondents.insert(id.clone(), who.clone());
+            }
+            source.insert(id.clone(), path.clone());
+            answers.insert(id, text);
+        }
+    }
+    let mut merged = Answers::new(answers);
+    merged.respondents = respondents;
+    Ok(merged)
+}
+
+/// Records who answered each answered question once more than one person is involved (the
+/// answers name respondents, or the policy has `[respondents]` rules): answers without one are
+/// attributed to the file's `respondent`, else to the committer (`user.name`/`user.email`).
+pub fn attribute(git: &Git, policy: &Policy, answers: &mut Answers) {
+    if answers.respondents.is_empty()
+        && answers.respondent.is_none()
+        && policy.respondents.is_empty()
+    {
+        return;
+    }
+    let committer = git.user_email().map(|email| Respondent {
+        name: git.user_name(),
+        email,
+    });
+    let fallback = answers.respondent.take().or_else(|| committer.clone());
+    if let Some(fallback) = fallback {
+        for (id, text) in &answers.answers {
+            if !text.trim().is_empty() && !answers.respondents.contains_key(id) {
+                answers.respondents.insert(id.clone(), fallback.clone());
+            }
+        }
+    }
+    answers.committer = committer.map(|c| c.email);
+}
+
+/// Answers given by someone the `[respondents]` rules do not allow, as failure reasons.
+/// Unattributed answers count as the committer's.
+pub fn violations(rules: &RespondentsPolicy, exam: &Exam, answers: &Answers) -> Vec<String> {
+    let mut out = Vec::new();
+    if rules.is_empty() {
+        return out;
+    }
+    for q in &exam.questions {
+        if !answers.is_answered(&q.id) {
+            continue;
+        }
+        let email = answers
+            .respondents
+            .get(&q.id)
+            .map(|r| r.email.as_str())
+            .or(answers.committer.as_deref())
+            .unwrap_or("an unknown respondent");
+        let is_committer = match (&answers.committer, answers.respondents.get(&q.id)) {
+            (_, None) => true,
+            (Some(committer), Some(who)) => who.email == *committer,
+            (None, Some(_)) => false,
+        };
+        if rules.committer_only.contains(&q.category) && !is_committer {
+            out.push(format!(
+                "{} ({}) must be answered by the committer, not {email}",
+                q.id, q.category
+            ));
+        }
+        for role in rules
+            .roles
+            .iter()
+            .filter(|r| r.categories.contains(&q.category))
+        {
+            if !role.allows(email, is_committer) {
+                out.push(format!(
+                    "{} ({}) was answered by {email}, who is not allowed to answer for {} (allowed: {})",
+                    q.id,
+                    q.category,
+                    role.name.as_deref().unwrap_or("this role"),
+                    role.allowed.join(", ")
+                ));
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::config::Policy;
+use crate::examiner::ExamContext;
+use crate::git::GitRepo;
+
+/// How often this process has sent each request, so the samples of a consensus grade
+/// (`grading.samples`) are cached as separate responses instead of one response repeated.
+static SENT: LazyLock<Mutex<HashMap<String, u32>>> = LazyLock::new(Default::default);
+
+/// A provider's response saved under `.git/aigit/cache/responses/`, addressed by the request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub created_at: DateTime<Utc>,
+    /// `<provider>/<model>` (see `Policy::provider_identity`).
+    pub model: String,
+    pub schema_sha256: String,
+    pub prompt_sha256: String,
+    /// Which repeat of the same request within one run this answered (0 for the first).
+    pub sample: u32,
+    pub response: String,
+}
+
+/// Whether provider calls go through the cache: `response_cache.enabled`, else only for
+/// consensus grading.
+pub fn enabled(policy: &Policy) -> bool {
+    policy
+        .response_cache
+        .enabled
+        .unwrap_or(policy.grading.samples.unwrap_or(1) > 1)
+}
+
+fn responses_dir(git_dir: &Path) -> PathBuf {
+    git_dir.join("aigit").join("cache").join("responses")
+}
+
+fn sha256(data: impl AsRef<[u8]>) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// Asks `provider` through the cache: a cached response to the same prompt, model and output
+/// `schema` that still parses, else `request`'s, saved once it parses.
+pub fn call<T>(
+    ctx: &ExamContext,
+    provider: &str,
+    schema: &Value,
+    prompt: &str,
+    request: impl FnOnce() -> Result<String>,
+    parse: impl Fn(&str) -> Result<T>,
+) -> Result<T> {
+    if !enabled(&ctx.policy) {
+        return parse(&request()?);
+    }
+    let model = ctx.policy.provider_identity(provider);
+    let schema_sha256 = sha256(serde_json::to_vec(schema)?);
+    let prompt_sha256 = sha256(prompt);
+    let key = sha256(format!("{model}\n{schema_sha256}\n{prompt_sha256}"));
+    let sample = {
+        let mut sent = SENT.lock().unwrap_or_else(|e| e.into_inner());
+        let n = sent.entry(key.clone()).or_default();
+        *n += 1;
+        *n - 1
+    };
+    let path =
+        responses_dir(&ctx.git_dir).join(format!("{}.json", sha256(format!("{key}\n{sample}"))));
+
+    let max_age = ctx
+        .policy
+        .response_cache
+        .max_age_days
+        .map(|days| chrono::Duration::days(days.into()));
+    let cached = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|raw| serde_json::from_str::<CachedResponse>(&raw).ok())
+        .filter(|c| max_age.is_none_or(|age| Utc::now() - c.created_at < age));
+    if let Some(parsed) = cached.and_then(|c| parse(&c.response).ok()) {
+        return Ok(parsed);
+    }
+
+    let response = request()?;
+    let parsed = parse(&response)?;
+    let entry = CachedResponse {
+        created_at: Utc::now(),
+        model,
+        schema_sha256,
+        prompt_sha256,
+        sample,
+        response,
+    };
+    if let Err(err) = write(&path, &entry) {
+        eprintln!("aigit: warning: failed to cache the {provider} response: {err:#}");
+    }
+    Ok(parsed)
+}
+
+fn write(path: &Path, entry: &CachedResponse) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    std::fs::write(path, serde_json::to_vec_pretty(entry)?)
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Deletes every cached response; returns how many were removed.
+pub fn clear(repo: &GitRepo) -> Result<usize> {
+    let dir = responses_dir(&repo.git_dir);
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(err) => return Err(err).with_context(|| format!("failed to read {}", dir.display())),
+    };
+    let mut removed = 0;
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|e| e == "json") {
+            std::fs::remove_file(&path)
+                .with_context(|| format!("failed to remove {}", path.display()))?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
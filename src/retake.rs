@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Policy;
+use crate::examiner::{Exam, ExamContext, ExamQuestion, Examiner};
+use crate::git::GitRepo;
+use crate::transcript::{Answers, Decision, Score};
+
+/// One try at an exam, kept in the transcript of an exam passed on a retake.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attempt {
+    pub timestamp: DateTime<Utc>,
+    /// Questions asked in this attempt (the whole exam for the first).
+    pub questions: Vec<String>,
+    /// The answers given in this attempt; a retake's answers replace them in the transcript.
+    pub answers: BTreeMap<String, String>,
+    pub total_score: f64,
+    pub decision: Decision,
+}
+
+/// A failed exam and its graded answers, kept so `aigit exam --retake` (or the prompt in
+/// `aigit commit`) can ask only its weak questions again. Cleared once the exam passes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetakeState {
+    /// Diff range examined (`None` = staged changes).
+    #[serde(default)]
+    pub range: Option<String>,
+    pub diff_patch_id: String,
+    pub exam: Exam,
+    pub answers: Answers,
+    pub score: Score,
+    pub attempts: Vec<Attempt>,
+}
+
+fn retakes_dir(repo: &GitRepo) -> PathBuf {
+    repo.git_dir.join("aigit").join("retakes")
+}
+
+fn state_path(repo: &GitRepo, diff_patch_id: &str) -> PathBuf {
+    retakes_dir(repo).join(format!("{diff_patch_id}.json"))
+}
+
+impl RetakeState {
+    /// The failed exam saved for this diff, if any.
+    pub fn load(repo: &GitRepo, diff_patch_id: &str) -> Result<Option<Self>> {
+        read(&state_path(repo, diff_patch_id))
+    }
+
+    fn save(&self, repo: &GitRepo) -> Result<()> {
+        let path = state_path(repo, &self.diff_patch_id);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&path, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// Whether `retake.max_attempts` is used up, so the exam must be taken again in full.
+    pub fn exhausted(&self, policy: &Policy) -> bool {
+        self.attempts.len() >= policy.retake.max_attempts()
+    }
+
+    /// Questions worth asking again: those graded below `min_total_score`, left empty, off-topic,
+    /// in a category under its `min_category_score`, answered with the wrong choice or location,
+    /// or flagged for hallucinations. When the exam
+    /// failed for another reason (e.g. a missing category), every question.
+    pub fn weak_questions(&self, policy: &Policy) -> Vec<String> {
+        let flagged: Vec<&str> = self
+            .score
+            .hallucination_flags
+            .iter()
+            .filter(|f| {
+                policy
+                    .hallucination_min_severity
+                    .is_none_or(|min| f.severity >= min)
+            })
+            .map(|f| f.question_id.as_str())
+            .collect();
+        let off_topic: Vec<&str> = self
+            .score
+            .off_topic(policy.min_category_relevance)
+            .iter()
+            .map(|q| q.id.as_str())
+            .collect();
+        let short: Vec<String> = self
+            .score
+            .below_category_minimums(&policy.min_category_score)
+            .into_iter()
+            .map(|s| s.category)
+            .collect();
+        let vague = crate::memory_safety::shortfalls(&policy.memory_safety, &self.score);
+        let weak: Vec<String> = self
+            .exam
+            .questions
+            .iter()
+            .filter(|q| {
+                let graded = self.score.per_question.iter().find(|p| p.id == q.id);
+                self.answers
+                    .get(&q.id)
+                    .unwrap_or_default()
+                    .trim()
+                    .is_empty()
+                    || graded.is_none_or(|p| {
+                        p.score < policy.min_total_score
+                            || p.choice_correct == Some(false)
+                            || p.evidence_valid == Some(false)
+                    })
+                    || off_topic.contains(&q.id.as_str())
+                    || short.contains(&q.category)
+                    || (q.category == crate::memory_safety::CATEGORY && !vague.is_empty())
+                    || flagged.contains(&q.id.as_str())
+            })
+            .map(|q| q.id.clone())
+            .collect();
+        if weak.is_empty() {
+            return self.exam.questions.iter().map(|q| q.id.clone()).collect();
+        }
+        weak
+    }
+
+    /// The retake: the weak questions, followed by a harder follow-up to each when `follow_ups`
+    /// is set. Follow-ups the examiner fails to write fall back to fixed ones per category.
+    pub fn retake_exam(
+        &self,
+        examiner: &dyn Examiner,
+        ctx: &ExamContext,
+        weak: &[String],
+        follow_ups: bool,
+    ) -> Exam {
+        let mut exam = self.exam.clone();
+        exam.questions.retain(|q| weak.contains(&q.id));
+        if follow_ups {
+            let extra = examiner
+                .generate_follow_ups(ctx, &self.exam, &self.answers, &self.score, weak)
+                .unwrap_or_else(|err| {
+                    eprintln!(
+                        "aigit: could not generate follow-up questions ({err}); asking the standard ones"
+                    );
+                    crate::examiner::fixed_follow_ups(
+                        &self.exam,
+                        weak,
+                        ctx.policy.language.as_deref(),
+                    )
+                });
+            exam.questions.extend(
+                extra
+                    .into_iter()
+                    .filter(|q| !self.exam.questions.iter().any(|e| e.id == q.id)),
+            );
+        }
+        exam
+    }
+
+    /// The full exam with a retake folded in: follow-ups join the questions, retaken answers
+    /// replace the earlier ones, and time spent is added up.
+    pub fn merge(&self, retake: &Exam, answers: Answers) -> (Exam, Answers) {
+        let mut exam = self.exam.clone();
+        let follow_ups: Vec<ExamQuestion> = retake
+            .questions
+            .iter()
+            .filter(|q| !exam.questions.iter().any(|e| e.id == q.id))
+            .cloned()
+            .collect();
+        exam.questions.extend(follow_ups);
+        let mut merged = self.answers.clone();
+        merged.answers.extend(answers.answers);
+        for (id, secs) in answers.answer_seconds {
+            *merged.answer_seconds.entry(id).or_default() += secs;
+        }
+        merged.respondents.extend(answers.respondents);
+        (exam, merged)
+    }
+}
+
+/// Records a graded attempt at the exam for this diff: a failure is saved for a retake, a pass
+/// clears it. `retaken` lists the questions asked when this was a retake; a full exam starts
+/// the attempt history over. Returns the history, for the transcript when it has more than one
+/// attempt.
+#[allow(clippy::too_many_arguments)]
+pub fn record(
+    repo: &GitRepo,
+    ctx: &ExamContext,
+    range: Option<&str>,
+    exam: &Exam,
+    answers: &Answers,
+    score: &Score,
+    decision: Decision,
+    retaken: Option<&[String]>,
+) -> Result<Vec<Attempt>> {
+    let mut attempts = match retaken {
+        Some(_) => RetakeState::load(repo, &ctx.diff_patch_id)?
+            .map(|s| s.attempts)
+            .unwrap_or_default(),
+        None => Vec::new(),
+    };
+    let questions: Vec<String> = match retaken {
+        Some(ids) => ids.to_vec(),
+        None => exam.questions.iter().map(|q| q.id.clone()).collect(),
+    };
+    attempts.push(Attempt {
+        timestamp: Utc::now(),
+        answers: questions
+            .iter()
+            .filter_map(|id| Some((id.clone(), answers.get(id)?.to_string())))
+            .collect(),
+        questions,
+        total_score: score.total_score,
+        decision,
+    });
+    if decision == Decision::Pass {
+        let _ = std::fs::remove_file(state_path(repo, &ctx.diff_patch_id));
+    } else {
+        RetakeState {
+            range: range.map(str::to_string),
+            diff_patch_id: ctx.diff_patch_id.clone(),
+            exam: exam.clone(),
+            answers: answers.clone(),
+            score: score.clone(),
+            attempts: attempts.clone(),
+        }
+        .save(repo)?;
+    }
+    Ok(attempts)
+}
+
+fn read(path: &Path) -> Result<Option<RetakeState>> {
+    let raw = match std::fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err).with_context(|| format!("failed to read {}", path.display())),
+    };
+    serde_json::from_str(&raw)
+        .map(Some)
+        .with_context(|| format!("failed to parse {}", path.display()))
+}
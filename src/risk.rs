@@ -0,0 +1,241 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Policy, RiskLevelPolicy};
+use crate::diff_stats::DiffStats;
+
+/// Paths that make any change touching them riskier, on top of `risk.paths`.
+const SENSITIVE_PATHS: &[&str] = &[
+    "**/auth/**",
+    "**/security/**",
+    "**/crypto/**",
+    "**/migrations/**",
+    "**/*.sql",
+    "**/billing/**",
+    "**/payments/**",
+    ".github/workflows/**",
+    "**/Dockerfile",
+    "**/*.tf",
+];
+
+/// Dependency manifests and lock files, by file name.
+const DEPENDENCY_FILES: &[&str] = &[
+    "Cargo.toml",
+    "Cargo.lock",
+    "package.json",
+    "package-lock.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "go.mod",
+    "go.sum",
+    "requirements.txt",
+    "pyproject.toml",
+    "poetry.lock",
+    "Gemfile",
+    "Gemfile.lock",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+];
+
+/// Code signals looked for in added and removed lines: (factor, weight, needles).
+const CODE_SIGNALS: &[(&str, f64, &[&str])] = &[
+    (
+        "unsafe code",
+        0.2,
+        &[
+            "unsafe {",
+            "unsafe fn",
+            "unsafe impl",
+            "static mut",
+            "transmute(",
+        ],
+    ),
+    (
+        "concurrency",
+        0.15,
+        &[
+            "Mutex",
+            "RwLock",
+            "Atomic",
+            "Condvar",
+            "thread::spawn",
+            "tokio::spawn",
+            "go func",
+            "synchronized",
+        ],
+    ),
+    (
+        "SQL",
+        0.15,
+        &[
+            "SELECT ",
+            "INSERT INTO",
+            "DELETE FROM",
+            "DROP TABLE",
+            "ALTER TABLE",
+            "CREATE TABLE",
+        ],
+    ),
+];
+
+/// How risky a change looks before anyone is examined on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RiskLevel {
+    Low,
+    Medium,
+    High,
+}
+
+impl std::fmt::Display for RiskLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            RiskLevel::Low => "low",
+            RiskLevel::Medium => "medium",
+            RiskLevel::High => "high",
+        })
+    }
+}
+
+/// The heuristic risk score of a diff and what raised it; recorded in the transcript.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskAssessment {
+    /// 0 (routine) to 1 (as risky as the heuristics can tell).
+    pub score: f64,
+    pub level: RiskLevel,
+    /// What raised the score, e.g. "unsafe code" or "dependency changes (Cargo.lock)".
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub factors: Vec<String>,
+}
+
+impl RiskAssessment {
+    /// "high (0.65: unsafe code, 612 changed lines)".
+    pub fn summary(&self) -> String {
+        if self.factors.is_empty() {
+            return format!("{} ({:.2})", self.level, self.score);
+        }
+        format!(
+            "{} ({:.2}: {})",
+            self.level,
+            self.score,
+            self.factors.join(", ")
+        )
+    }
+}
+
+/// Scores a diff on its size, the paths it touches, unsafe/concurrency/SQL code in its changed
+/// lines, and dependency changes.
+pub fn assess(policy: &Policy, diff: &str, changed_files: &[String]) -> RiskAssessment {
+    let stats = DiffStats::new(diff, changed_files);
+    let mut score = 0.0;
+    let mut factors = Vec::new();
+
+    let lines = stats.lines();
+    let size = match lines {
+        500.. => 0.25,
+        150.. => 0.15,
+        50.. => 0.05,
+        _ => 0.0,
+    };
+    if size > 0.0 {
+        score += size;
+        factors.push(format!("{lines} changed lines"));
+    }
+    if stats.files >= 10 {
+        score += 0.1;
+        factors.push(format!("{} files", stats.files));
+    }
+
+    let sensitive: Vec<String> = SENSITIVE_PATHS
+        .iter()
+        .map(|p| p.to_string())
+        .chain(policy.risk.paths.iter().cloned())
+        .collect();
+    if let Ok(set) = crate::config::build_globset(&sensitive) {
+        let hits: Vec<&str> = changed_files
+            .iter()
+            .filter(|f| set.is_match(f.as_str()))
+            .map(String::as_str)
+            .collect();
+        if !hits.is_empty() {
+            score += 0.25;
+            factors.push(format!("sensitive paths ({})", list(&hits)));
+        }
+    }
+
+    let changed: Vec<&str> = diff
+        .lines()
+        .filter(|l| !l.starts_with("+++") && !l.starts_with("---"))
+        .filter_map(|l| l.strip_prefix('+').or_else(|| l.strip_prefix('-')))
+        .collect();
+    for (factor, weight, needles) in CODE_SIGNALS {
+        if changed
+            .iter()
+            .any(|line| needles.iter().any(|n| line.contains(n)))
+        {
+            score += weight;
+            factors.push(factor.to_string());
+        }
+    }
+
+    let manifests: Vec<&str> = changed_files
+        .iter()
+        .filter(|f| is_dependency_file(f.rsplit('/').next().unwrap_or(f)))
+        .map(String::as_str)
+        .collect();
+    if !manifests.is_empty() {
+        score += 0.15;
+        factors.push(format!("dependency changes ({})", list(&manifests)));
+    }
+
+    let score: f64 = score.min(1.0);
+    let reaches = |level: &Option<RiskLevelPolicy>, default: f64| {
+        score >= level.as_ref().and_then(|l| l.at).unwrap_or(default)
+    };
+    let level = if reaches(&policy.risk.high, 0.6) {
+        RiskLevel::High
+    } else if reaches(&policy.risk.medium, 0.3) {
+        RiskLevel::Medium
+    } else {
+        RiskLevel::Low
+    };
+    RiskAssessment {
+        score,
+        level,
+        factors,
+    }
+}
+
+/// Whether a file name is a dependency manifest or lock file.
+pub fn is_dependency_file(name: &str) -> bool {
+    DEPENDENCY_FILES.contains(&name) || name.starts_with("requirements")
+}
+
+/// The `[risk.<level>]` settings for a level, if the policy has any.
+pub fn level_policy(policy: &Policy, level: RiskLevel) -> Option<&RiskLevelPolicy> {
+    match level {
+        RiskLevel::Low => None,
+        RiskLevel::Medium => policy.risk.medium.as_ref(),
+        RiskLevel::High => policy.risk.high.as_ref(),
+    }
+}
+
+/// `min_total_score` for a change at `level`: the policy's, raised by the level's own.
+pub fn min_total_score(policy: &Policy, level: Option<RiskLevel>) -> f64 {
+    level
+        .and_then(|l| level_policy(policy, l))
+        .and_then(|l| l.min_total_score)
+        .map_or(policy.min_total_score, |min| {
+            min.max(policy.min_total_score)
+        })
+}
+
+/// The first few of `items`, comma-separated.
+fn list(items: &[&str]) -> String {
+    const SHOWN: usize = 3;
+    let mut out = items[..items.len().min(SHOWN)].join(", ");
+    if items.len() > SHOWN {
+        out.push_str(&format!(", +{} more", items.len() - SHOWN));
+    }
+    out
+}
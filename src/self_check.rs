@@ -0,0 +1,129 @@
+use crate::examiner::{ExamContext, ExamQuestion};
+use crate::transcript::{Note, NoteCode, QuestionScore, SelfCheckResult};
+
+/// Id and category of the self-check question.
+pub const QUESTION_ID: &str = "self_check";
+
+/// An objective multiple-choice question whose answer is computed from the diff, so it works as
+/// an attention check no matter which provider wrote or grades the rest of the exam.
+pub struct SelfCheck {
+    pub question: ExamQuestion,
+    pub correct: usize,
+}
+
+/// Builds the self-check question for `ctx`. The variant and the decoys are derived from the
+/// patch id, so the same diff always gets the same question.
+pub fn build(ctx: &ExamContext) -> SelfCheck {
+    let seed = ctx
+        .diff_patch_id
+        .bytes()
+        .fold(0usize, |acc, b| acc.wrapping_mul(31).wrapping_add(b as usize));
+    let variants = [
+        ("How many files does this change touch?", ctx.changed_files.len()),
+        ("How many lines does this change add?", ctx.stats.insertions),
+        ("How many lines does this change remove?", ctx.stats.deletions),
+    ];
+    let (prompt, n) = if ctx.stats.lines() == 0 {
+        variants[0]
+    } else {
+        variants[seed % variants.len()]
+    };
+
+    // Decoys are spaced `step` apart on both sides; a window of three picks where the correct
+    // value lands among the sorted choices.
+    let step = (n / 4).max(1);
+    let candidates: Vec<usize> = [
+        n.checked_sub(3 * step),
+        n.checked_sub(2 * step),
+        n.checked_sub(step),
+        Some(n + step),
+        Some(n + 2 * step),
+        Some(n + 3 * step),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let start = (seed / variants.len()) % (candidates.len() - 2);
+    let mut values: Vec<usize> = candidates[start..start + 3].to_vec();
+    values.push(n);
+    values.sort_unstable();
+    let correct = values.iter().position(|v| *v == n).unwrap_or_default();
+
+    SelfCheck {
+        question: ExamQuestion {
+            id: QUESTION_ID.to_string(),
+            category: QUESTION_ID.to_string(),
+            prompt: format!("Self-check (answered from the diff): {prompt}"),
+            choices: Some(values.iter().map(|v| v.to_string()).collect()),
+            related_paths: Vec::new(),
+            related_hunks: Vec::new(),
+            correct_choice: crate::answer_key::letter(correct),
+            kind: None,
+        },
+        correct,
+    }
+}
+
+/// The choice an answer picks: a letter ("B", "b)", "B. 3") or the choice text itself.
+pub fn chosen(answer: &str, choices: &[String]) -> Option<usize> {
+    let answer = answer.trim();
+    let mut chars = answer.chars();
+    if let Some(letter) = chars.next().filter(char::is_ascii_alphabetic) {
+        let rest = chars.as_str();
+        if rest.is_empty() || rest.starts_with([')', '.', ':', ' ']) {
+            let idx = (letter.to_ascii_uppercase() as u8).wrapping_sub(b'A') as usize;
+            if idx < choices.len() {
+                return Some(idx);
+            }
+        }
+    }
+    choices.iter().position(|c| c.eq_ignore_ascii_case(answer))
+}
+
+/// Grades the self-check answer against the exam's question, recomputing the expected value from
+/// the diff.
+pub fn grade(
+    ctx: &ExamContext,
+    question: &ExamQuestion,
+    answer: &str,
+) -> (QuestionScore, SelfCheckResult) {
+    let expected = build(ctx);
+    let expected_text = expected
+        .question
+        .choices
+        .as_ref()
+        .and_then(|c| c.get(expected.correct))
+        .cloned()
+        .unwrap_or_default();
+    let choices = question.choices.clone().unwrap_or_default();
+    let picked = chosen(answer, &choices).and_then(|idx| choices.get(idx));
+    let passed = picked.is_some_and(|text| *text == expected_text);
+    let completeness = if answer.trim().is_empty() { 0.0 } else { 1.0 };
+    let value = if passed { 1.0 } else { 0.0 };
+    let mut notes = Vec::new();
+    if !passed {
+        notes.push(Note::new(
+            NoteCode::Inaccuracy,
+            format!("self-check: expected {expected_text}"),
+        ));
+    }
+    (
+        QuestionScore {
+            id: question.id.clone(),
+            category: question.category.clone(),
+            score: value,
+            completeness,
+            specificity: value,
+            category_relevance: 1.0,
+            notes,
+            interval: None,
+            choice_correct: Some(passed),
+            evidence_valid: None,
+        },
+        SelfCheckResult {
+            question_id: question.id.clone(),
+            expected: expected_text,
+            passed,
+        },
+    )
+}
@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::examiner::Exam;
+use crate::git::GitRepo;
+
+/// How often draft answers are written while typing.
+const SAVE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// An exam in progress: the generated questions and the answers typed so far, saved as they are
+/// written so a dead terminal does not lose them. `aigit exam --resume` picks it up; it expires
+/// once the diff it was generated for (its patch-id) changes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExamSession {
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Diff range examined (`None` = staged changes).
+    #[serde(default)]
+    pub range: Option<String>,
+    pub diff_patch_id: String,
+    pub exam: Exam,
+    /// Draft answers by question id.
+    #[serde(default)]
+    pub answers: BTreeMap<String, String>,
+    /// Seconds spent on each question so far.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub answer_seconds: BTreeMap<String, f64>,
+    #[serde(skip)]
+    path: PathBuf,
+    #[serde(skip)]
+    saved: Option<Instant>,
+    #[serde(skip)]
+    dirty: bool,
+}
+
+fn sessions_dir(repo: &GitRepo) -> PathBuf {
+    repo.git_dir.join("aigit").join("sessions")
+}
+
+impl ExamSession {
+    pub fn new(repo: &GitRepo, range: Option<String>, diff_patch_id: &str, exam: Exam) -> Self {
+        let now = Utc::now();
+        Self {
+            created_at: now,
+            updated_at: now,
+            range,
+            diff_patch_id: diff_patch_id.to_string(),
+            exam,
+            answers: BTreeMap::new(),
+            answer_seconds: BTreeMap::new(),
+            path: sessions_dir(repo).join(format!("{diff_patch_id}.json")),
+            saved: None,
+            dirty: false,
+        }
+    }
+
+    /// The saved session for this diff, if any.
+    pub fn load(repo: &GitRepo, diff_patch_id: &str) -> Result<Option<Self>> {
+        let path = sessions_dir(repo).join(format!("{diff_patch_id}.json"));
+        read(&path)
+    }
+
+    /// Removes sessions for the same range whose diff no longer matches `diff_patch_id`; returns
+    /// how many were removed.
+    pub fn expire(repo: &GitRepo, range: Option<&str>, diff_patch_id: &str) -> usize {
+        let Ok(dir) = std::fs::read_dir(sessions_dir(repo)) else {
+            return 0;
+        };
+        let mut removed = 0;
+        for entry in dir.flatten() {
+            let path = entry.path();
+            let Ok(Some(session)) = read(&path) else {
+                continue;
+            };
+            if session.range.as_deref() == range
+                && session.diff_patch_id != diff_patch_id
+                && std::fs::remove_file(&path).is_ok()
+            {
+                removed += 1;
+            }
+        }
+        removed
+    }
+
+    pub fn save(&mut self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        self.updated_at = Utc::now();
+        std::fs::write(&self.path, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("failed to write {}", self.path.display()))?;
+        self.saved = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Records a draft answer; it is saved right away unless the last save was under a second
+    /// ago, in which case `flush` writes it.
+    pub fn record(&mut self, id: &str, answer: &str) {
+        if self.answers.get(id).map(String::as_str) == Some(answer) {
+            return;
+        }
+        self.answers.insert(id.to_string(), answer.to_string());
+        self.dirty = true;
+        if self.saved.is_none_or(|at| at.elapsed() >= SAVE_INTERVAL) {
+            self.flush();
+        }
+    }
+
+    /// Adds time spent on a question; it is saved with the next answer or `flush`.
+    pub fn add_seconds(&mut self, id: &str, seconds: f64) {
+        *self.answer_seconds.entry(id.to_string()).or_default() += seconds;
+        self.dirty = true;
+    }
+
+    /// Saves answers recorded since the last save. Drafts are best effort: a failed write is
+    /// retried on the next call.
+    pub fn flush(&mut self) {
+        if self.dirty && self.save().is_ok() {
+            self.dirty = false;
+        }
+    }
+
+    /// The draft answer for a question, if one was typed.
+    pub fn draft(&self, id: &str) -> Option<&str> {
+        self.answers
+            .get(id)
+            .map(String::as_str)
+            .filter(|a| !a.trim().is_empty())
+    }
+
+    pub fn clear(&self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn read(path: &Path) -> Result<Option<ExamSession>> {
+    let raw = match std::fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err).with_context(|| format!("failed to read {}", path.display())),
+    };
+    let mut session: ExamSession = serde_json::from_str(&raw)
+        .with_context(|| format!("failed to parse {}", path.display()))?;
+    session.path = path.to_path_buf();
+    Ok(Some(session))
+}
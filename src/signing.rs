@@ -0,0 +1,345 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::approvals::Countersignature;
+use crate::config::Policy;
+use crate::git::{Git, GitRepo};
+use crate::transcript::Transcript;
+
+/// `ssh-keygen -Y` namespace transcripts are signed with.
+pub const SIGNATURE_NAMESPACE: &str = "aigit-transcript";
+
+/// `ssh-keygen -Y` namespace compliance bundle manifests are signed with.
+pub const BUNDLE_NAMESPACE: &str = "aigit-bundle";
+
+/// `ssh-keygen -Y` namespace countersignatures are signed with.
+pub const COUNTERSIGNATURE_NAMESPACE: &str = "aigit-countersignature";
+
+/// The kind of key a transcript was signed with, as in git's `gpg.format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureFormat {
+    Ssh,
+    Openpgp,
+}
+
+/// A signature over the transcript's canonical JSON, made by `aigit commit` when
+/// `signing.enabled` is set (or over a compliance bundle's manifest, by `aigit bundle`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptSignature {
+    pub format: SignatureFormat,
+    /// The signer's `user.email`: the principal looked up in `signing.allowed_signers`.
+    pub signer: String,
+    /// Armored signature, as written by `ssh-keygen -Y sign` or `gpg --detach-sign --armor`.
+    pub signature: String,
+}
+
+/// The bytes a transcript signature covers: the transcript as compact JSON with sorted keys,
+/// without its signature and countersignatures (approvers add those after it is signed); for a
+/// transcript upgraded from an older schema, the JSON it was stored as. Floats are written with
+/// nine decimals so that storing and loading the transcript does not change them.
+///
+/// The transcript signature therefore does not vouch for countersignatures: each carries its own
+/// signature over the transcript's `content_hash`, checked by [`check_countersignature`].
+pub fn canonical_bytes(t: &Transcript) -> Result<Vec<u8>> {
+    fn fix_floats(v: &mut Value) {
+        match v {
+            Value::Number(n) if n.is_f64() => {
+                *v = Value::String(format!("{:.9}", n.as_f64().unwrap_or_default()))
+            }
+            Value::Array(items) => items.iter_mut().for_each(fix_floats),
+            Value::Object(map) => map.values_mut().for_each(fix_floats),
+            _ => {}
+        }
+    }
+    let mut value = match &t.upgraded_from {
+        Some(source) => (**source).clone(),
+        None => serde_json::to_value(t)?,
+    };
+    if let Some(map) = value.as_object_mut() {
+        map.remove("signature");
+        map.remove("countersignatures");
+    }
+    fix_floats(&mut value);
+    Ok(serde_json::to_vec(&value)?)
+}
+
+/// Signs `t` with the user's key when `signing.enabled` is set; otherwise drops a signature
+/// that no longer matches it. Call after every change to a stored transcript.
+pub fn sign_if_enabled(git: &Git, policy: &Policy, t: &mut Transcript) -> Result<()> {
+    // Changed content is stored, and pinned, in the current schema.
+    t.upgraded_from = None;
+    t.signature = None;
+    if policy.signing.enabled.unwrap_or(false) {
+        t.signature = Some(sign(git, t)?);
+    }
+    Ok(())
+}
+
+/// Signs `t` as git signs commits: with `user.signingkey`, in the `gpg.format` (ssh or
+/// openpgp, the default) and with the `gpg.program` / `gpg.ssh.program` configured for it.
+pub fn sign(git: &Git, t: &Transcript) -> Result<TranscriptSignature> {
+    sign_bytes(git, SIGNATURE_NAMESPACE, &canonical_bytes(t)?)
+}
+
+/// Signs `data` like [`sign`] does a transcript; `namespace` applies to SSH signatures.
+pub fn sign_bytes(git: &Git, namespace: &str, data: &[u8]) -> Result<TranscriptSignature> {
+    let signer = git
+        .user_email()
+        .ok_or_else(|| anyhow!("set git config user.email to sign transcripts"))?;
+    let key = git.config_value("user.signingkey");
+    let format = git
+        .config_value("gpg.format")
+        .unwrap_or_else(|| "openpgp".to_string());
+    let (format, signature) = match format.as_str() {
+        "ssh" => {
+            let key = key.ok_or_else(|| {
+                anyhow!("set git config user.signingkey to sign transcripts with SSH")
+            })?;
+            let program = git
+                .config_value("gpg.ssh.program")
+                .unwrap_or_else(|| "ssh-keygen".to_string());
+            (
+                SignatureFormat::Ssh,
+                sign_ssh(&git.repo, &program, &key, namespace, data)?,
+            )
+        }
+        "openpgp" => {
+            let program = git
+                .config_value("gpg.program")
+                .unwrap_or_else(|| "gpg".to_string());
+            let key = key.unwrap_or_else(|| signer.clone());
+            let signature = run(
+                Command::new(&program).args(["--detach-sign", "--armor", "--local-user", &key]),
+                data,
+            )
+            .with_context(|| format!("failed to sign the transcript with {program}"))?;
+            (SignatureFormat::Openpgp, signature)
+        }
+        other => {
+            return Err(anyhow!(
+                "gpg.format {other} cannot sign transcripts; use ssh or openpgp"
+            ))
+        }
+    };
+    Ok(TranscriptSignature {
+        format,
+        signer,
+        signature,
+    })
+}
+
+/// `user.signingkey` is a private key file, or a public key (literal, `key::` prefixed, or a
+/// `.pub` file) whose private half is in the SSH agent.
+fn sign_ssh(
+    repo: &GitRepo,
+    program: &str,
+    key: &str,
+    namespace: &str,
+    data: &[u8],
+) -> Result<String> {
+    let tmp = tempfile::tempdir().context("failed to create temp dir for signing")?;
+    let literal = key.strip_prefix("key::").unwrap_or(key);
+    let key_path = if literal.starts_with("ssh-") || literal.starts_with("ecdsa-") {
+        let path = tmp.path().join("signing_key.pub");
+        std::fs::write(&path, literal)?;
+        path
+    } else {
+        let expanded = match key.strip_prefix("~/") {
+            Some(rest) => std::env::var_os("HOME")
+                .map(|home| Path::new(&home).join(rest))
+                .unwrap_or_else(|| key.into()),
+            None => key.into(),
+        };
+        repo.workdir.join(expanded)
+    };
+    run(
+        Command::new(program)
+            .args(["-Y", "sign", "-n", namespace, "-f"])
+            .arg(&key_path),
+        data,
+    )
+    .with_context(|| format!("failed to sign the transcript with {program}"))
+}
+
+/// Why `t`'s signature does not satisfy `[signing]`, if it does not: it is missing while
+/// `signing.required` is set, or `signing.allowed_signers` does not vouch for it.
+pub fn check(repo: &GitRepo, policy: &Policy, t: &Transcript) -> Option<String> {
+    let rules = &policy.signing;
+    let Some(signature) = &t.signature else {
+        return rules
+            .required
+            .unwrap_or(false)
+            .then(|| "transcript is not signed (signing.required)".to_string());
+    };
+    let allowed = rules.allowed_signers.as_deref()?;
+    let result = canonical_bytes(t).and_then(|data| {
+        verify(
+            &repo.workdir.join(allowed),
+            SIGNATURE_NAMESPACE,
+            &data,
+            signature,
+        )
+    });
+    result
+        .err()
+        .map(|err| format!("transcript signature: {err:#}"))
+}
+
+/// Checks that `c` is signed, by the approver it names, over `t`'s `content_hash` with a key
+/// `signing.allowed_signers` vouches for.
+pub fn check_countersignature(
+    repo: &GitRepo,
+    policy: &Policy,
+    t: &Transcript,
+    c: &Countersignature,
+) -> Result<()> {
+    let allowed = policy
+        .signing
+        .allowed_signers
+        .as_deref()
+        .ok_or_else(|| anyhow!("set signing.allowed_signers to check countersignatures"))?;
+    let signature = c
+        .signature
+        .as_ref()
+        .ok_or_else(|| anyhow!("countersignature by {} is not signed", c.email))?;
+    if !signature.signer.eq_ignore_ascii_case(&c.email) {
+        return Err(anyhow!(
+            "countersignature by {} is signed by {}",
+            c.email,
+            signature.signer
+        ));
+    }
+    verify(
+        &repo.workdir.join(allowed),
+        COUNTERSIGNATURE_NAMESPACE,
+        t.content_hash()?.as_bytes(),
+        signature,
+    )
+}
+
+/// Checks `signature` of `data` against an allowed-signers file; `namespace` applies to SSH
+/// signatures.
+fn verify(
+    allowed_signers: &Path,
+    namespace: &str,
+    data: &[u8],
+    signature: &TranscriptSignature,
+) -> Result<()> {
+    match signature.format {
+        SignatureFormat::Ssh => verify_ssh(
+            allowed_signers,
+            &signature.signer,
+            namespace,
+            data,
+            &signature.signature,
+        ),
+        SignatureFormat::Openpgp => verify_openpgp(
+            allowed_signers,
+            &signature.signer,
+            data,
+            &signature.signature,
+        ),
+    }
+}
+
+/// Checks an `ssh-keygen -Y sign` signature of `data` by `identity` against an allowed-signers
+/// file.
+pub fn verify_ssh(
+    allowed_signers: &Path,
+    identity: &str,
+    namespace: &str,
+    data: &[u8],
+    signature: &str,
+) -> Result<()> {
+    let tmp = tempfile::tempdir().context("failed to create temp dir for signature check")?;
+    let sig_path = tmp.path().join("data.sig");
+    std::fs::write(&sig_path, signature)?;
+    run(
+        Command::new("ssh-keygen")
+            .args(["-Y", "verify", "-f"])
+            .arg(allowed_signers)
+            .args(["-I", identity, "-n", namespace, "-s"])
+            .arg(&sig_path),
+        data,
+    )
+    .map(drop)
+    .map_err(|err| anyhow!("signature verification failed for {identity}: {err:#}"))
+}
+
+/// Checks a detached OpenPGP signature with `gpg` (the key must be in the keyring), then that
+/// the allowed-signers file has a line listing `identity` and the signing key's fingerprint.
+fn verify_openpgp(
+    allowed_signers: &Path,
+    identity: &str,
+    data: &[u8],
+    signature: &str,
+) -> Result<()> {
+    let tmp = tempfile::tempdir().context("failed to create temp dir for signature check")?;
+    let sig_path = tmp.path().join("data.asc");
+    std::fs::write(&sig_path, signature)?;
+    let status = run(
+        Command::new("gpg")
+            .args(["--batch", "--status-fd", "1", "--verify"])
+            .arg(&sig_path)
+            .arg("-"),
+        data,
+    )
+    .map_err(|err| anyhow!("signature verification failed for {identity}: {err:#}"))?;
+    let fingerprints: Vec<&str> = status
+        .lines()
+        .filter_map(|l| l.strip_prefix("[GNUPG:] VALIDSIG "))
+        .flat_map(|rest| {
+            let fields: Vec<&str> = rest.split_whitespace().collect();
+            [fields.first().copied(), fields.last().copied()]
+        })
+        .flatten()
+        .collect();
+    if fingerprints.is_empty() {
+        return Err(anyhow!(
+            "signature verification failed for {identity}: no valid signature"
+        ));
+    }
+    let allowed = std::fs::read_to_string(allowed_signers)
+        .with_context(|| format!("failed to read {}", allowed_signers.display()))?;
+    let vouched = allowed.lines().any(|line| {
+        let mut fields = line.split_whitespace();
+        let principals = fields.next().unwrap_or("");
+        !line.trim_start().starts_with('#')
+            && principals.split(',').any(|p| p == identity)
+            && fields.any(|f| fingerprints.iter().any(|fp| fp.eq_ignore_ascii_case(f)))
+    });
+    if !vouched {
+        return Err(anyhow!(
+            "{identity} is not allowed to sign with key {} in {}",
+            fingerprints[0],
+            allowed_signers.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Runs `cmd` with `data` on stdin; its stdout on success, its stderr as the error otherwise.
+fn run(cmd: &mut Command, data: &[u8]) -> Result<String> {
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to run {}", cmd.get_program().to_string_lossy()))?;
+    child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("{} missing stdin", cmd.get_program().to_string_lossy()))?
+        .write_all(data)?;
+    let out = child.wait_with_output()?;
+    if !out.status.success() {
+        return Err(anyhow!("{}", String::from_utf8_lossy(&out.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+}
@@ -0,0 +1,147 @@
+use std::io::IsTerminal;
+
+use crate::config::Policy;
+use crate::transcript::{NoteCode, QuestionScore, Transcript};
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// Longest key note shown in the table; the full notes stay in the transcript.
+const NOTE_CHARS: usize = 60;
+
+/// Whether the summary is colored: `tui.color`, else when stderr is a terminal and `NO_COLOR`
+/// is unset.
+pub fn color(policy: &Policy) -> bool {
+    policy.tui.color.unwrap_or_else(|| {
+        std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+    })
+}
+
+/// The score a question is held to: its category's `min_category_score`, else the total bar.
+fn bar(t: &Transcript, q: &QuestionScore) -> f64 {
+    t.thresholds
+        .min_category_score
+        .get(&q.category)
+        .copied()
+        .unwrap_or(t.thresholds.min_total_score)
+}
+
+/// Green at or above the bar, yellow within 0.15 under it, red further down.
+fn shade(value: f64, bar: f64) -> &'static str {
+    if value >= bar {
+        GREEN
+    } else if value >= bar - 0.15 {
+        YELLOW
+    } else {
+        RED
+    }
+}
+
+/// The note that says most about where the answer fell short: an inaccuracy, else missing
+/// specifics, else boilerplate, else praise; the grader's first of that kind.
+fn key_note(q: &QuestionScore) -> String {
+    let rank = |code: NoteCode| match code {
+        NoteCode::Inaccuracy => 0,
+        NoteCode::MissingSpecifics => 1,
+        NoteCode::Boilerplate => 2,
+        NoteCode::Excellent => 3,
+    };
+    let note = q
+        .notes
+        .iter()
+        .min_by_key(|n| rank(n.code))
+        .map(|n| n.message.trim().replace('\n', " "))
+        .unwrap_or_default();
+    if note.chars().count() <= NOTE_CHARS {
+        return note;
+    }
+    let cut: String = note.chars().take(NOTE_CHARS - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+/// One row per graded question (score, completeness, specificity and its key note) under a
+/// header, with a closing total row. Scores are colored against the bar they are held to.
+pub fn table(t: &Transcript, color: bool) -> Vec<String> {
+    let header = [
+        "question", "category", "score", "complete", "specific", "note",
+    ];
+    let rows: Vec<(&QuestionScore, [String; 6])> = t
+        .score
+        .per_question
+        .iter()
+        .map(|q| {
+            (
+                q,
+                [
+                    q.id.clone(),
+                    q.category.clone(),
+                    format!("{:.2}", q.score),
+                    format!("{:.2}", q.completeness),
+                    format!("{:.2}", q.specificity),
+                    key_note(q),
+                ],
+            )
+        })
+        .collect();
+    let mut widths = header.map(str::len);
+    for (_, cells) in &rows {
+        for (w, cell) in widths.iter_mut().zip(cells) {
+            *w = (*w).max(cell.chars().count());
+        }
+    }
+    let paint = |text: String, code: &str| {
+        if color {
+            format!("{code}{text}{RESET}")
+        } else {
+            text
+        }
+    };
+    let pad = |text: &str, i: usize| {
+        let fill = widths[i].saturating_sub(text.chars().count());
+        if (2..=4).contains(&i) {
+            format!("{}{text}", " ".repeat(fill))
+        } else {
+            format!("{text}{}", " ".repeat(fill))
+        }
+    };
+    let line = |cells: Vec<String>| format!("  {}", cells.join("  ").trim_end());
+
+    let mut out = vec![line(
+        header
+            .iter()
+            .enumerate()
+            .map(|(i, h)| paint(pad(h, i), BOLD))
+            .collect(),
+    )];
+    for (q, cells) in &rows {
+        let bar = bar(t, q);
+        out.push(line(
+            cells
+                .iter()
+                .enumerate()
+                .map(|(i, cell)| {
+                    let text = pad(cell, i);
+                    match i {
+                        2 => paint(text, shade(q.score, bar)),
+                        3 => paint(text, shade(q.completeness, bar)),
+                        4 => paint(text, shade(q.specificity, bar)),
+                        _ => text,
+                    }
+                })
+                .collect(),
+        ));
+    }
+    let total = t.score.total_score;
+    let min = t.thresholds.min_total_score;
+    let total_cells = vec![
+        pad("total", 0),
+        pad("", 1),
+        paint(pad(&format!("{total:.2}"), 2), shade(total, min)),
+        format!("(min {min:.2})"),
+    ];
+    out.push(line(total_cells));
+    out
+}
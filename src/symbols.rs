@@ -0,0 +1,433 @@
+use serde::{Deserialize, Serialize};
+
+/// A function, type or module a diff touches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangedSymbol {
+    pub name: String,
+    /// "function", "struct", "enum", "trait", "impl", "module", "class", "interface", "type",
+    /// "constant", "macro", or "symbol" for languages without a dedicated parser.
+    pub kind: String,
+    pub path: String,
+    /// "added", "removed" or "modified".
+    pub change: String,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    Other,
+}
+
+fn language(path: &str) -> Language {
+    let ext = path.rsplit_once('.').map(|(_, e)| e).unwrap_or_default();
+    match ext.to_ascii_lowercase().as_str() {
+        "rs" => Language::Rust,
+        "py" | "pyi" => Language::Python,
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" => Language::JavaScript,
+        _ => Language::Other,
+    }
+}
+
+type Definition = (String, &'static str);
+
+/// One side of a changed file, for finding the definition a changed line sits in.
+struct Image {
+    lines: Vec<String>,
+}
+
+impl Image {
+    /// The nearest definition above line `n` (1-based) that is indented less than it, walking
+    /// outwards through enclosing blocks.
+    fn enclosing(&self, lang: Language, n: usize) -> Option<Definition> {
+        let line = self.lines.get(n.checked_sub(1)?)?;
+        let mut limit = indentation(line);
+        for above in self.lines[..n - 1].iter().rev() {
+            if above.trim().is_empty() {
+                continue;
+            }
+            let indent = indentation(above);
+            if indent >= limit {
+                continue;
+            }
+            if let Some(def) = definition(lang, above) {
+                return Some(def);
+            }
+            limit = indent;
+        }
+        None
+    }
+}
+
+fn indentation(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+/// Per-file state while walking a diff.
+struct FileState<'a> {
+    path: &'a str,
+    lang: Language,
+    /// Blob ids from the `index <old>..<new>` line.
+    blobs: [Option<&'a str>; 2],
+    images: [Option<Option<Image>>; 2],
+    /// Definition lines this diff adds or removes, with their change.
+    changed_definitions: Vec<(Definition, &'static str)>,
+}
+
+impl FileState<'_> {
+    fn image(&mut self, side: usize, blob: &dyn Fn(&str) -> Option<String>) -> Option<&Image> {
+        let id = self.blobs[side];
+        self.images[side]
+            .get_or_insert_with(|| {
+                id.and_then(blob).map(|text| Image {
+                    lines: text.lines().map(str::to_string).collect(),
+                })
+            })
+            .as_ref()
+    }
+}
+
+/// Symbols defined or edited by a unified diff, in diff order. A definition line that is added
+/// or removed records that symbol as added or removed (both: modified). Any other changed line
+/// marks the definition enclosing it as modified: found by indentation in the file itself when
+/// `blob` can read the blob ids on the diff's `index` lines (needed for `--unified=0` diffs), else
+/// the nearest definition above it in the hunk or the one git names in the hunk header.
+pub fn extract(diff: &str, blob: &dyn Fn(&str) -> Option<String>) -> Vec<ChangedSymbol> {
+    let mut out: Vec<ChangedSymbol> = Vec::new();
+    let mut file: Option<FileState> = None;
+    let mut in_hunk = false;
+    let mut lines = [0usize; 2];
+    let mut enclosing: Option<Definition> = None;
+    for line in diff.lines() {
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            let path = header.rsplit_once(" b/").map(|(_, p)| p).unwrap_or(header);
+            file = Some(FileState {
+                path,
+                lang: language(path),
+                blobs: [None, None],
+                images: [None, None],
+                changed_definitions: Vec::new(),
+            });
+            in_hunk = false;
+            continue;
+        }
+        let Some(file) = file.as_mut() else {
+            continue;
+        };
+        if !in_hunk {
+            if let Some((old, new)) = line
+                .strip_prefix("index ")
+                .and_then(|ids| ids.split_whitespace().next())
+                .and_then(|ids| ids.split_once(".."))
+            {
+                let present = |id: &str| !id.is_empty() && id.chars().any(|c| c != '0');
+                file.blobs = [
+                    Some(old).filter(|id| present(id)),
+                    Some(new).filter(|id| present(id)),
+                ];
+            }
+        }
+        if let Some(rest) = line.strip_prefix("@@") {
+            in_hunk = true;
+            let (ranges, context) = rest.split_once("@@").unwrap_or((rest, ""));
+            for range in ranges.split_whitespace() {
+                let side = match range.chars().next() {
+                    Some('-') => 0,
+                    Some('+') => 1,
+                    _ => continue,
+                };
+                let start = range[1..].split(',').next().unwrap_or_default();
+                lines[side] = start.parse().unwrap_or(0);
+            }
+            enclosing = definition(file.lang, context);
+            continue;
+        }
+        if !in_hunk {
+            continue;
+        }
+        let (change, side, text) = match line.chars().next() {
+            Some('+') => ("added", 1, &line[1..]),
+            Some('-') => ("removed", 0, &line[1..]),
+            Some(' ') => {
+                if let Some(def) = definition(file.lang, &line[1..]) {
+                    enclosing = Some(def);
+                }
+                lines[0] += 1;
+                lines[1] += 1;
+                continue;
+            }
+            _ => continue,
+        };
+        let n = lines[side];
+        lines[side] += 1;
+        if let Some(def) = definition(file.lang, text) {
+            record(&mut out, file.path, &def, change);
+            file.changed_definitions.push((def.clone(), change));
+            enclosing = Some(def);
+            continue;
+        }
+        if text.trim().is_empty() {
+            continue;
+        }
+        let lang = file.lang;
+        let def = match file.image(side, blob) {
+            Some(image) => image.enclosing(lang, n),
+            None => enclosing.clone(),
+        };
+        if let Some(def) = def {
+            // The body of a definition added or removed as a whole changes with it.
+            let change = if file.changed_definitions.contains(&(def.clone(), change)) {
+                change
+            } else {
+                "modified"
+            };
+            record(&mut out, file.path, &def, change);
+        }
+    }
+    out
+}
+
+fn record(out: &mut Vec<ChangedSymbol>, path: &str, def: &Definition, change: &str) {
+    let (name, kind) = def;
+    match out
+        .iter_mut()
+        .find(|s| s.path == path && s.name == *name && s.kind == *kind)
+    {
+        Some(existing) => {
+            if existing.change != change {
+                existing.change = "modified".to_string();
+            }
+        }
+        None => out.push(ChangedSymbol {
+            name: name.clone(),
+            kind: kind.to_string(),
+            path: path.to_string(),
+            change: change.to_string(),
+        }),
+    }
+}
+
+/// Whether `text` names `symbol` as a whole identifier (so `parse` does not match `parser`).
+pub fn mentions(text: &str, symbol: &str) -> bool {
+    if symbol.is_empty() {
+        return false;
+    }
+    text.match_indices(symbol).any(|(i, _)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + symbol.len()..].chars().next();
+        !before.is_some_and(is_ident_char) && !after.is_some_and(is_ident_char)
+    })
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// The leading identifier of `text`.
+fn ident(text: &str) -> Option<String> {
+    let name: String = text
+        .trim_start()
+        .chars()
+        .take_while(|c| is_ident_char(*c))
+        .collect();
+    (!name.is_empty() && name != "_" && !name.starts_with(|c: char| c.is_ascii_digit()))
+        .then_some(name)
+}
+
+/// `text` without any leading words from `words`.
+fn strip_words<'a>(mut text: &'a str, words: &[&str]) -> &'a str {
+    loop {
+        text = text.trim_start();
+        let word_end = text.find(|c: char| !is_ident_char(c)).unwrap_or(text.len());
+        let word = &text[..word_end];
+        if word.is_empty() || !words.contains(&word) {
+            return text;
+        }
+        text = &text[word_end..];
+        // `pub(crate)`, `pub(in path)`
+        if word == "pub" && text.starts_with('(') {
+            text = text.split_once(')').map(|(_, rest)| rest).unwrap_or(text);
+        }
+        // `extern "C"`
+        if word == "extern" {
+            let rest = text.trim_start();
+            if let Some(abi) = rest.strip_prefix('"') {
+                text = abi.split_once('"').map(|(_, rest)| rest).unwrap_or(rest);
+            }
+        }
+    }
+}
+
+/// Text after a leading `<...>` (generic parameters), if any.
+fn skip_generics(text: &str) -> &str {
+    let text = text.trim_start();
+    if !text.starts_with('<') {
+        return text;
+    }
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => {
+                depth -= 1;
+                if depth == 0 {
+                    return &text[i + 1..];
+                }
+            }
+            _ => {}
+        }
+    }
+    ""
+}
+
+/// The symbol a source line defines, if any.
+fn definition(lang: Language, text: &str) -> Option<(String, &'static str)> {
+    match lang {
+        Language::Rust => rust_definition(text),
+        Language::Python => python_definition(text),
+        Language::JavaScript => js_definition(text),
+        Language::Other => keyword_definition(text).map(|name| (name, "symbol")),
+    }
+}
+
+const DEFINITION_KEYWORDS: &[&str] = &[
+    "fn ", "def ", "func ", "function ", "class ", "struct ", "enum ", "trait ", "impl ",
+    "interface ", "type ", "mod ",
+];
+
+/// The name after the first definition keyword in a line of a language without a parser.
+fn keyword_definition(text: &str) -> Option<String> {
+    let text = text.trim_start();
+    for kw in DEFINITION_KEYWORDS {
+        let Some(idx) = text.find(kw) else {
+            continue;
+        };
+        // keyword must start a word (e.g. not the "fn " in "callfn ")
+        if idx > 0 && text[..idx].ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+            continue;
+        }
+        let name = text[idx + kw.len()..]
+            .trim_start()
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .collect::<String>();
+        if !name.is_empty() {
+            return Some(name);
+        }
+    }
+    None
+}
+
+/// The item a Rust line declares, when it is public outside its crate (`pub`, not
+/// `pub(crate)` and the like). Impl blocks are not items of their own.
+pub fn public_rust_definition(text: &str) -> Option<(String, &'static str)> {
+    let rest = text.trim_start().strip_prefix("pub")?;
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    rust_definition(text).filter(|(_, kind)| *kind != "impl")
+}
+
+fn rust_definition(text: &str) -> Option<(String, &'static str)> {
+    let text = strip_words(text, &["pub", "async", "unsafe", "extern", "default"]);
+    if let Some(rest) = text.strip_prefix("impl") {
+        if !rest.starts_with(|c: char| c == '<' || c.is_whitespace()) {
+            return None;
+        }
+        // `impl<T> Trait for Type<T>` and `impl Type` both name `Type`.
+        let rest = skip_generics(rest);
+        let target = rest.split_once(" for ").map(|(_, t)| t).unwrap_or(rest);
+        return ident(target.trim_start().trim_start_matches('&')).map(|n| (n, "impl"));
+    }
+    let (keyword, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    let kind = match keyword {
+        "fn" => "function",
+        "struct" | "union" => "struct",
+        "enum" => "enum",
+        "trait" => "trait",
+        "mod" => "module",
+        "type" => "type",
+        "macro_rules!" => "macro",
+        // `const fn`, `const unsafe fn`
+        "const" if strip_words(rest, &["async", "unsafe", "extern"]).starts_with("fn ") => {
+            return rust_definition(rest);
+        }
+        "const" => "constant",
+        "static" => return ident(strip_words(rest, &["mut"])).map(|n| (n, "constant")),
+        _ => return None,
+    };
+    ident(rest).map(|name| (name, kind))
+}
+
+fn python_definition(text: &str) -> Option<(String, &'static str)> {
+    let text = strip_words(text, &["async"]);
+    if let Some(rest) = text.strip_prefix("def ") {
+        return ident(rest).map(|n| (n, "function"));
+    }
+    if let Some(rest) = text.strip_prefix("class ") {
+        return ident(rest).map(|n| (n, "class"));
+    }
+    None
+}
+
+const JS_CONTROL: &[&str] = &[
+    "if", "for", "while", "switch", "catch", "return", "function", "else", "do", "with", "new",
+    "typeof", "await", "super",
+];
+
+fn js_definition(text: &str) -> Option<(String, &'static str)> {
+    let text = strip_words(
+        text,
+        &[
+            "export",
+            "default",
+            "declare",
+            "abstract",
+            "async",
+            "public",
+            "private",
+            "protected",
+            "static",
+            "readonly",
+            "override",
+            "get",
+            "set",
+        ],
+    );
+    let (keyword, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    let kind = match keyword {
+        "function" | "function*" => "function",
+        "class" => "class",
+        "interface" => "interface",
+        "enum" => "enum",
+        "namespace" | "module" => "module",
+        "type" => "type",
+        "const" | "let" | "var" => {
+            // `const handler = (...) => ...`, `const f = function ...`, `const f = async ...`
+            let name = ident(rest)?;
+            let value = rest.split_once('=').map(|(_, v)| v.trim_start())?;
+            let is_function = value.starts_with("function")
+                || value.starts_with("async")
+                || (value.starts_with('(') && value.contains("=>"))
+                || value
+                    .split_once("=>")
+                    .is_some_and(|(param, _)| ident(param).is_some_and(|p| p == param.trim()));
+            return is_function.then_some((name, "function"));
+        }
+        _ => {
+            // Class methods: `name(args) {`
+            let name = ident(text)?;
+            let after = text.trim_start()[name.len()..].trim_start();
+            let is_method = after.starts_with('(')
+                && text.trim_end().ends_with('{')
+                && !JS_CONTROL.contains(&name.as_str());
+            return is_method.then_some((name, "function"));
+        }
+    };
+    let rest = rest.trim_start_matches('*');
+    ident(rest).map(|name| (name, kind))
+}
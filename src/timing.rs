@@ -0,0 +1,40 @@
+use crate::config::TimingPolicy;
+use crate::examiner::Exam;
+use crate::transcript::Answers;
+
+/// Questions answered faster than `[timing]` allows, and a too-short exam overall, as reasons.
+/// Untimed answers (from a file or the editor) are never flagged.
+pub fn violations(rules: &TimingPolicy, exam: &Exam, answers: &Answers) -> Vec<String> {
+    let mut out = Vec::new();
+    if rules.is_empty() || answers.answer_seconds.is_empty() {
+        return out;
+    }
+    if let Some(min) = rules.min_seconds_per_question {
+        for q in &exam.questions {
+            if !answers.is_answered(&q.id) {
+                continue;
+            }
+            let spent = answers.answer_seconds.get(&q.id).copied().unwrap_or(0.0);
+            if spent < min {
+                out.push(format!(
+                    "{} ({}) was answered in {spent:.1}s < min_seconds_per_question {min:.1}s",
+                    q.id, q.category
+                ));
+            }
+        }
+    }
+    if let Some(min) = rules.min_total_seconds {
+        let total = total_seconds(answers);
+        if total < min {
+            out.push(format!(
+                "the exam was answered in {total:.1}s < min_total_seconds {min:.1}s"
+            ));
+        }
+    }
+    out
+}
+
+/// Time spent on all questions together.
+pub fn total_seconds(answers: &Answers) -> f64 {
+    answers.answer_seconds.values().sum()
+}
@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use base64::Engine;
+
+/// Counts tokens the way tiktoken-style BPE encoders do: text is first split into pieces (words
+/// with their leading space, runs of up to three digits, punctuation runs, whitespace), then each
+/// piece is byte-pair merged using the encoder's ranks.
+///
+/// With a rank file (`tokenizer.bpe_file`, e.g. `cl100k_base.tiktoken`) counts are exact for
+/// models using that encoding. Without one, each piece's token count is estimated from its shape,
+/// which tracks code (many short punctuation tokens) and prose (about one token per word) far
+/// better than a flat chars-per-token ratio.
+#[derive(Debug, Clone, Default)]
+pub struct Tokenizer {
+    ranks: Option<HashMap<Vec<u8>, u32>>,
+}
+
+impl Tokenizer {
+    /// The estimating tokenizer.
+    pub fn estimate() -> Self {
+        Self { ranks: None }
+    }
+
+    /// Loads a tiktoken rank file: one `<base64 token> <rank>` pair per line.
+    pub fn from_bpe_file(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read BPE ranks from {}", path.display()))?;
+        let mut ranks = HashMap::new();
+        for (n, line) in raw.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (token, rank) = line.split_once(' ').ok_or_else(|| {
+                anyhow!("{}:{}: expected `<token> <rank>`", path.display(), n + 1)
+            })?;
+            let token = base64::engine::general_purpose::STANDARD
+                .decode(token)
+                .with_context(|| format!("{}:{}: invalid base64 token", path.display(), n + 1))?;
+            let rank = rank
+                .trim()
+                .parse::<u32>()
+                .with_context(|| format!("{}:{}: invalid rank", path.display(), n + 1))?;
+            ranks.insert(token, rank);
+        }
+        if ranks.is_empty() {
+            return Err(anyhow!("{} contains no BPE ranks", path.display()));
+        }
+        Ok(Self { ranks: Some(ranks) })
+    }
+
+    /// True when counts come from real BPE ranks rather than the estimate.
+    pub fn is_exact(&self) -> bool {
+        self.ranks.is_some()
+    }
+
+    pub fn count(&self, text: &str) -> usize {
+        pieces(text).map(|p| self.piece_tokens(p)).sum()
+    }
+
+    /// The longest prefix of `text` within `max_tokens`, cut at a line break when there is one
+    /// (so a hunk line is never split), plus whether anything was cut.
+    pub fn truncate<'a>(&self, text: &'a str, max_tokens: usize) -> (&'a str, bool) {
+        let mut used = 0;
+        let mut end = 0;
+        for piece in pieces(text) {
+            let n = self.piece_tokens(piece);
+            if used + n > max_tokens {
+                let prefix = &text[..end];
+                let cut = prefix.rfind('\n').map(|i| i + 1).unwrap_or(end);
+                return (&text[..cut], true);
+            }
+            used += n;
+            end += piece.len();
+        }
+        (text, false)
+    }
+
+    fn piece_tokens(&self, piece: &str) -> usize {
+        match &self.ranks {
+            Some(ranks) => bpe_count(ranks, piece.as_bytes()),
+            None => estimate_piece(piece),
+        }
+    }
+}
+
+/// Number of tokens byte-pair merging produces for one piece: repeatedly merge the adjacent pair
+/// whose concatenation has the lowest rank until no pair is in the vocabulary.
+fn bpe_count(ranks: &HashMap<Vec<u8>, u32>, piece: &[u8]) -> usize {
+    if piece.is_empty() {
+        return 0;
+    }
+    if ranks.contains_key(piece) {
+        return 1;
+    }
+    // Boundaries of the current parts; part i is piece[bounds[i]..bounds[i + 1]].
+    let mut bounds: Vec<usize> = (0..=piece.len()).collect();
+    loop {
+        let best = (0..bounds.len().saturating_sub(2))
+            .filter_map(|i| {
+                ranks
+                    .get(&piece[bounds[i]..bounds[i + 2]])
+                    .map(|rank| (*rank, i))
+            })
+            .min();
+        match best {
+            Some((_, i)) => {
+                bounds.remove(i + 1);
+            }
+            None => return bounds.len() - 1,
+        }
+    }
+}
+
+/// Token estimate for one piece without a vocabulary.
+fn estimate_piece(piece: &str) -> usize {
+    let chars = piece.chars().count();
+    let first = piece.chars().find(|c| !c.is_whitespace());
+    match first {
+        // Indentation and blank-line runs merge into few tokens.
+        None => 1,
+        Some(c) if c.is_alphabetic() => {
+            if !piece.is_ascii() {
+                // Non-Latin scripts are close to one token per character.
+                return chars;
+            }
+            let letters = piece.trim_start().len();
+            1 + letters.saturating_sub(1) / 7
+        }
+        Some(c) if c.is_numeric() => 1,
+        // Punctuation runs: common pairs (`::`, `->`, `();`) are single tokens.
+        Some(_) => piece.trim().chars().count().div_ceil(2).max(1),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Class {
+    Letter,
+    Digit,
+    Space,
+    Newline,
+    Other,
+}
+
+fn class(c: char) -> Class {
+    if c == '\n' || c == '\r' {
+        Class::Newline
+    } else if c.is_whitespace() {
+        Class::Space
+    } else if c.is_alphabetic() {
+        Class::Letter
+    } else if c.is_numeric() {
+        Class::Digit
+    } else {
+        Class::Other
+    }
+}
+
+/// Splits text into pre-tokenization pieces, following the cl100k pattern: contractions, an
+/// optional leading space or symbol plus a letter run, digit runs of at most three, an optional
+/// space plus a punctuation run with trailing newlines, newline runs, and whitespace (whose last
+/// space is left to start the next word).
+fn pieces(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let len = piece_len(rest);
+        let (piece, tail) = rest.split_at(len);
+        rest = tail;
+        Some(piece)
+    })
+}
+
+fn piece_len(s: &str) -> usize {
+    let chars: Vec<(usize, char)> = s.char_indices().take(64).collect();
+    let end_at = |i: usize| chars.get(i).map(|(b, _)| *b).unwrap_or(s.len());
+    let run = |from: usize, pred: &dyn Fn(Class) -> bool| {
+        let mut i = from;
+        while i < chars.len() && pred(class(chars[i].1)) {
+            i += 1;
+        }
+        i
+    };
+    let (_, c0) = chars[0];
+
+    if c0 == '\'' {
+        for suffix in ["s", "t", "re", "ve", "m", "ll", "d"] {
+            if s[1..]
+                .get(..suffix.len())
+                .is_some_and(|c| c.eq_ignore_ascii_case(suffix))
+            {
+                return 1 + suffix.len();
+            }
+        }
+    }
+    match class(c0) {
+        Class::Letter => end_at(run(0, &|c| c == Class::Letter)),
+        Class::Digit => end_at(run(0, &|c| c == Class::Digit).min(3)),
+        Class::Space | Class::Other
+            if chars
+                .get(1)
+                .is_some_and(|(_, c)| class(*c) == Class::Letter) =>
+        {
+            end_at(run(1, &|c| c == Class::Letter))
+        }
+        Class::Space if chars.get(1).is_some_and(|(_, c)| class(*c) == Class::Other) => {
+            let i = run(1, &|c| c == Class::Other);
+            end_at(run(i, &|c| c == Class::Newline))
+        }
+        Class::Other => {
+            let i = run(0, &|c| c == Class::Other);
+            end_at(run(i, &|c| c == Class::Newline))
+        }
+        Class::Space | Class::Newline => {
+            let i = run(0, &|c| c == Class::Space || c == Class::Newline);
+            // Whitespace through its last newline is one piece; a run of spaces leaves its last
+            // space to lead the following word, as `\s+(?!\S)` does.
+            match (0..i).rev().find(|j| class(chars[*j].1) == Class::Newline) {
+                Some(j) => end_at(j + 1),
+                None if i < chars.len() && i > 1 => end_at(i - 1),
+                None => end_at(i),
+            }
+        }
+    }
+}
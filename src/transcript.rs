@@ -1,72 +1,327 @@
 use std::collections::BTreeMap;
+use std::io::IsTerminal;
+use std::sync::Arc;
+use std::time::Instant;
 
 use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
-use crate::config::Policy;
+use crate::api_surface::ApiChange;
+use crate::approvals::Countersignature;
+use crate::chain::ChainLink;
+use crate::config::{
+    IntegrityPolicy, MemorySafetyPolicy, Policy, RespondentsPolicy, TimingPolicy,
+};
+use crate::context_manifest::ContextManifest;
 use crate::examiner::{Exam, ExamContext};
+use crate::failure_report::FailureReport;
 use crate::git::{Git, GitRepo};
+use crate::integrity::IntegrityFlag;
 use crate::redact::RedactionHit;
+use crate::remote_store::RemoteStore;
+use crate::respondents::Respondent;
+use crate::retake::Attempt;
+use crate::risk::RiskAssessment;
+use crate::session::ExamSession;
+use crate::signing::TranscriptSignature;
+
+/// Diff lines shown above each question in the terminal exam (`tui.diff_lines`).
+const DEFAULT_TUI_DIFF_LINES: usize = 12;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Answers {
     pub answers: BTreeMap<String, String>,
+    /// Who answered each question, when the exam was split between several people.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub respondents: BTreeMap<String, Respondent>,
+    /// In an answers file: who gave every answer not listed in `respondents`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub respondent: Option<Respondent>,
+    /// Email of the committer, recorded alongside `respondents`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub committer: Option<String>,
+    /// Seconds spent on each question, when the exam was taken interactively.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub answer_seconds: BTreeMap<String, f64>,
+    /// Answers whose text `store_answers` kept out of the transcript: the SHA-256 of each with
+    /// "hashed", `null` with "none".
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub withheld: BTreeMap<String, Option<String>>,
 }
 
 impl Answers {
+    /// Answers from a single, unnamed respondent.
+    pub fn new(answers: BTreeMap<String, String>) -> Self {
+        Self {
+            answers,
+            respondents: BTreeMap::new(),
+            respondent: None,
+            committer: None,
+            answer_seconds: BTreeMap::new(),
+            withheld: BTreeMap::new(),
+        }
+    }
+
     pub fn get(&self, id: &str) -> Option<&str> {
         self.answers.get(id).map(|s| s.as_str())
     }
 
+    /// Whether question `id` got a non-empty answer, also when its text was withheld.
+    pub fn is_answered(&self, id: &str) -> bool {
+        self.get(id).is_some_and(|a| !a.trim().is_empty()) || self.withheld.contains_key(id)
+    }
+
+    /// Loads answers from JSON, or from YAML/TOML when `path` ends in `.yaml`/`.yml`/`.toml`
+    /// (all with the same `answers` map), or from a Markdown form written by
+    /// `aigit exam --emit-template` (`.md`). `-` reads JSON from stdin.
     pub fn load_from_path(path: &str) -> Result<Self> {
         if path == "-" {
             let mut buf = String::new();
             use std::io::Read;
             std::io::stdin().read_to_string(&mut buf)?;
-            Ok(serde_json::from_str(&buf)?)
-        } else {
-            let raw = std::fs::read_to_string(path)?;
-            Ok(serde_json::from_str(&raw)?)
+            return Ok(serde_json::from_str(&buf)?);
+        }
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read answers from {path}"))?;
+        let ext = std::path::Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        match ext.as_deref() {
+            Some("yaml" | "yml") => crate::yaml::parse(&raw)
+                .and_then(|value| Ok(serde_json::from_value(value)?))
+                .with_context(|| format!("failed to parse YAML answers from {path}")),
+            Some("md" | "markdown") => Ok(crate::answer_template::parse(&raw)),
+            Some("toml") => toml::from_str(&raw)
+                .with_context(|| format!("failed to parse TOML answers from {path}")),
+            _ => serde_json::from_str(&raw)
+                .with_context(|| format!("failed to parse JSON answers from {path}")),
         }
     }
 
-    pub fn prompt_tui(exam: &Exam) -> Result<Self> {
+    /// Assembles answers from `<dir>/<question_id>.md` files (one file per question, so
+    /// different pipeline steps or people can each supply their own).
+    pub fn load_from_dir(dir: &str) -> Result<Self> {
+        let mut answers = BTreeMap::new();
+        let entries =
+            std::fs::read_dir(dir).with_context(|| format!("failed to read answers dir {dir}"))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("md") {
+                continue;
+            }
+            let Some(id) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let text = std::fs::read_to_string(&path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            answers.insert(id.to_string(), text.trim().to_string());
+        }
+        Ok(Self::new(answers))
+    }
+
+    /// Asks each question on the terminal: full screen (see `exam_tui`) when stdin and stdout are
+    /// terminals and `tui.full_screen` is not off, line by line otherwise. With a `session`, its
+    /// draft answers are offered again and new ones are saved to it as they are typed.
+    pub fn prompt_tui(
+        exam: &Exam,
+        ctx: &ExamContext,
+        session: Option<&mut ExamSession>,
+    ) -> Result<Self> {
+        if crate::exam_tui::available(ctx) {
+            return crate::exam_tui::run(exam, ctx, session);
+        }
+        Self::prompt_plain(exam, ctx, session)
+    }
+
+    /// Asks each question line by line. Unless `tui.show_diff` is off, the part of the diff the
+    /// examiner saw that each question is about is shown above it; unless `tui.live_feedback` is
+    /// off, each line of an open answer is followed by a check of the answer so far.
+    /// A draft answer is shown above its question and kept when the new answer is left empty.
+    /// The time from showing each question to reading its answer is recorded. Precheck issues
+    /// (see `precheck`) are listed at the end; on a terminal, the flagged questions can then be
+    /// answered again before submitting.
+    fn prompt_plain(
+        exam: &Exam,
+        ctx: &ExamContext,
+        mut session: Option<&mut ExamSession>,
+    ) -> Result<Self> {
+        let tui = &ctx.policy.tui;
         let mut answers = BTreeMap::new();
+        let mut seconds = BTreeMap::new();
+        let show_diff = tui.show_diff.unwrap_or(true);
+        let live_feedback = tui.live_feedback.unwrap_or(true);
+        let max_lines = tui.diff_lines.unwrap_or(DEFAULT_TUI_DIFF_LINES);
+        let color = tui.color.unwrap_or_else(|| {
+            std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+        });
         println!("aigit exam: answer the following questions.\n");
-        for q in &exam.questions {
-            println!("--- [{}] {} ---", q.category, q.prompt);
-            let text = if let Some(choices) = &q.choices {
-                if choices.is_empty() {
-                    println!("(end your answer with a single '.' on its own line)\n");
-                    read_multiline_until_dot()?
+        let mut ask: Vec<String> = exam.questions.iter().map(|q| q.id.clone()).collect();
+        loop {
+            for q in exam.questions.iter().filter(|q| ask.contains(&q.id)) {
+                let started = Instant::now();
+                println!("--- [{}] {} ---", q.category, q.prompt);
+                if show_diff {
+                    for line in crate::question_links::excerpt(&ctx.diff, q, max_lines) {
+                        println!("  {}", diff_line(&line, color));
+                    }
+                }
+                let draft = answers
+                    .get(&q.id)
+                    .filter(|a: &&String| !a.trim().is_empty())
+                    .cloned()
+                    .or_else(|| session.as_deref().and_then(|s| s.draft(&q.id)).map(str::to_string));
+                if let Some(draft) = &draft {
+                    println!("(draft answer, kept if you leave this one empty:)");
+                    for line in draft.lines() {
+                        println!("  | {line}");
+                    }
+                }
+                let mut feedback = |answer: &str| {
+                    if let Some(session) = session.as_deref_mut() {
+                        session.record(&q.id, answer.trim_end());
+                    }
+                    if !live_feedback {
+                        return;
+                    }
+                    let line = format!("  > {}", crate::answer_feedback::summarize(ctx, q, answer));
+                    if color {
+                        println!("\x1b[2m{line}\x1b[0m");
+                    } else {
+                        println!("{line}");
+                    }
+                };
+                let feedback: Option<&mut dyn FnMut(&str)> = Some(&mut feedback);
+                let text = if q.is_evidence() {
+                    println!("(cite a line as path:line, a range as path:start-end, or a hunk as path @@ -a,b +c,d @@)\n");
+                    read_single_line()?
+                } else if let Some(choices) = &q.choices {
+                    if choices.is_empty() {
+                        println!("(end your answer with a single '.' on its own line)\n");
+                        read_multiline_until_dot(feedback)?
+                    } else {
+                    println!("(choose one: A/B/C/D; or type the full text)\n");
+                    for (idx, c) in choices.iter().enumerate() {
+                        let letter = match idx {
+                            0 => "A",
+                            1 => "B",
+                            2 => "C",
+                            3 => "D",
+                            4 => "E",
+                            _ => "?",
+                        };
+                        println!("  {letter}) {c}");
+                    }
+                    read_single_line()?
+                    }
                 } else {
-                println!("(choose one: A/B/C/D; or type the full text)\n");
-                for (idx, c) in choices.iter().enumerate() {
-                    let letter = match idx {
-                        0 => "A",
-                        1 => "B",
-                        2 => "C",
-                        3 => "D",
-                        4 => "E",
-                        _ => "?",
-                    };
-                    println!("  {letter}) {c}");
+                    println!("(end your answer with a single '.' on its own line)\n");
+                    read_multiline_until_dot(feedback)?
+                };
+                let text = match draft {
+                    Some(draft) if text.trim().is_empty() => draft,
+                    _ => text,
+                };
+                let elapsed = started.elapsed().as_secs_f64();
+                let previous = seconds
+                    .get(&q.id)
+                    .copied()
+                    .or_else(|| session.as_deref().and_then(|s| s.answer_seconds.get(&q.id).copied()))
+                    .unwrap_or(0.0);
+                if let Some(session) = session.as_deref_mut() {
+                    session.add_seconds(&q.id, elapsed);
+                    session.record(&q.id, &text);
+                    session.flush();
                 }
-                read_single_line()?
+                seconds.insert(q.id.clone(), previous + elapsed);
+                answers.insert(q.id.clone(), text);
+                println!();
+            }
+            let issues = crate::precheck::check(&ctx.policy, exam, &answers);
+            if issues.is_empty() {
+                break;
+            }
+            crate::precheck::warn(&issues);
+            if !std::io::stdin().is_terminal() || !edit_or_submit()? {
+                break;
+            }
+            ask = crate::precheck::flagged(exam, &issues);
+        }
+        let mut answers = Self::new(answers);
+        answers.answer_seconds = seconds;
+        Ok(answers)
+    }
+
+    /// Writes the exam as a Markdown form (see `answer_template`), opens it in the user's editor
+    /// and reads the answers back. An untouched form aborts the exam, like an empty commit
+    /// message aborts `git commit`. With a `session`, the form starts from its draft answers and
+    /// what was written is saved back to it. On precheck issues, the form can be reopened from
+    /// the terminal before submitting.
+    pub fn prompt_editor(
+        git: &Git,
+        exam: &Exam,
+        ctx: &ExamContext,
+        mut session: Option<&mut ExamSession>,
+    ) -> Result<Self> {
+        let form = tempfile::Builder::new()
+            .prefix("aigit-exam-")
+            .suffix(".md")
+            .tempfile()
+            .context("failed to create the exam form")?;
+        let mut drafts = session.as_deref().map(|s| s.answers.clone()).unwrap_or_default();
+        loop {
+            std::fs::write(
+                form.path(),
+                crate::answer_template::render_filled(exam, &drafts),
+            )
+            .context("failed to write the exam form")?;
+            git.edit_file(form.path())?;
+            let raw =
+                std::fs::read_to_string(form.path()).context("failed to read the exam form")?;
+            let answers = crate::answer_template::parse(&raw);
+            if let Some(session) = session.as_deref_mut() {
+                for (id, answer) in &answers.answers {
+                    session.record(id, answer);
                 }
-            } else {
-                println!("(end your answer with a single '.' on its own line)\n");
-                read_multiline_until_dot()?
-            };
-            answers.insert(q.id.clone(), text);
-            println!();
+                session.flush();
+            }
+            if answers.answers.values().all(|a| a.is_empty()) {
+                return Err(anyhow!("aborting exam: no answers were written in the editor"));
+            }
+            let issues = crate::precheck::check(&ctx.policy, exam, &answers.answers);
+            if issues.is_empty() {
+                return Ok(answers);
+            }
+            crate::precheck::warn(&issues);
+            if !std::io::stdin().is_terminal() || !edit_or_submit()? {
+                return Ok(answers);
+            }
+            drafts = answers.answers;
         }
-        Ok(Self { answers })
     }
 }
 
+/// A diff excerpt line, colored like `git diff` when `color` is set.
+fn diff_line(line: &str, color: bool) -> String {
+    let code = match line.chars().next() {
+        _ if !color => return line.to_string(),
+        Some('+') => "32",
+        Some('-') => "31",
+        _ if line.contains(" @@ ") || line.starts_with("...") => "36",
+        _ => return line.to_string(),
+    };
+    format!("\x1b[{code}m{line}\x1b[0m")
+}
+
+/// After precheck issues: true to answer the flagged questions again, false to submit anyway.
+fn edit_or_submit() -> Result<bool> {
+    eprint!("aigit: (e)dit these answers or (s)ubmit anyway? [e/S] ");
+    let reply = read_single_line()?;
+    Ok(matches!(reply.trim(), "e" | "E" | "edit"))
+}
+
 fn read_single_line() -> Result<String> {
     use std::io::BufRead;
     let stdin = std::io::stdin();
@@ -75,7 +330,8 @@ fn read_single_line() -> Result<String> {
     Ok(line.trim_end().to_string())
 }
 
-fn read_multiline_until_dot() -> Result<String> {
+/// Reads lines up to a lone `.`, calling `feedback` with the answer so far after each one.
+fn read_multiline_until_dot(mut feedback: Option<&mut dyn FnMut(&str)>) -> Result<String> {
     use std::io::BufRead;
     let stdin = std::io::stdin();
     let mut out = String::new();
@@ -86,6 +342,9 @@ fn read_multiline_until_dot() -> Result<String> {
         }
         out.push_str(&line);
         out.push('\n');
+        if let Some(feedback) = feedback.as_deref_mut() {
+            feedback(&out);
+        }
     }
     Ok(out.trim_end().to_string())
 }
@@ -97,14 +356,371 @@ pub struct QuestionScore {
     pub score: f64,
     pub completeness: f64,
     pub specificity: f64,
-    pub notes: Vec<String>,
+    /// How on-topic the answer is for the question's category (0..1). Transcripts written before
+    /// this field existed read as fully relevant.
+    #[serde(default = "full_relevance")]
+    pub category_relevance: f64,
+    pub notes: Vec<Note>,
+    /// Spread of `score` across grading samples (`grading.samples > 1`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval: Option<ScoreInterval>,
+    /// Whether the picked choice matches the question's `correct_choice` (multiple-choice
+    /// questions with an answer key only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub choice_correct: Option<bool>,
+    /// Whether the answer cites a location the question accepts (evidence questions only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evidence_valid: Option<bool>,
+}
+
+/// What a grader's note on an answer is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteCode {
+    /// The answer is vague, short, or leaves out what the change actually does.
+    MissingSpecifics,
+    /// The answer gets something about the change wrong.
+    Inaccuracy,
+    /// The answer is generic text that could describe any change.
+    Boilerplate,
+    /// The answer is precise and complete; the only code that is not a deficiency.
+    Excellent,
+}
+
+impl NoteCode {
+    pub const ALL: [NoteCode; 4] = [
+        NoteCode::MissingSpecifics,
+        NoteCode::Inaccuracy,
+        NoteCode::Boilerplate,
+        NoteCode::Excellent,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NoteCode::MissingSpecifics => "missing_specifics",
+            NoteCode::Inaccuracy => "inaccuracy",
+            NoteCode::Boilerplate => "boilerplate",
+            NoteCode::Excellent => "excellent",
+        }
+    }
+
+    pub fn is_deficiency(self) -> bool {
+        self != NoteCode::Excellent
+    }
+
+    fn parse(raw: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.as_str() == raw)
+    }
+
+    /// Best guess for a free-text note (from a transcript written before notes were typed, or a
+    /// grader that ignored the schema). Anything unrecognized reads as missing specifics.
+    fn infer(message: &str) -> Self {
+        let lower = message.to_lowercase();
+        let has = |words: &[&str]| words.iter().any(|w| lower.contains(w));
+        if has(&["incorrect", "wrong", "inaccura", "contradict", "expected", "not part of"]) {
+            NoteCode::Inaccuracy
+        } else if has(&["boilerplate", "generic", "does not mention"]) {
+            NoteCode::Boilerplate
+        } else if has(&["excellent", "thorough"]) {
+            NoteCode::Excellent
+        } else {
+            NoteCode::MissingSpecifics
+        }
+    }
+}
+
+impl std::fmt::Display for NoteCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A grader's note on one answer. Transcripts written before notes were typed stored plain
+/// strings; those still deserialize, with a code guessed from the text (see [`NoteRepr`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "NoteRepr")]
+pub struct Note {
+    pub code: NoteCode,
+    pub message: String,
+    /// The changed file the note is about, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub related_path: Option<String>,
+}
+
+impl Note {
+    pub fn new(code: NoteCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            related_path: None,
+        }
+    }
+}
+
+impl std::fmt::Display for Note {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}", self.code, self.message)?;
+        if let Some(path) = &self.related_path {
+            write!(f, " ({path})")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NoteRepr {
+    Legacy(String),
+    Structured {
+        code: String,
+        message: String,
+        #[serde(default)]
+        related_path: Option<String>,
+    },
+}
+
+impl From<NoteRepr> for Note {
+    fn from(repr: NoteRepr) -> Self {
+        match repr {
+            NoteRepr::Legacy(message) => Note::new(NoteCode::infer(&message), message),
+            NoteRepr::Structured {
+                code,
+                message,
+                related_path,
+            } => Note {
+                code: NoteCode::parse(&code).unwrap_or_else(|| NoteCode::infer(&message)),
+                message,
+                related_path: related_path.filter(|p| !p.trim().is_empty()),
+            },
+        }
+    }
+}
+
+/// A confidence interval around a mean score.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ScoreInterval {
+    pub low: f64,
+    pub high: f64,
+    pub stddev: f64,
+}
+
+/// How sure the grade is, when it was averaged over several grading samples.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreConfidence {
+    pub samples: u32,
+    /// Confidence level of the intervals (e.g. 0.95).
+    pub level: f64,
+    /// Interval around `total_score`.
+    pub total: ScoreInterval,
+}
+
+/// Outcome of the diff-derived self-check question.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfCheckResult {
+    pub question_id: String,
+    /// The correct choice's text.
+    pub expected: String,
+    pub passed: bool,
+}
+
+fn full_relevance() -> f64 {
+    1.0
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Score {
     pub total_score: f64,
     pub per_question: Vec<QuestionScore>,
-    pub hallucination_flags: Vec<String>,
+    pub hallucination_flags: Vec<HallucinationFlag>,
+    /// Answers that repeat the commit message or another answer.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub integrity_flags: Vec<IntegrityFlag>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<ScoreConfidence>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub self_check: Option<SelfCheckResult>,
+    /// Who graded, as `<provider>/<model>` (see `aigit calibrate`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grader: Option<String>,
+    /// The grader's calibration offset, when one was applied to `total_score`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normalization: Option<Normalization>,
+}
+
+/// A learned calibration offset applied to a grader's total score.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Normalization {
+    pub offset: f64,
+    /// The total score as the grader gave it.
+    pub raw_total: f64,
+}
+
+impl Score {
+    /// The total score policy compares against `min_total_score`: the interval's lower bound
+    /// when `require_lower_bound` is set and the score was sampled, else the mean.
+    pub fn gated_total(&self, require_lower_bound: bool) -> f64 {
+        match (&self.confidence, require_lower_bound) {
+            (Some(c), true) => c.total.low,
+            _ => self.total_score,
+        }
+    }
+
+    /// Answered questions whose category relevance falls below `min`.
+    pub fn off_topic(&self, min: Option<f64>) -> Vec<&QuestionScore> {
+        let Some(min) = min else {
+            return Vec::new();
+        };
+        self.per_question
+            .iter()
+            .filter(|q| q.completeness > 0.0 && q.category_relevance < min)
+            .collect()
+    }
+
+    /// Categories whose questions average below their `min_category_score`, in category order.
+    pub fn below_category_minimums(&self, mins: &BTreeMap<String, f64>) -> Vec<CategoryShortfall> {
+        mins.iter()
+            .filter_map(|(category, &min_score)| {
+                let scores: Vec<f64> = self
+                    .per_question
+                    .iter()
+                    .filter(|q| &q.category == category)
+                    .map(|q| q.score)
+                    .collect();
+                if scores.is_empty() {
+                    return None;
+                }
+                let score = scores.iter().sum::<f64>() / scores.len() as f64;
+                (score < min_score).then(|| CategoryShortfall {
+                    category: category.clone(),
+                    score,
+                    min_score,
+                })
+            })
+            .collect()
+    }
+
+    /// Flags that count against `max_hallucination_flags`: those at or above `min_severity`
+    /// (all of them when unset).
+    pub fn counted_flags(&self, min_severity: Option<Severity>) -> usize {
+        self.hallucination_flags
+            .iter()
+            .filter(|f| min_severity.is_none_or(|min| f.severity >= min))
+            .count()
+    }
+
+    /// Whether a required self-check did not pass: answered wrong, or never asked (an exam from
+    /// before `self_check` was enabled, or one with the question removed).
+    pub fn failed_self_check(&self) -> bool {
+        self.self_check.as_ref().is_none_or(|c| !c.passed)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        })
+    }
+}
+
+/// A claim in an answer that the diff does not support. Transcripts written before flags were
+/// structured stored plain strings; those still deserialize (see [`FlagRepr`]).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(from = "FlagRepr")]
+pub struct HallucinationFlag {
+    pub question_id: String,
+    /// e.g. "file_not_in_diff", "nonexistent_symbol", "unsupported_claim", "contradicts_diff".
+    pub kind: String,
+    /// The file, symbol, or behavior the answer claims exists.
+    #[serde(default)]
+    pub claimed_artifact: Option<String>,
+    pub evidence: String,
+    pub severity: Severity,
+}
+
+impl HallucinationFlag {
+    pub fn file_not_in_diff(question_id: &str, path: &str) -> Self {
+        Self {
+            question_id: question_id.to_string(),
+            kind: "file_not_in_diff".to_string(),
+            claimed_artifact: Some(path.to_string()),
+            evidence: format!("mentions file not in diff: {path}"),
+            severity: Severity::Medium,
+        }
+    }
+
+    /// Reads a pre-structured flag string (`"<question id>: <text>"`). Its severity is unknown,
+    /// so it is treated as high: old transcripts must not start passing a stricter policy.
+    fn from_legacy(raw: &str) -> Self {
+        let (question_id, evidence) = match raw.split_once(": ") {
+            Some((id, rest)) if !id.contains(char::is_whitespace) => (id.to_string(), rest.to_string()),
+            _ => (String::new(), raw.to_string()),
+        };
+        let (kind, claimed_artifact) = match evidence.strip_prefix("mentions file not in diff: ") {
+            Some(path) => ("file_not_in_diff", Some(path.to_string())),
+            None => ("legacy", None),
+        };
+        Self {
+            question_id,
+            kind: kind.to_string(),
+            claimed_artifact,
+            evidence,
+            severity: Severity::High,
+        }
+    }
+}
+
+impl std::fmt::Display for HallucinationFlag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}: {}", self.severity, self.question_id, self.evidence)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FlagRepr {
+    Legacy(String),
+    Structured {
+        question_id: String,
+        kind: String,
+        #[serde(default)]
+        claimed_artifact: Option<String>,
+        evidence: String,
+        severity: Severity,
+    },
+}
+
+impl From<FlagRepr> for HallucinationFlag {
+    fn from(repr: FlagRepr) -> Self {
+        match repr {
+            FlagRepr::Legacy(raw) => HallucinationFlag::from_legacy(&raw),
+            FlagRepr::Structured {
+                question_id,
+                kind,
+                claimed_artifact,
+                evidence,
+                severity,
+            } => HallucinationFlag {
+                question_id,
+                kind,
+                claimed_artifact,
+                evidence,
+                severity,
+            },
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -115,20 +731,58 @@ pub enum Decision {
 }
 
 impl Decision {
-    pub fn from_score(policy: &Policy, exam: &Exam, answers: &Answers, score: &Score) -> Self {
-        if score.total_score < policy.min_total_score {
+    pub fn from_score(
+        policy: &Policy,
+        exam: &Exam,
+        answers: &Answers,
+        score: &Score,
+        changed_files: &[String],
+    ) -> Self {
+        if score.gated_total(policy.require_lower_bound()) < policy.min_total_score {
             return Decision::Fail;
         }
-        if (score.hallucination_flags.len() as u32) > policy.max_hallucination_flags {
+        if (score.counted_flags(policy.hallucination_min_severity) as u32)
+            > policy.max_hallucination_flags
+        {
             return Decision::Fail;
         }
-        for cat in &policy.required_categories {
-            let required_answered = exam
-                .questions
-                .iter()
-                .filter(|q| q.category == *cat)
-                .all(|q| answers.get(&q.id).unwrap_or("").trim().len() > 0);
-            if !required_answered {
+        if !score.off_topic(policy.min_category_relevance).is_empty() {
+            return Decision::Fail;
+        }
+        if !score
+            .below_category_minimums(&policy.min_category_score)
+            .is_empty()
+        {
+            return Decision::Fail;
+        }
+        if !crate::memory_safety::shortfalls(&policy.memory_safety, score).is_empty() {
+            return Decision::Fail;
+        }
+        if policy.self_check_required() && score.failed_self_check() {
+            return Decision::Fail;
+        }
+        if !crate::respondents::violations(&policy.respondents, exam, answers).is_empty() {
+            return Decision::Fail;
+        }
+        if policy.timing.fails()
+            && !crate::timing::violations(&policy.timing, exam, answers).is_empty()
+        {
+            return Decision::Fail;
+        }
+        if policy.integrity.fails() && !score.integrity_flags.is_empty() {
+            return Decision::Fail;
+        }
+        if crate::api_surface::unanswered(exam, answers) {
+            return Decision::Fail;
+        }
+        for cat in &policy.required_categories_for(changed_files) {
+            // An exam without a question in a required category fails rather than passing
+            // with nothing to answer.
+            let mut questions = exam.questions.iter().filter(|q| q.category == *cat).peekable();
+            if questions.peek().is_none() {
+                return Decision::Fail;
+            }
+            if !questions.all(|q| answers.is_answered(&q.id)) {
                 return Decision::Fail;
             }
         }
@@ -141,6 +795,9 @@ pub struct ProviderMetadata {
     pub provider: String,
     pub model: String,
     pub prompt_version: String,
+    /// Provider that graded the answers, when different from `provider`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grader: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -162,7 +819,138 @@ pub struct Transcript {
     pub decision: Decision,
     pub thresholds: PolicyThresholds,
     pub provider: ProviderMetadata,
+    /// Language the exam was written and graded in (`language` in policy; absent = English).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Risk pre-assessment of the diff, which may have lengthened the exam and raised
+    /// `min_total_score` (see `[risk]` in policy).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk: Option<RiskAssessment>,
+    /// Public Rust items the diff adds, removes or changes; their semver question must be
+    /// answered.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub api_changes: Vec<ApiChange>,
+    /// Categories that fell below their `min_category_score`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub category_shortfalls: Vec<CategoryShortfall>,
+    /// Why the exam failed and what to improve; absent for a pass.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_report: Option<FailureReport>,
     pub redactions: Vec<RedactionHit>,
+    /// Which files and how much of each reached the provider, and the context's token count.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_manifest: Option<ContextManifest>,
+    /// Set when this transcript was copied from an identical change (see `aigit transcript find
+    /// --attach`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reused_from: Option<ReusedFrom>,
+    /// Set when `aigit commit --amend` moved the transcript to the amended commit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amended: Option<Amended>,
+    /// Set once the answers were re-graded by an arbiter (see `aigit appeal`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub appeal: Option<Appeal>,
+    /// Sign-offs by approvers, for changes `approvals.groups` covers (see `aigit countersign`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub countersignatures: Vec<Countersignature>,
+    /// The committer's signature over the rest of the transcript (see `[signing]`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<TranscriptSignature>,
+    /// The transcript stored before this one and its hash, when `chain.enabled` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain: Option<ChainLink>,
+    /// Every attempt at the exam, when it was passed on a retake (see `aigit exam --retake`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attempts: Vec<Attempt>,
+    /// The Rego module's decision, when `decision.engine = "opa"` made it; `decision` is then
+    /// the module's and the thresholds' own is kept here as `builtin`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy_engine: Option<crate::decision_engine::EngineDecision>,
+    /// The effective policy the exam was taken under, for `aigit verify --policy recorded`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy: Option<Box<Policy>>,
+    /// The stored JSON this transcript was upgraded from (see [`migrate`]), until its content
+    /// changes: hashes and signatures cover it and it is what gets stored again, so reading
+    /// old history never breaks a trailer, chain link or signature.
+    #[serde(skip)]
+    pub upgraded_from: Option<Arc<serde_json::Value>>,
+}
+
+/// How much of each answer stored transcripts keep (`store_answers`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnswerStorage {
+    Full,
+    Hashed,
+    None,
+}
+
+impl AnswerStorage {
+    pub fn from_policy(policy: &Policy) -> Self {
+        match policy.store_answers.as_deref() {
+            Some("hashed") => Self::Hashed,
+            Some("none") => Self::None,
+            _ => Self::Full,
+        }
+    }
+}
+
+/// SHA-256 of an answer's text, as `store_answers = "hashed"` records it.
+pub fn answer_digest(text: &str) -> String {
+    hex::encode(Sha256::digest(text.as_bytes()))
+}
+
+/// The transcript a reused transcript was copied from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReusedFrom {
+    pub commit: String,
+    pub repo_id: String,
+    /// When the original exam was taken.
+    pub timestamp: DateTime<Utc>,
+}
+
+/// The commit an amended commit replaced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Amended {
+    pub from: String,
+    pub timestamp: DateTime<Utc>,
+    /// The amend changed the diff, so the exam was taken again; otherwise this is the exam
+    /// taken for `from`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub reexamined: bool,
+}
+
+/// A second grading of the same answers by the appeal arbiter. `score` and `decision` on the
+/// transcript are the arbiter's only when `overturned`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Appeal {
+    pub timestamp: DateTime<Utc>,
+    pub arbiter: String,
+    pub model: String,
+    pub original_score: Score,
+    pub original_decision: Decision,
+    /// The arbiter's grade; its per-question notes are the arbiter's reasoning.
+    pub arbiter_score: Score,
+    pub arbiter_decision: Decision,
+    pub overturned: bool,
+}
+
+/// A category whose questions scored below its `min_category_score`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryShortfall {
+    pub category: String,
+    /// Mean score of the category's questions.
+    pub score: f64,
+    pub min_score: f64,
+}
+
+impl std::fmt::Display for CategoryShortfall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} answers scored {:.2} < min_category_score {:.2}",
+            self.category, self.score, self.min_score
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -170,11 +958,71 @@ pub struct PolicyThresholds {
     pub min_total_score: f64,
     pub required_categories: Vec<String>,
     pub max_hallucination_flags: u32,
+    /// Only flags at or above this severity counted (absent = all).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hallucination_min_severity: Option<Severity>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_category_relevance: Option<f64>,
+    /// Category weights `total_score` was averaged with (`score_weights`).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub score_weights: BTreeMap<String, f64>,
+    /// Lowest mean score per category (`min_category_score`).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub min_category_score: BTreeMap<String, f64>,
+    /// `min_total_score` was checked against the sampled interval's lower bound.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub require_lower_bound: bool,
+    /// A wrong self-check answer fails the exam.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub self_check_required: bool,
+    /// Who may answer which categories.
+    #[serde(default, skip_serializing_if = "RespondentsPolicy::is_empty")]
+    pub respondents: RespondentsPolicy,
+    /// Minimum answer times.
+    #[serde(default, skip_serializing_if = "TimingPolicy::is_empty")]
+    pub timing: TimingPolicy,
+    /// Copied-answer detection.
+    #[serde(default, skip_serializing_if = "IntegrityPolicy::is_empty")]
+    pub integrity: IntegrityPolicy,
+    /// Specificity bar for the memory-safety question.
+    #[serde(default, skip_serializing_if = "MemorySafetyPolicy::is_empty")]
+    pub memory_safety: MemorySafetyPolicy,
 }
 
 impl Transcript {
+    /// Hex SHA-256 of the transcript's canonical JSON, which leaves out its signature and
+    /// countersignatures (see `signing::canonical_bytes`); what the chain and trailers pin.
+    pub fn content_hash(&self) -> Result<String> {
+        Ok(hex::encode(Sha256::digest(
+            crate::signing::canonical_bytes(self)?,
+        )))
+    }
+
+    /// The JSON to store: the transcript, or the older-schema JSON it was read from (with its
+    /// current signature and countersignatures) while that is what pins cover.
+    pub fn stored_value(&self) -> Result<serde_json::Value> {
+        let Some(source) = &self.upgraded_from else {
+            return Ok(serde_json::to_value(self)?);
+        };
+        let mut value = (**source).clone();
+        if let Some(map) = value.as_object_mut() {
+            map.remove("signature");
+            map.remove("countersignatures");
+            if let Some(signature) = &self.signature {
+                map.insert("signature".to_string(), serde_json::to_value(signature)?);
+            }
+            if !self.countersignatures.is_empty() {
+                map.insert(
+                    "countersignatures".to_string(),
+                    serde_json::to_value(&self.countersignatures)?,
+                );
+            }
+        }
+        Ok(value)
+    }
+
     pub fn from_exam_result(
-        _git: &Git,
+        git: &Git,
         policy: &Policy,
         ctx: &ExamContext,
         exam: &Exam,
@@ -183,8 +1031,8 @@ impl Transcript {
         decision: Decision,
     ) -> Result<Self> {
         let repo_fingerprint = fingerprint_repo(&ctx.repo_id);
-        Ok(Self {
-            schema_version: "aigit-transcript/0.1".to_string(),
+        let mut transcript = Self {
+            schema_version: SCHEMA_VERSION.to_string(),
             commit: None,
             timestamp: Utc::now(),
             repo_id: ctx.repo_id.clone(),
@@ -198,8 +1046,18 @@ impl Transcript {
             decision,
             thresholds: PolicyThresholds {
                 min_total_score: policy.min_total_score,
-                required_categories: policy.required_categories.clone(),
+                required_categories: required_categories(policy, ctx, exam),
                 max_hallucination_flags: policy.max_hallucination_flags,
+                hallucination_min_severity: policy.hallucination_min_severity,
+                min_category_relevance: policy.min_category_relevance,
+                score_weights: policy.score_weights.clone(),
+                min_category_score: policy.min_category_score.clone(),
+                require_lower_bound: policy.require_lower_bound(),
+                self_check_required: policy.self_check_required(),
+                respondents: policy.respondents.clone(),
+                timing: policy.timing.clone(),
+                integrity: policy.integrity.clone(),
+                memory_safety: policy.memory_safety.clone(),
             },
             provider: ProviderMetadata {
                 provider: policy
@@ -208,29 +1066,162 @@ impl Transcript {
                     .unwrap_or_else(|| "local".to_string()),
                 model: policy.model.clone().unwrap_or_else(|| "static".to_string()),
                 prompt_version: "static/0.1".to_string(),
+                grader: policy.grader.clone().filter(|g| Some(g) != policy.provider.as_ref()),
             },
+            language: policy.language.clone(),
+            risk: ctx.risk.clone(),
+            api_changes: ctx.api_changes.clone(),
+            category_shortfalls: score.below_category_minimums(&policy.min_category_score),
+            failure_report: None,
             redactions: ctx.redactions.clone(),
+            context_manifest: Some(ctx.context_manifest.clone()),
+            reused_from: None,
+            amended: None,
+            appeal: None,
+            countersignatures: Vec::new(),
+            signature: None,
+            chain: None,
+            attempts: Vec::new(),
+            policy_engine: None,
+            policy: Some(Box::new(policy.clone())),
+            upgraded_from: None,
+        };
+        if crate::decision_engine::enabled(policy) {
+            let change =
+                crate::decision_engine::change(git.user_name(), git.user_email(), &ctx.changed_files);
+            let verdict = crate::decision_engine::evaluate(&git.repo, policy, &transcript, change)?;
+            transcript.decision = verdict.decision();
+            transcript.policy_engine = Some(verdict);
+        }
+        transcript.failure_report = FailureReport::build(&transcript);
+        transcript.withhold_answers(AnswerStorage::from_policy(policy));
+        Ok(transcript)
+    }
+
+    /// Takes the answer text out per `store_answers`, before the transcript is signed and
+    /// stored: "hashed" keeps each answer's SHA-256 in `answers.withheld`, "none" only which
+    /// questions were answered. The answers of earlier `attempts` are dropped either way.
+    pub fn withhold_answers(&mut self, storage: AnswerStorage) {
+        if storage == AnswerStorage::Full {
+            return;
+        }
+        for (id, text) in std::mem::take(&mut self.answers.answers) {
+            if text.trim().is_empty() {
+                self.answers.answers.insert(id, text);
+                continue;
+            }
+            let digest = (storage == AnswerStorage::Hashed).then(|| answer_digest(&text));
+            self.answers.withheld.insert(id, digest);
+        }
+        for attempt in &mut self.attempts {
+            attempt.answers.clear();
+        }
+    }
+
+    /// Why the withheld answers cannot stand in for the real ones, if they cannot: each must
+    /// answer a question of the exam, and a digest must be a SHA-256.
+    pub fn withheld_answers_issue(&self) -> Option<String> {
+        self.answers.withheld.iter().find_map(|(id, digest)| {
+            if !self.exam.questions.iter().any(|q| q.id == *id) {
+                return Some(format!("withheld answer {id} is not a question of the exam"));
+            }
+            if self.answers.answers.contains_key(id) {
+                return Some(format!("answer {id} is both stored and withheld"));
+            }
+            digest
+                .as_deref()
+                .filter(|d| d.len() != 64 || !d.bytes().all(|b| b.is_ascii_hexdigit()))
+                .map(|d| format!("withheld answer {id} has a malformed SHA-256 {d:?}"))
         })
     }
 
-    pub fn verify_against_policy(&self, policy: &Policy) -> bool {
+    /// `current` with the grading thresholds the exam was taken under (`min_total_score`,
+    /// `min_category_score`, the required categories and `max_hallucination_flags`): from its
+    /// policy snapshot, or for transcripts written before snapshots were kept, from the
+    /// thresholds they recorded. Everything else, such as the decision engine, approvals,
+    /// signing and exemptions, stays as `current` has it: the note is not trusted with those.
+    pub fn recorded_policy(&self, current: &Policy) -> Policy {
+        let mut policy = current.clone();
+        match &self.policy {
+            Some(recorded) => {
+                policy.min_total_score = recorded.min_total_score;
+                policy.min_category_score = recorded.min_category_score.clone();
+                policy.required_categories = recorded.required_categories.clone();
+                policy.max_hallucination_flags = recorded.max_hallucination_flags;
+                for cat in &mut policy.categories {
+                    cat.required = recorded
+                        .categories
+                        .iter()
+                        .any(|c| c.id == cat.id && c.required);
+                }
+            }
+            None => {
+                let th = &self.thresholds;
+                policy.min_total_score = th.min_total_score;
+                policy.min_category_score = th.min_category_score.clone();
+                // Already lists the categories the change made required.
+                policy.required_categories = th.required_categories.clone();
+                for cat in &mut policy.categories {
+                    cat.required = false;
+                }
+                policy.max_hallucination_flags = th.max_hallucination_flags;
+            }
+        }
+        policy
+    }
+
+    pub fn verify_against_policy(&self, policy: &Policy, changed_files: &[String]) -> bool {
         if self.decision != Decision::Pass {
             return false;
         }
-        if self.score.total_score < policy.min_total_score {
+        let min_total_score =
+            crate::risk::min_total_score(policy, self.risk.as_ref().map(|r| r.level));
+        if self.score.gated_total(policy.require_lower_bound()) < min_total_score {
             return false;
         }
-        if (self.score.hallucination_flags.len() as u32) > policy.max_hallucination_flags {
+        if (self.score.counted_flags(policy.hallucination_min_severity) as u32)
+            > policy.max_hallucination_flags
+        {
             return false;
         }
-        for cat in &policy.required_categories {
-            let ok = self
+        if !self.score.off_topic(policy.min_category_relevance).is_empty() {
+            return false;
+        }
+        if !self
+            .score
+            .below_category_minimums(&policy.min_category_score)
+            .is_empty()
+        {
+            return false;
+        }
+        if !crate::memory_safety::shortfalls(&policy.memory_safety, &self.score).is_empty() {
+            return false;
+        }
+        if policy.self_check_required() && self.score.failed_self_check() {
+            return false;
+        }
+        if policy.timing.fails()
+            && !crate::timing::violations(&policy.timing, &self.exam, &self.answers).is_empty()
+        {
+            return false;
+        }
+        if policy.integrity.fails() && !self.score.integrity_flags.is_empty() {
+            return false;
+        }
+        if crate::api_surface::unanswered(&self.exam, &self.answers) {
+            return false;
+        }
+        for cat in &policy.required_categories_for(changed_files) {
+            let mut questions = self
                 .exam
                 .questions
                 .iter()
                 .filter(|q| q.category == *cat)
-                .all(|q| self.answers.get(&q.id).unwrap_or("").trim().len() > 0);
-            if !ok {
+                .peekable();
+            if questions.peek().is_none() {
+                return false;
+            }
+            if !questions.all(|q| self.answers.is_answered(&q.id)) {
                 return false;
             }
         }
@@ -238,6 +1229,16 @@ impl Transcript {
     }
 }
 
+/// The policy's required categories for the change, plus the API question's when asked.
+fn required_categories(policy: &Policy, ctx: &ExamContext, exam: &Exam) -> Vec<String> {
+    let mut out = policy.required_categories_for(&ctx.changed_files);
+    let api = crate::api_surface::CATEGORY;
+    if exam.questions.iter().any(|q| q.category == api) && !out.iter().any(|c| c == api) {
+        out.push(api.to_string());
+    }
+    out
+}
+
 fn fingerprint_repo(repo_id: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(repo_id.as_bytes());
@@ -245,53 +1246,109 @@ fn fingerprint_repo(repo_id: &str) -> String {
     hex::encode(hash)
 }
 
-pub fn print_human_result(t: &Transcript) {
+fn interval_suffix(score: &Score) -> String {
+    let mut out = match &score.confidence {
+        Some(c) => format!(
+            ", {:.0}% interval {:.2}-{:.2} over {} samples",
+            c.level * 100.0,
+            c.total.low,
+            c.total.high,
+            c.samples
+        ),
+        None => String::new(),
+    };
+    if let (Some(n), Some(grader)) = (&score.normalization, &score.grader) {
+        out.push_str(&format!(
+            ", normalized from {:.2} for {grader}",
+            n.raw_total
+        ));
+    }
+    out
+}
+
+/// Prints the decision with a per-question score table (colored per `summary::color`), then
+/// what failed and how to improve, or the warnings a pass still carries.
+pub fn print_human_result(t: &Transcript, policy: &Policy) {
+    let too_fast = crate::timing::violations(&t.thresholds.timing, &t.exam, &t.answers);
+    let table = crate::summary::table(t, crate::summary::color(policy));
     match t.decision {
         Decision::Pass => {
-            eprintln!("aigit: PASS (score {:.2})", t.score.total_score);
+            eprintln!("aigit: PASS (score {:.2}{})", t.score.total_score, interval_suffix(&t.score));
+            for line in &table {
+                eprintln!("{line}");
+            }
+            for reason in &too_fast {
+                eprintln!("aigit: warning: answered suspiciously fast: {reason}");
+            }
+            for flag in &t.score.integrity_flags {
+                eprintln!("aigit: warning: copied answer: {flag}");
+            }
         }
         Decision::Fail => {
-            eprintln!("aigit: FAIL (score {:.2})", t.score.total_score);
-            if t.score.total_score < t.thresholds.min_total_score {
-                eprintln!(
-                    "aigit: reason: total_score {:.2} < min_total_score {:.2}",
-                    t.score.total_score, t.thresholds.min_total_score
-                );
+            eprintln!("aigit: FAIL (score {:.2}{})", t.score.total_score, interval_suffix(&t.score));
+            for line in &table {
+                eprintln!("{line}");
             }
-            if (t.score.hallucination_flags.len() as u32) > t.thresholds.max_hallucination_flags {
-                eprintln!(
-                    "aigit: reason: hallucination_flags {} > max {}",
-                    t.score.hallucination_flags.len(),
-                    t.thresholds.max_hallucination_flags
-                );
+            // Transcripts written before failure reports were recorded get one built here.
+            let report = t
+                .failure_report
+                .clone()
+                .or_else(|| FailureReport::build(t))
+                .unwrap_or_default();
+            for check in &report.checks {
+                eprintln!("aigit: reason: {}", check.detail);
             }
-            if !t.thresholds.required_categories.is_empty() {
-                for cat in &t.thresholds.required_categories {
-                    let ok = t
-                        .exam
-                        .questions
-                        .iter()
-                        .filter(|q| q.category == *cat)
-                        .all(|q| t.answers.get(&q.id).unwrap_or("").trim().len() > 0);
-                    if !ok {
-                        eprintln!("aigit: reason: missing required category answer: {cat}");
-                    }
+            if !t.thresholds.timing.fails() {
+                for reason in &too_fast {
+                    eprintln!("aigit: warning: answered suspiciously fast: {reason}");
                 }
             }
+            if !t.thresholds.integrity.fails() {
+                for flag in &t.score.integrity_flags {
+                    eprintln!("aigit: warning: copied answer: {flag}");
+                }
+            }
+            if let Some(manifest) = t.context_manifest.as_ref().filter(|m| m.truncated) {
+                let cut: Vec<&str> = manifest
+                    .files
+                    .iter()
+                    .filter(|f| f.status == "partial" || f.status == "omitted")
+                    .map(|f| f.path.as_str())
+                    .collect();
+                eprintln!(
+                    "aigit: note: the diff was truncated to {} tokens; not fully shown: {}",
+                    manifest.max_tokens,
+                    cut.join(", ")
+                );
+            }
             if !t.score.hallucination_flags.is_empty() {
                 eprintln!("aigit: hallucination flags:");
                 for f in &t.score.hallucination_flags {
                     eprintln!("  - {f}");
                 }
             }
-            eprintln!("aigit: score breakdown:");
-            for q in &t.score.per_question {
-                eprintln!(
-                    "  - {} [{}]: score {:.2} (completeness {:.2}, specificity {:.2})",
-                    q.id, q.category, q.score, q.completeness, q.specificity
-                );
-                for n in &q.notes {
-                    eprintln!("      - {n}");
+            if !report.questions.is_empty() {
+                eprintln!("aigit: answers that pulled the score down:");
+                for q in &report.questions {
+                    eprintln!(
+                        "  - {} [{}]: score {:.2}, costing {:.2} of the total",
+                        q.id, q.category, q.score, q.cost
+                    );
+                    for advice in &q.advice {
+                        eprintln!("      - {advice}");
+                    }
+                }
+            }
+            let mut advice: Vec<&str> = Vec::new();
+            for check in &report.checks {
+                if !advice.contains(&check.advice.as_str()) {
+                    advice.push(&check.advice);
+                }
+            }
+            if !advice.is_empty() {
+                eprintln!("aigit: to improve:");
+                for a in advice {
+                    eprintln!("  - {a}");
                 }
             }
         }
@@ -300,37 +1357,349 @@ pub fn print_human_result(t: &Transcript) {
 
 pub struct TranscriptStore {
     kind: StoreKind,
+    /// Link new transcripts into the hash chain under `refs/aigit/chain` (`chain.enabled`).
+    chain: bool,
 }
 
+/// Notes ref transcripts are stored under (`refs/notes/aigit`).
+pub const NOTES_REF: &str = "aigit";
+
+/// Commit-message trailer pinning the transcript: `sha256:<hash> <decision> <total score>`.
+pub const TRAILER_KEY: &str = "Aigit-Transcript";
+
+/// Commit-message trailer carrying the whole transcript as base64 JSON (`trailer_store.embed`).
+pub const TRAILER_DATA_KEY: &str = "Aigit-Transcript-Data";
+
+/// Directory the tree store commits transcripts to, as `<patch_id>.json`.
+pub const TREE_STORE_DIR: &str = ".aigit/transcripts";
+
 enum StoreKind {
-    GitNotes,
+    GitNotes { notes_ref: String },
+    /// Trailers in the commit message, with the transcript also kept in git notes.
+    Trailer { notes_ref: String, embed: bool },
+    /// A file under `TREE_STORE_DIR` committed with the change, with the transcript also kept
+    /// in git notes. Commits find theirs by patch-id.
+    Tree {
+        notes_ref: String,
+    },
+    /// An HTTP audit service, with the transcript also kept in git notes (and queued under
+    /// `.git/aigit/remote-queue/` while the service is unreachable).
+    Remote {
+        notes_ref: String,
+        remote: RemoteStore,
+    },
 }
 
 impl TranscriptStore {
     pub fn git_notes() -> Self {
+        Self::git_notes_ref(NOTES_REF)
+    }
+
+    /// A git-notes store under `refs/notes/<notes_ref>`.
+    pub fn git_notes_ref(notes_ref: &str) -> Self {
         Self {
-            kind: StoreKind::GitNotes,
+            kind: StoreKind::GitNotes {
+                notes_ref: notes_ref.to_string(),
+            },
+            chain: false,
+        }
+    }
+
+    /// The backend selected by the policy's `store` key.
+    pub fn from_policy(policy: &Policy) -> Result<Self> {
+        match policy.store.as_deref().unwrap_or("git-notes") {
+            "git-notes" => Ok(Self {
+                chain: policy.chain.enabled.unwrap_or(false),
+                ..Self::git_notes()
+            }),
+            "trailer" => Ok(Self {
+                kind: StoreKind::Trailer {
+                    notes_ref: NOTES_REF.to_string(),
+                    embed: policy.trailer_store.embed.unwrap_or(false),
+                },
+                chain: policy.chain.enabled.unwrap_or(false),
+            }),
+            "remote" => Ok(Self {
+                kind: StoreKind::Remote {
+                    notes_ref: NOTES_REF.to_string(),
+                    remote: RemoteStore::from_policy(policy)?,
+                },
+                chain: policy.chain.enabled.unwrap_or(false),
+            }),
+            "tree" => Ok(Self {
+                kind: StoreKind::Tree {
+                    notes_ref: NOTES_REF.to_string(),
+                },
+                chain: policy.chain.enabled.unwrap_or(false),
+            }),
+            other => Err(anyhow!("unsupported store: {other}")),
         }
     }
 
+    /// Links a new transcript to the chain head, when the store keeps a chain. Call before
+    /// signing it.
+    pub fn link(&self, repo: &GitRepo, transcript: &mut Transcript) -> Result<()> {
+        if self.chain {
+            crate::chain::link(repo, transcript)?;
+        }
+        Ok(())
+    }
+
+    /// Whether transcripts go into the commit itself (its message or its tree), so they must be
+    /// complete (linked and signed, without `commit`) before `git commit` runs.
+    pub fn in_commit(&self) -> bool {
+        matches!(self.kind, StoreKind::Trailer { .. } | StoreKind::Tree { .. })
+    }
+
+    /// Where this store keeps `transcript`, for messages.
+    pub fn describe(&self, transcript: &Transcript) -> String {
+        match &self.kind {
+            StoreKind::GitNotes { .. } => "git notes".to_string(),
+            StoreKind::Trailer { .. } => "the commit message trailers and git notes".to_string(),
+            StoreKind::Tree { .. } => format!("{} and git notes", tree_path(transcript)),
+            StoreKind::Remote { .. } => "the remote store and git notes".to_string(),
+        }
+    }
+
+    /// Writes `transcript` into the working tree and stages it, for the tree store; a no-op for
+    /// the others.
+    pub fn stage(&self, repo: &GitRepo, transcript: &Transcript) -> Result<()> {
+        let StoreKind::Tree { .. } = &self.kind else {
+            return Ok(());
+        };
+        let path = tree_path(transcript);
+        let file = repo.workdir.join(&path);
+        if let Some(dir) = file.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        let mut json = serde_json::to_string_pretty(&transcript.stored_value()?)?;
+        json.push('\n');
+        std::fs::write(&file, json).with_context(|| format!("failed to write {path}"))?;
+        let status = std::process::Command::new("git")
+            .current_dir(&repo.workdir)
+            .args(["add", "--", &path])
+            .status()
+            .context("failed to run git add")?;
+        if !status.success() {
+            return Err(anyhow!("git add {path} failed"));
+        }
+        Ok(())
+    }
+
+    /// Removes `transcript` from the working tree and the index, for the tree store; a no-op for
+    /// the others.
+    pub fn unstage(&self, repo: &GitRepo, transcript: &Transcript) -> Result<()> {
+        let StoreKind::Tree { .. } = &self.kind else {
+            return Ok(());
+        };
+        let path = tree_path(transcript);
+        let status = std::process::Command::new("git")
+            .current_dir(&repo.workdir)
+            .args(["rm", "-q", "--ignore-unmatch", "--", &path])
+            .status()
+            .context("failed to run git rm")?;
+        if !status.success() {
+            return Err(anyhow!("git rm {path} failed"));
+        }
+        Ok(())
+    }
+
+    /// `Key: value` trailers to add to the commit message for `transcript`; none for git notes.
+    pub fn trailers(&self, transcript: &Transcript) -> Result<Vec<String>> {
+        let StoreKind::Trailer { embed, .. } = &self.kind else {
+            return Ok(Vec::new());
+        };
+        let mut out = vec![format!(
+            "{TRAILER_KEY}: sha256:{} {} {:.2}",
+            transcript.content_hash()?,
+            match transcript.decision {
+                Decision::Pass => "pass",
+                Decision::Fail => "fail",
+            },
+            transcript.score.total_score
+        )];
+        if *embed {
+            use base64::Engine;
+            out.push(format!(
+                "{TRAILER_DATA_KEY}: {}",
+                base64::engine::general_purpose::STANDARD
+                    .encode(serde_json::to_vec(&transcript.stored_value()?)?)
+            ));
+        }
+        Ok(out)
+    }
+
+    /// Stores `transcript` for `commit`; a chained transcript that is (or replaces) the newest
+    /// one becomes the chain head.
     pub fn store(&self, repo: &GitRepo, commit: &str, transcript: &Transcript) -> Result<()> {
-        match self.kind {
-            StoreKind::GitNotes => git_notes_store(repo, commit, transcript),
+        match &self.kind {
+            StoreKind::GitNotes { notes_ref }
+            | StoreKind::Trailer { notes_ref, .. }
+            | StoreKind::Tree { notes_ref, .. } => {
+                git_notes_store(repo, notes_ref, commit, transcript)?
+            }
+            StoreKind::Remote { notes_ref, remote } => {
+                git_notes_store(repo, notes_ref, commit, transcript)?;
+                let json = serde_json::to_string(&transcript.stored_value()?)?;
+                match remote.upload(commit, &json) {
+                    Ok(()) => {
+                        if let Err(err) = remote.flush(repo) {
+                            eprintln!("aigit: warning: queued transcripts not sent yet: {err:#}");
+                        }
+                    }
+                    Err(err) => {
+                        remote.enqueue(repo, commit, &json)?;
+                        eprintln!(
+                            "aigit: warning: {err:#}; kept in git notes and queued for upload with the next stored transcript"
+                        );
+                    }
+                }
+            }
+        }
+        if self.chain {
+            crate::chain::advance(repo, self, commit, transcript)?;
         }
+        Ok(())
     }
 
+    /// The transcript of `commit`, from any backend: git notes first for the git-notes store
+    /// (then the commit's trailers), the commit's trailers, its tree or the remote service first
+    /// for those stores.
     pub fn load(&self, repo: &GitRepo, commit: &str) -> Result<Transcript> {
-        match self.kind {
-            StoreKind::GitNotes => git_notes_load(repo, commit),
+        match &self.kind {
+            StoreKind::GitNotes { notes_ref } => {
+                git_notes_load(repo, notes_ref, commit).or_else(|err| {
+                    trailer_load(repo, notes_ref, commit)?.ok_or(err)
+                })
+            }
+            StoreKind::Trailer { notes_ref, .. } => match trailer_load(repo, notes_ref, commit)? {
+                Some(t) => Ok(t),
+                None => git_notes_load(repo, notes_ref, commit),
+            },
+            StoreKind::Tree { notes_ref } => tree_load(repo, commit)
+                .or_else(|err| git_notes_load(repo, notes_ref, commit).map_err(|_| err)),
+            StoreKind::Remote { notes_ref, remote } => match remote.download(commit) {
+                Ok(Some(raw)) => parse_transcript(&raw, "the remote store"),
+                Ok(None) => git_notes_load(repo, notes_ref, commit),
+                Err(err) => git_notes_load(repo, notes_ref, commit)
+                    .map_err(|_| anyhow!("{err:#}, and no transcript in git notes for {commit}")),
+            },
+        }
+    }
+
+    /// Drops the git note of `commit`, once its transcript moved to another commit. The remote
+    /// store keeps its copy.
+    pub fn remove(&self, repo: &GitRepo, commit: &str) -> Result<()> {
+        let out = std::process::Command::new("git")
+            .current_dir(&repo.workdir)
+            .args([
+                "notes",
+                &format!("--ref={}", self.notes_ref()),
+                "remove",
+                "--ignore-missing",
+                commit,
+            ])
+            .output()
+            .context("failed to run git notes remove")?;
+        if !out.status.success() {
+            return Err(anyhow!(
+                "git notes remove failed: {}",
+                String::from_utf8_lossy(&out.stderr).trim()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Commits that have a transcript in git notes.
+    pub fn list(&self, repo: &GitRepo) -> Result<Vec<String>> {
+        git_notes_list(repo, self.notes_ref())
+    }
+
+    /// Rewrites the transcripts in git notes that were stored in an older schema in the
+    /// current one (only reports what it would do with `dry_run`).
+    pub fn migrate_notes(
+        &self,
+        repo: &GitRepo,
+        dry_run: bool,
+    ) -> Result<Vec<(String, MigrationOutcome)>> {
+        let notes_ref = self.notes_ref();
+        let git = Git::new(repo.clone());
+        let mut out = Vec::new();
+        for commit in git_notes_list(repo, notes_ref)? {
+            let mut t = match git_notes_load(repo, notes_ref, &commit) {
+                Ok(t) => t,
+                Err(err) => {
+                    out.push((commit, MigrationOutcome::Unreadable(format!("{err:#}"))));
+                    continue;
+                }
+            };
+            let Some(from) = t
+                .upgraded_from
+                .as_ref()
+                .and_then(|source| source.get("schema_version"))
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+            else {
+                out.push((commit, MigrationOutcome::Current));
+                continue;
+            };
+            let pinned_by = if t.signature.is_some() {
+                Some("its signature")
+            } else if t.chain.is_some() {
+                Some("the transcript chain")
+            } else if git.trailer(&commit, TRAILER_KEY).is_some() {
+                Some("the commit's Aigit-Transcript trailer")
+            } else {
+                None
+            };
+            let outcome = match pinned_by {
+                Some(by) => MigrationOutcome::Pinned { from, by },
+                None => {
+                    if !dry_run {
+                        t.upgraded_from = None;
+                        git_notes_store(repo, notes_ref, &commit, &t)?;
+                    }
+                    MigrationOutcome::Upgraded { from }
+                }
+            };
+            out.push((commit, outcome));
+        }
+        Ok(out)
+    }
+
+    fn notes_ref(&self) -> &str {
+        match &self.kind {
+            StoreKind::GitNotes { notes_ref }
+            | StoreKind::Trailer { notes_ref, .. }
+            | StoreKind::Tree { notes_ref, .. }
+            | StoreKind::Remote { notes_ref, .. } => notes_ref,
         }
     }
 }
 
-fn git_notes_store(repo: &GitRepo, commit: &str, transcript: &Transcript) -> Result<()> {
-    let json = serde_json::to_string_pretty(transcript)?;
+/// What `aigit transcript migrate` did with one transcript in git notes.
+#[derive(Debug)]
+pub enum MigrationOutcome {
+    /// Already in `SCHEMA_VERSION`.
+    Current,
+    Upgraded { from: String },
+    /// Left in its schema, because rewriting it would break what pins its hash; it is still
+    /// upgraded whenever it is read.
+    Pinned { from: String, by: &'static str },
+    Unreadable(String),
+}
+
+fn git_notes_store(
+    repo: &GitRepo,
+    notes_ref: &str,
+    commit: &str,
+    transcript: &Transcript,
+) -> Result<()> {
+    let json = serde_json::to_string_pretty(&transcript.stored_value()?)?;
     let status = std::process::Command::new("git")
         .current_dir(&repo.workdir)
-        .args(["notes", "--ref=aigit", "add", "-f", "-m", &json, commit])
+        .args(["notes", &format!("--ref={notes_ref}"), "add", "-f", "-m", &json, commit])
         .status()
         .context("failed to run git notes add")?;
     if !status.success() {
@@ -339,23 +1708,179 @@ fn git_notes_store(repo: &GitRepo, commit: &str, transcript: &Transcript) -> Res
     Ok(())
 }
 
-fn git_notes_load(repo: &GitRepo, commit: &str) -> Result<Transcript> {
+fn git_notes_list(repo: &GitRepo, notes_ref: &str) -> Result<Vec<String>> {
     let out = std::process::Command::new("git")
         .current_dir(&repo.workdir)
-        .args(["notes", "--ref=aigit", "show", commit])
+        .args(["notes", &format!("--ref={notes_ref}"), "list"])
+        .output()
+        .context("failed to run git notes list")?;
+    if !out.status.success() {
+        return Ok(Vec::new());
+    }
+    let raw = String::from_utf8(out.stdout)?;
+    let mut commits = Vec::new();
+    for line in raw.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let _note_sha = parts.next();
+        let commit_sha = parts.next();
+        if let Some(c) = commit_sha {
+            commits.push(c.to_string());
+        }
+    }
+    Ok(commits)
+}
+
+fn git_notes_load(repo: &GitRepo, notes_ref: &str, commit: &str) -> Result<Transcript> {
+    let out = std::process::Command::new("git")
+        .current_dir(&repo.workdir)
+        .args(["notes", &format!("--ref={notes_ref}"), "show", commit])
         .output()
         .context("failed to run git notes show")?;
     if !out.status.success() {
         return Err(anyhow!("no transcript found in git notes for {commit}"));
     }
     let raw = String::from_utf8(out.stdout)?;
-    let t: Transcript = serde_json::from_str(&raw)
-        .with_context(|| "failed to parse transcript JSON from git notes")?;
-    if t.schema_version != "aigit-transcript/0.1" {
-        return Err(anyhow!(
-            "unsupported transcript schema {}",
-            t.schema_version
-        ));
+    parse_transcript(&raw, "git notes")
+}
+
+/// The transcript an `Aigit-Transcript` trailer of `commit` pins: the embedded one or the one
+/// in git notes, whichever still has the trailer's hash. `None` without the trailer.
+fn trailer_load(repo: &GitRepo, notes_ref: &str, commit: &str) -> Result<Option<Transcript>> {
+    use base64::Engine;
+    let git = Git::new(repo.clone());
+    let Some(reference) = git.trailer(commit, TRAILER_KEY) else {
+        return Ok(None);
+    };
+    let hash = reference
+        .split_whitespace()
+        .next()
+        .and_then(|h| h.strip_prefix("sha256:"))
+        .ok_or_else(|| anyhow!("malformed {TRAILER_KEY} trailer in {commit}: {reference}"))?;
+    let mut candidates = Vec::new();
+    if let Some(data) = git.trailer(commit, TRAILER_DATA_KEY) {
+        let raw = base64::engine::general_purpose::STANDARD
+            .decode(data.trim())
+            .with_context(|| format!("{TRAILER_DATA_KEY} trailer in {commit} is not base64"))?;
+        candidates.push(parse_transcript(
+            &String::from_utf8(raw)?,
+            &format!("the {TRAILER_DATA_KEY} trailer"),
+        )?);
+    }
+    if let Ok(t) = git_notes_load(repo, notes_ref, commit) {
+        candidates.push(t);
     }
+    candidates
+        .into_iter()
+        .find(|t| t.content_hash().is_ok_and(|h| h == hash))
+        .map(Some)
+        .ok_or_else(|| {
+            anyhow!(
+                "no transcript matches the {TRAILER_KEY} trailer of {commit} (missing from git notes or changed since)"
+            )
+        })
+}
+
+/// `TREE_STORE_DIR/<patch_id>.json` for `transcript`.
+fn tree_path(transcript: &Transcript) -> String {
+    format!(
+        "{TREE_STORE_DIR}/{}.json",
+        transcript.diff_fingerprint.patch_id
+    )
+}
+
+/// The transcript committed under `TREE_STORE_DIR` for `commit`'s patch-id. Looking it up in
+/// the commit's own tree finds it after a squash or rebase too, as long as the change is the
+/// same.
+fn tree_load(repo: &GitRepo, commit: &str) -> Result<Transcript> {
+    let git = Git::new(repo.clone());
+    let patch_id = git.patch_id_for_commit(commit)?;
+    let path = format!("{TREE_STORE_DIR}/{patch_id}.json");
+    let raw = git
+        .show_file(commit, &path)
+        .map_err(|_| anyhow!("no transcript found at {path} in {commit}"))?;
+    parse_transcript(&raw, &path)
+}
+
+/// Reads transcript JSON of any supported schema, upgrading older ones in memory.
+pub fn parse_transcript(raw: &str, source: &str) -> Result<Transcript> {
+    let mut value: serde_json::Value = serde_json::from_str(raw)
+        .with_context(|| format!("failed to parse transcript JSON from {source}"))?;
+    let original = (value.get("schema_version").and_then(|v| v.as_str()) != Some(SCHEMA_VERSION))
+        .then(|| value.clone());
+    migrate(&mut value)?;
+    let mut t: Transcript = serde_json::from_value(value)
+        .with_context(|| format!("failed to parse transcript JSON from {source}"))?;
+    t.upgraded_from = original.map(Arc::new);
     Ok(t)
 }
+
+/// Schema of the transcripts this version writes.
+pub const SCHEMA_VERSION: &str = "aigit-transcript/0.2";
+
+/// One upgrade step: the schema it reads, the schema it writes, and the rewrite.
+type Migration = (&'static str, &'static str, fn(&mut serde_json::Value) -> Result<()>);
+
+/// Every step from the oldest readable schema to `SCHEMA_VERSION`, in order.
+const MIGRATIONS: &[Migration] = &[(
+    "aigit-transcript/0.1",
+    "aigit-transcript/0.2",
+    typed_notes_and_flags,
+)];
+
+/// Upgrades transcript JSON to `SCHEMA_VERSION` in place, one step at a time; the schema it
+/// was written in when that was an older one. Unknown schemas (e.g. from a newer aigit) are an
+/// error.
+pub fn migrate(value: &mut serde_json::Value) -> Result<Option<String>> {
+    let original = value
+        .get("schema_version")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let mut version = original.clone();
+    while version != SCHEMA_VERSION {
+        let Some((_, next, step)) = MIGRATIONS.iter().find(|(from, _, _)| *from == version) else {
+            return Err(anyhow!("unsupported transcript schema {version}"));
+        };
+        step(value).with_context(|| format!("failed to upgrade a {version} transcript"))?;
+        value["schema_version"] = serde_json::Value::String(next.to_string());
+        version = next.to_string();
+    }
+    Ok((original != SCHEMA_VERSION).then_some(original))
+}
+
+/// 0.1 -> 0.2: grader notes and hallucination flags, which 0.1 also stored as plain strings,
+/// are always objects.
+fn typed_notes_and_flags(value: &mut serde_json::Value) -> Result<()> {
+    fn upgrade<T: Serialize + serde::de::DeserializeOwned>(
+        items: Option<&mut serde_json::Value>,
+    ) -> Result<()> {
+        for item in items
+            .and_then(|v| v.as_array_mut())
+            .into_iter()
+            .flatten()
+            .filter(|item| item.is_string())
+        {
+            *item = serde_json::to_value(serde_json::from_value::<T>(item.take())?)?;
+        }
+        Ok(())
+    }
+    for pointer in ["/score", "/appeal/original_score", "/appeal/arbiter_score"] {
+        let Some(score) = value.pointer_mut(pointer) else {
+            continue;
+        };
+        for question in score
+            .get_mut("per_question")
+            .and_then(|v| v.as_array_mut())
+            .into_iter()
+            .flatten()
+        {
+            upgrade::<Note>(question.get_mut("notes"))?;
+        }
+        upgrade::<HallucinationFlag>(score.get_mut("hallucination_flags"))?;
+    }
+    Ok(())
+}
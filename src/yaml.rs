@@ -0,0 +1,309 @@
+use anyhow::{anyhow, bail, Result};
+use serde_json::{Map, Number, Value};
+
+/// Parses the YAML that answers files use into JSON: nested block mappings whose values are
+/// plain, quoted (`'...'`, `"..."`) or block (`|`, `>`, with `-`/`+` chomping) scalars, plus
+/// comments and `---` markers. Sequences, flow collections other than `{}`/`[]`, anchors and
+/// tags are rejected rather than guessed at.
+pub fn parse(raw: &str) -> Result<Value> {
+    let raw = raw.strip_prefix('\u{feff}').unwrap_or(raw);
+    let mut parser = Parser {
+        lines: raw.lines().collect(),
+        pos: 0,
+    };
+    let Some((indent, _)) = parser.peek()? else {
+        return Ok(Value::Null);
+    };
+    let map = parser.mapping(indent)?;
+    if parser.peek()?.is_some() {
+        bail!("line {}: unexpected indentation", parser.pos + 1);
+    }
+    Ok(Value::Object(map))
+}
+
+struct Parser<'a> {
+    lines: Vec<&'a str>,
+    pos: usize,
+}
+
+#[derive(Clone, Copy)]
+enum Chomp {
+    Clip,
+    Strip,
+    Keep,
+}
+
+impl<'a> Parser<'a> {
+    /// The indentation and text of the next line with content, skipping blank lines, comments
+    /// and document markers.
+    fn peek(&mut self) -> Result<Option<(usize, &'a str)>> {
+        while let Some(&line) = self.lines.get(self.pos) {
+            let text = line.trim_start_matches(' ');
+            if text.trim().is_empty() || text.starts_with('#') || line == "---" || line == "..." {
+                self.pos += 1;
+                continue;
+            }
+            if text.starts_with('\t') {
+                bail!("line {}: tabs are not allowed in indentation", self.pos + 1);
+            }
+            return Ok(Some((line.len() - text.len(), text)));
+        }
+        Ok(None)
+    }
+
+    fn mapping(&mut self, indent: usize) -> Result<Map<String, Value>> {
+        let mut map = Map::new();
+        while let Some((ind, text)) = self.peek()? {
+            if ind < indent {
+                break;
+            }
+            let line_no = self.pos + 1;
+            if ind > indent {
+                bail!("line {line_no}: unexpected indentation");
+            }
+            if text == "-" || text.starts_with("- ") {
+                bail!("line {line_no}: sequences are not supported");
+            }
+            let (key, rest) = split_key(text)
+                .map_err(|e| anyhow!("line {line_no}: {e}"))?
+                .ok_or_else(|| anyhow!("line {line_no}: expected `key: value`"))?;
+            self.pos += 1;
+            let value = self.value(indent, rest, line_no)?;
+            if map.insert(key.clone(), value).is_some() {
+                bail!("line {line_no}: duplicate key {key}");
+            }
+        }
+        Ok(map)
+    }
+
+    /// The value of a key at `indent` whose text after the colon is `rest`.
+    fn value(&mut self, indent: usize, rest: &str, line_no: usize) -> Result<Value> {
+        let rest = rest.trim_start();
+        let bare = strip_comment(rest).trim_end();
+        match bare.chars().next() {
+            None => match self.peek()? {
+                Some((ind, text)) if ind > indent => {
+                    if text == "-" || text.starts_with("- ") {
+                        bail!("line {}: sequences are not supported", self.pos + 1);
+                    }
+                    Ok(Value::Object(self.mapping(ind)?))
+                }
+                _ => Ok(Value::Null),
+            },
+            Some('|' | '>') => self.block_scalar(indent, bare, line_no),
+            Some('"' | '\'') => self.quoted(rest, line_no),
+            Some('{' | '[') => match bare {
+                "{}" => Ok(Value::Object(Map::new())),
+                "[]" => Ok(Value::Array(Vec::new())),
+                _ => bail!("line {line_no}: flow collections are not supported"),
+            },
+            Some('&' | '*' | '!') => {
+                bail!("line {line_no}: anchors, aliases and tags are not supported")
+            }
+            Some(_) => {
+                // A plain scalar may continue on more indented lines, folded with spaces.
+                let mut text = bare.to_string();
+                while let Some((ind, more)) = self.peek()? {
+                    if ind <= indent {
+                        break;
+                    }
+                    text.push(' ');
+                    text.push_str(strip_comment(more).trim_end());
+                    self.pos += 1;
+                }
+                Ok(plain_scalar(&text))
+            }
+        }
+    }
+
+    fn block_scalar(&mut self, indent: usize, header: &str, line_no: usize) -> Result<Value> {
+        let chomp = match &header[1..] {
+            "" => Chomp::Clip,
+            "-" => Chomp::Strip,
+            "+" => Chomp::Keep,
+            _ => bail!("line {line_no}: unsupported block scalar header {header}"),
+        };
+        let mut lines: Vec<&str> = Vec::new();
+        let mut content_indent = None;
+        while let Some(&line) = self.lines.get(self.pos) {
+            let text = line.trim_start_matches(' ');
+            if text.trim().is_empty() {
+                lines.push("");
+                self.pos += 1;
+                continue;
+            }
+            let ind = line.len() - text.len();
+            let content_indent = *content_indent.get_or_insert(ind);
+            if ind <= indent || ind < content_indent {
+                break;
+            }
+            lines.push(&line[content_indent..]);
+            self.pos += 1;
+        }
+        let trailing = lines.iter().rev().take_while(|l| l.is_empty()).count();
+        lines.truncate(lines.len() - trailing);
+
+        let mut out = String::new();
+        for (i, line) in lines.iter().enumerate() {
+            if i > 0 {
+                let prev = lines[i - 1];
+                if header.starts_with('|') || prev.starts_with(' ') || line.starts_with(' ') {
+                    out.push('\n');
+                } else if line.is_empty() {
+                    // The break after a folded line is dropped; each blank line is one newline.
+                    if prev.is_empty() {
+                        out.push('\n');
+                    }
+                } else if prev.is_empty() {
+                    out.push('\n');
+                } else {
+                    out.push(' ');
+                }
+            }
+            out.push_str(line);
+        }
+        match chomp {
+            Chomp::Clip if !lines.is_empty() => out.push('\n'),
+            Chomp::Keep => {
+                out.push('\n');
+                out.push_str(&"\n".repeat(trailing));
+            }
+            _ => {}
+        }
+        Ok(Value::String(out))
+    }
+
+    /// A quoted scalar starting at `rest`; line breaks inside the quotes fold to a space, and a
+    /// blank line to a newline.
+    fn quoted(&mut self, rest: &str, line_no: usize) -> Result<Value> {
+        let mut text = rest.to_string();
+        loop {
+            if let Some((value, after)) =
+                unquote(&text).map_err(|e| anyhow!("line {line_no}: {e}"))?
+            {
+                if !strip_comment(after).trim().is_empty() {
+                    bail!("line {line_no}: unexpected text after quoted value");
+                }
+                return Ok(Value::String(value));
+            }
+            let Some(next) = self.lines.get(self.pos) else {
+                bail!("line {line_no}: unterminated quoted value");
+            };
+            let next = next.trim();
+            self.pos += 1;
+            if next.is_empty() {
+                text.push('\n');
+            } else {
+                if !text.ends_with('\n') {
+                    text.push(' ');
+                }
+                text.push_str(next);
+            }
+        }
+    }
+}
+
+/// Splits `key: rest` (the key plain or quoted). `None` when the line has no key.
+fn split_key(text: &str) -> Result<Option<(String, &str)>> {
+    if text.starts_with('"') || text.starts_with('\'') {
+        let Some((key, after)) = unquote(text)? else {
+            bail!("unterminated quoted key");
+        };
+        return Ok(after
+            .strip_prefix(':')
+            .filter(|r| r.is_empty() || r.starts_with(' '))
+            .map(|r| (key, r)));
+    }
+    let bytes = text.as_bytes();
+    for (i, &b) in bytes.iter().enumerate() {
+        if b == b'#' && i > 0 && bytes[i - 1] == b' ' {
+            break;
+        }
+        if b == b':' && (i + 1 == bytes.len() || bytes[i + 1] == b' ') {
+            let key = text[..i].trim_end();
+            if key.is_empty() {
+                return Ok(None);
+            }
+            return Ok(Some((key.to_string(), &text[i + 1..])));
+        }
+    }
+    Ok(None)
+}
+
+/// Reads the quoted scalar at the start of `text`, returning it and the text after the closing
+/// quote, or `None` when the quote is not closed.
+fn unquote(text: &str) -> Result<Option<(String, &str)>> {
+    let mut chars = text.char_indices();
+    let Some((_, quote)) = chars.next() else {
+        return Ok(None);
+    };
+    let mut out = String::new();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\'' if quote == '\'' => {
+                if text[i + 1..].starts_with('\'') {
+                    chars.next();
+                    out.push('\'');
+                } else {
+                    return Ok(Some((out, &text[i + 1..])));
+                }
+            }
+            '"' if quote == '"' => return Ok(Some((out, &text[i + 1..]))),
+            '\\' if quote == '"' => {
+                let Some((_, escaped)) = chars.next() else {
+                    return Ok(None);
+                };
+                out.push(match escaped {
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    '0' => '\0',
+                    '"' | '\\' | '/' | ' ' => escaped,
+                    'u' => {
+                        let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                        u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| anyhow!("invalid escape \\u{hex}"))?
+                    }
+                    other => bail!("unsupported escape \\{other}"),
+                });
+            }
+            _ => out.push(c),
+        }
+    }
+    Ok(None)
+}
+
+/// Drops a ` # comment` from the end of a plain value.
+fn strip_comment(text: &str) -> &str {
+    if text.starts_with('#') {
+        return "";
+    }
+    match text.find(" #") {
+        Some(i) => &text[..i],
+        None => text,
+    }
+}
+
+fn plain_scalar(text: &str) -> Value {
+    match text {
+        "~" | "null" | "Null" | "NULL" => return Value::Null,
+        "true" | "True" | "TRUE" => return Value::Bool(true),
+        "false" | "False" | "FALSE" => return Value::Bool(false),
+        _ => {}
+    }
+    let numeric = text
+        .trim_start_matches(['-', '+'])
+        .trim_start_matches('.')
+        .starts_with(|c: char| c.is_ascii_digit());
+    if numeric {
+        if let Ok(n) = text.parse::<i64>() {
+            return Value::Number(n.into());
+        }
+        if let Some(n) = text.parse::<f64>().ok().and_then(Number::from_f64) {
+            return Value::Number(n);
+        }
+    }
+    Value::String(text.to_string())
+}